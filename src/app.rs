@@ -1,16 +1,24 @@
 use std::cmp::Ordering;
 use std::collections::HashSet;
 use std::path::{Path, PathBuf};
-use std::sync::{atomic::AtomicBool, mpsc};
+use std::sync::atomic::AtomicBool;
+use std::sync::mpsc::{self, Sender};
 
-use crate::core::bulk::BulkProcessor;
+use crate::core::bulk::{BulkProcessor, WriteOptions};
+use crate::core::catalog;
+use crate::core::contacts;
+use crate::core::exiftool;
 use crate::core::formats;
-use crate::core::metadata::{MetadataEngine, MetadataError};
-use crate::core::presets::builtin_presets;
+use crate::core::metadata::{MergeStrategy, MetadataEngine, MetadataError, SidecarPolicy};
+use crate::core::presets::{self, builtin_presets};
+use crate::core::thumbnail::{self, THUMBNAIL_EDGE};
 use crate::models::{
-    MetadataTag, OperationResult, OperationSummary, OutputMode, PhotoEntry, PresetId,
-    ProgressEvent, StripPreset, TagCategory, TagValue,
+    ExportRename, ExportSummary, ImportRootSummary, ImportSummary, MetadataTag, OperationResult,
+    OperationSummary, OutputMode, PhotoEntry, PhotoId, PhotoMetadata, PresetId, PresetRule,
+    ProgressEvent, SkipReason, StripPreset, TagCategory, TagValue, ThumbnailData, COLOR_SPACE_KEY,
+    ORIENTATION_KEY,
 };
+use crate::settings::AppSettings;
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum ViewMode {
@@ -67,6 +75,36 @@ impl MetadataTab {
     }
 }
 
+/// Order of the rows in the metadata editor.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum TagSort {
+    /// By display name, then key.
+    #[default]
+    Alphabetical,
+    /// Grouped in [`TagCategory::ALL`] order, alphabetical within a group.
+    ByCategory,
+    /// The order the tags sit in the file, with added tags last.
+    FileOrder,
+}
+
+impl TagSort {
+    pub const ALL: [Self; 3] = [Self::Alphabetical, Self::ByCategory, Self::FileOrder];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Alphabetical => "A-Z",
+            Self::ByCategory => "Category",
+            Self::FileOrder => "File order",
+        }
+    }
+
+    /// The order after this one in [`Self::ALL`], wrapping around.
+    pub fn next(self) -> Self {
+        let index = Self::ALL.iter().position(|&sort| sort == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+}
+
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum TableColumn {
     Filename,
@@ -75,6 +113,8 @@ pub enum TableColumn {
     Gps,
     TagCount,
     FileSize,
+    Dimensions,
+    Format,
 }
 
 impl TableColumn {
@@ -86,6 +126,8 @@ impl TableColumn {
             Self::Gps => "GPS",
             Self::TagCount => "Tags",
             Self::FileSize => "File Size",
+            Self::Dimensions => "Dimensions",
+            Self::Format => "Format",
         }
     }
 }
@@ -105,11 +147,36 @@ impl Default for TableSort {
     }
 }
 
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct TemplateSummary {
+    pub photos_updated: usize,
+    pub tags_applied: usize,
+    pub skipped_keys: Vec<String>,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ExiftoolImportSummary {
+    pub photos_updated: usize,
+    pub tags_applied: usize,
+    pub unmatched_files: Vec<String>,
+}
+
+/// A photo whose saved metadata still carries privacy-sensitive tags.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PrivacyFinding {
+    pub path: PathBuf,
+    pub keys: Vec<String>,
+    /// The photo has unsaved edits, which may strip these tags once saved.
+    pub unsaved: bool,
+}
+
 #[derive(Debug)]
 pub enum AppError {
     InvalidPhotoIndex(usize),
     PresetNotFound(PresetId),
     NoSelection,
+    EmptyAllowlist,
+    UnrecognizedAllowlist(Vec<String>),
     Metadata(MetadataError),
 }
 
@@ -119,6 +186,10 @@ impl std::fmt::Display for AppError {
             Self::InvalidPhotoIndex(index) => write!(f, "invalid photo index: {index}"),
             Self::PresetNotFound(preset_id) => write!(f, "preset not found: {preset_id}"),
             Self::NoSelection => write!(f, "no photos selected"),
+            Self::EmptyAllowlist => write!(f, "allowlist is empty"),
+            Self::UnrecognizedAllowlist(tokens) => {
+                write!(f, "unrecognized allowlist entries: {}", tokens.join(", "))
+            }
             Self::Metadata(err) => write!(f, "metadata error: {err}"),
         }
     }
@@ -140,6 +211,194 @@ struct UndoEntry {
     dirty: bool,
 }
 
+/// Snapshots undone or redone together, restored newest first.
+type UndoBatch = Vec<UndoEntry>;
+
+/// A bulk run detached from [`AppState`] so it can execute on a worker thread.
+#[derive(Clone, Debug)]
+pub struct BulkJob {
+    pub preset: StripPreset,
+    pub output_mode: OutputMode,
+    photos: Vec<PhotoEntry>,
+    options: WriteOptions,
+    resume_log: Option<PathBuf>,
+    /// Skip photos the resume log already holds rather than starting it over.
+    resume: bool,
+}
+
+impl BulkJob {
+    pub fn total(&self) -> usize {
+        self.photos.len()
+    }
+
+    /// Record finished photos in a fresh log at `path`, replacing any left
+    /// by an earlier run; see [`BulkProcessor::process_with_log`].
+    pub fn with_resume_log(mut self, path: PathBuf) -> Self {
+        self.resume_log = Some(path);
+        self.resume = false;
+        self
+    }
+
+    /// Continue the interrupted run logged at `path`, skipping the photos it
+    /// finished that haven't changed since; see
+    /// [`BulkProcessor::process_with_resume`].
+    pub fn resuming_from(mut self, path: PathBuf) -> Self {
+        self.resume_log = Some(path);
+        self.resume = true;
+        self
+    }
+
+    pub fn run(
+        &self,
+        progress_tx: Sender<ProgressEvent>,
+        cancel_flag: Option<&AtomicBool>,
+    ) -> Vec<OperationResult> {
+        match &self.resume_log {
+            Some(log_path) if self.resume => BulkProcessor::process_with_resume(
+                &self.photos,
+                &self.preset,
+                &self.output_mode,
+                self.options,
+                progress_tx,
+                cancel_flag,
+                log_path,
+            ),
+            Some(log_path) => BulkProcessor::process_with_log(
+                &self.photos,
+                &self.preset,
+                &self.output_mode,
+                self.options,
+                progress_tx,
+                cancel_flag,
+                log_path,
+            ),
+            None => BulkProcessor::process_with_cancel(
+                &self.photos,
+                &self.preset,
+                &self.output_mode,
+                self.options,
+                progress_tx,
+                cancel_flag,
+            ),
+        }
+    }
+}
+
+/// Aggregate numbers over every loaded photo, shown in the session summary card.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SessionStats {
+    pub photo_count: usize,
+    /// Camera labels with how many photos each took, most used first.
+    pub cameras: Vec<(String, usize)>,
+    pub earliest_date: Option<String>,
+    pub latest_date: Option<String>,
+    pub gps_count: usize,
+    /// Sum of tag payload sizes; see [`TagValue::payload_len`].
+    pub metadata_bytes: u64,
+}
+
+impl SessionStats {
+    pub fn gps_percent(&self) -> f32 {
+        if self.photo_count == 0 {
+            return 0.0;
+        }
+        self.gps_count as f32 * 100.0 / self.photo_count as f32
+    }
+}
+
+/// Snapshot of the loaded photos for computing [`SessionStats`] off the UI thread.
+#[derive(Clone, Debug)]
+pub struct AnalyzeJob {
+    photos: Vec<PhotoEntry>,
+}
+
+impl AnalyzeJob {
+    pub fn total(&self) -> usize {
+        self.photos.len()
+    }
+
+    pub fn run(&self, progress_tx: Sender<ProgressEvent>) -> SessionStats {
+        let total = self.photos.len();
+        let mut cameras: Vec<(String, usize)> = Vec::new();
+        let mut dates = Vec::new();
+        let mut stats = SessionStats {
+            photo_count: total,
+            ..SessionStats::default()
+        };
+
+        for (index, photo) in self.photos.iter().enumerate() {
+            let metadata = &photo.metadata;
+            let camera = camera_label(photo);
+            if !camera.is_empty() {
+                match cameras.iter_mut().find(|(label, _)| *label == camera) {
+                    Some((_, count)) => *count += 1,
+                    None => cameras.push((camera, 1)),
+                }
+            }
+
+            // EXIF timestamps sort chronologically as plain strings.
+            if let Some(date) = metadata.date_taken.as_ref().filter(|date| !date.is_empty()) {
+                dates.push(date);
+            }
+
+            if metadata.has_gps {
+                stats.gps_count += 1;
+            }
+            stats.metadata_bytes += metadata
+                .all_tags()
+                .map(|tag| tag.value.payload_len() as u64)
+                .sum::<u64>();
+
+            let _ = progress_tx.send(ProgressEvent {
+                current: index + 1,
+                total,
+                filename: photo.filename.clone(),
+                success: true,
+            });
+        }
+
+        cameras.sort_by(|left, right| right.1.cmp(&left.1).then_with(|| left.0.cmp(&right.0)));
+        stats.cameras = cameras;
+        stats.earliest_date = dates.iter().min().map(|date| date.to_string());
+        stats.latest_date = dates.iter().max().map(|date| date.to_string());
+        stats
+    }
+}
+
+/// A single-photo save detached from [`AppState`] so slow writes (large TIFF/HEIC
+/// files) can run on a worker thread.
+#[derive(Clone, Debug)]
+pub struct SaveJob {
+    pub photo_id: PhotoId,
+    path: PathBuf,
+    metadata: PhotoMetadata,
+    dropped_empty_tags: usize,
+    write_sidecar: bool,
+    backup_original: bool,
+}
+
+impl SaveJob {
+    pub fn filename(&self) -> String {
+        self.path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default()
+    }
+
+    /// Empty-valued tags left out of the write because
+    /// [`AppState::drop_empty_tags_on_save`] was on.
+    pub fn dropped_empty_tags(&self) -> usize {
+        self.dropped_empty_tags
+    }
+
+    pub fn run(&self) -> Result<(), MetadataError> {
+        if self.backup_original {
+            MetadataEngine::backup_original(&self.path)?;
+        }
+        MetadataEngine::write_with_sidecars(&self.path, &self.metadata, self.write_sidecar)
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct AppState {
     pub photos: Vec<PhotoEntry>,
@@ -152,14 +411,28 @@ pub struct AppState {
     pub tag_filter: Option<TagCategory>,
     pub metadata_search_query: String,
     pub metadata_tab: MetadataTab,
+    pub modified_only: bool,
+    pub tag_sort: TagSort,
     pub table_sort: TableSort,
     pub bulk_output_mode: OutputMode,
+    /// Export into sub-folders that mirror the sources instead of one flat folder.
+    pub mirror_export_folders: bool,
+    /// Leave tags with an empty or whitespace-only value out of saved files.
+    pub drop_empty_tags_on_save: bool,
+    /// How imported tag sets treat tags a photo already has.
+    pub import_merge_strategy: MergeStrategy,
     pub active_preset: Option<PresetId>,
     pub is_processing: bool,
+    pub is_saving: bool,
     pub progress: Option<ProgressEvent>,
     pub operation_results: Vec<OperationResult>,
     pub last_summary: Option<OperationSummary>,
-    undo_stack: Vec<UndoEntry>,
+    pub session_stats: Option<SessionStats>,
+    pub settings: AppSettings,
+    undo_stack: Vec<UndoBatch>,
+    redo_stack: Vec<UndoBatch>,
+    /// Snapshots collected since [`AppState::begin_undo_group`], if a group is open.
+    undo_group: Option<UndoBatch>,
 }
 
 impl Default for AppState {
@@ -175,54 +448,150 @@ impl Default for AppState {
             tag_filter: None,
             metadata_search_query: String::new(),
             metadata_tab: MetadataTab::All,
+            modified_only: false,
+            tag_sort: TagSort::default(),
             table_sort: TableSort::default(),
             bulk_output_mode: OutputMode::Overwrite,
+            mirror_export_folders: false,
+            drop_empty_tags_on_save: false,
+            import_merge_strategy: MergeStrategy::default(),
             active_preset: None,
             is_processing: false,
+            is_saving: false,
             progress: None,
             operation_results: Vec::new(),
             last_summary: None,
+            session_stats: None,
+            settings: AppSettings::default(),
             undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            undo_group: None,
         }
     }
 }
 
 impl AppState {
-    pub fn import_paths<I, P>(&mut self, paths: I) -> Vec<PathBuf>
+    /// Adopt `settings` and reset the bulk output mode and active preset to
+    /// the defaults they name. A default preset that no longer exists leaves
+    /// no preset active.
+    pub fn apply_settings(&mut self, settings: AppSettings) {
+        self.bulk_output_mode = settings.default_output.output_mode();
+        self.active_preset = settings
+            .default_preset
+            .filter(|id| self.presets.iter().any(|preset| preset.id == *id));
+        self.settings = settings;
+    }
+
+    /// Adopt `settings` changed mid-session. The bulk output mode and active
+    /// preset are only reset when their defaults changed, so toggling an
+    /// unrelated setting keeps an export folder or preset picked this session.
+    pub fn change_settings(&mut self, settings: AppSettings) {
+        if settings.default_output != self.settings.default_output {
+            self.bulk_output_mode = settings.default_output.output_mode();
+        }
+        if settings.default_preset != self.settings.default_preset {
+            self.active_preset = settings
+                .default_preset
+                .filter(|id| self.presets.iter().any(|preset| preset.id == *id));
+        }
+        self.settings = settings;
+    }
+
+    fn sidecar_policy(&self) -> SidecarPolicy {
+        SidecarPolicy::for_sidecars(self.settings.write_sidecars)
+    }
+
+    /// Every loaded photo that has a sidecar, paired with whether that
+    /// sidecar is stale relative to the photo on disk.
+    pub fn audit_sidecars(&self) -> Vec<(PathBuf, bool)> {
+        self.photos
+            .iter()
+            .filter_map(|photo| {
+                MetadataEngine::sidecar_is_stale(&photo.path)
+                    .map(|stale| (photo.path.clone(), stale))
+            })
+            .collect()
+    }
+
+    /// Every loaded photo whose saved metadata still carries privacy-sensitive
+    /// tags (see [`catalog::is_privacy_sensitive`]). The saved copy is audited
+    /// rather than the session's, so an unsaved strip doesn't read as clean;
+    /// such photos are flagged instead. Photos with none are left out.
+    pub fn privacy_audit(&self) -> Vec<PrivacyFinding> {
+        self.photos
+            .iter()
+            .filter_map(|photo| {
+                let keys: Vec<String> = photo
+                    .persisted_metadata
+                    .all_tags()
+                    .filter(|tag| catalog::is_privacy_sensitive(tag))
+                    .map(|tag| tag.key.clone())
+                    .collect();
+                (!keys.is_empty()).then(|| PrivacyFinding {
+                    path: photo.path.clone(),
+                    keys,
+                    unsaved: photo.dirty,
+                })
+            })
+            .collect()
+    }
+
+    /// Load `paths` as photos. Returns every path that was left out, with why.
+    pub fn import_paths<I, P>(&mut self, paths: I) -> Vec<(PathBuf, SkipReason)>
     where
         I: IntoIterator<Item = P>,
         P: AsRef<Path>,
     {
         let mut skipped = Vec::new();
-        let mut next_id = self
+        let mut next_id = self.next_photo_id();
+        let strip_preset = self
+            .settings
+            .strip_on_import
+            .and_then(|id| self.preset_by_id(id))
+            .cloned();
+        let mut imported: HashSet<PathBuf> = self
             .photos
             .iter()
-            .map(|photo| photo.id)
-            .max()
-            .unwrap_or(0)
-            .saturating_add(1);
+            .map(|photo| canonical_key(&photo.path))
+            .collect();
 
         for candidate in paths {
             let path = candidate.as_ref();
 
             if !path.is_file() {
-                skipped.push(path.to_path_buf());
+                skipped.push((path.to_path_buf(), SkipReason::NotAFile));
                 continue;
             }
 
-            if self.photos.iter().any(|photo| photo.path == path) {
+            // Resolve symlinks, relative segments and (on case-insensitive
+            // filesystems) letter case so one file can't be imported twice.
+            // The canonical form is only the dedupe key: it turns into a `\\?\`
+            // path on Windows and `/private/var` on macOS, so the entry keeps
+            // the path it was given; writes resolve it themselves, so saving
+            // a symlink rewrites its target and leaves the link in place.
+            if !imported.insert(canonical_key(path)) {
+                skipped.push((path.to_path_buf(), SkipReason::Duplicate));
                 continue;
             }
 
             let format = formats::detect_format(path);
             if format.is_unknown() {
-                skipped.push(path.to_path_buf());
+                skipped.push((path.to_path_buf(), SkipReason::UnknownFormat));
                 continue;
             }
+            let path = path.to_path_buf();
 
-            let mut entry = PhotoEntry::from_path(next_id, path.to_path_buf(), format);
-            if let Ok(metadata) = MetadataEngine::read(path) {
+            let mut entry = PhotoEntry::from_path(next_id, path.clone(), format);
+            entry.dimensions = formats::read_dimensions(&path);
+            if let Ok((metadata, warnings)) =
+                MetadataEngine::read_with_warnings(&path, self.sidecar_policy())
+            {
                 entry.set_loaded_metadata(metadata);
+                entry.read_warnings = warnings;
+            }
+            if let Some(preset) = &strip_preset {
+                MetadataEngine::apply_preset_to_metadata(&mut entry.metadata, preset);
+                entry.recompute_dirty();
             }
 
             self.photos.push(entry);
@@ -237,6 +606,91 @@ impl AppState {
         skipped
     }
 
+    /// Import dropped files and folders, walking folders recursively. Hidden
+    /// entries and metadata sidecars found while walking are skipped, and the
+    /// counts are reported per top-level path.
+    pub fn import_tree<I, P>(&mut self, roots: I) -> ImportSummary
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<Path>,
+    {
+        let mut summary = ImportSummary::default();
+
+        for root in roots {
+            let root = root.as_ref();
+            let mut files = Vec::new();
+            let mut skipped_files = Vec::new();
+            collect_import_files(root, &mut files, &mut skipped_files);
+            let found = files.len() + skipped_files.len();
+
+            let before = self.photos.len();
+            skipped_files.extend(self.import_paths(files));
+            let imported = self.photos.len() - before;
+
+            summary.roots.push(ImportRootSummary {
+                root: root.to_path_buf(),
+                found,
+                imported,
+                skipped: skipped_files.len(),
+                skipped_files,
+            });
+        }
+
+        summary
+    }
+
+    /// Append a copy of a photo, with its own id and an independent copy of its
+    /// metadata, so alternative edits can be tried side by side. Both entries
+    /// still point at the same file: saving either one overwrites it, so export
+    /// a copy to keep both versions. Returns the index of the copy.
+    pub fn duplicate_photo(&mut self, photo_index: usize) -> Result<usize, AppError> {
+        let mut copy = self
+            .photos
+            .get(photo_index)
+            .ok_or(AppError::InvalidPhotoIndex(photo_index))?
+            .clone();
+        copy.id = self.next_photo_id();
+        copy.selected = false;
+
+        self.photos.push(copy);
+        Ok(self.photos.len() - 1)
+    }
+
+    /// Where the photo with `id` sits in [`Self::photos`], if it is still loaded.
+    pub fn photo_index(&self, id: PhotoId) -> Option<usize> {
+        self.photos.iter().position(|photo| photo.id == id)
+    }
+
+    fn next_photo_id(&self) -> PhotoId {
+        self.photos
+            .iter()
+            .map(|photo| photo.id)
+            .max()
+            .unwrap_or(0)
+            .saturating_add(1)
+    }
+
+    /// Register a user-built preset alongside the built-ins. Returns its id.
+    pub fn add_custom_preset(&mut self, name: &str, rules: Vec<PresetRule>) -> PresetId {
+        let id = self
+            .presets
+            .iter()
+            .map(|preset| preset.id)
+            .max()
+            .unwrap_or(0)
+            .saturating_add(1);
+        let description = format!("{} custom rule(s)", rules.len());
+        self.presets.push(StripPreset::new(
+            id,
+            name,
+            description,
+            "settings",
+            rules,
+            false,
+        ));
+        id
+    }
+
     pub fn set_view_mode(&mut self, view_mode: ViewMode) {
         self.view_mode = view_mode;
     }
@@ -257,6 +711,14 @@ impl AppState {
         self.metadata_tab = tab;
     }
 
+    pub fn set_modified_only(&mut self, modified_only: bool) {
+        self.modified_only = modified_only;
+    }
+
+    pub fn set_tag_sort(&mut self, sort: TagSort) {
+        self.tag_sort = sort;
+    }
+
     pub fn set_table_sort(&mut self, sort: TableSort) {
         self.table_sort = sort;
     }
@@ -384,6 +846,15 @@ impl AppState {
                     .total_tag_count()
                     .cmp(&right_photo.metadata.total_tag_count()),
                 TableColumn::FileSize => left_photo.file_size.cmp(&right_photo.file_size),
+                TableColumn::Dimensions => left_photo
+                    .pixel_dimensions()
+                    .map(|dimensions| dimensions.pixel_count())
+                    .cmp(
+                        &right_photo
+                            .pixel_dimensions()
+                            .map(|dimensions| dimensions.pixel_count()),
+                    ),
+                TableColumn::Format => left_photo.format.as_str().cmp(right_photo.format.as_str()),
             };
 
             let adjusted = if self.table_sort.descending {
@@ -405,6 +876,28 @@ impl AppState {
         indices
     }
 
+    /// Every tag in any loaded photo whose value contains `query`, ignoring
+    /// ASCII case, paired with the id of the photo holding it. A blank query
+    /// matches nothing.
+    pub fn find_tag_occurrences(&self, query: &str) -> Vec<(PhotoId, MetadataTag)> {
+        let query = query.trim().to_ascii_lowercase();
+        if query.is_empty() {
+            return Vec::new();
+        }
+
+        let query = &query;
+        self.photos
+            .iter()
+            .flat_map(|photo| {
+                photo
+                    .metadata
+                    .all_tags()
+                    .filter(move |tag| tag.value.to_string().to_ascii_lowercase().contains(query))
+                    .map(move |tag| (photo.id, tag.clone()))
+            })
+            .collect()
+    }
+
     pub fn inspector_tags(&self, photo_index: usize) -> Vec<MetadataTag> {
         let Some(photo) = self.photos.get(photo_index) else {
             return Vec::new();
@@ -423,6 +916,10 @@ impl AppState {
         let filter = self.tag_filter;
 
         tags.retain(|tag| {
+            if self.modified_only && !photo.is_tag_modified(&tag.key) {
+                return false;
+            }
+
             if !query.is_empty() {
                 let key = tag.key.to_ascii_lowercase();
                 let name = tag.display_name.to_ascii_lowercase();
@@ -439,7 +936,7 @@ impl AppState {
             true
         });
 
-        tags.sort_by(|left, right| {
+        let by_name = |left: &MetadataTag, right: &MetadataTag| {
             left.display_name
                 .to_ascii_lowercase()
                 .cmp(&right.display_name.to_ascii_lowercase())
@@ -448,7 +945,18 @@ impl AppState {
                         .to_ascii_lowercase()
                         .cmp(&right.key.to_ascii_lowercase())
                 })
-        });
+        };
+        let category_rank =
+            |category: TagCategory| TagCategory::ALL.iter().position(|&c| c == category);
+        match self.tag_sort {
+            TagSort::Alphabetical => tags.sort_by(by_name),
+            TagSort::ByCategory => tags.sort_by(|left, right| {
+                category_rank(left.category)
+                    .cmp(&category_rank(right.category))
+                    .then_with(|| by_name(left, right))
+            }),
+            TagSort::FileOrder => tags.sort_by_key(|tag| tag.order),
+        }
 
         tags
     }
@@ -459,6 +967,11 @@ impl AppState {
         tag_key: &str,
         value: TagValue,
     ) -> Result<(), AppError> {
+        let photo = self
+            .photos
+            .get(photo_index)
+            .ok_or(AppError::InvalidPhotoIndex(photo_index))?;
+        MetadataEngine::check_tag_edit(&photo.metadata, tag_key, &value)?;
         self.push_undo_snapshot(photo_index)?;
 
         let photo = self
@@ -543,19 +1056,357 @@ impl AppState {
         Ok(())
     }
 
-    pub fn save_photo_changes(&mut self, photo_index: usize) -> Result<(), AppError> {
+    pub fn apply_template(
+        &mut self,
+        template_index: usize,
+        target_indices: &[usize],
+    ) -> Result<TemplateSummary, AppError> {
+        let template = self
+            .photos
+            .get(template_index)
+            .ok_or(AppError::InvalidPhotoIndex(template_index))?;
+
+        let (blocked, allowed): (Vec<MetadataTag>, Vec<MetadataTag>) = template
+            .metadata
+            .all_tags()
+            .cloned()
+            .partition(|tag| self.settings.template_blocklist.blocks(tag));
+
+        let mut summary = TemplateSummary {
+            skipped_keys: blocked.into_iter().map(|tag| tag.key).collect(),
+            ..TemplateSummary::default()
+        };
+
+        self.check_photo_indices(target_indices)?;
+        for &index in target_indices {
+            if index == template_index {
+                continue;
+            }
+
+            self.push_undo_snapshot(index)?;
+
+            let photo = &mut self.photos[index];
+
+            for tag in &allowed {
+                MetadataEngine::set_tag_in_metadata(
+                    &mut photo.metadata,
+                    &tag.key,
+                    tag.value.clone(),
+                );
+            }
+            photo.recompute_dirty();
+
+            summary.photos_updated += 1;
+            summary.tags_applied += allowed.len();
+        }
+
+        Ok(summary)
+    }
+
+    /// Remove from each target every tag whose key the profile photo doesn't
+    /// carry, so the targets end up with (at most) the profile's tag set.
+    /// Returns how many tags were removed in total.
+    pub fn conform_to_profile(
+        &mut self,
+        profile_index: usize,
+        target_indices: &[usize],
+    ) -> Result<usize, AppError> {
+        let profile_keys = self
+            .photos
+            .get(profile_index)
+            .ok_or(AppError::InvalidPhotoIndex(profile_index))?
+            .metadata
+            .all_tags()
+            .map(|tag| tag.key.to_ascii_lowercase())
+            .collect::<HashSet<_>>();
+
+        self.check_photo_indices(target_indices)?;
+        let mut removed = 0;
+        for &index in target_indices {
+            if index == profile_index {
+                continue;
+            }
+
+            let extra_keys = self.photos[index]
+                .metadata
+                .all_tags()
+                .filter(|tag| !profile_keys.contains(&tag.key.to_ascii_lowercase()))
+                .map(|tag| tag.key.clone())
+                .collect::<Vec<_>>();
+            if extra_keys.is_empty() {
+                continue;
+            }
+
+            self.push_undo_snapshot(index)?;
+            let photo = &mut self.photos[index];
+            removed += MetadataEngine::remove_tags_by_key(&mut photo.metadata, &extra_keys);
+            photo.recompute_dirty();
+        }
+
+        Ok(removed)
+    }
+
+    /// Fail on the first of `indices` that is out of range, so an operation
+    /// across several photos can check them all before changing any.
+    fn check_photo_indices(&self, indices: &[usize]) -> Result<(), AppError> {
+        match indices.iter().find(|&&index| index >= self.photos.len()) {
+            Some(&index) => Err(AppError::InvalidPhotoIndex(index)),
+            None => Ok(()),
+        }
+    }
+
+    /// Set `Exif.Photo.ColorSpace` to `value` (see [`COLOR_SPACE_SRGB`]) on each
+    /// photo in `selection`. Photos already at `value` and indices out of range
+    /// are skipped. Returns how many photos changed.
+    ///
+    /// [`COLOR_SPACE_SRGB`]: crate::models::COLOR_SPACE_SRGB
+    pub fn bulk_set_color_space(&mut self, value: i64, selection: &[usize]) -> usize {
+        let target = TagValue::Integer(value);
+        let mut changed = 0;
+        for &index in selection {
+            let Some(photo) = self.photos.get(index) else {
+                continue;
+            };
+            if photo
+                .metadata
+                .find_tag(COLOR_SPACE_KEY)
+                .is_some_and(|tag| tag.value == target)
+            {
+                continue;
+            }
+
+            if self
+                .edit_tag(index, COLOR_SPACE_KEY, target.clone())
+                .is_ok()
+            {
+                changed += 1;
+            }
+        }
+        changed
+    }
+
+    /// Set the year in the copyright notice of every photo in `selection` to
+    /// `year` (see [`contacts::replace_copyright_year`]). Notices without a
+    /// year, or already at `year`, are left alone. Returns how many photos
+    /// changed.
+    pub fn bulk_set_copyright_year(&mut self, year: u32, selection: &[usize]) -> usize {
+        let mut changed = 0;
+        for &index in selection {
+            let Some(tag) = self
+                .photos
+                .get(index)
+                .and_then(|photo| photo.metadata.find_tag(contacts::COPYRIGHT_KEY))
+            else {
+                continue;
+            };
+            let TagValue::Text(notice) = &tag.value else {
+                continue;
+            };
+            let Some(updated) = contacts::replace_copyright_year(notice, year) else {
+                continue;
+            };
+            if updated == *notice {
+                continue;
+            }
+
+            if self
+                .edit_tag(index, contacts::COPYRIGHT_KEY, TagValue::Text(updated))
+                .is_ok()
+            {
+                changed += 1;
+            }
+        }
+        changed
+    }
+
+    /// Replace `find` with `replace` in the `key` tag of every selected photo.
+    /// Only text-like values (text, datetime, unknown) are touched; each change
+    /// goes through [`Self::edit_tag`] so it can be undone. Returns how many
+    /// photos changed.
+    pub fn replace_in_tag(
+        &mut self,
+        key: &str,
+        find: &str,
+        replace: &str,
+        case_insensitive: bool,
+    ) -> usize {
+        if find.is_empty() {
+            return 0;
+        }
+
+        let mut changed = 0;
+        for index in self.selected_indices_sorted() {
+            let Some(tag) = self
+                .photos
+                .get(index)
+                .and_then(|photo| photo.metadata.find_tag(key))
+            else {
+                continue;
+            };
+            let replaced = match &tag.value {
+                TagValue::Text(text) => {
+                    TagValue::Text(replace_text(text, find, replace, case_insensitive))
+                }
+                TagValue::DateTime(text) => {
+                    TagValue::DateTime(replace_text(text, find, replace, case_insensitive))
+                }
+                TagValue::Unknown(text) => {
+                    TagValue::Unknown(replace_text(text, find, replace, case_insensitive))
+                }
+                TagValue::TextList(items) => TagValue::TextList(
+                    items
+                        .iter()
+                        .map(|item| replace_text(item, find, replace, case_insensitive))
+                        .collect(),
+                ),
+                _ => continue,
+            };
+            if replaced == tag.value {
+                continue;
+            }
+
+            if self.edit_tag(index, key, replaced).is_ok() {
+                changed += 1;
+            }
+        }
+        changed
+    }
+
+    /// Downscaled thumbnail for a photo, decoding the original only the first
+    /// time and again after [`Self::reload_photo_from_disk`] finds it changed.
+    pub fn thumbnail_for(&mut self, photo_index: usize) -> Option<&ThumbnailData> {
+        let photo = self.photos.get_mut(photo_index)?;
+        if photo.needs_thumbnail() {
+            photo.set_thumbnail(thumbnail::decode_thumbnail(&photo.path, THUMBNAIL_EDGE));
+        }
+        photo.thumbnail.as_ref()
+    }
+
+    pub fn orientation_mismatches(&self) -> Vec<usize> {
+        self.photos
+            .iter()
+            .enumerate()
+            .filter(|(_, photo)| photo.has_orientation_mismatch())
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Reset the orientation tag to upright so it agrees with the stored pixels.
+    pub fn fix_orientation_mismatch(&mut self, photo_index: usize) -> Result<bool, AppError> {
         let photo = self
             .photos
-            .get_mut(photo_index)
+            .get(photo_index)
             .ok_or(AppError::InvalidPhotoIndex(photo_index))?;
 
-        MetadataEngine::write(&photo.path, &photo.metadata)?;
-        photo.persisted_metadata = photo.metadata.clone();
-        photo.dirty = false;
-        Ok(())
+        if !photo.has_orientation_mismatch() {
+            return Ok(false);
+        }
+
+        self.edit_tag(photo_index, ORIENTATION_KEY, TagValue::Integer(1))?;
+        Ok(true)
+    }
+
+    /// Apply tags from an `exiftool -json` dump to loaded photos whose file name
+    /// matches the record's `SourceFile`, merged by [`Self::import_merge_strategy`].
+    /// Each updated photo gets one undo entry.
+    pub fn import_exiftool_json(&mut self, path: &Path) -> Result<ExiftoolImportSummary, AppError> {
+        let records = exiftool::read_exiftool_json(path)?;
+        let mut summary = ExiftoolImportSummary::default();
+
+        for record in records {
+            let file_name = record.file_name();
+            let matches = self
+                .photos
+                .iter()
+                .enumerate()
+                .filter(|(_, photo)| photo.filename.eq_ignore_ascii_case(file_name))
+                .map(|(index, _)| index)
+                .collect::<Vec<_>>();
+
+            if matches.is_empty() {
+                summary.unmatched_files.push(record.source_file.clone());
+                continue;
+            }
+
+            for index in matches {
+                self.push_undo_snapshot(index)?;
+                let photo = &mut self.photos[index];
+                let applied = MetadataEngine::merge_metadata(
+                    &mut photo.metadata,
+                    &record.tags,
+                    self.import_merge_strategy,
+                );
+                photo.recompute_dirty();
+
+                summary.photos_updated += 1;
+                summary.tags_applied += applied;
+            }
+        }
+
+        Ok(summary)
+    }
+
+    /// Save one photo. Returns how many empty tags were dropped on the way out.
+    pub fn save_photo_changes(&mut self, photo_index: usize) -> Result<usize, AppError> {
+        let job = self.begin_save(photo_index)?;
+        let result = job.run();
+        self.finish_save(job, result)
+    }
+
+    /// Snapshot a photo's metadata into a [`SaveJob`]. `is_saving` stays set until
+    /// the job is handed back to [`Self::finish_save`].
+    pub fn begin_save(&mut self, photo_index: usize) -> Result<SaveJob, AppError> {
+        let photo = self
+            .photos
+            .get(photo_index)
+            .ok_or(AppError::InvalidPhotoIndex(photo_index))?;
+
+        let mut metadata = photo.metadata.clone();
+        let dropped_empty_tags = if self.drop_empty_tags_on_save {
+            MetadataEngine::remove_empty_tags(&mut metadata)
+        } else {
+            0
+        };
+
+        self.is_saving = true;
+        Ok(SaveJob {
+            photo_id: photo.id,
+            path: photo.path.clone(),
+            metadata,
+            dropped_empty_tags,
+            write_sidecar: self.settings.write_sidecars,
+            backup_original: self.settings.backup_originals,
+        })
     }
 
-    pub fn save_all_dirty(&mut self) -> Result<usize, AppError> {
+    /// Record the outcome of a [`SaveJob`]. Edits made while the write was running
+    /// leave the photo dirty, since only the snapshot reached the disk.
+    pub fn finish_save(
+        &mut self,
+        job: SaveJob,
+        result: Result<(), MetadataError>,
+    ) -> Result<usize, AppError> {
+        self.is_saving = false;
+        result?;
+
+        // The photo list may have changed while the write ran, so find the
+        // photo again by id.
+        if let Some(photo) = self
+            .photo_index(job.photo_id)
+            .and_then(|index| self.photos.get_mut(index))
+        {
+            if job.dropped_empty_tags > 0 {
+                MetadataEngine::remove_empty_tags(&mut photo.metadata);
+            }
+            photo.persisted_metadata = job.metadata;
+            photo.recompute_dirty();
+        }
+        Ok(job.dropped_empty_tags)
+    }
+
+    /// Save every dirty photo. Returns the number saved and the number of empty
+    /// tags dropped across them.
+    pub fn save_all_dirty(&mut self) -> Result<(usize, usize), AppError> {
         let dirty_indices = self
             .photos
             .iter()
@@ -563,11 +1414,12 @@ impl AppState {
             .filter_map(|(index, photo)| if photo.dirty { Some(index) } else { None })
             .collect::<Vec<_>>();
 
+        let mut dropped = 0;
         for index in &dirty_indices {
-            self.save_photo_changes(*index)?;
+            dropped += self.save_photo_changes(*index)?;
         }
 
-        Ok(dirty_indices.len())
+        Ok((dirty_indices.len(), dropped))
     }
 
     pub fn clear_all_metadata(&mut self) -> usize {
@@ -582,6 +1434,11 @@ impl AppState {
         count
     }
 
+    /// Number of photos with unsaved edits.
+    pub fn unsaved_count(&self) -> usize {
+        self.photos.iter().filter(|photo| photo.dirty).count()
+    }
+
     pub fn revert_photo(&mut self, photo_index: usize) -> Result<(), AppError> {
         self.push_undo_snapshot(photo_index)?;
 
@@ -595,29 +1452,77 @@ impl AppState {
         Ok(())
     }
 
+    /// Revert every dirty photo to its persisted metadata. Returns how many were reverted.
+    pub fn revert_all_dirty(&mut self) -> usize {
+        let dirty_indices = self
+            .photos
+            .iter()
+            .enumerate()
+            .filter(|(_, photo)| photo.dirty)
+            .map(|(index, _)| index)
+            .collect::<Vec<_>>();
+
+        dirty_indices
+            .into_iter()
+            .filter(|&index| self.revert_photo(index).is_ok())
+            .count()
+    }
+
+    /// Start collecting every change into one undo step, until
+    /// [`Self::end_undo_group`]. Beginning a group while one is open is a no-op.
+    pub fn begin_undo_group(&mut self) {
+        self.undo_group.get_or_insert_with(Vec::new);
+    }
+
+    /// Close the open undo group. Returns whether it recorded any change; an
+    /// empty group leaves the undo history untouched.
+    pub fn end_undo_group(&mut self) -> bool {
+        match self.undo_group.take() {
+            Some(batch) if !batch.is_empty() => {
+                self.undo_stack.push(batch);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Undo the most recent change, or the whole of the most recent undo
+    /// group. Undoing closes any group still open.
     pub fn undo_last_change(&mut self) -> bool {
-        let Some(entry) = self.undo_stack.pop() else {
+        self.end_undo_group();
+        let Some(batch) = self.undo_stack.pop() else {
             return false;
         };
 
-        let Some(photo) = self.photos.get_mut(entry.index) else {
+        let reverted = self.restore_batch(batch);
+        self.redo_stack.push(reverted);
+        true
+    }
+
+    /// Re-apply the change most recently reverted by [`Self::undo_last_change`].
+    /// Any new edit clears the redo history.
+    pub fn redo_last_change(&mut self) -> bool {
+        self.end_undo_group();
+        let Some(batch) = self.redo_stack.pop() else {
             return false;
         };
 
-        photo.metadata = entry.metadata;
-        photo.persisted_metadata = entry.persisted_metadata;
-        photo.dirty = entry.dirty;
+        let reapplied = self.restore_batch(batch);
+        self.undo_stack.push(reapplied);
         true
     }
 
     pub fn reload_photo_from_disk(&mut self, photo_index: usize) -> Result<(), AppError> {
+        let policy = self.sidecar_policy();
         let photo = self
             .photos
             .get_mut(photo_index)
             .ok_or(AppError::InvalidPhotoIndex(photo_index))?;
 
-        let metadata = MetadataEngine::read(&photo.path)?;
+        let (metadata, warnings) = MetadataEngine::read_with_warnings(&photo.path, policy)?;
         photo.set_loaded_metadata(metadata);
+        photo.read_warnings = warnings;
+        photo.refresh_file_stats();
         Ok(())
     }
 
@@ -627,50 +1532,250 @@ impl AppState {
         output_mode: OutputMode,
         cancel_flag: Option<&AtomicBool>,
     ) -> Result<OperationSummary, AppError> {
+        let job = self.begin_bulk_selected(preset_id, output_mode)?;
+        Ok(self.run_job_inline(job, cancel_flag))
+    }
+
+    /// Strip everything except the comma-separated tag keys/categories in `allowlist`
+    /// from the selection. Any token that is neither is rejected before touching files.
+    pub fn run_bulk_allowlist(
+        &mut self,
+        allowlist: &str,
+        output_mode: OutputMode,
+        cancel_flag: Option<&AtomicBool>,
+    ) -> Result<OperationSummary, AppError> {
+        let job = self.begin_bulk_allowlist(allowlist, output_mode)?;
+        Ok(self.run_job_inline(job, cancel_flag))
+    }
+
+    /// Compute [`SessionStats`] over every loaded photo and keep them for the summary card.
+    pub fn analyze_session(&mut self) -> SessionStats {
+        let (progress_tx, _progress_rx) = mpsc::channel();
+        let stats = self.begin_analyze().run(progress_tx);
+        self.finish_analyze(stats.clone());
+        stats
+    }
+
+    pub fn begin_analyze(&self) -> AnalyzeJob {
+        AnalyzeJob {
+            photos: self.photos.clone(),
+        }
+    }
+
+    pub fn finish_analyze(&mut self, stats: SessionStats) {
+        self.session_stats = Some(stats);
+    }
+
+    /// Copy every loaded photo into `dir` with its current metadata, mirroring
+    /// source folders when `mirror_export_folders` is set. Exports that had to be
+    /// renamed to avoid clobbering another file are listed in the summary.
+    pub fn export_all(&self, dir: &Path) -> ExportSummary {
+        let common_ancestor = if self.mirror_export_folders {
+            BulkProcessor::common_ancestor(self.photos.iter().map(|photo| photo.path.as_path()))
+        } else {
+            None
+        };
+
+        let mut summary = ExportSummary::default();
+        for photo in &self.photos {
+            let target_dir = match &common_ancestor {
+                Some(ancestor) => {
+                    let mirrored = BulkProcessor::relative_export_path(dir, ancestor, &photo.path);
+                    let target_dir = mirrored.parent().unwrap_or(dir).to_path_buf();
+                    if std::fs::create_dir_all(&target_dir).is_err() {
+                        summary.failed += 1;
+                        continue;
+                    }
+                    target_dir
+                }
+                None => dir.to_path_buf(),
+            };
+
+            let output_path =
+                BulkProcessor::unique_export_path(&target_dir, &photo.filename, "_export");
+            let exported = MetadataEngine::export_with_metadata(
+                &photo.path,
+                &output_path,
+                &photo.metadata,
+                self.settings.write_sidecars,
+            );
+            if let Err(err) = exported {
+                log::error!("op=export path={} error={err}", photo.path.display());
+                summary.failed += 1;
+                continue;
+            }
+
+            summary.exported += 1;
+            let expected_name = BulkProcessor::export_file_name(&photo.filename, "_export");
+            if output_path.file_name() != Some(expected_name.as_ref()) {
+                log::warn!(
+                    "op=export path={} renamed_to={}",
+                    photo.path.display(),
+                    output_path.display()
+                );
+                summary.renamed.push(ExportRename {
+                    source: photo.path.clone(),
+                    output_path,
+                });
+            }
+        }
+
+        summary
+    }
+
+    /// Copy each selected photo into `dir` with `preset` applied to the copy's
+    /// metadata, named with the preset's suffix. The loaded photos, and the files
+    /// they came from, are left untouched.
+    pub fn export_selected_with_preset(
+        &mut self,
+        dir: &Path,
+        preset_id: PresetId,
+    ) -> Result<OperationSummary, AppError> {
+        let preset = self
+            .preset_by_id(preset_id)
+            .cloned()
+            .ok_or(AppError::PresetNotFound(preset_id))?;
+        let indices = self.selected_indices_sorted();
+        if indices.is_empty() {
+            return Err(AppError::NoSelection);
+        }
+
+        let suffix = preset.default_suffix();
+        let results = indices
+            .iter()
+            .filter_map(|&index| self.photos.get(index))
+            .map(|photo| {
+                let output_path = BulkProcessor::unique_export_path(dir, &photo.filename, &suffix);
+                let mut metadata = photo.metadata.clone();
+                MetadataEngine::apply_preset_to_metadata(&mut metadata, &preset);
+
+                let exported = MetadataEngine::export_with_metadata(
+                    &photo.path,
+                    &output_path,
+                    &metadata,
+                    self.settings.write_sidecars,
+                );
+                match exported {
+                    Ok(()) => OperationResult::success(photo.id, output_path),
+                    Err(err) => {
+                        log::error!(
+                            "op=export preset={} path={} error={err}",
+                            preset.name,
+                            photo.path.display()
+                        );
+                        OperationResult::failure(photo.id, output_path, err.to_string())
+                    }
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let summary = OperationSummary::from_results(indices.len(), &results);
+        self.operation_results = results;
+        self.last_summary = Some(summary.clone());
+        Ok(summary)
+    }
+
+    /// Snapshot the selection into a [`BulkJob`] that can be run off the UI thread.
+    /// `is_processing` stays set until the job is handed back to [`Self::finish_bulk`].
+    pub fn begin_bulk_selected(
+        &mut self,
+        preset_id: PresetId,
+        output_mode: OutputMode,
+    ) -> Result<BulkJob, AppError> {
         let preset = self
             .preset_by_id(preset_id)
             .cloned()
             .ok_or(AppError::PresetNotFound(preset_id))?;
 
-        let selected_indices = self.selected_indices_sorted();
-        if selected_indices.is_empty() {
+        self.begin_bulk_with_preset(preset, output_mode)
+    }
+
+    pub fn begin_bulk_allowlist(
+        &mut self,
+        allowlist: &str,
+        output_mode: OutputMode,
+    ) -> Result<BulkJob, AppError> {
+        let (entries, unrecognized) = presets::parse_allowlist(allowlist);
+        if !unrecognized.is_empty() {
+            return Err(AppError::UnrecognizedAllowlist(unrecognized));
+        }
+        if entries.is_empty() {
+            return Err(AppError::EmptyAllowlist);
+        }
+
+        self.begin_bulk_with_preset(presets::allowlist_preset(entries), output_mode)
+    }
+
+    /// Strip every tag from the selection and write no sidecars, removing any
+    /// an output already has, so the files carry no metadata at all.
+    pub fn begin_hard_clean(&mut self, output_mode: OutputMode) -> Result<BulkJob, AppError> {
+        let mut job = self.begin_bulk_with_preset(presets::hard_clean_preset(), output_mode)?;
+        job.options.sidecars = false;
+        Ok(job)
+    }
+
+    fn begin_bulk_with_preset(
+        &mut self,
+        preset: StripPreset,
+        output_mode: OutputMode,
+    ) -> Result<BulkJob, AppError> {
+        let indices = self.selected_indices_sorted();
+        if indices.is_empty() {
             return Err(AppError::NoSelection);
         }
 
-        let selected_photos = selected_indices
+        let photos = indices
             .iter()
             .filter_map(|&index| self.photos.get(index).cloned())
             .collect::<Vec<_>>();
 
-        let (progress_tx, progress_rx) = mpsc::channel();
-
         self.is_processing = true;
-        let results = BulkProcessor::process_with_cancel(
-            &selected_photos,
-            &preset,
-            &output_mode,
-            progress_tx,
-            cancel_flag,
-        );
+        self.progress = None;
+
+        Ok(BulkJob {
+            preset,
+            output_mode,
+            photos,
+            options: WriteOptions {
+                sidecars: self.settings.write_sidecars,
+                backup_originals: self.settings.backup_originals,
+            },
+            resume_log: None,
+            resume: false,
+        })
+    }
 
-        for event in progress_rx.try_iter() {
-            self.progress = Some(event);
+    /// Workers count a photo and report it in two steps, so events can
+    /// arrive out of order; the highest count seen is kept so progress never
+    /// moves backwards.
+    pub fn record_progress(&mut self, mut event: ProgressEvent) {
+        if let Some(progress) = &self.progress {
+            event.current = event.current.max(progress.current);
         }
+        self.progress = Some(event);
+    }
 
+    pub fn finish_bulk(&mut self, job: BulkJob, results: Vec<OperationResult>) -> OperationSummary {
         self.is_processing = false;
         self.operation_results = results.clone();
-        let summary = OperationSummary::from_results(selected_photos.len(), &results);
+        let summary = OperationSummary::from_results(job.photos.len(), &results);
         self.last_summary = Some(summary.clone());
-        self.bulk_output_mode = output_mode.clone();
-        self.active_preset = Some(preset_id);
+        self.bulk_output_mode = job.output_mode.clone();
+        if self.preset_by_id(job.preset.id).is_some() {
+            self.active_preset = Some(job.preset.id);
+        }
 
-        match output_mode {
+        match job.output_mode {
             OutputMode::Overwrite => {
-                for index in selected_indices {
-                    let _ = self.reload_photo_from_disk(index);
+                // Photos may have been removed or reordered while the job ran,
+                // so find each one again by id.
+                for photo in &job.photos {
+                    if let Some(index) = self.photo_index(photo.id) {
+                        let _ = self.reload_photo_from_disk(index);
+                    }
                 }
             }
-            OutputMode::ExportTo(_) | OutputMode::Suffix(_) => {
+            OutputMode::ExportTo(_) | OutputMode::Suffix(_) | OutputMode::PresetSuffix => {
                 let output_paths = results
                     .iter()
                     .filter(|result| result.success)
@@ -680,23 +1785,66 @@ impl AppState {
             }
         }
 
-        Ok(summary)
+        summary
+    }
+
+    fn run_job_inline(
+        &mut self,
+        job: BulkJob,
+        cancel_flag: Option<&AtomicBool>,
+    ) -> OperationSummary {
+        let (progress_tx, progress_rx) = mpsc::channel();
+        let results = job.run(progress_tx, cancel_flag);
+
+        for event in progress_rx.try_iter() {
+            self.record_progress(event);
+        }
+
+        self.finish_bulk(job, results)
     }
 
     fn push_undo_snapshot(&mut self, photo_index: usize) -> Result<(), AppError> {
-        let photo = self
-            .photos
-            .get(photo_index)
+        let entry = self
+            .snapshot(photo_index)
             .ok_or(AppError::InvalidPhotoIndex(photo_index))?;
 
-        self.undo_stack.push(UndoEntry {
+        match &mut self.undo_group {
+            Some(group) => group.push(entry),
+            None => self.undo_stack.push(vec![entry]),
+        }
+        self.redo_stack.clear();
+        Ok(())
+    }
+
+    fn snapshot(&self, photo_index: usize) -> Option<UndoEntry> {
+        let photo = self.photos.get(photo_index)?;
+        Some(UndoEntry {
             index: photo_index,
             metadata: photo.metadata.clone(),
             persisted_metadata: photo.persisted_metadata.clone(),
             dirty: photo.dirty,
-        });
+        })
+    }
 
-        Ok(())
+    /// Restore a batch newest snapshot first, returning the snapshots that
+    /// undo the restore, in the same order.
+    fn restore_batch(&mut self, batch: UndoBatch) -> UndoBatch {
+        let mut replaced = Vec::with_capacity(batch.len());
+        for entry in batch.into_iter().rev() {
+            if let Some(current) = self.snapshot(entry.index) {
+                replaced.push(current);
+                self.restore_snapshot(entry);
+            }
+        }
+        replaced
+    }
+
+    fn restore_snapshot(&mut self, entry: UndoEntry) {
+        if let Some(photo) = self.photos.get_mut(entry.index) {
+            photo.metadata = entry.metadata;
+            photo.persisted_metadata = entry.persisted_metadata;
+            photo.dirty = entry.dirty;
+        }
     }
 
     fn selected_indices_sorted(&self) -> Vec<usize> {
@@ -739,6 +1887,59 @@ impl AppView {
     }
 }
 
+fn collect_import_files(
+    path: &Path,
+    files: &mut Vec<PathBuf>,
+    skipped: &mut Vec<(PathBuf, SkipReason)>,
+) {
+    if path.is_file() {
+        if MetadataEngine::is_sidecar(path) {
+            skipped.push((path.to_path_buf(), SkipReason::Sidecar));
+        } else if MetadataEngine::is_backup(path) {
+            skipped.push((path.to_path_buf(), SkipReason::Backup));
+        } else {
+            files.push(path.to_path_buf());
+        }
+        return;
+    }
+
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let entry_path = entry.path();
+        let hidden = entry.file_name().to_string_lossy().starts_with('.');
+        if hidden {
+            if entry_path.is_file() {
+                skipped.push((entry_path, SkipReason::Hidden));
+            }
+            continue;
+        }
+        collect_import_files(&entry_path, files, skipped);
+    }
+}
+
+/// `text` with every occurrence of `find` replaced. Case-insensitive matching
+/// only folds ASCII letters, which keeps byte offsets aligned with `text`.
+fn replace_text(text: &str, find: &str, replace: &str, case_insensitive: bool) -> String {
+    if !case_insensitive {
+        return text.replace(find, replace);
+    }
+
+    let haystack = text.to_ascii_lowercase();
+    let needle = find.to_ascii_lowercase();
+    let mut result = String::with_capacity(text.len());
+    let mut last = 0;
+    for (start, _) in haystack.match_indices(&needle) {
+        result.push_str(&text[last..start]);
+        result.push_str(replace);
+        last = start + needle.len();
+    }
+    result.push_str(&text[last..]);
+    result
+}
+
 fn photo_matches_query(photo: &PhotoEntry, query: &str) -> bool {
     if photo.filename.to_ascii_lowercase().contains(query) {
         return true;
@@ -771,3 +1972,9 @@ fn camera_label(photo: &PhotoEntry) -> String {
         (None, None) => String::new(),
     }
 }
+
+/// `path` with symlinks and relative segments resolved, or as given when it
+/// can't be resolved.
+fn canonical_key(path: &Path) -> PathBuf {
+    std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
@@ -0,0 +1,238 @@
+use std::fmt;
+use std::path::PathBuf;
+
+use crate::app::{AppError, AppState};
+use crate::core::presets;
+use crate::models::{OperationResult, OperationSummary, OutputMode, PresetId, SkipReason};
+use crate::settings::AppSettings;
+
+/// The subcommand that runs headless; anything else opens the editor window.
+pub const COMMAND: &str = "strip";
+
+pub const USAGE: &str = "\
+Usage: exif_editor strip [PRESET] [OUTPUT] [--sidecars] <FILES>...
+
+Strips metadata from FILES with a built-in preset, without opening a window.
+Without the strip command the editor window opens, with any FILES imported.
+
+Preset (default: --privacy):
+  --strip-all          Remove every metadata tag
+  --strip-gps          Remove only location metadata
+  --privacy            Remove GPS, serial numbers, and software tags
+  --preset <NAME>      Any built-in preset by name, e.g. \"keep-basics\"
+
+Output (default: a copy next to each file, suffixed with the preset name):
+  --out <DIR>          Write stripped copies into DIR
+  --suffix <SUFFIX>    Write copies next to each file with SUFFIX added
+  --overwrite          Strip the files in place
+
+  --sidecars           Write a JSON sidecar next to each output
+  -h, --help           Show this help";
+
+/// How the program was asked to start.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Launch {
+    /// Run headless with the arguments after the strip command.
+    Headless(Vec<String>),
+    /// Open the editor window and import these files, as "Open with" does.
+    Window(Vec<PathBuf>),
+}
+
+impl Launch {
+    /// Pick a launch mode from the arguments, without the program name. A
+    /// bare help flag prints the usage rather than importing a file by that name.
+    pub fn from_args(mut args: Vec<String>) -> Self {
+        match args.first().map(String::as_str) {
+            Some(COMMAND) => {
+                args.remove(0);
+                Self::Headless(args)
+            }
+            Some("-h" | "--help") => Self::Headless(args),
+            _ => Self::Window(args.into_iter().map(PathBuf::from).collect()),
+        }
+    }
+}
+
+/// What a command line asks for, once parsed.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CliOptions {
+    pub preset_id: PresetId,
+    pub output_mode: OutputMode,
+    pub write_sidecars: bool,
+    pub files: Vec<PathBuf>,
+}
+
+/// The result of a headless run.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CliOutcome {
+    pub summary: OperationSummary,
+    pub results: Vec<OperationResult>,
+    /// Files the import left out, with why; they are not part of the summary.
+    pub skipped: Vec<(PathBuf, SkipReason)>,
+}
+
+#[derive(Debug)]
+pub enum CliError {
+    UnknownFlag(String),
+    MissingValue(&'static str),
+    UnknownPreset(String),
+    ConflictingOutput,
+    NoFiles,
+    App(AppError),
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownFlag(flag) => write!(f, "unknown option: {flag}"),
+            Self::MissingValue(flag) => write!(f, "{flag} needs a value"),
+            Self::UnknownPreset(name) => write!(f, "no built-in preset named {name}"),
+            Self::ConflictingOutput => {
+                write!(f, "--out, --suffix and --overwrite can't be combined")
+            }
+            Self::NoFiles => write!(f, "no image files to process"),
+            Self::App(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl std::error::Error for CliError {}
+
+impl From<AppError> for CliError {
+    fn from(value: AppError) -> Self {
+        Self::App(value)
+    }
+}
+
+/// Parse command-line arguments, without the program name. `Ok(None)` means
+/// help was asked for.
+pub fn parse_args<I, S>(args: I) -> Result<Option<CliOptions>, CliError>
+where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+{
+    let builtins = presets::builtin_presets();
+    let preset_named = |name: &str| {
+        presets::preset_by_name(&builtins, &name.replace(['-', '_'], " "))
+            .map(|preset| preset.id)
+            .ok_or_else(|| CliError::UnknownPreset(String::from(name)))
+    };
+
+    let mut preset_id = None;
+    let mut output_mode = None;
+    let mut write_sidecars = false;
+    let mut files = Vec::new();
+    let mut args = args.into_iter().map(Into::into);
+    while let Some(arg) = args.next() {
+        let mut set_output = |mode: OutputMode| match output_mode.replace(mode) {
+            Some(_) => Err(CliError::ConflictingOutput),
+            None => Ok(()),
+        };
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "--strip-all" => preset_id = Some(preset_named("Strip All")?),
+            "--strip-gps" => preset_id = Some(preset_named("GPS Only")?),
+            "--privacy" => preset_id = Some(preset_named("Privacy Clean")?),
+            "--preset" => {
+                let name = args.next().ok_or(CliError::MissingValue("--preset"))?;
+                preset_id = Some(preset_named(&name)?);
+            }
+            "--out" => {
+                let dir = args.next().ok_or(CliError::MissingValue("--out"))?;
+                set_output(OutputMode::ExportTo(PathBuf::from(dir)))?;
+            }
+            "--suffix" => {
+                let suffix = args.next().ok_or(CliError::MissingValue("--suffix"))?;
+                set_output(OutputMode::Suffix(suffix))?;
+            }
+            "--overwrite" => set_output(OutputMode::Overwrite)?,
+            "--sidecars" => write_sidecars = true,
+            flag if flag.starts_with('-') && flag.len() > 1 => {
+                return Err(CliError::UnknownFlag(arg));
+            }
+            _ => files.push(PathBuf::from(arg)),
+        }
+    }
+
+    if files.is_empty() {
+        return Err(CliError::NoFiles);
+    }
+    Ok(Some(CliOptions {
+        preset_id: match preset_id {
+            Some(id) => id,
+            None => preset_named("Privacy Clean")?,
+        },
+        output_mode: output_mode.unwrap_or(OutputMode::PresetSuffix),
+        write_sidecars,
+        files,
+    }))
+}
+
+/// Import `options.files` into a fresh session and run the bulk processor
+/// over all of them, the way the window's bulk action does.
+pub fn run(options: &CliOptions) -> Result<CliOutcome, CliError> {
+    let mut state = AppState::default();
+    state.apply_settings(AppSettings {
+        write_sidecars: options.write_sidecars,
+        ..AppSettings::default()
+    });
+    let skipped = state.import_paths(options.files.iter().cloned());
+    if state.photos.is_empty() {
+        return Err(CliError::NoFiles);
+    }
+
+    state.select_range(0, state.photos.len() - 1);
+    let summary = state.run_bulk_selected(options.preset_id, options.output_mode.clone(), None)?;
+    Ok(CliOutcome {
+        summary,
+        results: state.operation_results,
+        skipped,
+    })
+}
+
+/// Entry point for headless mode, which runs only behind the [`COMMAND`]
+/// (`strip`) command or a bare help flag; see [`Launch::from_args`]. Takes
+/// the arguments after the command, prints the outcome and returns the
+/// process exit code: 0 when every file was stripped, 1 when any failed, 2
+/// for a bad command line.
+pub fn main<I, S>(args: I) -> i32
+where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+{
+    let options = match parse_args(args) {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            println!("{USAGE}");
+            return 0;
+        }
+        Err(err) => {
+            eprintln!("error: {err}\n\n{USAGE}");
+            return 2;
+        }
+    };
+
+    let outcome = match run(&options) {
+        Ok(outcome) => outcome,
+        Err(err) => {
+            eprintln!("error: {err}");
+            return 1;
+        }
+    };
+    for (path, reason) in &outcome.skipped {
+        eprintln!("skipped {}: {}", path.display(), reason.label());
+    }
+    for result in &outcome.results {
+        match result.error_message() {
+            None => println!("{}", result.output_path().display()),
+            Some(error) => eprintln!("failed {}: {error}", result.output_path().display()),
+        }
+    }
+    println!("{}", outcome.summary);
+
+    if outcome.summary.is_complete_success() {
+        0
+    } else {
+        1
+    }
+}
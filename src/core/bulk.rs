@@ -1,13 +1,156 @@
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write as _;
 use std::path::{Path, PathBuf};
 use std::sync::{
     atomic::{AtomicBool, AtomicUsize, Ordering},
     mpsc::Sender,
+    Mutex,
 };
 
 use rayon::prelude::*;
 
-use crate::core::metadata::MetadataEngine;
+use crate::core::metadata::{FileStamp, MetadataEngine};
 use crate::models::{OperationResult, OutputMode, PhotoEntry, ProgressEvent, StripPreset};
+use crate::platform;
+
+/// Name of the resume log under the config directory.
+const RESUME_LOG_FILE_NAME: &str = "bulk_resume.log";
+
+/// Sources a bulk run has finished, kept on disk so an interrupted run can
+/// continue instead of starting over. The first line names the run (preset
+/// and output mode); each line after it is one finished source as
+/// `size<TAB>modified_ms<TAB>path`, the file's state right after it was
+/// processed. A log written for a different run is discarded when opened.
+pub struct ResumeLog {
+    path: PathBuf,
+    completed: HashMap<PathBuf, FileStamp>,
+    file: Mutex<Option<File>>,
+}
+
+impl ResumeLog {
+    pub fn default_path() -> PathBuf {
+        platform::config_dir().join(RESUME_LOG_FILE_NAME)
+    }
+
+    /// Open the log at `path` to resume the run named `run_key`. A log that
+    /// can't be written still works for this run, it just won't survive it.
+    pub fn open(path: &Path, run_key: &str) -> Self {
+        let contents = fs::read_to_string(path).unwrap_or_default();
+        let mut lines = contents.lines();
+        if lines.next() != Some(run_key) {
+            return Self::create(path, run_key);
+        }
+
+        let completed = lines.filter_map(parse_entry).collect();
+        let file = OpenOptions::new().append(true).open(path);
+        Self::with_file(path, completed, file)
+    }
+
+    /// Start a fresh log at `path` for the run named `run_key`, discarding
+    /// whatever an earlier run left there.
+    pub fn create(path: &Path, run_key: &str) -> Self {
+        if let Some(parent) = path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        let file = File::create(path).and_then(|mut file| {
+            writeln!(file, "{run_key}")?;
+            Ok(file)
+        });
+        Self::with_file(path, HashMap::new(), file)
+    }
+
+    fn with_file(
+        path: &Path,
+        completed: HashMap<PathBuf, FileStamp>,
+        file: std::io::Result<File>,
+    ) -> Self {
+        let file = match file {
+            Ok(file) => Some(file),
+            Err(err) => {
+                log::warn!("op=resume_log path={} error={err}", path.display());
+                None
+            }
+        };
+
+        Self {
+            path: path.to_path_buf(),
+            completed,
+            file: Mutex::new(file),
+        }
+    }
+
+    /// Whether `source` was finished and hasn't changed since. A file edited
+    /// or replaced after the interrupted run is processed again.
+    pub fn is_completed(&self, source: &Path) -> bool {
+        self.completed
+            .get(source)
+            .is_some_and(|stamp| FileStamp::of(source).as_ref() == Some(stamp))
+    }
+
+    pub fn completed_count(&self) -> usize {
+        self.completed.len()
+    }
+
+    /// Append `source` as finished. Written through at once so a crash loses
+    /// at most the photo in flight.
+    fn record(&self, source: &Path) {
+        let Some(stamp) = FileStamp::of(source) else {
+            return;
+        };
+        let Ok(mut file) = self.file.lock() else {
+            return;
+        };
+        if let Some(file) = file.as_mut() {
+            let line = format!(
+                "{}\t{}\t{}",
+                stamp.size,
+                stamp.modified_ms,
+                source.display()
+            );
+            if let Err(err) = writeln!(file, "{line}").and_then(|()| file.flush()) {
+                log::warn!("op=resume_log path={} error={err}", self.path.display());
+            }
+        }
+    }
+
+    /// Delete the log once its run has nothing left to resume.
+    fn remove(&self) {
+        if let Ok(mut file) = self.file.lock() {
+            file.take();
+        }
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// One `size<TAB>modified_ms<TAB>path` line of a [`ResumeLog`]. Lines from
+/// logs without file stamps are dropped, so those files are redone.
+fn parse_entry(line: &str) -> Option<(PathBuf, FileStamp)> {
+    let mut fields = line.splitn(3, '\t');
+    let size = fields.next()?.parse().ok()?;
+    let modified_ms = fields.next()?.parse().ok()?;
+    let path = fields.next().filter(|path| !path.is_empty())?;
+    Some((PathBuf::from(path), FileStamp { size, modified_ms }))
+}
+
+/// How a bulk run writes each output.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct WriteOptions {
+    /// Give each output a JSON sidecar; see [`MetadataEngine::write_with_sidecars`].
+    pub sidecars: bool,
+    /// Keep a `.orig` copy of a photo before it is overwritten in place; see
+    /// [`MetadataEngine::backup_original`].
+    pub backup_originals: bool,
+}
+
+impl WriteOptions {
+    pub fn sidecars(sidecars: bool) -> Self {
+        Self {
+            sidecars,
+            ..Self::default()
+        }
+    }
+}
 
 pub struct BulkProcessor;
 
@@ -18,35 +161,159 @@ impl BulkProcessor {
         output_mode: &OutputMode,
         progress_tx: Sender<ProgressEvent>,
     ) -> Vec<OperationResult> {
-        Self::process_with_cancel(photos, preset, output_mode, progress_tx, None)
+        Self::process_with_cancel(
+            photos,
+            preset,
+            output_mode,
+            WriteOptions::sidecars(true),
+            progress_tx,
+            None,
+        )
     }
 
     pub fn process_with_cancel(
         photos: &[PhotoEntry],
         preset: &StripPreset,
         output_mode: &OutputMode,
+        options: WriteOptions,
         progress_tx: Sender<ProgressEvent>,
         cancel_flag: Option<&AtomicBool>,
+    ) -> Vec<OperationResult> {
+        Self::run(
+            photos,
+            preset,
+            output_mode,
+            options,
+            progress_tx,
+            cancel_flag,
+            None,
+        )
+    }
+
+    /// Like [`Self::process_with_cancel`], recording finished photos in a
+    /// fresh resume log at `log_path` so the run can be resumed with
+    /// [`Self::process_with_resume`] if it is interrupted. Any log an earlier
+    /// run left there is discarded. The log is deleted once a run gets
+    /// through every photo without a failure.
+    pub fn process_with_log(
+        photos: &[PhotoEntry],
+        preset: &StripPreset,
+        output_mode: &OutputMode,
+        options: WriteOptions,
+        progress_tx: Sender<ProgressEvent>,
+        cancel_flag: Option<&AtomicBool>,
+        log_path: &Path,
+    ) -> Vec<OperationResult> {
+        let resume_log = ResumeLog::create(log_path, &Self::resume_key(preset, output_mode));
+        Self::run_logged(
+            photos,
+            preset,
+            output_mode,
+            options,
+            progress_tx,
+            cancel_flag,
+            &resume_log,
+        )
+    }
+
+    /// Like [`Self::process_with_log`], but keeps the log at `log_path` when
+    /// it was written for this preset and output mode, and skips the sources
+    /// it records that haven't changed since. Skipped photos report success
+    /// with their usual output path.
+    pub fn process_with_resume(
+        photos: &[PhotoEntry],
+        preset: &StripPreset,
+        output_mode: &OutputMode,
+        options: WriteOptions,
+        progress_tx: Sender<ProgressEvent>,
+        cancel_flag: Option<&AtomicBool>,
+        log_path: &Path,
+    ) -> Vec<OperationResult> {
+        let resume_log = ResumeLog::open(log_path, &Self::resume_key(preset, output_mode));
+        if resume_log.completed_count() > 0 {
+            log::info!(
+                "op=bulk_resume preset={} completed={}",
+                preset.name,
+                resume_log.completed_count()
+            );
+        }
+        Self::run_logged(
+            photos,
+            preset,
+            output_mode,
+            options,
+            progress_tx,
+            cancel_flag,
+            &resume_log,
+        )
+    }
+
+    fn run_logged(
+        photos: &[PhotoEntry],
+        preset: &StripPreset,
+        output_mode: &OutputMode,
+        options: WriteOptions,
+        progress_tx: Sender<ProgressEvent>,
+        cancel_flag: Option<&AtomicBool>,
+        resume_log: &ResumeLog,
+    ) -> Vec<OperationResult> {
+        let results = Self::run(
+            photos,
+            preset,
+            output_mode,
+            options,
+            progress_tx,
+            cancel_flag,
+            Some(resume_log),
+        );
+        if results.len() == photos.len() && results.iter().all(|result| result.success) {
+            resume_log.remove();
+        }
+        results
+    }
+
+    /// First line of a resume log, naming the run it belongs to. The rules are
+    /// part of it since an allowlist run reuses one preset id for any list.
+    fn resume_key(preset: &StripPreset, output_mode: &OutputMode) -> String {
+        format!(
+            "preset={} rules={:?} output={output_mode:?}",
+            preset.id, preset.rules
+        )
+    }
+
+    fn run(
+        photos: &[PhotoEntry],
+        preset: &StripPreset,
+        output_mode: &OutputMode,
+        options: WriteOptions,
+        progress_tx: Sender<ProgressEvent>,
+        cancel_flag: Option<&AtomicBool>,
+        resume_log: Option<&ResumeLog>,
     ) -> Vec<OperationResult> {
         let total = photos.len();
+        let output_mode = &output_mode.resolve(preset);
         let progress_counter = AtomicUsize::new(0);
 
         let mut indexed: Vec<(usize, OperationResult)> = photos
             .par_iter()
             .enumerate()
             .filter_map(|(index, photo)| {
-                if let Some(flag) = cancel_flag {
-                    if flag.load(Ordering::Relaxed) {
-                        return None;
-                    }
+                if is_cancelled(cancel_flag) {
+                    return None;
                 }
 
-                let output_path = Self::output_path(photo, output_mode);
-                let operation = MetadataEngine::apply_preset(&photo.path, preset, &output_path);
-
-                let result = match operation {
-                    Ok(_) => OperationResult::success(photo.id, output_path),
-                    Err(err) => OperationResult::failure(photo.id, output_path, err.to_string()),
+                let result = if resume_log.is_some_and(|log| log.is_completed(&photo.path)) {
+                    OperationResult::success(
+                        photo.id,
+                        Self::output_path(photo, preset, output_mode),
+                    )
+                } else {
+                    let result =
+                        Self::process_photo(photo, preset, output_mode, options, cancel_flag)?;
+                    if let (true, Some(log)) = (result.success, resume_log) {
+                        log.record(&photo.path);
+                    }
+                    result
                 };
 
                 let current = progress_counter.fetch_add(1, Ordering::Relaxed) + 1;
@@ -62,14 +329,175 @@ impl BulkProcessor {
             .collect();
 
         indexed.sort_by_key(|(index, _)| *index);
+        log::info!(
+            "op=bulk preset={} total={total} processed={}",
+            preset.name,
+            indexed.len()
+        );
         indexed.into_iter().map(|(_, result)| result).collect()
     }
 
-    pub fn output_path(photo: &PhotoEntry, output_mode: &OutputMode) -> PathBuf {
+    /// Strip one photo into its output. Exports are assembled in a staging
+    /// file (see [`MetadataEngine::stage_output`]) and only moved over the
+    /// output once whole, so a failed or cancelled photo leaves whatever was
+    /// at the output path before. Cancellation is checked again between the
+    /// copy and the metadata write; a photo interrupted there yields `None`,
+    /// like a photo that never started.
+    pub fn process_photo(
+        photo: &PhotoEntry,
+        preset: &StripPreset,
+        output_mode: &OutputMode,
+        options: WriteOptions,
+        cancel_flag: Option<&AtomicBool>,
+    ) -> Option<OperationResult> {
+        let output_path = Self::output_path(photo, preset, output_mode);
+        let backed_up = if options.backup_originals
+            && MetadataEngine::is_same_file(&output_path, &photo.path)
+        {
+            MetadataEngine::backup_original(&photo.path).map(|_| ())
+        } else {
+            Ok(())
+        };
+        let operation = backed_up
+            .and_then(|()| MetadataEngine::stage_output(&photo.path, &output_path))
+            .and_then(|staging| {
+                let written = if is_cancelled(cancel_flag) {
+                    Ok(None)
+                } else {
+                    let target = staging.as_deref().unwrap_or(&output_path);
+                    MetadataEngine::write_preset_output(
+                        &photo.path,
+                        preset,
+                        target,
+                        options.sidecars,
+                    )
+                    .and_then(|_| match &staging {
+                        Some(staging) => {
+                            MetadataEngine::finish_staged_output(staging, &output_path)
+                        }
+                        None => Ok(()),
+                    })
+                    .map(Some)
+                };
+                if let Some(staging) = staging.filter(|_| !matches!(written, Ok(Some(())))) {
+                    MetadataEngine::discard_staged_output(&staging);
+                }
+                written
+            });
+
+        match operation {
+            Ok(Some(())) => Some(OperationResult::success(photo.id, output_path)),
+            Ok(None) => None,
+            Err(err) => {
+                log::error!(
+                    "op=bulk preset={} path={} error={err}",
+                    preset.name,
+                    photo.path.display()
+                );
+                Some(OperationResult::failure(
+                    photo.id,
+                    output_path,
+                    err.to_string(),
+                ))
+            }
+        }
+    }
+
+    /// Where `photo`'s output goes. Exporting into the folder a photo already
+    /// lives in, however that folder is spelled, adds the preset suffix, so
+    /// the original is never overwritten.
+    pub fn output_path(
+        photo: &PhotoEntry,
+        preset: &StripPreset,
+        output_mode: &OutputMode,
+    ) -> PathBuf {
         match output_mode {
             OutputMode::Overwrite => photo.path.clone(),
-            OutputMode::ExportTo(dir) => dir.join(&photo.filename),
+            OutputMode::ExportTo(dir) => {
+                let target = dir.join(&photo.filename);
+                if MetadataEngine::is_same_file(&target, &photo.path) {
+                    Self::add_suffix(&target, &preset.default_suffix())
+                } else {
+                    target
+                }
+            }
             OutputMode::Suffix(suffix) => Self::add_suffix(&photo.path, suffix),
+            OutputMode::PresetSuffix => Self::add_suffix(&photo.path, &preset.default_suffix()),
+        }
+    }
+
+    /// Deepest directory that contains every file in `sources`.
+    pub fn common_ancestor<'a, I>(sources: I) -> Option<PathBuf>
+    where
+        I: IntoIterator<Item = &'a Path>,
+    {
+        let mut ancestor: Option<PathBuf> = None;
+        for source in sources {
+            let parent = source.parent()?;
+            ancestor = Some(match ancestor {
+                None => parent.to_path_buf(),
+                Some(current) => current
+                    .components()
+                    .zip(parent.components())
+                    .take_while(|(left, right)| left == right)
+                    .map(|(component, _)| component)
+                    .collect(),
+            });
+        }
+        ancestor
+    }
+
+    /// Mirror `source`'s location below `common_ancestor` under the export `root`.
+    /// Sources outside the ancestor fall back to `root` plus their file name.
+    pub fn relative_export_path(root: &Path, common_ancestor: &Path, source: &Path) -> PathBuf {
+        match source.strip_prefix(common_ancestor) {
+            Ok(relative) if !relative.as_os_str().is_empty() => root.join(relative),
+            _ => root.join(source.file_name().unwrap_or(source.as_os_str())),
+        }
+    }
+
+    /// First free `<stem><suffix>[_N].<ext>` path for `filename` inside `export_dir`.
+    pub fn unique_export_path(export_dir: &Path, filename: &str, suffix: &str) -> PathBuf {
+        let input_path = Path::new(filename);
+        let stem = input_path
+            .file_stem()
+            .map(|value| value.to_string_lossy().to_string())
+            .unwrap_or_else(|| String::from("photo"));
+        let extension = input_path
+            .extension()
+            .map(|value| value.to_string_lossy().to_string());
+
+        for attempt in 0..1000_usize {
+            let candidate_name = if attempt == 0 {
+                Self::export_file_name(filename, suffix)
+            } else {
+                match &extension {
+                    Some(ext) => format!("{stem}{suffix}_{attempt}.{ext}"),
+                    None => format!("{stem}{suffix}_{attempt}"),
+                }
+            };
+
+            let candidate = export_dir.join(candidate_name);
+            if !candidate.exists() {
+                return candidate;
+            }
+        }
+
+        export_dir.join(format!("{stem}{suffix}_overflow"))
+    }
+
+    /// The name [`Self::unique_export_path`] picks when nothing is in the way.
+    /// Any other name means the export was renamed to avoid a collision.
+    pub fn export_file_name(filename: &str, suffix: &str) -> String {
+        let input_path = Path::new(filename);
+        let stem = input_path
+            .file_stem()
+            .map(|value| value.to_string_lossy().to_string())
+            .unwrap_or_else(|| String::from("photo"));
+
+        match input_path.extension() {
+            Some(ext) => format!("{stem}{suffix}.{}", ext.to_string_lossy()),
+            None => format!("{stem}{suffix}"),
         }
     }
 
@@ -93,3 +521,7 @@ impl BulkProcessor {
         path.with_file_name(file_name)
     }
 }
+
+fn is_cancelled(cancel_flag: Option<&AtomicBool>) -> bool {
+    cancel_flag.is_some_and(|flag| flag.load(Ordering::Relaxed))
+}
@@ -0,0 +1,147 @@
+use crate::models::{MetadataTag, TagCategory, TagValue};
+
+pub struct TagDef {
+    pub key: &'static str,
+    pub display_name: &'static str,
+    pub category: TagCategory,
+    pub default_value: TagValue,
+}
+
+/// Tags the editor knows how to add, and the keys preset rules may name.
+#[rustfmt::skip]
+pub const TAG_CATALOG: &[TagDef] = &[
+    // Description / text tags
+    TagDef { key: "Exif.Image.ImageDescription", display_name: "Image Description", category: TagCategory::Description, default_value: TagValue::Text(String::new()) },
+    TagDef { key: "Exif.Image.Artist", display_name: "Artist", category: TagCategory::Description, default_value: TagValue::Text(String::new()) },
+    TagDef { key: "Exif.Image.Copyright", display_name: "Copyright", category: TagCategory::Description, default_value: TagValue::Text(String::new()) },
+    // Camera tags
+    TagDef { key: "Exif.Image.Make", display_name: "Make", category: TagCategory::Camera, default_value: TagValue::Text(String::new()) },
+    TagDef { key: "Exif.Image.Model", display_name: "Model", category: TagCategory::Camera, default_value: TagValue::Text(String::new()) },
+    TagDef { key: "Exif.Photo.LensMake", display_name: "Lens Make", category: TagCategory::Camera, default_value: TagValue::Text(String::new()) },
+    TagDef { key: "Exif.Photo.LensModel", display_name: "Lens Model", category: TagCategory::Camera, default_value: TagValue::Text(String::new()) },
+    TagDef { key: "Exif.Photo.LensSerialNumber", display_name: "Lens Serial Number", category: TagCategory::Camera, default_value: TagValue::Text(String::new()) },
+    TagDef { key: "Exif.Photo.OwnerName", display_name: "Owner Name", category: TagCategory::Camera, default_value: TagValue::Text(String::new()) },
+    TagDef { key: "Exif.Photo.SerialNumber", display_name: "Serial Number", category: TagCategory::Camera, default_value: TagValue::Text(String::new()) },
+    // DateTime tags
+    TagDef { key: "Exif.Photo.DateTimeOriginal", display_name: "Date Taken", category: TagCategory::DateTime, default_value: TagValue::DateTime(String::new()) },
+    TagDef { key: "Exif.Photo.CreateDate", display_name: "Create Date", category: TagCategory::DateTime, default_value: TagValue::DateTime(String::new()) },
+    TagDef { key: "Exif.Image.ModifyDate", display_name: "Modify Date", category: TagCategory::DateTime, default_value: TagValue::DateTime(String::new()) },
+    TagDef { key: "Exif.Photo.OffsetTime", display_name: "Offset Time", category: TagCategory::DateTime, default_value: TagValue::Text(String::new()) },
+    TagDef { key: "Exif.Photo.OffsetTimeOriginal", display_name: "Offset Time Original", category: TagCategory::DateTime, default_value: TagValue::Text(String::new()) },
+    TagDef { key: "Exif.Photo.OffsetTimeDigitized", display_name: "Offset Time Digitized", category: TagCategory::DateTime, default_value: TagValue::Text(String::new()) },
+    TagDef { key: "Exif.Photo.SubSecTime", display_name: "Sub Sec Time", category: TagCategory::DateTime, default_value: TagValue::Text(String::new()) },
+    TagDef { key: "Exif.Photo.SubSecTimeOriginal", display_name: "Sub Sec Time Original", category: TagCategory::DateTime, default_value: TagValue::Text(String::new()) },
+    TagDef { key: "Exif.Photo.SubSecTimeDigitized", display_name: "Sub Sec Time Digitized", category: TagCategory::DateTime, default_value: TagValue::Text(String::new()) },
+    // Software
+    TagDef { key: "Exif.Image.Software", display_name: "Software", category: TagCategory::Software, default_value: TagValue::Text(String::new()) },
+    // Image properties
+    TagDef { key: "Exif.Image.Orientation", display_name: "Orientation", category: TagCategory::Image, default_value: TagValue::Integer(1) },
+    TagDef { key: "Exif.Image.XResolution", display_name: "X Resolution", category: TagCategory::Image, default_value: TagValue::Rational(72, 1) },
+    TagDef { key: "Exif.Image.YResolution", display_name: "Y Resolution", category: TagCategory::Image, default_value: TagValue::Rational(72, 1) },
+    TagDef { key: "Exif.Image.ImageWidth", display_name: "Image Width", category: TagCategory::Image, default_value: TagValue::Integer(0) },
+    TagDef { key: "Exif.Image.ImageHeight", display_name: "Image Height", category: TagCategory::Image, default_value: TagValue::Integer(0) },
+    // Capture settings
+    TagDef { key: "Exif.Photo.ISO", display_name: "ISO", category: TagCategory::Capture, default_value: TagValue::Integer(100) },
+    TagDef { key: "Exif.Photo.ExposureTime", display_name: "Exposure Time", category: TagCategory::Capture, default_value: TagValue::Rational(1, 60) },
+    TagDef { key: "Exif.Photo.FNumber", display_name: "F-Number", category: TagCategory::Capture, default_value: TagValue::Rational(28, 10) },
+    TagDef { key: "Exif.Photo.FocalLength", display_name: "Focal Length", category: TagCategory::Capture, default_value: TagValue::Rational(50, 1) },
+    TagDef { key: "Exif.Photo.ApertureValue", display_name: "Aperture Value", category: TagCategory::Capture, default_value: TagValue::Rational(30, 10) },
+    TagDef { key: "Exif.Photo.ExposureProgram", display_name: "Exposure Program", category: TagCategory::Capture, default_value: TagValue::Integer(0) },
+    TagDef { key: "Exif.Photo.MeteringMode", display_name: "Metering Mode", category: TagCategory::Capture, default_value: TagValue::Integer(0) },
+    TagDef { key: "Exif.Photo.Flash", display_name: "Flash", category: TagCategory::Capture, default_value: TagValue::Integer(0) },
+    TagDef { key: "Exif.Photo.WhiteBalance", display_name: "White Balance", category: TagCategory::Capture, default_value: TagValue::Integer(0) },
+    TagDef { key: "Exif.Photo.ExposureMode", display_name: "Exposure Mode", category: TagCategory::Capture, default_value: TagValue::Integer(0) },
+    TagDef { key: "Exif.Photo.ColorSpace", display_name: "Color Space", category: TagCategory::Capture, default_value: TagValue::Integer(1) },
+    TagDef { key: "Exif.Photo.FileSource", display_name: "File Source", category: TagCategory::Capture, default_value: TagValue::Integer(3) },
+    TagDef { key: "Exif.Photo.SceneType", display_name: "Scene Type", category: TagCategory::Capture, default_value: TagValue::Integer(1) },
+    // Location
+    TagDef { key: "Exif.GPSInfo.GPSCoordinates", display_name: "GPS Coordinates", category: TagCategory::Location, default_value: TagValue::Gps(0.0, 0.0, None) },
+];
+
+/// Values cameras write when the photographer left a setting alone, so a tag
+/// holding one says little about the shot. A key may list several.
+const CAMERA_DEFAULTS: &[(&str, f64)] = &[
+    ("Exif.Image.Orientation", 1.0),
+    ("Exif.Image.XResolution", 72.0),
+    ("Exif.Image.YResolution", 72.0),
+    ("Exif.Image.ResolutionUnit", 2.0),
+    ("Exif.Photo.ISO", 100.0),
+    ("Exif.Photo.Flash", 0.0),
+    ("Exif.Photo.Flash", 16.0),
+    ("Exif.Photo.ExposureCompensation", 0.0),
+    ("Exif.Photo.ExposureMode", 0.0),
+    ("Exif.Photo.WhiteBalance", 0.0),
+    ("Exif.Photo.LightSource", 0.0),
+    ("Exif.Photo.SceneCaptureType", 0.0),
+    ("Exif.Photo.Contrast", 0.0),
+    ("Exif.Photo.Saturation", 0.0),
+    ("Exif.Photo.Sharpness", 0.0),
+    ("Exif.Photo.CustomRendered", 0.0),
+    ("Exif.Photo.GainControl", 0.0),
+    ("Exif.Photo.DigitalZoomRatio", 0.0),
+    ("Exif.Photo.DigitalZoomRatio", 1.0),
+    ("Exif.Photo.SubjectDistanceRange", 0.0),
+    ("Exif.Photo.SensingMethod", 2.0),
+    ("Exif.Photo.ColorSpace", 1.0),
+    ("Exif.Photo.FileSource", 3.0),
+    ("Exif.Photo.SceneType", 1.0),
+];
+
+/// Whether `tag` holds a value its camera writes by default, judged from a
+/// small table of common defaults. Values compare numerically, so a
+/// resolution of 720/10 matches 72. Tags outside the table never match.
+pub fn is_camera_default(tag: &MetadataTag) -> bool {
+    let Some(value) = tag.value.as_f64() else {
+        return false;
+    };
+    CAMERA_DEFAULTS
+        .iter()
+        .any(|(key, default)| key.eq_ignore_ascii_case(&tag.key) && (value - default).abs() < 1e-9)
+}
+
+/// Tags outside the location ones that can identify a photographer or their
+/// gear.
+const PRIVACY_TAG_KEYS: &[&str] = &[
+    "Exif.Photo.BodySerialNumber",
+    "Exif.Image.CameraSerialNumber",
+    "Exif.Photo.SerialNumber",
+    "Exif.Photo.LensSerialNumber",
+    "Exif.Photo.OwnerName",
+    "Exif.Photo.CameraOwnerName",
+    "Exif.Photo.MakerNote",
+];
+
+/// Whether `tag` can place or identify whoever took the photo: GPS and
+/// other location tags, serial numbers, the owner name and maker notes.
+pub fn is_privacy_sensitive(tag: &MetadataTag) -> bool {
+    matches!(tag.value, TagValue::Gps(..))
+        || tag.category == TagCategory::Location
+        || tag.key.to_ascii_lowercase().contains("gps")
+        || PRIVACY_TAG_KEYS
+            .iter()
+            .any(|key| key.eq_ignore_ascii_case(&tag.key))
+}
+
+/// Catalog entry for `key`, compared case-insensitively.
+pub fn lookup(key: &str) -> Option<&'static TagDef> {
+    let key = key.trim();
+    TAG_CATALOG
+        .iter()
+        .find(|def| def.key.eq_ignore_ascii_case(key))
+}
+
+/// Catalog entries in `category` (or any, when `None`) whose name, key or
+/// category label contains `query`, ignoring case.
+pub fn search(query: &str, category: Option<TagCategory>) -> Vec<&'static TagDef> {
+    let query = query.trim().to_ascii_lowercase();
+    TAG_CATALOG
+        .iter()
+        .filter(|def| category.is_none_or(|category| def.category == category))
+        .filter(|def| {
+            query.is_empty()
+                || def.display_name.to_ascii_lowercase().contains(&query)
+                || def.key.to_ascii_lowercase().contains(&query)
+                || def.category.as_str().to_ascii_lowercase().contains(&query)
+        })
+        .collect()
+}
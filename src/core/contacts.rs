@@ -0,0 +1,127 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::models::PhotoEntry;
+
+const ARTIST_KEY: &str = "Exif.Image.Artist";
+const OWNER_KEY: &str = "Exif.Photo.OwnerName";
+pub const COPYRIGHT_KEY: &str = "Exif.Image.Copyright";
+
+/// One person credited somewhere in the session, with every copyright notice
+/// their photos carry.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ContactInfo {
+    /// The artist, or the camera owner for photos without one.
+    pub name: String,
+    /// Distinct copyright notices, sorted.
+    pub copyrights: Vec<String>,
+    pub photo_count: usize,
+}
+
+/// Collate the people credited across `photos`, one entry per distinct name
+/// (compared ignoring case and surrounding whitespace), sorted by name.
+/// Photos with neither an artist nor an owner are left out.
+pub fn collect_contacts(photos: &[PhotoEntry]) -> Vec<ContactInfo> {
+    let mut contacts: BTreeMap<String, (ContactInfo, BTreeSet<String>)> = BTreeMap::new();
+
+    for photo in photos {
+        let text = |key: &str| {
+            photo
+                .metadata
+                .find_tag(key)
+                .map(|tag| tag.value.to_string().trim().to_string())
+                .filter(|value| !value.is_empty())
+        };
+        let Some(name) = text(ARTIST_KEY).or_else(|| text(OWNER_KEY)) else {
+            continue;
+        };
+
+        let (contact, copyrights) = contacts.entry(name.to_lowercase()).or_insert_with(|| {
+            let contact = ContactInfo {
+                name,
+                ..ContactInfo::default()
+            };
+            (contact, BTreeSet::new())
+        });
+        contact.photo_count += 1;
+        copyrights.extend(text(COPYRIGHT_KEY));
+    }
+
+    contacts
+        .into_values()
+        .map(|(mut contact, copyrights)| {
+            contact.copyrights = copyrights.into_iter().collect();
+            contact
+        })
+        .collect()
+}
+
+/// Render `contacts` as vCard 3.0 cards, copyright notices as notes.
+pub fn contacts_to_vcard(contacts: &[ContactInfo]) -> String {
+    let mut vcard = String::new();
+    for contact in contacts {
+        let name = escape_vcard(&contact.name);
+        vcard.push_str("BEGIN:VCARD\r\nVERSION:3.0\r\n");
+        vcard.push_str(&format!("FN:{name}\r\nN:{name};;;;\r\n"));
+        for copyright in &contact.copyrights {
+            vcard.push_str(&format!("NOTE:{}\r\n", escape_vcard(copyright)));
+        }
+        vcard.push_str("END:VCARD\r\n");
+    }
+    vcard
+}
+
+/// Four-digit numbers outside this range are taken for something else, like
+/// a street or suite number.
+const PLAUSIBLE_YEARS: std::ops::RangeInclusive<u32> = 1900..=2100;
+
+/// `notice` with its year replaced by `year`, e.g. `© 2023 Jane` to
+/// `© 2024 Jane`. A year is a run of exactly four digits within
+/// [`PLAUSIBLE_YEARS`]; with several, as in `2019-2023`, the last one is
+/// updated. `None` when the notice has no year.
+pub fn replace_copyright_year(notice: &str, year: u32) -> Option<String> {
+    let bytes = notice.as_bytes();
+    let mut last_year = None;
+    let mut start = 0;
+    while start < bytes.len() {
+        if !bytes[start].is_ascii_digit() {
+            start += 1;
+            continue;
+        }
+        let end = bytes[start..]
+            .iter()
+            .position(|byte| !byte.is_ascii_digit())
+            .map_or(bytes.len(), |len| start + len);
+        let is_year = end - start == 4
+            && notice[start..end]
+                .parse()
+                .is_ok_and(|value| PLAUSIBLE_YEARS.contains(&value));
+        if is_year {
+            last_year = Some(start..end);
+        }
+        start = end;
+    }
+
+    let range = last_year?;
+    Some(format!(
+        "{}{year:04}{}",
+        &notice[..range.start],
+        &notice[range.end..]
+    ))
+}
+
+/// Escape the characters vCard text values reserve (RFC 2426 §4).
+fn escape_vcard(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for ch in value.chars() {
+        match ch {
+            '\\' | ',' | ';' => {
+                escaped.push('\\');
+                escaped.push(ch);
+            }
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,105 @@
+use crate::models::ORIENTATION_KEY;
+
+/// One labelled value of an enumerated integer tag.
+pub type EnumLabel = (i64, &'static str);
+
+const ORIENTATION: &[EnumLabel] = &[
+    (1, "Horizontal (normal)"),
+    (2, "Mirror horizontal"),
+    (3, "Rotate 180"),
+    (4, "Mirror vertical"),
+    (5, "Mirror horizontal and rotate 270 CW"),
+    (6, "Rotate 90 CW"),
+    (7, "Mirror horizontal and rotate 90 CW"),
+    (8, "Rotate 270 CW"),
+];
+
+const EXPOSURE_PROGRAM: &[EnumLabel] = &[
+    (0, "Not defined"),
+    (1, "Manual"),
+    (2, "Program AE"),
+    (3, "Aperture-priority AE"),
+    (4, "Shutter speed priority AE"),
+    (5, "Creative (slow speed)"),
+    (6, "Action (high speed)"),
+    (7, "Portrait"),
+    (8, "Landscape"),
+];
+
+const METERING_MODE: &[EnumLabel] = &[
+    (0, "Unknown"),
+    (1, "Average"),
+    (2, "Center-weighted average"),
+    (3, "Spot"),
+    (4, "Multi-spot"),
+    (5, "Multi-segment"),
+    (6, "Partial"),
+    (255, "Other"),
+];
+
+const FLASH: &[EnumLabel] = &[
+    (0x00, "No flash"),
+    (0x01, "Fired"),
+    (0x05, "Fired, return not detected"),
+    (0x07, "Fired, return detected"),
+    (0x08, "On, did not fire"),
+    (0x09, "On, fired"),
+    (0x0D, "On, return not detected"),
+    (0x0F, "On, return detected"),
+    (0x10, "Off, did not fire"),
+    (0x18, "Auto, did not fire"),
+    (0x19, "Auto, fired"),
+    (0x1D, "Auto, fired, return not detected"),
+    (0x1F, "Auto, fired, return detected"),
+    (0x20, "No flash function"),
+    (0x41, "Fired, red-eye reduction"),
+    (0x49, "On, red-eye reduction"),
+    (0x58, "Auto, did not fire, red-eye reduction"),
+    (0x59, "Auto, fired, red-eye reduction"),
+];
+
+const WHITE_BALANCE: &[EnumLabel] = &[(0, "Auto"), (1, "Manual")];
+
+const COLOR_SPACE: &[EnumLabel] = &[(1, "sRGB"), (2, "Adobe RGB"), (0xFFFF, "Uncalibrated")];
+
+const FILE_SOURCE: &[EnumLabel] = &[
+    (0, "Others"),
+    (1, "Film scanner"),
+    (2, "Reflection print scanner"),
+    (3, "Digital camera"),
+];
+
+const SCENE_TYPE: &[EnumLabel] = &[(1, "Directly photographed")];
+
+/// Labelled values for `key`, or `None` when the tag is not an enumeration
+/// the editor knows.
+pub fn labels_for(key: &str) -> Option<&'static [EnumLabel]> {
+    let labels = match key {
+        ORIENTATION_KEY => ORIENTATION,
+        "Exif.Photo.ExposureProgram" => EXPOSURE_PROGRAM,
+        "Exif.Photo.MeteringMode" => METERING_MODE,
+        "Exif.Photo.Flash" => FLASH,
+        "Exif.Photo.WhiteBalance" => WHITE_BALANCE,
+        "Exif.Photo.ColorSpace" => COLOR_SPACE,
+        "Exif.Photo.FileSource" => FILE_SOURCE,
+        "Exif.Photo.SceneType" => SCENE_TYPE,
+        _ => return None,
+    };
+    Some(labels)
+}
+
+/// Human label for `value` of tag `key`.
+pub fn label_for(key: &str, value: i64) -> Option<&'static str> {
+    labels_for(key)?
+        .iter()
+        .find(|(known, _)| *known == value)
+        .map(|(_, label)| *label)
+}
+
+/// Integer stored for the human `label` of tag `key`.
+pub fn value_for(key: &str, label: &str) -> Option<i64> {
+    labels_for(key)?
+        .iter()
+        .find(|(_, known)| *known == label)
+        .map(|(value, _)| *value)
+}
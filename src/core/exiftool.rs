@@ -0,0 +1,387 @@
+use std::fs;
+use std::path::Path;
+
+use serde_json::{Map, Value};
+
+use crate::core::metadata::{Result, GPS_COORDINATES_KEY};
+use crate::models::{PhotoMetadata, TagValue};
+
+const SOURCE_FILE: &str = "SourceFile";
+/// Signed GPS values live in exiftool's Composite group; the EXIF ones are unsigned.
+const GPS_GROUP: &str = "Composite";
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum ValueKind {
+    Text,
+    Integer,
+    Rational,
+    DateTime,
+}
+
+/// One exiftool tag name and the crate key it maps to. `group` is the family 0
+/// group exiftool prints with `-G`.
+struct ExiftoolTag {
+    name: &'static str,
+    group: &'static str,
+    key: &'static str,
+    kind: ValueKind,
+}
+
+const fn tag(name: &'static str, key: &'static str, kind: ValueKind) -> ExiftoolTag {
+    ExiftoolTag {
+        name,
+        group: "EXIF",
+        key,
+        kind,
+    }
+}
+
+const EXIFTOOL_TAGS: &[ExiftoolTag] = &[
+    tag("Make", "Exif.Image.Make", ValueKind::Text),
+    tag("Model", "Exif.Image.Model", ValueKind::Text),
+    tag("Software", "Exif.Image.Software", ValueKind::Text),
+    tag("Artist", "Exif.Image.Artist", ValueKind::Text),
+    tag("Copyright", "Exif.Image.Copyright", ValueKind::Text),
+    tag(
+        "ImageDescription",
+        "Exif.Image.ImageDescription",
+        ValueKind::Text,
+    ),
+    tag("LensMake", "Exif.Photo.LensMake", ValueKind::Text),
+    tag("LensModel", "Exif.Photo.LensModel", ValueKind::Text),
+    tag(
+        "LensSerialNumber",
+        "Exif.Photo.LensSerialNumber",
+        ValueKind::Text,
+    ),
+    tag("OwnerName", "Exif.Photo.OwnerName", ValueKind::Text),
+    tag("SerialNumber", "Exif.Photo.SerialNumber", ValueKind::Text),
+    tag(
+        "DateTimeOriginal",
+        "Exif.Photo.DateTimeOriginal",
+        ValueKind::DateTime,
+    ),
+    tag("CreateDate", "Exif.Photo.CreateDate", ValueKind::DateTime),
+    tag("ModifyDate", "Exif.Image.ModifyDate", ValueKind::DateTime),
+    tag("OffsetTime", "Exif.Photo.OffsetTime", ValueKind::Text),
+    tag(
+        "OffsetTimeOriginal",
+        "Exif.Photo.OffsetTimeOriginal",
+        ValueKind::Text,
+    ),
+    tag("Orientation", "Exif.Image.Orientation", ValueKind::Integer),
+    tag("ISO", "Exif.Photo.ISO", ValueKind::Integer),
+    tag(
+        "ExposureProgram",
+        "Exif.Photo.ExposureProgram",
+        ValueKind::Integer,
+    ),
+    tag(
+        "MeteringMode",
+        "Exif.Photo.MeteringMode",
+        ValueKind::Integer,
+    ),
+    tag("Flash", "Exif.Photo.Flash", ValueKind::Integer),
+    tag(
+        "WhiteBalance",
+        "Exif.Photo.WhiteBalance",
+        ValueKind::Integer,
+    ),
+    tag(
+        "ExposureMode",
+        "Exif.Photo.ExposureMode",
+        ValueKind::Integer,
+    ),
+    tag("ColorSpace", "Exif.Photo.ColorSpace", ValueKind::Integer),
+    tag(
+        "FocalLengthIn35mmFormat",
+        "Exif.Photo.FocalLengthIn35mmFormat",
+        ValueKind::Integer,
+    ),
+    tag("ImageWidth", "Exif.Image.ImageWidth", ValueKind::Integer),
+    tag("ImageHeight", "Exif.Image.ImageHeight", ValueKind::Integer),
+    tag(
+        "ExposureTime",
+        "Exif.Photo.ExposureTime",
+        ValueKind::Rational,
+    ),
+    tag("FNumber", "Exif.Photo.FNumber", ValueKind::Rational),
+    tag("FocalLength", "Exif.Photo.FocalLength", ValueKind::Rational),
+    tag(
+        "ApertureValue",
+        "Exif.Photo.ApertureValue",
+        ValueKind::Rational,
+    ),
+    tag("XResolution", "Exif.Image.XResolution", ValueKind::Rational),
+    tag("YResolution", "Exif.Image.YResolution", ValueKind::Rational),
+];
+
+/// exiftool's printed Orientation values, used when the JSON was produced without `-n`.
+const ORIENTATION_NAMES: &[(&str, i64)] = &[
+    ("Horizontal (normal)", 1),
+    ("Mirror horizontal", 2),
+    ("Rotate 180", 3),
+    ("Mirror vertical", 4),
+    ("Mirror horizontal and rotate 270 CW", 5),
+    ("Rotate 90 CW", 6),
+    ("Mirror horizontal and rotate 90 CW", 7),
+    ("Rotate 270 CW", 8),
+];
+
+/// The tags exiftool reported for one file, already mapped to crate keys.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExiftoolRecord {
+    pub source_file: String,
+    pub tags: Vec<(String, TagValue)>,
+}
+
+impl ExiftoolRecord {
+    /// The file name component of `SourceFile`, used to match loaded photos.
+    pub fn file_name(&self) -> &str {
+        self.source_file
+            .rsplit(['/', '\\'])
+            .next()
+            .unwrap_or(&self.source_file)
+    }
+}
+
+pub fn read_exiftool_json(path: &Path) -> Result<Vec<ExiftoolRecord>> {
+    let contents = fs::read_to_string(path)?;
+    parse_exiftool_json(&contents)
+}
+
+/// Parse the array printed by `exiftool -json`. Group prefixes from `-G`
+/// (`EXIF:Make`) are accepted, as are both numeric (`-n`) and printed values.
+/// Tags without a mapping, or whose value can't be converted, are skipped.
+pub fn parse_exiftool_json(contents: &str) -> Result<Vec<ExiftoolRecord>> {
+    let objects: Vec<Map<String, Value>> = serde_json::from_str(contents)?;
+    Ok(objects.iter().map(parse_record).collect())
+}
+
+/// Render `metadata` the way `exiftool -json -G` would, as a one-element array.
+/// `SourceFile` is `*`, which `exiftool -json=` applies to every target file.
+/// Rationals are written as `n/d` strings so they re-import losslessly.
+pub fn export_exiftool_json(metadata: &PhotoMetadata) -> String {
+    let mut object = Map::new();
+    object.insert(SOURCE_FILE.to_string(), Value::from("*"));
+
+    for metadata_tag in metadata.all_tags() {
+        if let TagValue::Gps(latitude, longitude, altitude) = metadata_tag.value {
+            if metadata_tag.key.eq_ignore_ascii_case(GPS_COORDINATES_KEY) {
+                object.insert(format!("{GPS_GROUP}:GPSLatitude"), Value::from(latitude));
+                object.insert(format!("{GPS_GROUP}:GPSLongitude"), Value::from(longitude));
+                if let Some(altitude) = altitude {
+                    object.insert(format!("{GPS_GROUP}:GPSAltitude"), Value::from(altitude));
+                }
+            }
+            continue;
+        }
+
+        let Some(mapping) = EXIFTOOL_TAGS
+            .iter()
+            .find(|tag| tag.key.eq_ignore_ascii_case(&metadata_tag.key))
+        else {
+            continue;
+        };
+
+        let value = match &metadata_tag.value {
+            TagValue::Integer(value) => Value::from(*value),
+            TagValue::Float(value) => Value::from(*value),
+            TagValue::Rational(numerator, 1) => Value::from(*numerator),
+            TagValue::Rational(numerator, denominator) => {
+                Value::from(format!("{numerator}/{denominator}"))
+            }
+            TagValue::Text(text) | TagValue::DateTime(text) | TagValue::Unknown(text) => {
+                Value::from(text.as_str())
+            }
+            TagValue::TextList(items) => Value::from(items.clone()),
+            TagValue::Gps(..) | TagValue::Binary(_) => continue,
+        };
+
+        object.insert(format!("{}:{}", mapping.group, mapping.name), value);
+    }
+
+    serde_json::to_string_pretty(&Value::Array(vec![Value::Object(object)]))
+        .unwrap_or_else(|_| String::from("[]"))
+}
+
+fn parse_record(object: &Map<String, Value>) -> ExiftoolRecord {
+    let mut source_file = String::new();
+    let mut tags = Vec::new();
+    let mut latitude = None;
+    let mut longitude = None;
+    let mut altitude = None;
+
+    for (name, value) in object {
+        let name = strip_group(name);
+
+        match name {
+            SOURCE_FILE => {
+                source_file = value.as_str().unwrap_or_default().to_string();
+                continue;
+            }
+            "GPSLatitude" => {
+                latitude = parse_coordinate(value);
+                continue;
+            }
+            "GPSLongitude" => {
+                longitude = parse_coordinate(value);
+                continue;
+            }
+            "GPSAltitude" => {
+                altitude = parse_altitude(value);
+                continue;
+            }
+            _ => {}
+        }
+
+        let Some(mapping) = EXIFTOOL_TAGS.iter().find(|tag| tag.name == name) else {
+            continue;
+        };
+
+        if let Some(value) = convert_value(mapping, value) {
+            tags.push((mapping.key.to_string(), value));
+        }
+    }
+
+    if let (Some(latitude), Some(longitude)) = (latitude, longitude) {
+        tags.push((
+            GPS_COORDINATES_KEY.to_string(),
+            TagValue::Gps(latitude, longitude, altitude),
+        ));
+    }
+
+    ExiftoolRecord { source_file, tags }
+}
+
+fn strip_group(name: &str) -> &str {
+    name.rsplit(':').next().unwrap_or(name)
+}
+
+fn convert_value(mapping: &ExiftoolTag, value: &Value) -> Option<TagValue> {
+    match mapping.kind {
+        ValueKind::Text => match value {
+            Value::String(text) => Some(TagValue::Text(text.clone())),
+            Value::Number(number) => Some(TagValue::Text(number.to_string())),
+            _ => None,
+        },
+        ValueKind::DateTime => value
+            .as_str()
+            .map(|text| TagValue::DateTime(text.to_string())),
+        ValueKind::Integer => parse_integer(mapping, value).map(TagValue::Integer),
+        ValueKind::Rational => parse_rational(value)
+            .map(|(numerator, denominator)| TagValue::Rational(numerator, denominator)),
+    }
+}
+
+fn parse_integer(mapping: &ExiftoolTag, value: &Value) -> Option<i64> {
+    match value {
+        Value::Number(number) => number
+            .as_i64()
+            .or_else(|| number.as_f64().map(|value| value.round() as i64)),
+        Value::String(text) => {
+            let text = text.trim();
+            if mapping.name == "Orientation" {
+                if let Some((_, orientation)) =
+                    ORIENTATION_NAMES.iter().find(|(name, _)| *name == text)
+                {
+                    return Some(*orientation);
+                }
+            }
+            text.parse().ok()
+        }
+        _ => None,
+    }
+}
+
+fn parse_rational(value: &Value) -> Option<(u32, u32)> {
+    match value {
+        Value::Number(number) => float_to_rational(number.as_f64()?),
+        Value::String(text) => {
+            // Printed values carry units ("50.0 mm"), so only the first token matters.
+            let text = text.split_whitespace().next()?;
+            if let Some((numerator, denominator)) = text.split_once('/') {
+                let numerator = numerator.parse().ok()?;
+                let denominator = denominator.parse().ok()?;
+                return (denominator != 0).then_some((numerator, denominator));
+            }
+            float_to_rational(text.parse().ok()?)
+        }
+        _ => None,
+    }
+}
+
+fn float_to_rational(value: f64) -> Option<(u32, u32)> {
+    if !value.is_finite() || value < 0.0 || value > f64::from(u32::MAX) {
+        return None;
+    }
+
+    // Shutter speeds like 0.016666 are meant as 1/60.
+    if value > 0.0 && value < 1.0 {
+        let inverse = (1.0 / value).round();
+        if inverse <= f64::from(u32::MAX) && ((1.0 / inverse) - value).abs() < value * 1e-3 {
+            return Some((1, inverse as u32));
+        }
+    }
+
+    let mut denominator = 1u32;
+    while denominator < 1_000_000 && (value * f64::from(denominator)).fract().abs() > 1e-9 {
+        denominator *= 10;
+    }
+
+    let numerator = (value * f64::from(denominator)).round();
+    if numerator > f64::from(u32::MAX) {
+        return None;
+    }
+
+    let numerator = numerator as u32;
+    let divisor = gcd(numerator, denominator).max(1);
+    Some((numerator / divisor, denominator / divisor))
+}
+
+fn gcd(mut left: u32, mut right: u32) -> u32 {
+    while right != 0 {
+        (left, right) = (right, left % right);
+    }
+    left
+}
+
+/// Accepts signed decimals (`-n`) and printed DMS strings like `40 deg 26' 46.00" N`.
+fn parse_coordinate(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(number) => number.as_f64(),
+        Value::String(text) => {
+            let parts = numbers_in(text);
+            let (&degrees, rest) = parts.split_first()?;
+            let minutes = rest.first().copied().unwrap_or(0.0);
+            let seconds = rest.get(1).copied().unwrap_or(0.0);
+            let magnitude = degrees.abs() + minutes / 60.0 + seconds / 3600.0;
+
+            let negative = degrees < 0.0 || text.trim_end().ends_with(['S', 'W']);
+            Some(if negative { -magnitude } else { magnitude })
+        }
+        _ => None,
+    }
+}
+
+/// Accepts signed metres (`-n`) and printed values like `12.3 m Below Sea Level`.
+fn parse_altitude(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(number) => number.as_f64(),
+        Value::String(text) => {
+            let altitude = *numbers_in(text).first()?;
+            Some(if text.contains("Below") {
+                -altitude.abs()
+            } else {
+                altitude
+            })
+        }
+        _ => None,
+    }
+}
+
+fn numbers_in(text: &str) -> Vec<f64> {
+    text.split(|c: char| !(c.is_ascii_digit() || c == '.' || c == '-'))
+        .filter_map(|token| token.parse().ok())
+        .collect()
+}
@@ -1,12 +1,33 @@
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
 use std::path::Path;
 
-use crate::models::ImageFormat;
+use crate::models::{Dimensions, ImageFormat};
+
+const JPEG_EXIF_HEADER: &[u8] = b"Exif\0\0";
+const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+const JXL_CONTAINER_SIGNATURE: [u8; 12] = [
+    0x00, 0x00, 0x00, 0x0C, b'J', b'X', b'L', b' ', 0x0D, 0x0A, 0x87, 0x0A,
+];
+
+/// Leading bytes read when sniffing; enough for an ISO-BMFF `ftyp` box with
+/// a handful of compatible brands.
+const SNIFF_LEN: u64 = 64;
 
 pub const SUPPORTED_EXTENSIONS: &[&str] = &[
-    "jpg", "jpeg", "png", "tif", "tiff", "webp", "heic", "heif", "avif", "jxl",
+    "jpg", "jpeg", "png", "tif", "tiff", "webp", "heic", "heif", "hif", "avif", "jxl",
 ];
 
+/// Identify an image by its magic bytes, falling back to the file extension
+/// when the file can't be read or its contents aren't recognised.
 pub fn detect_format(path: &Path) -> ImageFormat {
+    match sniff_format(path) {
+        ImageFormat::Unknown => extension_format(path),
+        format => format,
+    }
+}
+
+fn extension_format(path: &Path) -> ImageFormat {
     let Some(ext) = path.extension().and_then(|value| value.to_str()) else {
         return ImageFormat::Unknown;
     };
@@ -16,13 +37,85 @@ pub fn detect_format(path: &Path) -> ImageFormat {
         "png" => ImageFormat::Png,
         "tif" | "tiff" => ImageFormat::Tiff,
         "webp" => ImageFormat::WebP,
-        "heic" | "heif" => ImageFormat::Heif,
+        "heic" | "heif" | "hif" => ImageFormat::Heif,
         "avif" => ImageFormat::Avif,
         "jxl" => ImageFormat::Jxl,
         _ => ImageFormat::Unknown,
     }
 }
 
+/// Identify an image from its leading bytes, regardless of the file name.
+pub fn sniff_format(path: &Path) -> ImageFormat {
+    let Ok(file) = File::open(path) else {
+        return ImageFormat::Unknown;
+    };
+
+    let mut header = Vec::with_capacity(SNIFF_LEN as usize);
+    if file.take(SNIFF_LEN).read_to_end(&mut header).is_err() {
+        return ImageFormat::Unknown;
+    }
+    format_from_magic(&header)
+}
+
+/// Match the magic bytes at the start of an image file.
+pub fn format_from_magic(header: &[u8]) -> ImageFormat {
+    if header.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        return ImageFormat::Jpeg;
+    }
+    if header.starts_with(&PNG_SIGNATURE) {
+        return ImageFormat::Png;
+    }
+    if header.starts_with(b"II*\0") || header.starts_with(b"MM\0*") {
+        return ImageFormat::Tiff;
+    }
+    if header.len() >= 12 && &header[..4] == b"RIFF" && &header[8..12] == b"WEBP" {
+        return ImageFormat::WebP;
+    }
+    if header.starts_with(&[0xFF, 0x0A]) || header.starts_with(&JXL_CONTAINER_SIGNATURE) {
+        return ImageFormat::Jxl;
+    }
+    if header.len() >= 12 && &header[4..8] == b"ftyp" {
+        return ftyp_format(header);
+    }
+
+    ImageFormat::Unknown
+}
+
+/// Classify an ISO-BMFF file by its `ftyp` brands. The major brand is often
+/// the generic `mif1`, so the compatible brands are checked for AVIF too.
+fn ftyp_format(header: &[u8]) -> ImageFormat {
+    let box_len = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as usize;
+    let end = box_len.clamp(12, header.len());
+    let compatible = header.get(16..end).unwrap_or_default();
+    let brands = std::iter::once(&header[8..12]).chain(compatible.chunks_exact(4));
+
+    let mut format = ImageFormat::Unknown;
+    for brand in brands {
+        match brand {
+            b"avif" | b"avis" => return ImageFormat::Avif,
+            b"heic" | b"heix" | b"hevc" | b"hevx" | b"heim" | b"heis" | b"mif1" | b"msf1" => {
+                format = ImageFormat::Heif;
+            }
+            _ => {}
+        }
+    }
+    format
+}
+
+/// Whether `format` reliably keeps GPS coordinates in its embedded EXIF.
+/// PNG and WebP readers often ignore or drop their EXIF chunk, so a location
+/// saved there may only survive in the sidecar.
+pub fn format_supports_gps(format: ImageFormat) -> bool {
+    match format {
+        ImageFormat::Jpeg
+        | ImageFormat::Tiff
+        | ImageFormat::Heif
+        | ImageFormat::Avif
+        | ImageFormat::Jxl => true,
+        ImageFormat::Png | ImageFormat::WebP | ImageFormat::Unknown => false,
+    }
+}
+
 pub fn is_supported(path: &Path) -> bool {
     !detect_format(path).is_unknown()
 }
@@ -30,3 +123,196 @@ pub fn is_supported(path: &Path) -> bool {
 pub fn supported_extensions() -> &'static [&'static str] {
     SUPPORTED_EXTENSIONS
 }
+
+/// Read pixel dimensions from the image header without decoding the image.
+/// Only JPEG and PNG headers are understood; other formats return `None`.
+pub fn read_dimensions(path: &Path) -> Option<Dimensions> {
+    let mut file = File::open(path).ok()?;
+    let mut signature = [0u8; 8];
+    file.read_exact(&mut signature).ok()?;
+
+    if signature[..2] == [0xFF, 0xD8] {
+        file.seek(SeekFrom::Start(2)).ok()?;
+        return jpeg_dimensions(&mut file);
+    }
+
+    if signature == PNG_SIGNATURE {
+        return png_dimensions(&mut file);
+    }
+
+    None
+}
+
+/// Return the TIFF-structured payload of a JPEG's APP1 `Exif` segment, if any.
+pub fn read_jpeg_exif(path: &Path) -> Option<Vec<u8>> {
+    let mut file = File::open(path).ok()?;
+    let mut soi = [0u8; 2];
+    file.read_exact(&mut soi).ok()?;
+    if soi != [0xFF, 0xD8] {
+        return None;
+    }
+
+    let mut header = [0u8; 4];
+    loop {
+        file.read_exact(&mut header).ok()?;
+        let [0xFF, marker, high, low] = header else {
+            return None;
+        };
+        if marker == 0xD9 || marker == 0xDA {
+            return None;
+        }
+
+        let length = usize::from(u16::from_be_bytes([high, low]));
+        if length < 2 {
+            return None;
+        }
+
+        let mut payload = vec![0u8; length - 2];
+        file.read_exact(&mut payload).ok()?;
+        if marker == 0xE1 && is_valid_exif_payload(&payload) {
+            payload.drain(..JPEG_EXIF_HEADER.len());
+            return Some(payload);
+        }
+    }
+}
+
+/// A marker segment in a JPEG header, spanning `start..end` of the file
+/// including its `FF xx` marker and length field.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct JpegSegment {
+    pub marker: u8,
+    pub start: usize,
+    pub end: usize,
+}
+
+impl JpegSegment {
+    /// The segment body after the marker and length field.
+    pub fn payload<'a>(&self, bytes: &'a [u8]) -> &'a [u8] {
+        &bytes[self.start + 4..self.end]
+    }
+}
+
+/// Scan the marker segments ahead of a JPEG's image data. Stops at the start
+/// of scan or at the first malformed segment; non-JPEG bytes yield nothing.
+pub fn jpeg_segments(bytes: &[u8]) -> Vec<JpegSegment> {
+    let mut segments = Vec::new();
+    if !bytes.starts_with(&[0xFF, 0xD8]) {
+        return segments;
+    }
+
+    let mut start = 2;
+    while let Some(&[0xFF, marker, high, low]) = bytes.get(start..start + 4) {
+        if marker == 0xD9 || marker == 0xDA {
+            break;
+        }
+        let length = usize::from(u16::from_be_bytes([high, low]));
+        let end = start + 2 + length;
+        if length < 2 || end > bytes.len() {
+            break;
+        }
+        segments.push(JpegSegment { marker, start, end });
+        start = end;
+    }
+    segments
+}
+
+/// APP1 segments that announce themselves as EXIF, in file order.
+pub fn jpeg_exif_segments(bytes: &[u8]) -> Vec<JpegSegment> {
+    jpeg_segments(bytes)
+        .into_iter()
+        .filter(|segment| {
+            segment.marker == 0xE1 && segment.payload(bytes).starts_with(JPEG_EXIF_HEADER)
+        })
+        .collect()
+}
+
+/// Drop every EXIF segment except the first one with an intact TIFF header,
+/// so every reader sees the same block. Returns `None` when the JPEG has at
+/// most one EXIF segment and needs no repair.
+pub fn keep_first_jpeg_exif(bytes: &[u8]) -> Option<Vec<u8>> {
+    let segments = jpeg_exif_segments(bytes);
+    if segments.len() < 2 {
+        return None;
+    }
+
+    let keep = segments
+        .iter()
+        .position(|segment| is_valid_exif_payload(segment.payload(bytes)))
+        .unwrap_or(0);
+
+    let mut repaired = Vec::with_capacity(bytes.len());
+    let mut copied = 0;
+    for (index, segment) in segments.iter().enumerate() {
+        if index != keep {
+            repaired.extend_from_slice(&bytes[copied..segment.start]);
+            copied = segment.end;
+        }
+    }
+    repaired.extend_from_slice(&bytes[copied..]);
+    Some(repaired)
+}
+
+fn is_valid_exif_payload(payload: &[u8]) -> bool {
+    payload
+        .strip_prefix(JPEG_EXIF_HEADER)
+        .is_some_and(|tiff| tiff.starts_with(b"II*\0") || tiff.starts_with(b"MM\0*"))
+}
+
+fn jpeg_dimensions<R: Read + Seek>(reader: &mut R) -> Option<Dimensions> {
+    let mut byte = [0u8; 1];
+
+    loop {
+        reader.read_exact(&mut byte).ok()?;
+        if byte[0] != 0xFF {
+            return None;
+        }
+
+        let mut marker = 0xFF;
+        while marker == 0xFF {
+            reader.read_exact(&mut byte).ok()?;
+            marker = byte[0];
+        }
+
+        // Standalone markers carry no length field.
+        if marker == 0x01 || (0xD0..=0xD8).contains(&marker) {
+            continue;
+        }
+        if marker == 0xD9 || marker == 0xDA {
+            return None;
+        }
+
+        let mut length = [0u8; 2];
+        reader.read_exact(&mut length).ok()?;
+        let length = u16::from_be_bytes(length);
+        if length < 2 {
+            return None;
+        }
+
+        let is_start_of_frame =
+            (0xC0..=0xCF).contains(&marker) && !matches!(marker, 0xC4 | 0xC8 | 0xCC);
+        if is_start_of_frame {
+            let mut frame = [0u8; 5];
+            reader.read_exact(&mut frame).ok()?;
+            return Some(Dimensions {
+                width: u16::from_be_bytes([frame[3], frame[4]]) as u32,
+                height: u16::from_be_bytes([frame[1], frame[2]]) as u32,
+            });
+        }
+
+        reader.seek(SeekFrom::Current(i64::from(length) - 2)).ok()?;
+    }
+}
+
+fn png_dimensions<R: Read>(reader: &mut R) -> Option<Dimensions> {
+    let mut header = [0u8; 16];
+    reader.read_exact(&mut header).ok()?;
+
+    if &header[4..8] != b"IHDR" {
+        return None;
+    }
+
+    Some(Dimensions {
+        width: u32::from_be_bytes([header[8], header[9], header[10], header[11]]),
+        height: u32::from_be_bytes([header[12], header[13], header[14], header[15]]),
+    })
+}
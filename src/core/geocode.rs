@@ -0,0 +1,201 @@
+use std::fmt;
+use std::sync::Arc;
+
+use futures::AsyncReadExt;
+use gpui_http_client::{AsyncBody, HttpClient, Url};
+use serde::Deserialize;
+
+pub const NOMINATIM_SEARCH_URL: &str = "https://nominatim.openstreetmap.org/search";
+pub const NOMINATIM_REVERSE_URL: &str = "https://nominatim.openstreetmap.org/reverse";
+
+/// Tag written by "Apply as location tag" in the map popup.
+pub const LOCATION_TAG_KEY: &str = "Iptc.Application2.Sub-location";
+
+/// Coordinates rounded to five decimal places (about a metre), used to cache
+/// reverse lookups so reopening the same location doesn't hit the network.
+pub type CoordinateKey = (i64, i64);
+
+#[derive(Debug)]
+pub enum GeocodeError {
+    EmptyQuery,
+    Network(String),
+    Status(u16),
+    InvalidResponse(String),
+    NoResults,
+}
+
+impl fmt::Display for GeocodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EmptyQuery => write!(f, "Enter a place to search for"),
+            Self::Network(err) => write!(f, "Location lookup failed: {err}"),
+            Self::Status(code) => write!(f, "Location lookup failed with HTTP {code}"),
+            Self::InvalidResponse(err) => write!(f, "Unexpected location lookup response: {err}"),
+            Self::NoResults => write!(f, "No matching place found"),
+        }
+    }
+}
+
+impl std::error::Error for GeocodeError {}
+
+/// A single place resolved by the geocoder.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GeocodeResult {
+    pub latitude: f64,
+    pub longitude: f64,
+    pub display_name: String,
+}
+
+#[derive(Deserialize)]
+struct NominatimPlace {
+    lat: String,
+    lon: String,
+    #[serde(default)]
+    display_name: String,
+}
+
+#[derive(Deserialize)]
+struct NominatimReverse {
+    #[serde(default)]
+    display_name: String,
+    #[serde(default)]
+    address: NominatimAddress,
+    error: Option<String>,
+}
+
+#[derive(Default, Deserialize)]
+struct NominatimAddress {
+    city: Option<String>,
+    town: Option<String>,
+    village: Option<String>,
+    hamlet: Option<String>,
+    county: Option<String>,
+    state: Option<String>,
+    country: Option<String>,
+}
+
+/// Nominatim search URL returning at most one JSON result for `query`.
+pub fn search_url(query: &str) -> Result<String, GeocodeError> {
+    let query = query.trim();
+    if query.is_empty() {
+        return Err(GeocodeError::EmptyQuery);
+    }
+
+    Url::parse_with_params(
+        NOMINATIM_SEARCH_URL,
+        &[("q", query), ("format", "json"), ("limit", "1")],
+    )
+    .map(String::from)
+    .map_err(|err| GeocodeError::InvalidResponse(err.to_string()))
+}
+
+/// Parse the first place of a Nominatim JSON search response. Nominatim
+/// encodes coordinates as strings, and out-of-range values are rejected.
+pub fn parse_search_response(body: &str) -> Result<GeocodeResult, GeocodeError> {
+    let places: Vec<NominatimPlace> =
+        serde_json::from_str(body).map_err(|err| GeocodeError::InvalidResponse(err.to_string()))?;
+    let place = places.into_iter().next().ok_or(GeocodeError::NoResults)?;
+
+    let latitude = place.lat.trim().parse::<f64>().ok();
+    let longitude = place.lon.trim().parse::<f64>().ok();
+    match (latitude, longitude) {
+        (Some(latitude), Some(longitude))
+            if (-90.0..=90.0).contains(&latitude) && (-180.0..=180.0).contains(&longitude) =>
+        {
+            Ok(GeocodeResult {
+                latitude,
+                longitude,
+                display_name: place.display_name,
+            })
+        }
+        _ => Err(GeocodeError::InvalidResponse(format!(
+            "invalid coordinates {}, {}",
+            place.lat, place.lon
+        ))),
+    }
+}
+
+pub fn coordinate_key(latitude: f64, longitude: f64) -> CoordinateKey {
+    (
+        (latitude * 1e5).round() as i64,
+        (longitude * 1e5).round() as i64,
+    )
+}
+
+/// Nominatim reverse-lookup URL for a coordinate, at city-level detail.
+pub fn reverse_url(latitude: f64, longitude: f64) -> String {
+    format!("{NOMINATIM_REVERSE_URL}?lat={latitude:.6}&lon={longitude:.6}&format=json&zoom=10")
+}
+
+/// Parse a Nominatim reverse response into a short label such as
+/// "Paris, France", falling back to the full display name.
+pub fn parse_reverse_response(body: &str) -> Result<String, GeocodeError> {
+    let place: NominatimReverse =
+        serde_json::from_str(body).map_err(|err| GeocodeError::InvalidResponse(err.to_string()))?;
+    if place.error.is_some() {
+        return Err(GeocodeError::NoResults);
+    }
+
+    let address = place.address;
+    let locality = address
+        .city
+        .or(address.town)
+        .or(address.village)
+        .or(address.hamlet)
+        .or(address.county)
+        .or(address.state);
+    let label = [locality, address.country]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    if !label.is_empty() {
+        Ok(label)
+    } else if !place.display_name.is_empty() {
+        Ok(place.display_name)
+    } else {
+        Err(GeocodeError::NoResults)
+    }
+}
+
+/// Look up `query` on Nominatim and return the best match.
+pub async fn search(
+    client: Arc<dyn HttpClient>,
+    query: &str,
+) -> Result<GeocodeResult, GeocodeError> {
+    let url = search_url(query)?;
+    let body = fetch_text(client, &url).await?;
+    parse_search_response(&body)
+}
+
+/// Resolve a coordinate to a human-readable place name on Nominatim.
+pub async fn reverse(
+    client: Arc<dyn HttpClient>,
+    latitude: f64,
+    longitude: f64,
+) -> Result<String, GeocodeError> {
+    let body = fetch_text(client, &reverse_url(latitude, longitude)).await?;
+    parse_reverse_response(&body)
+}
+
+async fn fetch_text(client: Arc<dyn HttpClient>, url: &str) -> Result<String, GeocodeError> {
+    let mut response = client
+        .get(url, AsyncBody::default(), true)
+        .await
+        .map_err(|err| GeocodeError::Network(err.to_string()))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        return Err(GeocodeError::Status(status.as_u16()));
+    }
+
+    let mut body = String::new();
+    response
+        .body_mut()
+        .read_to_string(&mut body)
+        .await
+        .map_err(|err| GeocodeError::Network(err.to_string()))?;
+
+    Ok(body)
+}
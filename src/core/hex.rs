@@ -0,0 +1,57 @@
+use std::fmt::Write as _;
+
+/// Bytes shown on each line of a hex dump.
+pub const HEX_ROW_BYTES: usize = 16;
+
+/// Bytes formatted per page of the binary tag viewer. Only one page is ever
+/// formatted at a time, so multi-megabyte MakerNote blobs stay cheap to show.
+pub const HEX_PAGE_BYTES: usize = HEX_ROW_BYTES * 16;
+
+/// Format `len` bytes of `bytes` starting at `offset` as a classic hex dump:
+/// an absolute offset column, hex bytes, and printable ASCII. The window is
+/// clamped to the end of `bytes`; an offset past the end yields an empty string.
+pub fn hex_page(bytes: &[u8], offset: usize, len: usize) -> String {
+    let start = offset.min(bytes.len());
+    let end = offset.saturating_add(len).min(bytes.len());
+    let mut output = String::new();
+
+    for (row_index, row) in bytes[start..end].chunks(HEX_ROW_BYTES).enumerate() {
+        if row_index > 0 {
+            output.push('\n');
+        }
+
+        let _ = write!(output, "{:08x} ", start + row_index * HEX_ROW_BYTES);
+        for column in 0..HEX_ROW_BYTES {
+            match row.get(column) {
+                Some(byte) => {
+                    let _ = write!(output, " {byte:02x}");
+                }
+                None => output.push_str("   "),
+            }
+        }
+
+        output.push_str("  |");
+        output.extend(row.iter().map(|&byte| {
+            if byte.is_ascii_graphic() || byte == b' ' {
+                byte as char
+            } else {
+                '.'
+            }
+        }));
+        output.push('|');
+    }
+
+    output
+}
+
+/// Offset of the page `delta` pages away from `offset`, kept within `total` bytes.
+pub fn step_page(offset: usize, delta: isize, total: usize) -> usize {
+    let last_page = total.saturating_sub(1) / HEX_PAGE_BYTES * HEX_PAGE_BYTES;
+    let step = delta.unsigned_abs().saturating_mul(HEX_PAGE_BYTES);
+    let next = if delta < 0 {
+        offset.saturating_sub(step)
+    } else {
+        offset.saturating_add(step)
+    };
+    next.min(last_page)
+}
@@ -0,0 +1,246 @@
+use crate::core::formats::{self, JpegSegment};
+
+/// Prefix of every IPTC key the editor reads or writes. Only the
+/// Application record (record 2) is carried; it holds the user-facing fields.
+pub const IPTC_KEY_PREFIX: &str = "Iptc.Application2.";
+
+const APP13: u8 = 0xED;
+const PHOTOSHOP_HEADER: &[u8] = b"Photoshop 3.0\0";
+const RESOURCE_SIGNATURE: &[u8] = b"8BIM";
+/// Image resource that holds the IPTC-IIM datasets.
+const IPTC_RESOURCE_ID: u16 = 0x0404;
+const DATASET_MARKER: u8 = 0x1C;
+const ENVELOPE_RECORD: u8 = 1;
+const APPLICATION_RECORD: u8 = 2;
+/// Envelope dataset 1:90 declaring the character set; `ESC % G` is UTF-8.
+const CODED_CHARACTER_SET: u8 = 90;
+const UTF8_MARKER: &[u8] = b"\x1B%G";
+
+/// Application record datasets with their exiv2-style key names. Datasets
+/// missing here are carried under their number, e.g. `Iptc.Application2.0x41`.
+const DATASET_NAMES: &[(u8, &str)] = &[
+    (5, "ObjectName"),
+    (15, "Category"),
+    (25, "Keywords"),
+    (40, "SpecialInstructions"),
+    (55, "DateCreated"),
+    (60, "TimeCreated"),
+    (80, "Byline"),
+    (85, "BylineTitle"),
+    (90, "City"),
+    (92, "Sub-location"),
+    (95, "ProvinceState"),
+    (100, "CountryCode"),
+    (101, "CountryName"),
+    (103, "TransmissionReference"),
+    (105, "Headline"),
+    (110, "Credit"),
+    (115, "Source"),
+    (116, "Copyright"),
+    (118, "Contact"),
+    (120, "Caption"),
+    (122, "Writer"),
+];
+
+/// The IPTC key for an Application record dataset.
+pub fn dataset_key(dataset: u8) -> String {
+    match DATASET_NAMES.iter().find(|(number, _)| *number == dataset) {
+        Some((_, name)) => format!("{IPTC_KEY_PREFIX}{name}"),
+        None => format!("{IPTC_KEY_PREFIX}0x{dataset:02X}"),
+    }
+}
+
+/// The Application record dataset a key names, or `None` for keys outside
+/// the record.
+pub fn dataset_for_key(key: &str) -> Option<u8> {
+    let name = key
+        .get(..IPTC_KEY_PREFIX.len())
+        .filter(|prefix| prefix.eq_ignore_ascii_case(IPTC_KEY_PREFIX))
+        .map(|_| &key[IPTC_KEY_PREFIX.len()..])?;
+    if let Some(hex) = name.strip_prefix("0x") {
+        return u8::from_str_radix(hex, 16).ok();
+    }
+    DATASET_NAMES
+        .iter()
+        .find(|(_, known)| known.eq_ignore_ascii_case(name))
+        .map(|(number, _)| *number)
+}
+
+/// Decode the Application record of an IPTC-IIM block into `(key, values)`
+/// pairs in block order. A repeated dataset, like one keyword per dataset,
+/// yields one entry holding every value.
+pub fn decode_datasets(block: &[u8]) -> Vec<(String, Vec<String>)> {
+    let mut values: Vec<(u8, Vec<String>)> = Vec::new();
+    let mut offset = 0;
+    while let Some(&[DATASET_MARKER, record, dataset, high, low]) = block.get(offset..offset + 5) {
+        let length = usize::from(u16::from_be_bytes([high, low]));
+        // Extended lengths (top bit set) only appear on binary datasets.
+        if length & 0x8000 != 0 {
+            break;
+        }
+        let Some(data) = block.get(offset + 5..offset + 5 + length) else {
+            break;
+        };
+        offset += 5 + length;
+
+        // Record version (2:00) is structural, not something to edit.
+        if record != APPLICATION_RECORD || dataset == 0 {
+            continue;
+        }
+        let text = String::from_utf8_lossy(data)
+            .trim_end_matches('\0')
+            .to_string();
+        match values.iter_mut().find(|(number, _)| *number == dataset) {
+            Some((_, existing)) => existing.push(text),
+            None => values.push((dataset, vec![text])),
+        }
+    }
+
+    values
+        .into_iter()
+        .map(|(dataset, text)| (dataset_key(dataset), text))
+        .collect()
+}
+
+/// Encode `(key, values)` pairs as a UTF-8 IPTC-IIM block, one dataset per
+/// value. Keys outside the Application record are skipped, and values are
+/// cut to the 32 KB a dataset can hold.
+pub fn encode_datasets<'a, I>(tags: I) -> Vec<u8>
+where
+    I: IntoIterator<Item = (&'a str, Vec<String>)>,
+{
+    let mut block = Vec::new();
+    push_dataset(
+        &mut block,
+        ENVELOPE_RECORD,
+        CODED_CHARACTER_SET,
+        UTF8_MARKER,
+    );
+    push_dataset(&mut block, APPLICATION_RECORD, 0, &[0x00, 0x04]);
+
+    for (key, values) in tags {
+        let Some(dataset) = dataset_for_key(key) else {
+            log::warn!("op=write_iptc key={key} skipped=not_an_application_dataset");
+            continue;
+        };
+        for value in values {
+            push_dataset(&mut block, APPLICATION_RECORD, dataset, value.as_bytes());
+        }
+    }
+    block
+}
+
+fn push_dataset(block: &mut Vec<u8>, record: u8, dataset: u8, data: &[u8]) {
+    let mut end = data.len().min(0x7FFF);
+    // Don't cut a UTF-8 sequence in half.
+    while end < data.len() && (data[end] & 0xC0) == 0x80 {
+        end -= 1;
+    }
+    block.extend_from_slice(&[DATASET_MARKER, record, dataset]);
+    block.extend_from_slice(&(end as u16).to_be_bytes());
+    block.extend_from_slice(&data[..end]);
+}
+
+/// The IPTC-IIM block of a JPEG's Photoshop APP13 segment, if any.
+pub fn read_jpeg_iptc(bytes: &[u8]) -> Option<Vec<u8>> {
+    formats::jpeg_segments(bytes)
+        .into_iter()
+        .filter_map(|segment| photoshop_resources(bytes, &segment))
+        .flatten()
+        .find(|(id, _)| *id == IPTC_RESOURCE_ID)
+        .map(|(_, data)| data.to_vec())
+}
+
+/// Replace the IPTC block of a JPEG with `block`, or drop it when `block` is
+/// `None`. Other Photoshop resources in the APP13 segment are kept. Returns
+/// `None` for bytes that aren't a JPEG.
+pub fn replace_jpeg_iptc(bytes: &[u8], block: Option<&[u8]>) -> Option<Vec<u8>> {
+    if !bytes.starts_with(&[0xFF, 0xD8]) {
+        return None;
+    }
+    let segments = formats::jpeg_segments(bytes);
+    let existing = segments
+        .iter()
+        .find(|segment| photoshop_resources(bytes, segment).is_some());
+
+    let mut payload = PHOTOSHOP_HEADER.to_vec();
+    if let Some(resources) = existing.and_then(|segment| photoshop_resources(bytes, segment)) {
+        for (id, data) in resources.filter(|(id, _)| *id != IPTC_RESOURCE_ID) {
+            push_resource(&mut payload, id, data);
+        }
+    }
+    if let Some(block) = block {
+        push_resource(&mut payload, IPTC_RESOURCE_ID, block);
+    }
+    let keep_segment = payload.len() > PHOTOSHOP_HEADER.len();
+    if keep_segment && payload.len() + 2 > usize::from(u16::MAX) {
+        log::warn!("op=write_iptc skipped=block_too_large");
+        return None;
+    }
+
+    let mut segment = Vec::new();
+    if keep_segment {
+        segment.extend_from_slice(&[0xFF, APP13]);
+        segment.extend_from_slice(&((payload.len() + 2) as u16).to_be_bytes());
+        segment.extend_from_slice(&payload);
+    }
+
+    // Put the segment where the old one was, or after the APPn run at the head.
+    let (start, end) = match existing {
+        Some(existing) => (existing.start, existing.end),
+        None => {
+            let insert_at = segments
+                .iter()
+                .take_while(|segment| (0xE0..=0xEF).contains(&segment.marker))
+                .last()
+                .map_or(2, |segment| segment.end);
+            (insert_at, insert_at)
+        }
+    };
+    let mut rewritten = Vec::with_capacity(bytes.len() + segment.len());
+    rewritten.extend_from_slice(&bytes[..start]);
+    rewritten.extend_from_slice(&segment);
+    rewritten.extend_from_slice(&bytes[end..]);
+    Some(rewritten)
+}
+
+/// The `(id, data)` image resources of a Photoshop APP13 segment, or `None`
+/// for any other segment.
+fn photoshop_resources<'a>(
+    bytes: &'a [u8],
+    segment: &JpegSegment,
+) -> Option<impl Iterator<Item = (u16, &'a [u8])>> {
+    if segment.marker != APP13 {
+        return None;
+    }
+    let resources = segment.payload(bytes).strip_prefix(PHOTOSHOP_HEADER)?;
+
+    let mut offset = 0;
+    Some(std::iter::from_fn(move || {
+        let header = resources.get(offset..offset + 7)?;
+        if &header[..4] != RESOURCE_SIGNATURE {
+            return None;
+        }
+        let id = u16::from_be_bytes([header[4], header[5]]);
+        // Pascal-string name, padded so the length byte plus text is even.
+        let name_len = usize::from(header[6]);
+        let size_at = offset + 6 + (name_len + 2) / 2 * 2;
+        let size = resources.get(size_at..size_at + 4)?;
+        let size = u32::from_be_bytes([size[0], size[1], size[2], size[3]]) as usize;
+        let data = resources.get(size_at + 4..size_at + 4 + size)?;
+        offset = size_at + 4 + size + size % 2;
+        Some((id, data))
+    }))
+}
+
+fn push_resource(payload: &mut Vec<u8>, id: u16, data: &[u8]) {
+    payload.extend_from_slice(RESOURCE_SIGNATURE);
+    payload.extend_from_slice(&id.to_be_bytes());
+    // Empty name: a zero length byte padded to two bytes.
+    payload.extend_from_slice(&[0, 0]);
+    payload.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    payload.extend_from_slice(data);
+    if data.len() % 2 == 1 {
+        payload.push(0);
+    }
+}
@@ -1,18 +1,152 @@
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 use std::fmt;
 use std::fs;
+use std::io::{ErrorKind, Write};
 use std::path::{Path, PathBuf};
-use std::time::UNIX_EPOCH;
+use std::time::{Duration, UNIX_EPOCH};
 
+use chrono::{DateTime, Local};
 use little_exif::exif_tag::ExifTag;
+use little_exif::filetype::FileExtension;
+use little_exif::ifd::ExifTagGroup;
 use little_exif::metadata::Metadata as ExifMetadata;
+use serde::{Deserialize, Serialize};
+
+use crate::core::xmp::{self, XmpValue};
+use crate::core::{formats, iptc};
+use crate::models::{
+    format_rational_for_key, is_list_tag, normalize_exif_datetime, ExifIfd, ImageFormat,
+    MetadataTag, MetadataWarning, PhotoMetadata, PresetRule, RawExifFormat, RawExifType,
+    StripPreset, TagCategory, TagValue, ThumbnailData, ORIENTATION_KEY,
+};
+
+const SIDECAR_SUFFIX: &str = ".exif_editor.json";
+const BACKUP_EXTENSION: &str = "orig";
+
+/// The single tag the editor shows a photo's GPS position as. Reading merges
+/// the separate latitude, longitude and altitude fields into it and writing
+/// splits it back out.
+pub const GPS_COORDINATES_KEY: &str = "Exif.GPSInfo.GPSCoordinates";
+
+/// Separate GPS position fields that only ever live inside
+/// [`GPS_COORDINATES_KEY`] once they reach a [`PhotoMetadata`].
+const GPS_PART_KEYS: [&str; 6] = [
+    "Exif.GPSInfo.GPSLatitude",
+    "Exif.GPSInfo.GPSLatitudeRef",
+    "Exif.GPSInfo.GPSLongitude",
+    "Exif.GPSInfo.GPSLongitudeRef",
+    "Exif.GPSInfo.GPSAltitude",
+    "Exif.GPSInfo.GPSAltitudeRef",
+];
+
+/// Sidecar layout version written by this build. Version 1 sidecars carry no
+/// `version` field; their tags have no `order` and may hold the GPS position
+/// fields as separate tags. See [`MetadataEngine::migrate_sidecar`].
+pub const SIDECAR_VERSION: u32 = 2;
+
+/// On-disk sidecar layout: the metadata fields at the top level, plus the
+/// photo's size and modification time as of the save that wrote it. Older
+/// sidecars have no `source`.
+#[derive(Serialize, Deserialize)]
+struct Sidecar<M> {
+    #[serde(default = "first_sidecar_version")]
+    version: u32,
+    #[serde(flatten)]
+    metadata: M,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    source: Option<FileStamp>,
+}
+
+fn first_sidecar_version() -> u32 {
+    1
+}
+
+/// A file's size and modification time, used to tell whether it changed
+/// since it was last seen.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub(crate) struct FileStamp {
+    pub(crate) size: u64,
+    pub(crate) modified_ms: u64,
+}
 
-use crate::models::{MetadataTag, PhotoMetadata, PresetRule, StripPreset, TagCategory, TagValue};
+impl FileStamp {
+    pub(crate) fn of(path: &Path) -> Option<Self> {
+        let meta = fs::metadata(path).ok()?;
+        let modified = meta.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+        Some(Self {
+            size: meta.len(),
+            modified_ms: u64::try_from(modified.as_millis()).ok()?,
+        })
+    }
+}
+
+/// How [`MetadataEngine::merge_metadata`] treats a tag the photo already has.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum MergeStrategy {
+    /// Imported values replace existing ones.
+    #[default]
+    OverwriteAll,
+    /// Imported values only replace existing tags whose value is blank.
+    FillEmpty,
+    /// Existing tags win; only tags the photo lacks are added.
+    KeepExisting,
+}
+
+impl MergeStrategy {
+    pub const ALL: [Self; 3] = [Self::OverwriteAll, Self::FillEmpty, Self::KeepExisting];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::OverwriteAll => "Overwrite all",
+            Self::FillEmpty => "Fill empty only",
+            Self::KeepExisting => "Keep existing",
+        }
+    }
+
+    /// The strategy after this one in [`Self::ALL`], wrapping around.
+    pub fn next(self) -> Self {
+        let index = Self::ALL
+            .iter()
+            .position(|&strategy| strategy == self)
+            .unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+}
+
+/// When [`MetadataEngine::read_with_policy`] takes a photo's sidecar over the
+/// metadata embedded in the file.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum SidecarPolicy {
+    /// Use the sidecar unless the photo was modified after it, e.g. by
+    /// another app, in which case the embedded metadata wins.
+    #[default]
+    Newest,
+    /// Use the sidecar whenever one exists.
+    Always,
+    /// Never read sidecars.
+    Ignore,
+}
+
+impl SidecarPolicy {
+    /// The policy for a session that does or doesn't keep sidecars.
+    pub fn for_sidecars(enabled: bool) -> Self {
+        if enabled {
+            Self::Newest
+        } else {
+            Self::Ignore
+        }
+    }
+}
 
 #[derive(Debug)]
 pub enum MetadataError {
     FileNotFound(PathBuf),
     InvalidTagKey(String),
+    /// A single GPS position field set on a photo without coordinates.
+    PartialGps(String),
+    /// A value that doesn't fit the EXIF type the tag was read as.
+    InvalidTagValue(String),
+    UnsupportedFormat(PathBuf),
     Io(std::io::Error),
     Serialization(serde_json::Error),
 }
@@ -22,6 +156,16 @@ impl fmt::Display for MetadataError {
         match self {
             Self::FileNotFound(path) => write!(f, "file not found: {}", path.display()),
             Self::InvalidTagKey(key) => write!(f, "invalid metadata tag key: {key}"),
+            Self::PartialGps(key) => write!(
+                f,
+                "{key} needs existing GPS coordinates; set the full position instead"
+            ),
+            Self::InvalidTagValue(key) => {
+                write!(f, "value does not fit the stored type of {key}")
+            }
+            Self::UnsupportedFormat(path) => {
+                write!(f, "format not writable: {}", path.display())
+            }
             Self::Io(err) => write!(f, "io error: {err}"),
             Self::Serialization(err) => write!(f, "metadata serialization error: {err}"),
         }
@@ -48,56 +192,335 @@ pub struct MetadataEngine;
 
 impl MetadataEngine {
     pub fn read(path: &Path) -> Result<PhotoMetadata> {
+        Self::read_with_policy(path, SidecarPolicy::default())
+    }
+
+    /// Like [`Self::read`], with `policy` deciding whether an existing
+    /// sidecar is used or the metadata comes from the file itself.
+    pub fn read_with_policy(path: &Path, policy: SidecarPolicy) -> Result<PhotoMetadata> {
+        Self::read_with_warnings(path, policy).map(|(metadata, _)| metadata)
+    }
+
+    /// Like [`Self::read_with_policy`], also reporting why the file's own
+    /// metadata couldn't be used when the result had to be synthesized from
+    /// the file instead.
+    pub fn read_with_warnings(
+        path: &Path,
+        policy: SidecarPolicy,
+    ) -> Result<(PhotoMetadata, Vec<MetadataWarning>)> {
         if !path.exists() {
             return Err(MetadataError::FileNotFound(path.to_path_buf()));
         }
 
         let sidecar = Self::sidecar_path(path);
-        if sidecar.exists() {
-            let contents = fs::read_to_string(&sidecar)?;
-            let mut metadata: PhotoMetadata = serde_json::from_str(&contents)?;
-            metadata.update_summary_fields();
-            return Ok(metadata);
+        let use_sidecar = sidecar.exists()
+            && match policy {
+                SidecarPolicy::Always => true,
+                SidecarPolicy::Newest => Self::sidecar_is_current(path, &sidecar),
+                SidecarPolicy::Ignore => false,
+            };
+        // A sidecar that can't be used is passed over for the file's own
+        // metadata rather than failing the whole read.
+        let mut warnings = Vec::new();
+        if use_sidecar {
+            match Self::read_sidecar(&sidecar) {
+                Ok(metadata) => {
+                    log::info!("op=read source=sidecar path={}", path.display());
+                    return Ok((metadata, Vec::new()));
+                }
+                Err(err) => {
+                    log::warn!(
+                        "op=read path={} sidecar=ignored error={err}",
+                        path.display()
+                    );
+                    warnings.push(MetadataWarning::SidecarIgnored(err.to_string()));
+                }
+            }
         }
 
-        match Self::read_exif_from_file(path) {
-            Some(metadata) if !metadata.exif_tags.is_empty() => Ok(metadata),
-            _ => Self::default_metadata_for_path(path),
-        }
+        let warning = match Self::read_exif_from_file(path) {
+            Ok(mut metadata) if !metadata.exif_tags.is_empty() => {
+                Self::read_iptc_from_file(path, &mut metadata);
+                Self::read_xmp_from_file(path, &mut metadata);
+                log::info!(
+                    "op=read source=exif path={} tags={}",
+                    path.display(),
+                    metadata.total_tag_count()
+                );
+                return Ok((metadata, warnings));
+            }
+            Ok(_) => MetadataWarning::NoExif,
+            Err(warning) => warning,
+        };
+        log::info!(
+            "op=read source=default path={} warning=\"{warning}\"",
+            path.display()
+        );
+        let mut metadata = Self::default_metadata_for_path(path)?;
+        Self::read_iptc_from_file(path, &mut metadata);
+        Self::read_xmp_from_file(path, &mut metadata);
+        warnings.push(warning);
+        Ok((metadata, warnings))
+    }
+
+    fn read_sidecar(sidecar: &Path) -> Result<PhotoMetadata> {
+        let contents = fs::read_to_string(sidecar)?;
+        let Sidecar {
+            version,
+            mut metadata,
+            ..
+        } = serde_json::from_str::<Sidecar<PhotoMetadata>>(&contents)?;
+        Self::migrate_sidecar(&mut metadata, version);
+        metadata.update_summary_fields();
+        Ok(metadata)
     }
 
     pub fn write(path: &Path, metadata: &PhotoMetadata) -> Result<()> {
+        Self::write_with_sidecars(path, metadata, true)
+    }
+
+    /// Like [`Self::write`]; with `sidecars` off no sidecar is written, and
+    /// one left from an earlier save is removed since it would now be stale.
+    pub fn write_with_sidecars(
+        path: &Path,
+        metadata: &PhotoMetadata,
+        sidecars: bool,
+    ) -> Result<()> {
         if !path.exists() {
             return Err(MetadataError::FileNotFound(path.to_path_buf()));
         }
+        // Privileged processes can write through the read-only flag, so check
+        // it here rather than relying on the write to fail.
+        if fs::metadata(path)?.permissions().readonly() {
+            let err = std::io::Error::new(
+                ErrorKind::PermissionDenied,
+                format!("{} is read-only", path.display()),
+            );
+            log::error!("op=write path={} error={err}", path.display());
+            return Err(MetadataError::Io(err));
+        }
 
-        Self::write_exif_to_file(path, metadata);
+        let embedded = Self::write_iptc_to_file(path, metadata, sidecars)
+            .and_then(|()| Self::write_exif_to_file(path, metadata, sidecars))
+            .and_then(|()| Self::write_xmp_to_file(path, metadata, sidecars));
+        if let Err(err) = embedded {
+            log::error!("op=write path={} error={err}", path.display());
+            return Err(err);
+        }
 
         let sidecar = Self::sidecar_path(path);
+        if !sidecars {
+            if sidecar.exists() {
+                fs::remove_file(&sidecar)?;
+            }
+            log::info!(
+                "op=write path={} tags={} sidecar=false",
+                path.display(),
+                metadata.total_tag_count()
+            );
+            return Ok(());
+        }
+
         if let Some(parent) = sidecar.parent() {
             fs::create_dir_all(parent)?;
         }
 
-        let encoded = serde_json::to_string_pretty(metadata)?;
-        fs::write(sidecar, encoded)?;
+        let encoded = serde_json::to_string_pretty(&Sidecar {
+            version: SIDECAR_VERSION,
+            metadata,
+            source: FileStamp::of(path),
+        })?;
+        if let Err(err) = write_atomically(&sidecar, encoded) {
+            log::error!("op=write path={} error={err}", path.display());
+            return Err(err.into());
+        }
+
+        log::info!(
+            "op=write path={} tags={}",
+            path.display(),
+            metadata.total_tag_count()
+        );
         Ok(())
     }
 
     pub fn apply_preset(path: &Path, preset: &StripPreset, output: &Path) -> Result<PhotoMetadata> {
+        let Some(staging) = Self::stage_output(path, output)? else {
+            return Self::write_preset_output(path, preset, output, true);
+        };
+        let written = Self::write_preset_output(path, preset, &staging, true)
+            .and_then(|metadata| Self::finish_staged_output(&staging, output).map(|()| metadata));
+        if written.is_err() {
+            Self::discard_staged_output(&staging);
+        }
+        written
+    }
+
+    /// Where [`Self::backup_original`] keeps a photo's untouched bytes:
+    /// `photo.jpg.orig`.
+    pub fn backup_path(path: &Path) -> PathBuf {
+        let base_name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| String::from("photo"));
+
+        path.with_file_name(format!("{base_name}.{BACKUP_EXTENSION}"))
+    }
+
+    /// Copy `path` to [`Self::backup_path`] unless a backup is already there,
+    /// so it always holds the file as it was before the first save. The copy
+    /// goes through a temp file renamed into place, since a partial backup
+    /// would never be replaced. Returns whether a backup was made.
+    pub fn backup_original(path: &Path) -> Result<bool> {
         if !path.exists() {
             return Err(MetadataError::FileNotFound(path.to_path_buf()));
         }
 
-        if path != output {
-            if let Some(parent) = output.parent() {
-                fs::create_dir_all(parent)?;
+        let backup = Self::backup_path(path);
+        if backup.exists() {
+            return Ok(false);
+        }
+        write_atomically(&backup, fs::read(path)?)?;
+        log::info!(
+            "op=backup path={} backup={}",
+            path.display(),
+            backup.display()
+        );
+        Ok(true)
+    }
+
+    /// First step of [`Self::apply_preset`]: copy `path` into a fresh hidden
+    /// file next to `output` for the preset to be written into, so `output`
+    /// only changes once the export is whole. Returns `None` when `output` is
+    /// `path` itself (see [`Self::is_same_file`]), which is written in place.
+    pub fn stage_output(path: &Path, output: &Path) -> Result<Option<PathBuf>> {
+        if !path.exists() {
+            return Err(MetadataError::FileNotFound(path.to_path_buf()));
+        }
+        if Self::is_same_file(path, output) {
+            return Ok(None);
+        }
+        if fs::metadata(output).is_ok_and(|meta| meta.permissions().readonly()) {
+            return Err(MetadataError::Io(std::io::Error::new(
+                ErrorKind::PermissionDenied,
+                format!("{} is read-only", output.display()),
+            )));
+        }
+
+        if let Some(parent) = output.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let (staging, mut file) = create_staging_file(output)?;
+        let copied =
+            fs::File::open(path).and_then(|mut source| std::io::copy(&mut source, &mut file));
+        drop(file);
+        if let Err(err) = copied {
+            let _ = fs::remove_file(&staging);
+            return Err(err.into());
+        }
+        Ok(Some(staging))
+    }
+
+    /// Last step of [`Self::apply_preset`]: move a finished export from its
+    /// staging file over `output`, along with the sidecars written next to it.
+    /// Sidecars the export didn't produce are dropped from `output` the way
+    /// [`Self::write_with_sidecars`] drops them; a foreign `.xmp` stays.
+    pub fn finish_staged_output(staging: &Path, output: &Path) -> Result<()> {
+        let xmp_sidecar = Self::xmp_sidecar_path(staging);
+        let output_xmp = Self::xmp_sidecar_path(output);
+        let foreign_xmp = output_xmp.exists()
+            && !fs::read_to_string(&output_xmp).is_ok_and(|packet| xmp::is_own_packet(&packet));
+        if foreign_xmp && xmp_sidecar.exists() {
+            // Same rule as `write_xmp_to_file`: the other program's sidecar
+            // stays, and the export's XMP tags live on in its JSON sidecar.
+            if !Self::sidecar_path(staging).exists() {
+                return Err(MetadataError::Io(std::io::Error::new(
+                    ErrorKind::AlreadyExists,
+                    format!("{} belongs to another program", output_xmp.display()),
+                )));
             }
-            fs::copy(path, output)?;
+            fs::remove_file(&xmp_sidecar)?;
         }
 
-        let mut metadata = Self::read(path)?;
+        fs::rename(staging, output)?;
+
+        let sidecar = Self::sidecar_path(staging);
+        let output_sidecar = Self::sidecar_path(output);
+        if sidecar.exists() {
+            fs::rename(&sidecar, &output_sidecar)?;
+        } else if output_sidecar.exists() {
+            fs::remove_file(&output_sidecar)?;
+        }
+
+        if xmp_sidecar.exists() {
+            fs::rename(&xmp_sidecar, &output_xmp)?;
+        } else if output_xmp.exists() && !foreign_xmp {
+            fs::remove_file(&output_xmp)?;
+        }
+
+        sync_parent_dir(output);
+        log::info!(
+            "op=export staging={} path={}",
+            staging.display(),
+            output.display()
+        );
+        Ok(())
+    }
+
+    /// Remove an export that never finished: its staging file and whatever
+    /// sidecars were written next to it. The output path is never touched.
+    pub fn discard_staged_output(staging: &Path) {
+        for path in [
+            staging.to_path_buf(),
+            Self::sidecar_path(staging),
+            Self::xmp_sidecar_path(staging),
+        ] {
+            if path.exists() {
+                if let Err(err) = fs::remove_file(&path) {
+                    log::warn!("op=discard path={} error={err}", path.display());
+                }
+            }
+        }
+    }
+
+    /// Copy `path` to `output` with `metadata` written into the copy. The copy
+    /// is assembled in a staging file (see [`Self::stage_output`]), so a write
+    /// that fails leaves nothing at `output` still carrying the original's
+    /// metadata.
+    pub fn export_with_metadata(
+        path: &Path,
+        output: &Path,
+        metadata: &PhotoMetadata,
+        sidecars: bool,
+    ) -> Result<()> {
+        let Some(staging) = Self::stage_output(path, output)? else {
+            return Self::write_with_sidecars(output, metadata, sidecars);
+        };
+        let written = Self::write_with_sidecars(&staging, metadata, sidecars)
+            .and_then(|()| Self::finish_staged_output(&staging, output));
+        if written.is_err() {
+            Self::discard_staged_output(&staging);
+        }
+        written
+    }
+
+    /// Whether `a` and `b` name the same file however they are spelled: `.`
+    /// and `..` segments and symlinks are resolved first. A path that doesn't
+    /// exist yet is resolved through its folder.
+    pub fn is_same_file(a: &Path, b: &Path) -> bool {
+        a == b || resolve_path(a).is_some_and(|a| resolve_path(b) == Some(a))
+    }
+
+    /// Second half of [`Self::apply_preset`]: write `path`'s metadata, filtered
+    /// through `preset`, to the already-copied `output`.
+    pub fn write_preset_output(
+        path: &Path,
+        preset: &StripPreset,
+        output: &Path,
+        sidecars: bool,
+    ) -> Result<PhotoMetadata> {
+        let mut metadata = Self::read_with_policy(path, SidecarPolicy::for_sidecars(sidecars))?;
         Self::apply_preset_to_metadata(&mut metadata, preset);
-        Self::write(output, &metadata)?;
+        Self::write_with_sidecars(output, &metadata, sidecars)?;
         Ok(metadata)
     }
 
@@ -108,18 +531,172 @@ impl MetadataEngine {
         }
 
         let mut metadata = Self::read(path)?;
+        Self::check_tag_edit(&metadata, key, value)?;
         Self::set_tag_in_metadata(&mut metadata, key, value.clone());
         Self::write(path, &metadata)?;
         Ok(metadata)
     }
 
+    /// Where [`Self::write`] keeps a photo's XMP tags: `photo.jpg.xmp`.
+    pub fn xmp_sidecar_path(path: &Path) -> PathBuf {
+        let base_name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| String::from("photo"));
+
+        path.with_file_name(format!("{base_name}.xmp"))
+    }
+
     pub fn sidecar_path(path: &Path) -> PathBuf {
         let base_name = path
             .file_name()
             .map(|name| name.to_string_lossy().to_string())
             .unwrap_or_else(|| String::from("photo"));
 
-        path.with_file_name(format!("{base_name}.exif_editor.json"))
+        path.with_file_name(format!("{base_name}{SIDECAR_SUFFIX}"))
+    }
+
+    /// Hidden sibling a save writes first and then renames over `path`:
+    /// `.photo.jpg.tmp`. Import skips it like any other hidden file.
+    pub fn temp_path(path: &Path) -> PathBuf {
+        let base_name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| String::from("photo"));
+
+        path.with_file_name(format!(".{base_name}.tmp"))
+    }
+
+    /// Whether `sidecar` was written no earlier than `path` was last modified.
+    /// Saving writes the photo before its sidecar, so this only fails once
+    /// something else has touched the photo. Unknown times count as current.
+    fn sidecar_is_current(path: &Path, sidecar: &Path) -> bool {
+        let modified = |path: &Path| fs::metadata(path).and_then(|meta| meta.modified()).ok();
+        match (modified(path), modified(sidecar)) {
+            (Some(photo), Some(sidecar_time)) if photo > sidecar_time => {
+                log::info!("op=read path={} sidecar=stale", path.display());
+                false
+            }
+            _ => true,
+        }
+    }
+
+    /// Bring metadata read from a `version` sidecar up to the current model.
+    fn migrate_sidecar(metadata: &mut PhotoMetadata, version: u32) {
+        if version > SIDECAR_VERSION {
+            log::warn!("op=read sidecar_version={version} supported={SIDECAR_VERSION}");
+        }
+        if version < 2 {
+            for (order, tag) in (0..).zip(metadata.all_tags_mut()) {
+                tag.order = order;
+            }
+
+            let mut parts = metadata
+                .exif_tags
+                .iter()
+                .filter_map(|tag| Some((gps_part_index(&tag.key)?, tag.value.clone(), tag.order)))
+                .collect::<Vec<_>>();
+            // Values before their references, so a hemisphere flips the right sign.
+            parts.sort_by_key(|(part, _, _)| *part);
+            let Some(first_order) = parts.iter().map(|(_, _, order)| *order).min() else {
+                return;
+            };
+            metadata
+                .exif_tags
+                .retain(|tag| gps_part_index(&tag.key).is_none());
+            // Like a file read, a position needs both its latitude and its
+            // longitude; a lone field has nothing to fold into and is dropped.
+            let has_both_halves = [0, 2]
+                .into_iter()
+                .all(|half| parts.iter().any(|(part, _, _)| *part == half));
+            if has_both_halves && metadata.find_tag(GPS_COORDINATES_KEY).is_none() {
+                let mut tag = MetadataTag::new(
+                    GPS_COORDINATES_KEY,
+                    "GPS Coordinates",
+                    TagValue::Gps(0.0, 0.0, None),
+                    TagCategory::Location,
+                );
+                tag.order = first_order;
+                metadata.exif_tags.push(tag);
+            }
+            for (part, value, _) in parts {
+                let Some(folded) = fold_gps_part(metadata, part, &value) else {
+                    log::warn!(
+                        "op=migrate_sidecar key={} error=no GPS coordinates",
+                        GPS_PART_KEYS[part]
+                    );
+                    continue;
+                };
+                let existing = metadata
+                    .exif_tags
+                    .iter_mut()
+                    .find(|tag| tag.key.eq_ignore_ascii_case(GPS_COORDINATES_KEY));
+                match existing {
+                    Some(tag) => tag.value = folded,
+                    None => {
+                        let mut tag = MetadataTag::new(
+                            GPS_COORDINATES_KEY,
+                            "GPS Coordinates",
+                            folded,
+                            TagCategory::Location,
+                        );
+                        tag.order = first_order;
+                        metadata.exif_tags.push(tag);
+                    }
+                }
+            }
+            metadata.exif_tags.sort_by_key(|tag| tag.order);
+        }
+    }
+
+    /// Whether `path`'s sidecar is out of date, or `None` when it has none.
+    /// Sidecars record the photo's size and modification time when they are
+    /// written; older ones without that record fall back to comparing mtimes.
+    /// A sidecar that can't be parsed counts as stale.
+    pub fn sidecar_is_stale(path: &Path) -> Option<bool> {
+        let sidecar = Self::sidecar_path(path);
+        if !sidecar.exists() {
+            return None;
+        }
+        let parsed = fs::read_to_string(&sidecar)
+            .ok()
+            .and_then(|contents| serde_json::from_str::<Sidecar<PhotoMetadata>>(&contents).ok());
+        let stale = match parsed {
+            Some(Sidecar {
+                source: Some(stamp),
+                ..
+            }) => FileStamp::of(path) != Some(stamp),
+            Some(Sidecar { source: None, .. }) => !Self::sidecar_is_current(path, &sidecar),
+            None => true,
+        };
+        Some(stale)
+    }
+
+    pub fn is_sidecar(path: &Path) -> bool {
+        path.file_name()
+            .is_some_and(|name| name.to_string_lossy().ends_with(SIDECAR_SUFFIX))
+    }
+
+    /// Whether `path` is named like a [`Self::backup_path`].
+    pub fn is_backup(path: &Path) -> bool {
+        path.extension().is_some_and(|ext| ext == BACKUP_EXTENSION)
+    }
+
+    /// Decode the JPEG thumbnail embedded in the EXIF IFD1 (`ThumbnailOffset` /
+    /// `ThumbnailLength`), which is far cheaper than decoding the full image.
+    pub fn extract_thumbnail(path: &Path) -> Option<ThumbnailData> {
+        let tiff = formats::read_jpeg_exif(path)?;
+        let (offset, length) = embedded_thumbnail_location(&tiff)?;
+        let jpeg = tiff.get(offset..offset.checked_add(length)?)?;
+
+        let image = image::load_from_memory_with_format(jpeg, image::ImageFormat::Jpeg)
+            .ok()?
+            .to_rgba8();
+        Some(ThumbnailData {
+            width: image.width(),
+            height: image.height(),
+            pixels: image.into_raw(),
+        })
     }
 
     pub fn apply_preset_to_metadata(metadata: &mut PhotoMetadata, preset: &StripPreset) {
@@ -129,7 +706,60 @@ impl MetadataEngine {
         metadata.update_summary_fields();
     }
 
+    /// Whether [`Self::set_tag_in_metadata`] can apply `value` to `tag_key`.
+    /// A single GPS position field needs coordinates to fold into; on a photo
+    /// without them the other half of the position would have to be made up.
+    /// A tag the editor has no name for is written back in the EXIF type it
+    /// was read as, so the value has to fit that type.
+    pub fn check_tag_edit(metadata: &PhotoMetadata, tag_key: &str, value: &TagValue) -> Result<()> {
+        if let Some(part) = gps_part_index(tag_key) {
+            if fold_gps_part(metadata, part, value).is_none() {
+                return Err(MetadataError::PartialGps(tag_key.to_string()));
+            }
+        }
+        if let Some(tag) = metadata.find_tag(tag_key) {
+            if let Some(raw) = tag.raw_type {
+                let edited = MetadataTag {
+                    value: value.clone(),
+                    ..tag.clone()
+                };
+                if unknown_tag_to_exif(&edited, raw).is_none() {
+                    return Err(MetadataError::InvalidTagValue(tag_key.to_string()));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Set `tag_key` to `value`, adding the tag if it is missing. A GPS
+    /// position field is folded into [`GPS_COORDINATES_KEY`] instead, and any
+    /// stray position fields are dropped, so a photo never shows its location
+    /// twice. A position field the photo has no coordinates for is ignored;
+    /// see [`Self::check_tag_edit`].
     pub fn set_tag_in_metadata(metadata: &mut PhotoMetadata, tag_key: &str, value: TagValue) {
+        let value = match value {
+            TagValue::Text(text) | TagValue::Unknown(text) if is_list_tag(tag_key) => {
+                TagValue::text_list(&text)
+            }
+            value => value,
+        };
+        let (tag_key, value) = match gps_part_index(tag_key) {
+            Some(part) => match fold_gps_part(metadata, part, &value) {
+                Some(folded) => (GPS_COORDINATES_KEY, folded),
+                None => {
+                    log::warn!("op=set_tag key={tag_key} error=no GPS coordinates");
+                    return;
+                }
+            },
+            None => (tag_key, value),
+        };
+        if tag_key.eq_ignore_ascii_case(GPS_COORDINATES_KEY) {
+            Self::retain_all(metadata, |tag| gps_part_index(&tag.key).is_none());
+        }
+
+        if metadata.find_tag(tag_key).map(|tag| &tag.value) != Some(&value) {
+            metadata.record_provenance(tag_key);
+        }
         if Self::update_existing_tag(&mut metadata.exif_tags, tag_key, &value)
             || Self::update_existing_tag(&mut metadata.iptc_tags, tag_key, &value)
             || Self::update_existing_tag(&mut metadata.xmp_tags, tag_key, &value)
@@ -145,9 +775,18 @@ impl MetadataEngine {
             category: infer_category_from_key(tag_key),
             editable: true,
             marked_for_removal: false,
+            order: metadata.next_tag_order(),
+            raw_type: None,
         };
 
-        metadata.exif_tags.push(tag);
+        let tags = if tag_key.starts_with("Iptc.") {
+            &mut metadata.iptc_tags
+        } else if tag_key.starts_with("Xmp.") {
+            &mut metadata.xmp_tags
+        } else {
+            &mut metadata.exif_tags
+        };
+        tags.push(tag);
         metadata.update_summary_fields();
     }
 
@@ -181,14 +820,59 @@ impl MetadataEngine {
         before.saturating_sub(metadata.total_tag_count())
     }
 
-    fn read_exif_from_file(path: &Path) -> Option<PhotoMetadata> {
-        let exif = ExifMetadata::new_from_path(path).ok()?;
+    /// Drop text tags whose value is empty or only whitespace. Returns how many were removed.
+    pub fn remove_empty_tags(metadata: &mut PhotoMetadata) -> usize {
+        let before = metadata.total_tag_count();
+        Self::retain_all(metadata, |tag| !is_blank(&tag.value));
+        metadata.update_summary_fields();
+        before.saturating_sub(metadata.total_tag_count())
+    }
+
+    /// Apply an imported tag set to `dest`, resolving tags it already has by
+    /// `strategy`. Returns how many tags were written.
+    pub fn merge_metadata(
+        dest: &mut PhotoMetadata,
+        src: &[(String, TagValue)],
+        strategy: MergeStrategy,
+    ) -> usize {
+        let mut applied = 0;
+        for (key, value) in src {
+            let take = match dest.find_tag(key) {
+                None => true,
+                Some(existing) => match strategy {
+                    MergeStrategy::OverwriteAll => true,
+                    MergeStrategy::FillEmpty => is_blank(&existing.value),
+                    MergeStrategy::KeepExisting => false,
+                },
+            };
+            if take {
+                Self::set_tag_in_metadata(dest, key, value.clone());
+                applied += 1;
+            }
+        }
+        applied
+    }
+
+    /// The EXIF embedded in `path`, or why there is none to show.
+    fn read_exif_from_file(path: &Path) -> std::result::Result<PhotoMetadata, MetadataWarning> {
+        let exif = match Self::read_single_exif_jpeg(path) {
+            Some(bytes) => ExifMetadata::new_from_vec(&bytes, FileExtension::JPEG),
+            None => ExifMetadata::new_from_path(path),
+        }
+        .map_err(|err| match err.kind() {
+            // little_exif reports a damaged container or block as invalid data,
+            // and a file without any metadata block as a plain error.
+            ErrorKind::InvalidData | ErrorKind::UnexpectedEof => {
+                MetadataWarning::Unreadable(err.to_string())
+            }
+            _ => MetadataWarning::NoExif,
+        })?;
 
         // Collect all tags from the metadata iterator
         let tags: Vec<&ExifTag> = (&exif).into_iter().collect();
 
         if tags.is_empty() {
-            return None;
+            return Err(MetadataWarning::NoExif);
         }
 
         let mut exif_tags = Vec::new();
@@ -198,11 +882,22 @@ impl MetadataEngine {
         let mut gps_lon_dms: Option<(f64, f64, f64)> = None;
         let mut gps_alt_ref: Option<u8> = None;
         let mut gps_alt: Option<f64> = None;
+        // Where the first GPS field sat, so the merged coordinate tag takes its place.
+        let mut gps_position: Option<u32> = None;
 
-        for tag in tags {
+        for (position, tag) in (0u32..).zip(tags) {
             let hex = tag.as_u16();
 
             // Collect GPS sub-IFD fields from native GPS variants
+            if matches!(
+                tag,
+                ExifTag::GPSLatitudeRef(_)
+                    | ExifTag::GPSLatitude(_)
+                    | ExifTag::GPSLongitudeRef(_)
+                    | ExifTag::GPSLongitude(_)
+            ) {
+                gps_position.get_or_insert(position);
+            }
             match tag {
                 ExifTag::GPSLatitudeRef(s) => {
                     gps_lat_ref = Some(s.trim_end_matches('\0').to_string());
@@ -231,7 +926,13 @@ impl MetadataEngine {
                     continue;
                 }
                 ExifTag::GPSAltitude(rats) if !rats.is_empty() => {
-                    gps_alt = Some(rats[0].clone().into());
+                    // A zero denominator would read as NaN; sea level is the
+                    // closest honest reading.
+                    gps_alt = Some(if rats[0].denominator == 0 {
+                        0.0
+                    } else {
+                        rats[0].clone().into()
+                    });
                     continue;
                 }
                 _ => {}
@@ -251,7 +952,8 @@ impl MetadataEngine {
                 continue;
             }
 
-            if let Some(converted) = convert_exif_tag(tag, hex) {
+            if let Some(mut converted) = convert_exif_tag(tag, hex) {
+                converted.order = position;
                 exif_tags.push(converted);
             }
         }
@@ -268,14 +970,24 @@ impl MetadataEngine {
                 lon = -lon;
             }
 
-            let altitude = gps_alt.map(|a| if gps_alt_ref == Some(1) { -a } else { a });
+            // Sea level reads as 0 even under a below-sea-level reference.
+            let altitude = gps_alt.map(|a| {
+                if gps_alt_ref == Some(1) && a > 0.0 {
+                    -a
+                } else {
+                    a
+                }
+            });
 
-            exif_tags.push(MetadataTag::new(
-                "Exif.GPSInfo.GPSCoordinates",
+            let mut coordinates = MetadataTag::new(
+                GPS_COORDINATES_KEY,
                 "GPS Coordinates",
                 TagValue::Gps(lat, lon, altitude),
                 TagCategory::Location,
-            ));
+            );
+            coordinates.order = gps_position.unwrap_or_default();
+            exif_tags.push(coordinates);
+            exif_tags.sort_by_key(|tag| tag.order);
         }
 
         let mut metadata = PhotoMetadata {
@@ -286,30 +998,238 @@ impl MetadataEngine {
             date_taken: None,
             camera_make: None,
             camera_model: None,
+            dimensions: None,
+            provenance: BTreeMap::new(),
         };
 
         metadata.update_summary_fields();
-        Some(metadata)
+        Ok(metadata)
     }
 
-    fn write_exif_to_file(path: &Path, metadata: &PhotoMetadata) {
-        let ext = path
-            .extension()
-            .map(|e| e.to_string_lossy().to_ascii_lowercase())
-            .unwrap_or_default();
+    /// The bytes of a JPEG that carries more than one EXIF segment, reduced to
+    /// the first valid one. `None` for anything else, which little_exif can
+    /// read straight from disk.
+    fn read_single_exif_jpeg(path: &Path) -> Option<Vec<u8>> {
+        if formats::detect_format(path) != ImageFormat::Jpeg {
+            return None;
+        }
+        let bytes = fs::read(path).ok()?;
+        Self::keep_first_exif_segment(path, &bytes, "read")
+    }
 
-        if !matches!(ext.as_str(), "jpg" | "jpeg" | "png" | "webp" | "heic" | "heif") {
+    fn keep_first_exif_segment(path: &Path, bytes: &[u8], op: &str) -> Option<Vec<u8>> {
+        let repaired = formats::keep_first_jpeg_exif(bytes)?;
+        log::warn!(
+            "op={op} path={} exif_segments={} using=first",
+            path.display(),
+            formats::jpeg_exif_segments(bytes).len()
+        );
+        Some(repaired)
+    }
+
+    /// Add the Application record datasets of a JPEG's IPTC block to
+    /// `metadata`, after its EXIF tags. Other formats carry no IPTC here.
+    fn read_iptc_from_file(path: &Path, metadata: &mut PhotoMetadata) {
+        if formats::detect_format(path) != ImageFormat::Jpeg {
+            return;
+        }
+        let Some(block) = fs::read(path)
+            .ok()
+            .and_then(|bytes| iptc::read_jpeg_iptc(&bytes))
+        else {
+            return;
+        };
+
+        for (key, values) in iptc::decode_datasets(&block) {
+            let value = if is_list_tag(&key) {
+                TagValue::TextList(values)
+            } else {
+                TagValue::Text(values.join(", "))
+            };
+            let mut tag = MetadataTag::new(
+                key.as_str(),
+                display_name_from_key(&key),
+                value,
+                infer_category_from_key(&key),
+            );
+            tag.order = metadata.next_tag_order();
+            metadata.iptc_tags.push(tag);
+        }
+        metadata.update_summary_fields();
+    }
+
+    /// Read the `.xmp` sidecar [`Self::write_xmp_to_file`] wrote back into
+    /// `metadata.xmp_tags`. A sidecar from another program is left to it.
+    fn read_xmp_from_file(path: &Path, metadata: &mut PhotoMetadata) {
+        let Some(packet) = fs::read_to_string(Self::xmp_sidecar_path(path))
+            .ok()
+            .filter(|packet| xmp::is_own_packet(packet))
+        else {
             return;
+        };
+
+        for (key, value) in xmp::parse_packet(&packet) {
+            let value = match value {
+                XmpValue::Simple(text) => TagValue::Text(text),
+                XmpValue::Bag(items) => TagValue::TextList(items),
+            };
+            let mut tag = MetadataTag::new(
+                key.as_str(),
+                display_name_from_key(&key),
+                value,
+                infer_category_from_key(&key),
+            );
+            tag.order = metadata.next_tag_order();
+            metadata.xmp_tags.push(tag);
+        }
+        metadata.update_summary_fields();
+    }
+
+    /// Write `metadata.iptc_tags` into a JPEG's Photoshop APP13 segment, or
+    /// drop the IPTC block when there are none. Other formats, and blocks too
+    /// big for the segment, only keep their IPTC tags in the JSON sidecar;
+    /// without one the edits would be lost, so that is an error.
+    fn write_iptc_to_file(path: &Path, metadata: &PhotoMetadata, sidecars: bool) -> Result<()> {
+        let not_embedded = |reason: &str| {
+            if sidecars {
+                log::warn!(
+                    "op=write_iptc path={} skipped={reason} tags={}",
+                    path.display(),
+                    metadata.iptc_tags.len()
+                );
+                Ok(())
+            } else {
+                Err(MetadataError::Io(std::io::Error::new(
+                    ErrorKind::Unsupported,
+                    format!(
+                        "IPTC tags can't be embedded in {}: {reason}",
+                        path.display()
+                    ),
+                )))
+            }
+        };
+        if formats::detect_format(path) != ImageFormat::Jpeg {
+            if !metadata.iptc_tags.is_empty() {
+                return not_embedded("not_a_jpeg");
+            }
+            return Ok(());
+        }
+
+        let bytes = fs::read(path)?;
+        let block = (!metadata.iptc_tags.is_empty()).then(|| {
+            iptc::encode_datasets(
+                metadata
+                    .iptc_tags
+                    .iter()
+                    .map(|tag| (tag.key.as_str(), list_items(tag))),
+            )
+        });
+        match iptc::replace_jpeg_iptc(&bytes, block.as_deref()) {
+            Some(rewritten) if rewritten != bytes => write_atomically(path, rewritten)?,
+            Some(_) => {}
+            None if block.is_some() => return not_embedded("block_too_large"),
+            None => {}
+        }
+        Ok(())
+    }
+
+    /// Write `metadata.xmp_tags` to an `.xmp` sidecar next to the photo (see
+    /// [`Self::xmp_sidecar_path`]). With no XMP tags, a sidecar this app
+    /// wrote is removed; one from another program is never written over or
+    /// removed. Its tags are then only kept when `sidecars` is set.
+    fn write_xmp_to_file(path: &Path, metadata: &PhotoMetadata, sidecars: bool) -> Result<()> {
+        let sidecar = Self::xmp_sidecar_path(path);
+        let existing = fs::read_to_string(&sidecar).ok();
+        let foreign = sidecar.exists() && !existing.as_deref().is_some_and(xmp::is_own_packet);
+        if metadata.xmp_tags.is_empty() {
+            if sidecar.exists() && !foreign {
+                fs::remove_file(&sidecar)?;
+            }
+            return Ok(());
+        }
+        if foreign {
+            let err = std::io::Error::new(
+                ErrorKind::AlreadyExists,
+                format!("{} belongs to another program", sidecar.display()),
+            );
+            if !sidecars {
+                return Err(err.into());
+            }
+            log::warn!("op=write_xmp path={} skipped={err}", path.display());
+            return Ok(());
+        }
+
+        let packet = xmp::build_packet(metadata.xmp_tags.iter().map(|tag| {
+            let value = if is_list_tag(&tag.key) {
+                XmpValue::Bag(list_items(tag))
+            } else {
+                XmpValue::Simple(tag.value.to_string())
+            };
+            (tag.key.as_str(), value)
+        }));
+        write_atomically(&sidecar, packet)?;
+        Ok(())
+    }
+
+    /// Merge `metadata`'s EXIF tags into the file's own block. A container
+    /// little_exif can't rebuild is only skipped when `sidecars` is set, since
+    /// the sidecar then still carries the edits.
+    fn write_exif_to_file(path: &Path, metadata: &PhotoMetadata, sidecars: bool) -> Result<()> {
+        // Choose the container from the file's bytes so a mislabeled file is
+        // rewritten in its real format rather than the one its name claims.
+        let file_type = match formats::detect_format(path) {
+            ImageFormat::Jpeg => FileExtension::JPEG,
+            ImageFormat::Png => FileExtension::PNG {
+                as_zTXt_chunk: true,
+            },
+            ImageFormat::WebP => FileExtension::WEBP,
+            ImageFormat::Heif => FileExtension::HEIF,
+            _ => return Err(MetadataError::UnsupportedFormat(path.to_path_buf())),
+        };
+
+        let mut buffer = fs::read(path)?;
+        if file_type == FileExtension::JPEG {
+            if let Some(repaired) = Self::keep_first_exif_segment(path, &buffer, "write") {
+                buffer = repaired;
+            }
+        }
+        // Tags are merged into the existing block so ones the editor can't
+        // represent survive a save. With nothing left to write, drop the
+        // block instead so a fully stripped photo really has no metadata.
+        // A clear that fails is only harmless when there was never an EXIF
+        // block to clear; otherwise the photo would keep what it was meant to
+        // lose.
+        if metadata.exif_tags.is_empty() {
+            let mut cleared = buffer.clone();
+            match ExifMetadata::clear_metadata(&mut cleared, file_type) {
+                Ok(()) => write_atomically(path, cleared)?,
+                Err(err) if !may_hold_exif(&buffer) => {
+                    log::info!("op=write path={} exif=none error={err}", path.display());
+                }
+                Err(err) => return Err(err.into()),
+            }
+            return Ok(());
         }
 
-        let mut exif = match ExifMetadata::new_from_path(path) {
+        let mut exif = match ExifMetadata::new_from_vec(&buffer, file_type) {
             Ok(e) => e,
             Err(_) => ExifMetadata::new(),
         };
+        // Tags the editor reads but the metadata no longer holds were removed
+        // by the user or a preset; take them out of the merged block too.
+        for stale in stale_exif_tags(&exif, metadata) {
+            exif.remove_tag(stale);
+        }
 
         for tag in metadata.all_tags() {
-            if let Some(exif_tag) = metadata_tag_to_exif(tag) {
-                exif.set_tag(exif_tag);
+            match metadata_tag_to_exif(tag) {
+                Some(exif_tag) => exif.set_tag(exif_tag),
+                // Skipping an unknown tag would leave its old value in the
+                // merged block while the save reported success.
+                None if tag.raw_type.is_some() => {
+                    return Err(MetadataError::InvalidTagValue(tag.key.clone()));
+                }
+                None => {}
             }
 
             // Write GPS as individual EXIF fields
@@ -318,7 +1238,16 @@ impl MetadataEngine {
             }
         }
 
-        let _ = exif.write_to_file(path);
+        // A container little_exif can't rebuild keeps its bytes. Without a
+        // sidecar the edits would be lost, so that is an error too.
+        match exif.write_to_vec(&mut buffer, file_type) {
+            Ok(()) => write_atomically(path, buffer)?,
+            Err(err) if sidecars => {
+                log::warn!("op=write path={} exif=skipped error={err}", path.display());
+            }
+            Err(err) => return Err(err.into()),
+        }
+        Ok(())
     }
 
     fn default_metadata_for_path(path: &Path) -> Result<PhotoMetadata> {
@@ -343,15 +1272,15 @@ impl MetadataEngine {
             TagCategory::Image,
         ));
 
+        // EXIF datetimes are local wall-clock time with no zone.
         if let Ok(modified) = file_meta.modified() {
-            if let Ok(elapsed) = modified.duration_since(UNIX_EPOCH) {
-                exif_tags.push(MetadataTag::new(
-                    "Exif.Photo.DateTimeOriginal",
-                    "Date Taken",
-                    TagValue::DateTime(elapsed.as_secs().to_string()),
-                    TagCategory::DateTime,
-                ));
-            }
+            let modified: DateTime<Local> = modified.into();
+            exif_tags.push(MetadataTag::new(
+                "Exif.Photo.DateTimeOriginal",
+                "Date Taken",
+                TagValue::DateTime(modified.format("%Y:%m:%d %H:%M:%S").to_string()),
+                TagCategory::DateTime,
+            ));
         }
 
         let mut metadata = PhotoMetadata {
@@ -362,6 +1291,8 @@ impl MetadataEngine {
             date_taken: None,
             camera_make: None,
             camera_model: None,
+            dimensions: None,
+            provenance: BTreeMap::new(),
         };
 
         metadata.update_summary_fields();
@@ -381,6 +1312,11 @@ impl MetadataEngine {
             PresetRule::SetTag(key, value) => {
                 Self::set_tag_in_metadata(metadata, key, TagValue::Text(value.clone()))
             }
+            PresetRule::NormalizeDateTimes => Self::normalize_datetimes(metadata),
+            PresetRule::ResetInvalidOrientation => Self::reset_invalid_orientation(metadata),
+            PresetRule::RemoveEmptyTags => {
+                Self::remove_empty_tags(metadata);
+            }
         }
     }
 
@@ -420,6 +1356,7 @@ impl MetadataEngine {
         metadata.date_taken = None;
         metadata.camera_make = None;
         metadata.camera_model = None;
+        metadata.provenance.clear();
     }
 
     fn remove_gps(metadata: &mut PhotoMetadata) {
@@ -433,6 +1370,41 @@ impl MetadataEngine {
         });
     }
 
+    fn normalize_datetimes(metadata: &mut PhotoMetadata) {
+        let mut changed = Vec::new();
+        for tag in metadata.all_tags_mut() {
+            let raw = match &tag.value {
+                TagValue::DateTime(raw) => raw,
+                TagValue::Text(raw) if tag.key.to_ascii_lowercase().contains("datetime") => raw,
+                _ => continue,
+            };
+
+            if let Some(normalized) = normalize_exif_datetime(raw) {
+                let value = TagValue::DateTime(normalized);
+                if tag.value != value {
+                    tag.value = value;
+                    changed.push(tag.key.clone());
+                }
+            }
+        }
+
+        for key in changed {
+            metadata.record_provenance(&key);
+        }
+    }
+
+    fn reset_invalid_orientation(metadata: &mut PhotoMetadata) {
+        let Some(tag) = metadata.find_tag_mut(ORIENTATION_KEY) else {
+            return;
+        };
+        if matches!(tag.value, TagValue::Integer(1..=8)) {
+            return;
+        }
+
+        tag.value = TagValue::Integer(1);
+        metadata.record_provenance(ORIENTATION_KEY);
+    }
+
     fn retain_all<F>(metadata: &mut PhotoMetadata, mut predicate: F)
     where
         F: FnMut(&MetadataTag) -> bool,
@@ -440,6 +1412,21 @@ impl MetadataEngine {
         metadata.exif_tags.retain(|tag| predicate(tag));
         metadata.iptc_tags.retain(|tag| predicate(tag));
         metadata.xmp_tags.retain(|tag| predicate(tag));
+
+        let PhotoMetadata {
+            exif_tags,
+            iptc_tags,
+            xmp_tags,
+            provenance,
+            ..
+        } = metadata;
+        provenance.retain(|key, _| {
+            exif_tags
+                .iter()
+                .chain(iptc_tags.iter())
+                .chain(xmp_tags.iter())
+                .any(|tag| tag.key.eq_ignore_ascii_case(key))
+        });
     }
 
     fn update_existing_tag(tags: &mut [MetadataTag], tag_key: &str, value: &TagValue) -> bool {
@@ -456,6 +1443,181 @@ impl MetadataEngine {
     }
 }
 
+/// `path` made absolute with symlinks and relative segments resolved, or
+/// `None` when neither it nor its folder exists.
+fn resolve_path(path: &Path) -> Option<PathBuf> {
+    fs::canonicalize(path).ok().or_else(|| {
+        let parent = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        Some(fs::canonicalize(parent).ok()?.join(path.file_name()?))
+    })
+}
+
+/// Tags in `exif` that [`MetadataEngine::read`] would show but `metadata`
+/// lacks. GPS position fields count as present while the merged coordinate
+/// tag is; IFD pointers and thumbnail fields are never stale.
+fn stale_exif_tags(exif: &ExifMetadata, metadata: &PhotoMetadata) -> Vec<ExifTag> {
+    let has_coordinates = metadata.find_tag(GPS_COORDINATES_KEY).is_some();
+    exif.into_iter()
+        .filter(|tag| match tag {
+            ExifTag::GPSLatitudeRef(_)
+            | ExifTag::GPSLatitude(_)
+            | ExifTag::GPSLongitudeRef(_)
+            | ExifTag::GPSLongitude(_)
+            | ExifTag::GPSAltitudeRef(_)
+            | ExifTag::GPSAltitude(_) => !has_coordinates,
+            ExifTag::ExifOffset(_)
+            | ExifTag::GPSInfo(_)
+            | ExifTag::InteropOffset(_)
+            | ExifTag::ThumbnailOffset(..)
+            | ExifTag::ThumbnailLength(_)
+            | ExifTag::StripOffsets(..)
+            | ExifTag::StripByteCounts(_) => false,
+            _ => convert_exif_tag(tag, tag.as_u16())
+                .is_some_and(|converted| metadata.find_tag(&converted.key).is_none()),
+        })
+        .cloned()
+        .collect()
+}
+
+/// Whether `bytes` might hold an EXIF block: every container names it with
+/// some casing of `exif` (the JPEG header, PNG's `eXIf` chunk and raw profile
+/// keyword, WebP's `EXIF` chunk, HEIF's `Exif` item).
+fn may_hold_exif(bytes: &[u8]) -> bool {
+    bytes
+        .windows(4)
+        .any(|window| window.eq_ignore_ascii_case(b"exif"))
+}
+
+/// Replace `path` with `contents` without ever leaving it half written: the
+/// bytes go to a fresh temp file next to it (see [`create_temp_file`]), are
+/// synced to disk and renamed over `path`. A rename the filesystem refuses
+/// fails the write rather than copying over `path`, which could leave it
+/// half written. The temp file is removed on every path out. A symlinked
+/// `path` is resolved first so the rename replaces its target, not the link.
+fn write_atomically(path: &Path, contents: impl AsRef<[u8]>) -> std::io::Result<()> {
+    let path = &fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let (temp, mut file) = create_temp_file(path)?;
+    let written = (|| {
+        file.write_all(contents.as_ref())?;
+        // Keep the original's permissions rather than the new file's defaults.
+        if let Ok(existing) = fs::metadata(path) {
+            file.set_permissions(existing.permissions())?;
+        }
+        file.sync_all()?;
+        drop(file);
+        fs::rename(&temp, path)?;
+        sync_parent_dir(path);
+        Ok(())
+    })();
+    if temp.exists() {
+        let _ = fs::remove_file(&temp);
+    }
+    written
+}
+
+/// Create the temp file for [`write_atomically`], starting at
+/// [`MetadataEngine::temp_path`] and numbering on (`.photo.jpg.1.tmp`, ...)
+/// past names that are taken, so a file in use is never written over.
+fn create_temp_file(path: &Path) -> std::io::Result<(PathBuf, fs::File)> {
+    let first = MetadataEngine::temp_path(path);
+    create_numbered_file(|attempt| match attempt {
+        0 => first.clone(),
+        n => first.with_extension(format!("{n}.tmp")),
+    })
+}
+
+/// Create the hidden file [`MetadataEngine::stage_output`] assembles an
+/// export in: `.photo.part.jpg`, then `.photo.part1.jpg` and so on. It keeps
+/// the output's extension, which format detection falls back on.
+fn create_staging_file(output: &Path) -> std::io::Result<(PathBuf, fs::File)> {
+    let stem = output
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_else(|| String::from("photo"));
+    let extension = output
+        .extension()
+        .map(|ext| format!(".{}", ext.to_string_lossy()))
+        .unwrap_or_default();
+    create_numbered_file(|attempt| {
+        let number = if attempt == 0 {
+            String::new()
+        } else {
+            attempt.to_string()
+        };
+        output.with_file_name(format!(".{stem}.part{number}{extension}"))
+    })
+}
+
+/// Create the first of `name(0)`, `name(1)`, ... that doesn't exist yet. A
+/// file at one of these names that hasn't changed in [`ORPHANED_TEMP_AGE`]
+/// was left by a save that never finished, and is removed so its name can
+/// be used again.
+fn create_numbered_file(name: impl Fn(u32) -> PathBuf) -> std::io::Result<(PathBuf, fs::File)> {
+    let mut attempt = 0;
+    loop {
+        let temp = name(attempt);
+        let create = || fs::File::options().write(true).create_new(true).open(&temp);
+        let created = match create() {
+            Err(err) if err.kind() == ErrorKind::AlreadyExists && remove_orphan(&temp) => create(),
+            created => created,
+        };
+        match created {
+            Ok(file) => return Ok((temp, file)),
+            Err(err) if err.kind() == ErrorKind::AlreadyExists => {}
+            Err(err) => return Err(err),
+        }
+        attempt += 1;
+        if attempt == TEMP_FILE_ATTEMPTS {
+            return Err(ErrorKind::AlreadyExists.into());
+        }
+    }
+}
+
+/// How many temp names [`create_temp_file`] tries before giving up.
+const TEMP_FILE_ATTEMPTS: u32 = 16;
+
+/// How long a temp file has to sit unchanged before it counts as orphaned.
+/// Any save still writing it would have touched it far more recently.
+const ORPHANED_TEMP_AGE: Duration = Duration::from_secs(60 * 60);
+
+/// Remove `temp` if it is an orphaned temp file (see [`create_numbered_file`]).
+/// Returns whether it was removed.
+fn remove_orphan(temp: &Path) -> bool {
+    let orphaned = fs::symlink_metadata(temp).is_ok_and(|meta| {
+        meta.is_file()
+            && meta
+                .modified()
+                .ok()
+                .and_then(|modified| modified.elapsed().ok())
+                .is_some_and(|age| age >= ORPHANED_TEMP_AGE)
+    });
+    if !orphaned || fs::remove_file(temp).is_err() {
+        return false;
+    }
+    log::info!("op=write removed_orphan={}", temp.display());
+    true
+}
+
+/// Flush the rename itself to disk. Only Unix can open a directory for this;
+/// a failure leaves the data written, just not the rename guaranteed durable.
+fn sync_parent_dir(path: &Path) {
+    #[cfg(unix)]
+    {
+        let parent = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+            .unwrap_or(Path::new("."));
+        if let Err(err) = fs::File::open(parent).and_then(|dir| dir.sync_all()) {
+            log::warn!("op=write path={} dir_sync_failed={err}", path.display());
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+}
+
 // ---------------------------------------------------------------------------
 // EXIF tag conversion helpers
 // ---------------------------------------------------------------------------
@@ -671,6 +1833,16 @@ fn convert_exif_tag(tag: &ExifTag, _hex: u16) -> Option<MetadataTag> {
             "Image Height",
             TagValue::Integer(v.first().copied().unwrap_or(0) as i64),
         ),
+        ExifTag::ExifImageWidth(v) => (
+            "Exif.Photo.PixelXDimension",
+            "Pixel X Dimension",
+            TagValue::Integer(v.first().copied().unwrap_or(0) as i64),
+        ),
+        ExifTag::ExifImageHeight(v) => (
+            "Exif.Photo.PixelYDimension",
+            "Pixel Y Dimension",
+            TagValue::Integer(v.first().copied().unwrap_or(0) as i64),
+        ),
 
         // -- Unsigned rational tags --
         ExifTag::ExposureTime(v) => {
@@ -821,6 +1993,17 @@ fn convert_exif_tag(tag: &ExifTag, _hex: u16) -> Option<MetadataTag> {
             "Components Configuration",
             TagValue::Binary(v.clone()),
         ),
+        // Single-byte UNDEF enumerations, edited as integers.
+        ExifTag::FileSource(v) if !v.is_empty() => (
+            "Exif.Photo.FileSource",
+            "File Source",
+            TagValue::Integer(i64::from(v[0])),
+        ),
+        ExifTag::SceneType(v) if !v.is_empty() => (
+            "Exif.Photo.SceneType",
+            "Scene Type",
+            TagValue::Integer(i64::from(v[0])),
+        ),
 
         // -- Lens info (multi-value rational) --
         ExifTag::LensInfo(v) if !v.is_empty() => {
@@ -838,115 +2021,76 @@ fn convert_exif_tag(tag: &ExifTag, _hex: u16) -> Option<MetadataTag> {
             ("Exif.Photo.LensInfo", "Lens Info", TagValue::Text(display))
         }
 
-        // -- Unknown variants: surface as text/binary --
-        ExifTag::UnknownSTRING(s, hex, _) => {
-            let key_str = format!("Exif.Unknown.0x{hex:04X}");
-            let display = format!("Tag 0x{hex:04X}");
-            return Some(MetadataTag {
-                key: key_str,
-                display_name: display,
-                value: TagValue::Text(clean_string(s)),
-                category: TagCategory::Other,
-                editable: true,
-                marked_for_removal: false,
-            });
+        // -- Unknown variants: typed where a single value allows, otherwise a
+        // comma-separated list; the storage type is kept for writing back --
+        ExifTag::UnknownSTRING(s, hex, group) => {
+            return Some(unknown_tag(
+                *hex,
+                TagValue::Text(clean_string(s)),
+                RawExifFormat::String,
+                group,
+            ));
         }
-        ExifTag::UnknownINT16U(v, hex, _) => {
-            let key_str = format!("Exif.Unknown.0x{hex:04X}");
-            let display = format!("Tag 0x{hex:04X}");
-            let text = v
-                .iter()
-                .map(|n| n.to_string())
-                .collect::<Vec<_>>()
-                .join(", ");
-            return Some(MetadataTag {
-                key: key_str,
-                display_name: display,
-                value: TagValue::Text(text),
-                category: TagCategory::Other,
-                editable: false,
-                marked_for_removal: false,
-            });
+        ExifTag::UnknownINT16U(v, hex, group) => {
+            let value = match v.as_slice() {
+                [single] => TagValue::Integer(i64::from(*single)),
+                values => TagValue::Text(join_values(values.iter())),
+            };
+            return Some(unknown_tag(*hex, value, RawExifFormat::Int16U, group));
         }
-        ExifTag::UnknownINT32U(v, hex, _) => {
-            let key_str = format!("Exif.Unknown.0x{hex:04X}");
-            let display = format!("Tag 0x{hex:04X}");
-            let text = v
-                .iter()
-                .map(|n| n.to_string())
-                .collect::<Vec<_>>()
-                .join(", ");
-            return Some(MetadataTag {
-                key: key_str,
-                display_name: display,
-                value: TagValue::Text(text),
-                category: TagCategory::Other,
-                editable: false,
-                marked_for_removal: false,
-            });
+        ExifTag::UnknownINT32U(v, hex, group) => {
+            let value = match v.as_slice() {
+                [single] => TagValue::Integer(i64::from(*single)),
+                values => TagValue::Text(join_values(values.iter())),
+            };
+            return Some(unknown_tag(*hex, value, RawExifFormat::Int32U, group));
         }
-        ExifTag::UnknownRATIONAL64U(v, hex, _) => {
-            let key_str = format!("Exif.Unknown.0x{hex:04X}");
-            let display = format!("Tag 0x{hex:04X}");
-            let text = v
-                .iter()
-                .map(|r| format!("{}/{}", r.nominator, r.denominator))
-                .collect::<Vec<_>>()
-                .join(", ");
-            return Some(MetadataTag {
-                key: key_str,
-                display_name: display,
-                value: TagValue::Text(text),
-                category: TagCategory::Other,
-                editable: false,
-                marked_for_removal: false,
-            });
+        ExifTag::UnknownRATIONAL64U(v, hex, group) => {
+            let value = match v.as_slice() {
+                [single] if single.denominator != 0 => {
+                    TagValue::Rational(single.nominator, single.denominator)
+                }
+                values => TagValue::Text(join_values(
+                    values
+                        .iter()
+                        .map(|r| format!("{}/{}", r.nominator, r.denominator)),
+                )),
+            };
+            return Some(unknown_tag(*hex, value, RawExifFormat::Rational64U, group));
         }
-        ExifTag::UnknownRATIONAL64S(v, hex, _) => {
-            let key_str = format!("Exif.Unknown.0x{hex:04X}");
-            let display = format!("Tag 0x{hex:04X}");
-            let text = v
-                .iter()
-                .map(|r| format!("{}/{}", r.nominator, r.denominator))
-                .collect::<Vec<_>>()
-                .join(", ");
-            return Some(MetadataTag {
-                key: key_str,
-                display_name: display,
-                value: TagValue::Text(text),
-                category: TagCategory::Other,
-                editable: false,
-                marked_for_removal: false,
-            });
+        ExifTag::UnknownRATIONAL64S(v, hex, group) => {
+            let text = join_values(
+                v.iter()
+                    .map(|r| format!("{}/{}", r.nominator, r.denominator)),
+            );
+            return Some(unknown_tag(
+                *hex,
+                TagValue::Text(text),
+                RawExifFormat::Rational64S,
+                group,
+            ));
         }
-        ExifTag::UnknownUNDEF(v, hex, _) => {
-            let key_str = format!("Exif.Unknown.0x{hex:04X}");
-            let display = format!("Tag 0x{hex:04X}");
-            return Some(MetadataTag {
-                key: key_str,
-                display_name: display,
-                value: TagValue::Binary(v.clone()),
-                category: TagCategory::Other,
-                editable: false,
-                marked_for_removal: false,
-            });
+        ExifTag::UnknownUNDEF(v, hex, group) => {
+            return Some(unknown_tag(
+                *hex,
+                TagValue::Binary(v.clone()),
+                RawExifFormat::Undef,
+                group,
+            ));
         }
-        ExifTag::UnknownINT8U(v, hex, _) => {
-            let key_str = format!("Exif.Unknown.0x{hex:04X}");
-            let display = format!("Tag 0x{hex:04X}");
-            return Some(MetadataTag {
-                key: key_str,
-                display_name: display,
-                value: TagValue::Binary(v.clone()),
-                category: TagCategory::Other,
-                editable: false,
-                marked_for_removal: false,
-            });
+        ExifTag::UnknownINT8U(v, hex, group) => {
+            return Some(unknown_tag(
+                *hex,
+                TagValue::Binary(v.clone()),
+                RawExifFormat::Int8U,
+                group,
+            ));
         }
 
         // Catch-all for remaining tags
         _ => return None,
     };
+    let value = sanitize_rational(key, value);
 
     Some(MetadataTag {
         key: key.to_string(),
@@ -955,10 +2099,205 @@ fn convert_exif_tag(tag: &ExifTag, _hex: u16) -> Option<MetadataTag> {
         category: infer_category_from_key(key),
         editable: true,
         marked_for_removal: false,
+        order: 0,
+        raw_type: None,
     })
 }
 
+fn gps_part_index(key: &str) -> Option<usize> {
+    GPS_PART_KEYS
+        .iter()
+        .position(|part| part.eq_ignore_ascii_case(key))
+}
+
+/// The photo's coordinates with one position field, `GPS_PART_KEYS[part]`,
+/// set from `value`, or `None` when the photo has no coordinates to set it
+/// on. A value that doesn't fit the field leaves them unchanged. A full
+/// coordinate is taken as is.
+fn fold_gps_part(metadata: &PhotoMetadata, part: usize, value: &TagValue) -> Option<TagValue> {
+    if matches!(value, TagValue::Gps(..)) {
+        return Some(value.clone());
+    }
+    let Some(MetadataTag {
+        value: TagValue::Gps(lat, lon, alt),
+        ..
+    }) = metadata.find_tag(GPS_COORDINATES_KEY)
+    else {
+        return None;
+    };
+    let (mut lat, mut lon, mut alt) = (*lat, *lon, *alt);
+    let number = match value {
+        TagValue::Text(text) | TagValue::Unknown(text) => text.trim().parse().ok(),
+        value => value.as_f64(),
+    };
+    let reference = value.to_string().trim().to_ascii_uppercase();
+
+    match part {
+        0 => lat = number.map_or(lat, |n| keep_hemisphere(n, lat)),
+        1 if reference == "N" => lat = lat.abs(),
+        1 if reference == "S" => lat = -lat.abs(),
+        2 => lon = number.map_or(lon, |n| keep_hemisphere(n, lon)),
+        3 if reference == "E" => lon = lon.abs(),
+        3 if reference == "W" => lon = -lon.abs(),
+        4 => alt = number.map(|n| alt.map_or(n, |a| keep_hemisphere(n, a))),
+        5 => {
+            let below_sea_level = number == Some(1.0);
+            alt = alt.map(|a| if below_sea_level { -a.abs() } else { a.abs() });
+        }
+        _ => {}
+    }
+    Some(TagValue::Gps(lat, lon, alt))
+}
+
+/// `entered` with the sign of `current`, the way a position field (stored
+/// unsigned beside its reference) reads: typing `33.9` over `-33.8` stays
+/// south. A negative entry is taken as is.
+fn keep_hemisphere(entered: f64, current: f64) -> f64 {
+    if entered < 0.0 {
+        entered
+    } else {
+        entered.copysign(current)
+    }
+}
+
+/// Key under which a tag the editor has no name for is shown.
+fn unknown_tag_key(hex: u16) -> String {
+    format!("Exif.Unknown.0x{hex:04X}")
+}
+
+fn unknown_tag(
+    hex: u16,
+    value: TagValue,
+    format: RawExifFormat,
+    group: &ExifTagGroup,
+) -> MetadataTag {
+    let ifd = match group {
+        ExifTagGroup::GENERIC => ExifIfd::Generic,
+        ExifTagGroup::EXIF => ExifIfd::Exif,
+        ExifTagGroup::INTEROP => ExifIfd::Interop,
+        ExifTagGroup::GPS => ExifIfd::Gps,
+    };
+    let mut tag = MetadataTag::new(
+        unknown_tag_key(hex),
+        format!("Tag 0x{hex:04X}"),
+        value,
+        TagCategory::Other,
+    );
+    // Raw bytes have no editor; everything else round-trips through `raw_type`.
+    tag.editable = !matches!(tag.value, TagValue::Binary(_));
+    tag.raw_type = Some(RawExifType { format, ifd });
+    tag
+}
+
+fn join_values<T: ToString>(values: impl Iterator<Item = T>) -> String {
+    values
+        .map(|value| value.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Rebuild the `Unknown*` variant an `Exif.Unknown.0xXXXX` tag was read from.
+/// `None` when the key has no tag number or the value doesn't fit the type.
+fn unknown_tag_to_exif(tag: &MetadataTag, raw: RawExifType) -> Option<ExifTag> {
+    let hex = tag
+        .key
+        .rsplit_once("0x")
+        .and_then(|(_, hex)| u16::from_str_radix(hex, 16).ok())?;
+    let group = match raw.ifd {
+        ExifIfd::Generic => ExifTagGroup::GENERIC,
+        ExifIfd::Exif => ExifTagGroup::EXIF,
+        ExifIfd::Interop => ExifTagGroup::INTEROP,
+        ExifIfd::Gps => ExifTagGroup::GPS,
+    };
+    let list = |value: &TagValue| -> Vec<String> {
+        match value {
+            TagValue::Text(text) | TagValue::Unknown(text) => text
+                .split(',')
+                .map(|item| item.trim().to_string())
+                .filter(|item| !item.is_empty())
+                .collect(),
+            TagValue::Rational(n, d) => vec![format!("{n}/{d}")],
+            value => vec![value.to_string()],
+        }
+    };
+    let parse_all = |value: &TagValue| -> Option<Vec<i64>> {
+        list(value).iter().map(|item| item.parse().ok()).collect()
+    };
+    let fraction = |item: &str| -> Option<(i64, i64)> {
+        let (n, d) = item.split_once('/').unwrap_or((item, "1"));
+        let d = d.trim().parse().ok().filter(|d| *d != 0)?;
+        Some((n.trim().parse().ok()?, d))
+    };
+
+    match (raw.format, &tag.value) {
+        (RawExifFormat::String, value) => {
+            Some(ExifTag::UnknownSTRING(value.to_string(), hex, group))
+        }
+        (RawExifFormat::Int16U, value) => {
+            let values = parse_all(value)?
+                .into_iter()
+                .map(u16::try_from)
+                .collect::<std::result::Result<Vec<_>, _>>()
+                .ok()?;
+            Some(ExifTag::UnknownINT16U(values, hex, group))
+        }
+        (RawExifFormat::Int32U, value) => {
+            let values = parse_all(value)?
+                .into_iter()
+                .map(u32::try_from)
+                .collect::<std::result::Result<Vec<_>, _>>()
+                .ok()?;
+            Some(ExifTag::UnknownINT32U(values, hex, group))
+        }
+        (RawExifFormat::Rational64U, value) => {
+            let values = list(value)
+                .iter()
+                .map(|item| {
+                    let (n, d) = fraction(item)?;
+                    Some(ur64(u32::try_from(n).ok()?, u32::try_from(d).ok()?))
+                })
+                .collect::<Option<Vec<_>>>()?;
+            Some(ExifTag::UnknownRATIONAL64U(values, hex, group))
+        }
+        (RawExifFormat::Rational64S, value) => {
+            let values = list(value)
+                .iter()
+                .map(|item| {
+                    let (n, d) = fraction(item)?;
+                    Some(little_exif::rational::iR64 {
+                        nominator: i32::try_from(n).ok()?,
+                        denominator: i32::try_from(d).ok()?,
+                    })
+                })
+                .collect::<Option<Vec<_>>>()?;
+            Some(ExifTag::UnknownRATIONAL64S(values, hex, group))
+        }
+        (RawExifFormat::Int8U, TagValue::Binary(bytes)) => {
+            Some(ExifTag::UnknownINT8U(bytes.clone(), hex, group))
+        }
+        (RawExifFormat::Undef, TagValue::Binary(bytes)) => {
+            Some(ExifTag::UnknownUNDEF(bytes.clone(), hex, group))
+        }
+        _ => None,
+    }
+}
+
+/// Malformed files can carry a zero denominator, which the editor and the
+/// display helpers can't divide by. Read it as a whole number instead.
+fn sanitize_rational(key: &str, value: TagValue) -> TagValue {
+    match value {
+        TagValue::Rational(numerator, 0) => {
+            log::warn!("op=read key={key} rational={numerator}/0 fixed={numerator}/1");
+            TagValue::Rational(numerator, 1)
+        }
+        value => value,
+    }
+}
+
 fn metadata_tag_to_exif(tag: &MetadataTag) -> Option<ExifTag> {
+    if let Some(raw) = tag.raw_type {
+        return unknown_tag_to_exif(tag, raw);
+    }
     let key = tag.key.as_str();
     match (&tag.value, key) {
         // String tags
@@ -984,6 +2323,20 @@ fn metadata_tag_to_exif(tag: &MetadataTag) -> Option<ExifTag> {
         }
         (TagValue::DateTime(s), "Exif.Photo.CreateDate") => Some(ExifTag::CreateDate(s.clone())),
         (TagValue::DateTime(s), "Exif.Image.ModifyDate") => Some(ExifTag::ModifyDate(s.clone())),
+        (TagValue::Text(s), "Exif.Photo.OffsetTime") => Some(ExifTag::OffsetTime(s.clone())),
+        (TagValue::Text(s), "Exif.Photo.OffsetTimeOriginal") => {
+            Some(ExifTag::OffsetTimeOriginal(s.clone()))
+        }
+        (TagValue::Text(s), "Exif.Photo.OffsetTimeDigitized") => {
+            Some(ExifTag::OffsetTimeDigitized(s.clone()))
+        }
+        (TagValue::Text(s), "Exif.Photo.SubSecTime") => Some(ExifTag::SubSecTime(s.clone())),
+        (TagValue::Text(s), "Exif.Photo.SubSecTimeOriginal") => {
+            Some(ExifTag::SubSecTimeOriginal(s.clone()))
+        }
+        (TagValue::Text(s), "Exif.Photo.SubSecTimeDigitized") => {
+            Some(ExifTag::SubSecTimeDigitized(s.clone()))
+        }
 
         // Integer tags
         (TagValue::Integer(v), "Exif.Image.Orientation") => {
@@ -1006,12 +2359,24 @@ fn metadata_tag_to_exif(tag: &MetadataTag) -> Option<ExifTag> {
         (TagValue::Integer(v), "Exif.Photo.ExposureMode") => {
             Some(ExifTag::ExposureMode(vec![*v as u16]))
         }
+        (TagValue::Integer(v), "Exif.Photo.FileSource") => u8::try_from(*v)
+            .ok()
+            .map(|byte| ExifTag::FileSource(vec![byte])),
+        (TagValue::Integer(v), "Exif.Photo.SceneType") => u8::try_from(*v)
+            .ok()
+            .map(|byte| ExifTag::SceneType(vec![byte])),
         (TagValue::Integer(v), "Exif.Image.ImageWidth") => {
             Some(ExifTag::ImageWidth(vec![*v as u32]))
         }
         (TagValue::Integer(v), "Exif.Image.ImageHeight") => {
             Some(ExifTag::ImageHeight(vec![*v as u32]))
         }
+        (TagValue::Integer(v), "Exif.Photo.PixelXDimension") => {
+            Some(ExifTag::ExifImageWidth(vec![*v as u32]))
+        }
+        (TagValue::Integer(v), "Exif.Photo.PixelYDimension") => {
+            Some(ExifTag::ExifImageHeight(vec![*v as u32]))
+        }
 
         // Rational tags
         (TagValue::Rational(n, d), "Exif.Photo.ExposureTime") => {
@@ -1059,13 +2424,25 @@ fn write_gps_tags(exif: &mut ExifMetadata, lat: f64, lon: f64, alt: &Option<f64>
 
     if let Some(altitude) = alt {
         let alt_ref: u8 = if *altitude < 0.0 { 1 } else { 0 };
-        let alt_abs = altitude.abs();
-        let alt_num = (alt_abs * 100.0).round() as u32;
+        let (alt_num, alt_den) = altitude_to_rational(altitude.abs());
         exif.set_tag(ExifTag::GPSAltitudeRef(vec![alt_ref]));
-        exif.set_tag(ExifTag::GPSAltitude(vec![ur64(alt_num, 100)]));
+        exif.set_tag(ExifTag::GPSAltitude(vec![ur64(alt_num, alt_den)]));
     }
 }
 
+/// The finest of millimetres, centimetres, decimetres or metres whose
+/// numerator still fits in a `u32`, so ordinary altitudes keep three decimals
+/// and only implausibly large ones lose precision.
+fn altitude_to_rational(metres: f64) -> (u32, u32) {
+    for denominator in [1000u32, 100, 10, 1] {
+        let numerator = (metres * f64::from(denominator)).round();
+        if numerator <= f64::from(u32::MAX) {
+            return (numerator as u32, denominator);
+        }
+    }
+    (u32::MAX, 1)
+}
+
 fn ur64(nominator: u32, denominator: u32) -> little_exif::rational::uR64 {
     little_exif::rational::uR64 {
         nominator,
@@ -1078,15 +2455,27 @@ fn ur64(nominator: u32, denominator: u32) -> little_exif::rational::uR64 {
 // ---------------------------------------------------------------------------
 
 /// Convert decimal degrees to DMS (degrees, minutes, seconds_numerator, seconds_denominator).
-/// Seconds are expressed as a rational with denominator 10000 for sub-second precision.
+/// Seconds are expressed as a rational with denominator 10000 for sub-second precision,
+/// about 3 mm on the ground. Seconds that round up to 60 carry into the minutes, and
+/// minutes into the degrees, so every component stays in range.
 pub fn decimal_to_dms(decimal: f64) -> (u32, u32, u32, u32) {
+    const SECONDS_DEN: u32 = 10000;
+
     let d = decimal.abs();
-    let degrees = d as u32;
+    let mut degrees = d as u32;
     let minutes_full = (d - degrees as f64) * 60.0;
-    let minutes = minutes_full as u32;
+    let mut minutes = minutes_full as u32;
     let seconds = (minutes_full - minutes as f64) * 60.0;
-    let seconds_num = (seconds * 10000.0).round() as u32;
-    (degrees, minutes, seconds_num, 10000)
+    let mut seconds_num = (seconds * f64::from(SECONDS_DEN)).round() as u32;
+    if seconds_num >= 60 * SECONDS_DEN {
+        seconds_num -= 60 * SECONDS_DEN;
+        minutes += 1;
+    }
+    if minutes >= 60 {
+        minutes -= 60;
+        degrees += 1;
+    }
+    (degrees, minutes, seconds_num, SECONDS_DEN)
 }
 
 /// Convert DMS components to decimal degrees.
@@ -1094,10 +2483,179 @@ pub fn dms_to_decimal(degrees: f64, minutes: f64, seconds: f64) -> f64 {
     degrees + minutes / 60.0 + seconds / 3600.0
 }
 
+/// Format a signed coordinate as `40°26'46"N`, using the hemisphere letter for
+/// the sign. Seconds keep up to four decimals, matching [`decimal_to_dms`].
+pub fn format_dms_coordinate(decimal: f64, is_latitude: bool) -> String {
+    let (degrees, minutes, seconds_num, seconds_den) = decimal_to_dms(decimal);
+    let whole = seconds_num / seconds_den;
+    let fraction = seconds_num % seconds_den;
+    let seconds = if fraction == 0 {
+        whole.to_string()
+    } else {
+        format!("{whole}.{fraction:04}")
+            .trim_end_matches('0')
+            .to_string()
+    };
+
+    let hemisphere = match (is_latitude, decimal < 0.0) {
+        (true, false) => 'N',
+        (true, true) => 'S',
+        (false, false) => 'E',
+        (false, true) => 'W',
+    };
+    format!("{degrees}°{minutes}'{seconds}\"{hemisphere}")
+}
+
+/// Parse a DMS coordinate such as `40°26'46"N`, `40 26 46.5 N` or `-73°59'`.
+/// The hemisphere may lead or trail and must match the axis (N/S for latitude,
+/// E/W for longitude). Missing minutes/seconds count as zero.
+pub fn parse_dms_coordinate(raw: &str, is_latitude: bool) -> Option<f64> {
+    let mut text = raw.trim().to_ascii_uppercase();
+    let hemisphere = [text.chars().next(), text.chars().last()]
+        .into_iter()
+        .flatten()
+        .find(|ch| matches!(ch, 'N' | 'S' | 'E' | 'W'));
+    let negative_hemisphere = match (hemisphere, is_latitude) {
+        (None, _) => false,
+        (Some('N'), true) | (Some('E'), false) => false,
+        (Some('S'), true) | (Some('W'), false) => true,
+        _ => return None,
+    };
+    if let Some(hemisphere) = hemisphere {
+        text = text.trim_matches(hemisphere).to_string();
+    }
+
+    let text = text.trim();
+    let (negative_sign, text) = match text.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, text),
+    };
+    if negative_sign && hemisphere.is_some() {
+        return None;
+    }
+
+    let parts = text
+        .split(|ch: char| ch.is_whitespace() || matches!(ch, '°' | '\'' | '"' | '′' | '″'))
+        .filter(|part| !part.is_empty())
+        .map(|part| part.parse::<f64>().ok().filter(|value| *value >= 0.0))
+        .collect::<Option<Vec<_>>>()?;
+    let (degrees, minutes, seconds) = match parts.as_slice() {
+        [degrees] => (*degrees, 0.0, 0.0),
+        [degrees, minutes] => (*degrees, *minutes, 0.0),
+        [degrees, minutes, seconds] => (*degrees, *minutes, *seconds),
+        _ => return None,
+    };
+    if minutes >= 60.0 || seconds >= 60.0 {
+        return None;
+    }
+
+    let decimal = dms_to_decimal(degrees, minutes, seconds);
+    Some(if negative_sign || negative_hemisphere {
+        -decimal
+    } else {
+        decimal
+    })
+}
+
+/// Unit GPS altitudes are displayed and typed in. Stored altitudes are
+/// always meters.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum AltitudeUnit {
+    #[default]
+    Meters,
+    Feet,
+}
+
+const METERS_PER_FOOT: f64 = 0.3048;
+
+impl AltitudeUnit {
+    pub fn suffix(self) -> &'static str {
+        match self {
+            Self::Meters => "m",
+            Self::Feet => "ft",
+        }
+    }
+
+    pub fn from_meters(self, meters: f64) -> f64 {
+        match self {
+            Self::Meters => meters,
+            Self::Feet => meters / METERS_PER_FOOT,
+        }
+    }
+
+    pub fn to_meters(self, value: f64) -> f64 {
+        match self {
+            Self::Meters => value,
+            Self::Feet => value * METERS_PER_FOOT,
+        }
+    }
+}
+
+/// Plain-text listing of `tags`, one `key: value` line each, for pasting into
+/// an email or issue. Rational capture settings are followed by their
+/// photographer-facing reading, and line breaks inside values become spaces.
+pub fn format_metadata_report(tags: &[MetadataTag]) -> String {
+    tags.iter()
+        .map(|tag| {
+            let value = match tag.value {
+                TagValue::Rational(n, d) => match format_rational_for_key(&tag.key, n, d) {
+                    reading if reading.is_empty() => format!("{n}/{d}"),
+                    reading => format!("{n}/{d} ({reading})"),
+                },
+                ref value => value.to_string(),
+            };
+            let value = value.split(['\r', '\n']).filter(|line| !line.is_empty());
+            format!("{}: {}\n", tag.key, value.collect::<Vec<_>>().join(" "))
+        })
+        .collect()
+}
+
+/// Format an altitude stored in meters for display in `unit`, with
+/// `precision` decimal places and no unit suffix.
+pub fn format_altitude(meters: f64, unit: AltitudeUnit, precision: usize) -> String {
+    format!("{:.precision$}", unit.from_meters(meters))
+}
+
+/// Parse an altitude typed in `unit` back to meters.
+pub fn parse_altitude(raw: &str, unit: AltitudeUnit) -> Option<f64> {
+    raw.trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|value| value.is_finite())
+        .map(|value| unit.to_meters(value))
+}
+
 // ---------------------------------------------------------------------------
 // Existing helpers
 // ---------------------------------------------------------------------------
 
+/// Whether a text-like value is empty or only whitespace. Other kinds always
+/// count as set.
+fn is_blank(value: &TagValue) -> bool {
+    match value {
+        TagValue::Text(value) | TagValue::DateTime(value) | TagValue::Unknown(value) => {
+            value.trim().is_empty()
+        }
+        TagValue::TextList(items) => items.iter().all(|item| item.trim().is_empty()),
+        _ => false,
+    }
+}
+
+/// The separate values a tag is written as: the items of a list, a list
+/// tag's text split on commas, or the one value of any other tag.
+fn list_items(tag: &MetadataTag) -> Vec<String> {
+    match &tag.value {
+        TagValue::TextList(items) => items.clone(),
+        TagValue::Text(text) if is_list_tag(&tag.key) => text
+            .split(',')
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .map(String::from)
+            .collect(),
+        value => vec![value.to_string()],
+    }
+}
+
 fn clean_string(s: &str) -> String {
     s.trim_end_matches('\0').trim().to_string()
 }
@@ -1149,7 +2707,65 @@ fn infer_category_from_key(tag_key: &str) -> TagCategory {
     }
 }
 
-fn category_from_token(token: &str) -> Option<TagCategory> {
+const TIFF_TAG_THUMBNAIL_OFFSET: u16 = 0x0201;
+const TIFF_TAG_THUMBNAIL_LENGTH: u16 = 0x0202;
+
+/// Find the thumbnail offset and length in IFD1 of a TIFF-structured EXIF block.
+/// Offsets are relative to the start of `tiff`.
+fn embedded_thumbnail_location(tiff: &[u8]) -> Option<(usize, usize)> {
+    let little_endian = match tiff.get(..2)? {
+        b"II" => true,
+        b"MM" => false,
+        _ => return None,
+    };
+    let read_u16 = |at: usize| -> Option<u16> {
+        let bytes = [*tiff.get(at)?, *tiff.get(at + 1)?];
+        Some(if little_endian {
+            u16::from_le_bytes(bytes)
+        } else {
+            u16::from_be_bytes(bytes)
+        })
+    };
+    let read_u32 = |at: usize| -> Option<u32> {
+        let bytes: [u8; 4] = tiff.get(at..at + 4)?.try_into().ok()?;
+        Some(if little_endian {
+            u32::from_le_bytes(bytes)
+        } else {
+            u32::from_be_bytes(bytes)
+        })
+    };
+
+    if read_u16(2)? != 42 {
+        return None;
+    }
+
+    let ifd0 = read_u32(4)? as usize;
+    let ifd0_entries = read_u16(ifd0)? as usize;
+    let ifd1 = read_u32(ifd0 + 2 + ifd0_entries * 12)? as usize;
+    if ifd1 == 0 {
+        return None;
+    }
+
+    let mut offset = None;
+    let mut length = None;
+    for index in 0..read_u16(ifd1)? as usize {
+        let entry = ifd1 + 2 + index * 12;
+        // Both tags are LONG per the spec, but some writers use SHORT.
+        let value = match read_u16(entry + 2)? {
+            3 => u32::from(read_u16(entry + 8)?),
+            _ => read_u32(entry + 8)?,
+        };
+        match read_u16(entry)? {
+            TIFF_TAG_THUMBNAIL_OFFSET => offset = Some(value as usize),
+            TIFF_TAG_THUMBNAIL_LENGTH => length = Some(value as usize),
+            _ => {}
+        }
+    }
+
+    Some((offset?, length?)).filter(|&(_, length)| length > 0)
+}
+
+pub(crate) fn category_from_token(token: &str) -> Option<TagCategory> {
     match token {
         "camera" => Some(TagCategory::Camera),
         "capture" => Some(TagCategory::Capture),
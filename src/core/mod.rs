@@ -1,5 +1,14 @@
 pub mod bulk;
+pub mod catalog;
+pub mod contacts;
+pub mod enum_labels;
+pub mod exiftool;
 pub mod formats;
+pub mod geocode;
+pub mod hex;
+pub mod iptc;
 pub mod metadata;
 pub mod presets;
+pub mod preview;
 pub mod thumbnail;
+pub mod xmp;
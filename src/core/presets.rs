@@ -1,4 +1,16 @@
-use crate::models::{PresetRule, StripPreset, TagCategory};
+use std::fmt;
+
+use crate::core::catalog;
+use crate::core::metadata::category_from_token;
+use crate::models::{PresetId, PresetRule, StripPreset, TagCategory};
+
+/// Id reserved for presets built on the fly from a typed allowlist.
+pub const ALLOWLIST_PRESET_ID: PresetId = 0;
+
+/// Id reserved for the hard-clean run. Like [`ALLOWLIST_PRESET_ID`], it never
+/// names a saved preset; custom presets count up from the built-ins and never
+/// reach it.
+pub const HARD_CLEAN_PRESET_ID: PresetId = PresetId::MAX;
 
 pub fn builtin_presets() -> Vec<StripPreset> {
     vec![
@@ -75,6 +87,18 @@ pub fn builtin_presets() -> Vec<StripPreset> {
             ],
             true,
         ),
+        StripPreset::new(
+            7,
+            "Touch Up",
+            "Normalize timestamps, reset invalid orientation, and drop empty tags",
+            "sparkles",
+            vec![
+                PresetRule::NormalizeDateTimes,
+                PresetRule::ResetInvalidOrientation,
+                PresetRule::RemoveEmptyTags,
+            ],
+            true,
+        ),
     ]
 }
 
@@ -83,3 +107,272 @@ pub fn preset_by_name<'a>(presets: &'a [StripPreset], name: &str) -> Option<&'a
         .iter()
         .find(|preset| preset.name.eq_ignore_ascii_case(name))
 }
+
+/// Split a comma-separated allowlist into entries `RemoveAllExcept` understands
+/// (category names or catalog tag keys) and tokens that are neither. A typo'd
+/// key is unrecognized rather than kept, since it would silently match nothing.
+pub fn parse_allowlist(input: &str) -> (Vec<String>, Vec<String>) {
+    let mut accepted = Vec::new();
+    let mut unrecognized = Vec::new();
+
+    for token in input
+        .split(',')
+        .map(str::trim)
+        .filter(|token| !token.is_empty())
+    {
+        if category_from_token(&token.to_ascii_lowercase()).is_some() {
+            accepted.push(token.to_string());
+        } else if let Ok(key) = catalog_key(token) {
+            accepted.push(key);
+        } else {
+            unrecognized.push(token.to_string());
+        }
+    }
+
+    (accepted, unrecognized)
+}
+
+pub fn allowlist_preset(entries: Vec<String>) -> StripPreset {
+    StripPreset::new(
+        ALLOWLIST_PRESET_ID,
+        "Keep Only",
+        format!("Keep only {}", entries.join(", ")),
+        "list-filter",
+        vec![PresetRule::RemoveAllExcept(entries)],
+        false,
+    )
+}
+
+/// Strips every tag. Runs with it write no sidecars; see
+/// [`crate::app::AppState::begin_hard_clean`].
+pub fn hard_clean_preset() -> StripPreset {
+    StripPreset::new(
+        HARD_CLEAN_PRESET_ID,
+        "Hard Clean",
+        "Remove every metadata tag and any sidecar",
+        "trash",
+        vec![PresetRule::RemoveAll],
+        false,
+    )
+}
+
+/// The rule types the preset builder offers, one per [`PresetRule`] variant.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RuleKind {
+    RemoveCategory,
+    RemoveTag,
+    RemoveAllExcept,
+    RemoveAll,
+    RemoveGps,
+    RemoveThumbnail,
+    SetTag,
+    NormalizeDateTimes,
+    ResetInvalidOrientation,
+    RemoveEmptyTags,
+}
+
+impl RuleKind {
+    pub const ALL: [RuleKind; 10] = [
+        Self::RemoveCategory,
+        Self::RemoveTag,
+        Self::RemoveAllExcept,
+        Self::RemoveAll,
+        Self::RemoveGps,
+        Self::RemoveThumbnail,
+        Self::SetTag,
+        Self::NormalizeDateTimes,
+        Self::ResetInvalidOrientation,
+        Self::RemoveEmptyTags,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::RemoveCategory => "Remove category",
+            Self::RemoveTag => "Remove tag",
+            Self::RemoveAllExcept => "Keep only",
+            Self::RemoveAll => "Remove all",
+            Self::RemoveGps => "Remove GPS",
+            Self::RemoveThumbnail => "Remove thumbnail",
+            Self::SetTag => "Set tag",
+            Self::NormalizeDateTimes => "Normalize timestamps",
+            Self::ResetInvalidOrientation => "Reset invalid orientation",
+            Self::RemoveEmptyTags => "Remove empty tags",
+        }
+    }
+
+    /// Whether the rule needs a tag key (or, for "Keep only", a key list).
+    pub fn takes_key(self) -> bool {
+        matches!(self, Self::RemoveTag | Self::RemoveAllExcept | Self::SetTag)
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum RuleBuildError {
+    MissingArgument(RuleKind),
+    UnknownCategory(String),
+    UnknownKeys(Vec<String>),
+}
+
+impl fmt::Display for RuleBuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingArgument(kind) => write!(f, "{} needs a value", kind.label()),
+            Self::UnknownCategory(token) => write!(f, "unknown tag category: {token}"),
+            Self::UnknownKeys(keys) => write!(f, "unknown tag keys: {}", keys.join(", ")),
+        }
+    }
+}
+
+impl std::error::Error for RuleBuildError {}
+
+/// Collects rules for a custom preset from the builder form. Tag keys are
+/// checked against [`catalog::TAG_CATALOG`] and stored in its spelling, so
+/// a typo is caught here rather than silently matching nothing on a run.
+#[derive(Clone, Debug, Default)]
+pub struct PresetRuleBuilder {
+    rules: Vec<PresetRule>,
+}
+
+impl PresetRuleBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Validate and append one rule. `argument` is the category for
+    /// "Remove category", the key for "Remove tag"/"Set tag", or a
+    /// comma-separated list of keys and categories for "Keep only"; `value`
+    /// is only read by "Set tag".
+    pub fn add(
+        &mut self,
+        kind: RuleKind,
+        argument: &str,
+        value: &str,
+    ) -> Result<&PresetRule, RuleBuildError> {
+        let rule = Self::build_rule(kind, argument, value)?;
+        self.rules.push(rule);
+        Ok(&self.rules[self.rules.len() - 1])
+    }
+
+    pub fn remove(&mut self, index: usize) -> Option<PresetRule> {
+        (index < self.rules.len()).then(|| self.rules.remove(index))
+    }
+
+    pub fn rules(&self) -> &[PresetRule] {
+        &self.rules
+    }
+
+    pub fn build(self) -> Vec<PresetRule> {
+        self.rules
+    }
+
+    pub fn build_rule(
+        kind: RuleKind,
+        argument: &str,
+        value: &str,
+    ) -> Result<PresetRule, RuleBuildError> {
+        let argument = argument.trim();
+        if (kind.takes_key() || kind == RuleKind::RemoveCategory) && argument.is_empty() {
+            return Err(RuleBuildError::MissingArgument(kind));
+        }
+
+        Ok(match kind {
+            RuleKind::RemoveCategory => PresetRule::RemoveCategory(
+                category_from_token(&argument.to_ascii_lowercase())
+                    .ok_or_else(|| RuleBuildError::UnknownCategory(argument.to_string()))?,
+            ),
+            RuleKind::RemoveTag => PresetRule::RemoveTag(catalog_key(argument)?),
+            RuleKind::RemoveAllExcept => {
+                let (entries, unknown) = parse_allowlist(argument);
+                if !unknown.is_empty() {
+                    return Err(RuleBuildError::UnknownKeys(unknown));
+                }
+                if entries.is_empty() {
+                    return Err(RuleBuildError::MissingArgument(kind));
+                }
+                PresetRule::RemoveAllExcept(entries)
+            }
+            RuleKind::RemoveAll => PresetRule::RemoveAll,
+            RuleKind::RemoveGps => PresetRule::RemoveGps,
+            RuleKind::RemoveThumbnail => PresetRule::RemoveThumbnail,
+            RuleKind::SetTag => PresetRule::SetTag(catalog_key(argument)?, value.to_string()),
+            RuleKind::NormalizeDateTimes => PresetRule::NormalizeDateTimes,
+            RuleKind::ResetInvalidOrientation => PresetRule::ResetInvalidOrientation,
+            RuleKind::RemoveEmptyTags => PresetRule::RemoveEmptyTags,
+        })
+    }
+}
+
+/// How many suggestions [`argument_suggestions`] offers at once.
+const MAX_SUGGESTIONS: usize = 8;
+
+/// Keys or categories the builder's pickers offer for `kind`, matching the
+/// entry being typed in `argument` (for "Keep only", the text after the last
+/// comma) by key or display name. Empty for rules without an argument.
+pub fn argument_suggestions(kind: RuleKind, argument: &str) -> Vec<&'static str> {
+    let query = match kind {
+        RuleKind::RemoveAllExcept => argument.rsplit(',').next().unwrap_or_default(),
+        _ => argument,
+    }
+    .trim()
+    .to_ascii_lowercase();
+    let categories = TagCategory::ALL
+        .iter()
+        .map(|category| category.as_str())
+        .filter(|label| label.to_ascii_lowercase().contains(&query));
+    let keys = catalog::TAG_CATALOG
+        .iter()
+        .filter(|def| {
+            def.key.to_ascii_lowercase().contains(&query)
+                || def.display_name.to_ascii_lowercase().contains(&query)
+        })
+        .map(|def| def.key);
+
+    match kind {
+        RuleKind::RemoveCategory => categories.collect(),
+        RuleKind::RemoveAllExcept => categories.chain(keys).take(MAX_SUGGESTIONS).collect(),
+        RuleKind::RemoveTag | RuleKind::SetTag => keys.take(MAX_SUGGESTIONS).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// `argument` after picking `picked` from [`argument_suggestions`]. "Keep
+/// only" swaps it in for the entry being typed and leaves room for the next
+/// one; every other rule takes it whole.
+pub fn pick_argument(kind: RuleKind, argument: &str, picked: &str) -> String {
+    if kind != RuleKind::RemoveAllExcept {
+        return picked.to_string();
+    }
+    let done = argument
+        .rsplit_once(',')
+        .map(|(done, _)| format!("{}, ", done.trim_end()))
+        .unwrap_or_default();
+    format!("{done}{picked}, ")
+}
+
+/// One line describing `rule` for the builder's rule list, e.g.
+/// "Remove tag: Exif.Image.Artist".
+pub fn rule_summary(rule: &PresetRule) -> String {
+    let (kind, argument) = match rule {
+        PresetRule::RemoveCategory(category) => {
+            (RuleKind::RemoveCategory, category.as_str().to_string())
+        }
+        PresetRule::RemoveTag(key) => (RuleKind::RemoveTag, key.clone()),
+        PresetRule::RemoveAllExcept(entries) => (RuleKind::RemoveAllExcept, entries.join(", ")),
+        PresetRule::SetTag(key, value) => (RuleKind::SetTag, format!("{key} = {value}")),
+        PresetRule::RemoveAll => return RuleKind::RemoveAll.label().to_string(),
+        PresetRule::RemoveGps => return RuleKind::RemoveGps.label().to_string(),
+        PresetRule::RemoveThumbnail => return RuleKind::RemoveThumbnail.label().to_string(),
+        PresetRule::NormalizeDateTimes => return RuleKind::NormalizeDateTimes.label().to_string(),
+        PresetRule::ResetInvalidOrientation => {
+            return RuleKind::ResetInvalidOrientation.label().to_string()
+        }
+        PresetRule::RemoveEmptyTags => return RuleKind::RemoveEmptyTags.label().to_string(),
+    };
+    format!("{}: {argument}", kind.label())
+}
+
+fn catalog_key(key: &str) -> Result<String, RuleBuildError> {
+    catalog::lookup(key)
+        .map(|def| def.key.to_string())
+        .ok_or_else(|| RuleBuildError::UnknownKeys(vec![key.to_string()]))
+}
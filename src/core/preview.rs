@@ -0,0 +1,35 @@
+use std::io::Cursor;
+use std::path::Path;
+
+use image::metadata::Orientation;
+use image::{DynamicImage, ImageFormat};
+
+/// Longest edge, in pixels, of the decoded carousel preview.
+pub const PREVIEW_MAX_EDGE: u32 = 1600;
+
+/// Rotate/flip `image` so it displays upright for the given EXIF orientation.
+/// Values outside 1-8 are treated as 1.
+pub fn apply_orientation(mut image: DynamicImage, orientation: i64) -> DynamicImage {
+    let orientation = u8::try_from(orientation)
+        .ok()
+        .and_then(Orientation::from_exif)
+        .unwrap_or(Orientation::NoTransforms);
+    image.apply_orientation(orientation);
+    image
+}
+
+/// Decode `path`, downscale it to fit `max_edge` and apply `orientation`,
+/// returning PNG bytes. Returns `None` when the format cannot be decoded.
+pub fn oriented_png(path: &Path, orientation: i64, max_edge: u32) -> Option<Vec<u8>> {
+    let mut image = image::open(path).ok()?;
+    if image.width().max(image.height()) > max_edge {
+        image = image.thumbnail(max_edge, max_edge);
+    }
+
+    let image = apply_orientation(image, orientation);
+    let mut bytes = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)
+        .ok()?;
+    Some(bytes)
+}
@@ -7,6 +7,24 @@ use std::time::SystemTime;
 
 use crate::models::{PhotoEntry, ThumbnailData};
 
+/// Longest edge, in pixels, of thumbnails decoded for the carousel strip.
+pub const THUMBNAIL_EDGE: u32 = 192;
+
+/// Decode `path` and downscale it to fit within `max_edge`, as RGBA pixels.
+pub fn decode_thumbnail(path: &Path, max_edge: u32) -> Option<ThumbnailData> {
+    let mut image = image::open(path).ok()?;
+    if image.width().max(image.height()) > max_edge {
+        image = image.thumbnail(max_edge, max_edge);
+    }
+    let image = image.to_rgba8();
+
+    Some(ThumbnailData {
+        width: image.width(),
+        height: image.height(),
+        pixels: image.into_raw(),
+    })
+}
+
 pub struct ThumbnailCache {
     pub cache_dir: PathBuf,
     pub max_size: usize,
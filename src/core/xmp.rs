@@ -0,0 +1,185 @@
+/// Prefix of every XMP key; the rest is `<namespace prefix>.<property>`.
+pub const XMP_KEY_PREFIX: &str = "Xmp.";
+
+/// Written as the packet's `xmp:CreatorTool`, so a sidecar this app wrote can
+/// be told apart from one another program keeps next to the photo.
+pub const CREATOR_TOOL: &str = "Exif Editor";
+
+/// Namespace prefixes the writer knows, with their URIs.
+const NAMESPACES: &[(&str, &str)] = &[
+    ("dc", "http://purl.org/dc/elements/1.1/"),
+    ("xmp", "http://ns.adobe.com/xap/1.0/"),
+    ("xmpRights", "http://ns.adobe.com/xap/1.0/rights/"),
+    ("photoshop", "http://ns.adobe.com/photoshop/1.0/"),
+    (
+        "Iptc4xmpCore",
+        "http://iptc.org/std/Iptc4xmpCore/1.0/xmlns/",
+    ),
+    ("exif", "http://ns.adobe.com/exif/1.0/"),
+    ("tiff", "http://ns.adobe.com/tiff/1.0/"),
+    ("lr", "http://ns.adobe.com/lightroom/1.0/"),
+];
+
+/// A property value: a single text, or an unordered list written as an
+/// `rdf:Bag`, like `dc:subject` keywords.
+#[derive(Clone, Debug, PartialEq)]
+pub enum XmpValue {
+    Simple(String),
+    Bag(Vec<String>),
+}
+
+/// Split an XMP key into its namespace prefix and property name, e.g.
+/// `Xmp.dc.title` into `("dc", "title")`. `None` for keys in a namespace the
+/// writer doesn't know.
+pub fn split_key(key: &str) -> Option<(&'static str, &str)> {
+    let rest = key
+        .get(..XMP_KEY_PREFIX.len())
+        .filter(|prefix| prefix.eq_ignore_ascii_case(XMP_KEY_PREFIX))
+        .map(|_| &key[XMP_KEY_PREFIX.len()..])?;
+    let (prefix, property) = rest.split_once('.')?;
+    let valid = !property.is_empty()
+        && property
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '_' || ch == '-');
+    let (known, _) = NAMESPACES
+        .iter()
+        .find(|(known, _)| known.eq_ignore_ascii_case(prefix))?;
+    valid.then_some((*known, property))
+}
+
+/// Build a standalone XMP packet holding `(key, value)` pairs. Keys outside
+/// the known namespaces are skipped.
+pub fn build_packet<'a, I>(tags: I) -> String
+where
+    I: IntoIterator<Item = (&'a str, XmpValue)>,
+{
+    let mut used = vec!["xmp"];
+    let mut properties = Vec::new();
+    for (key, value) in tags {
+        let Some((prefix, property)) = split_key(key) else {
+            log::warn!("op=write_xmp key={key} skipped=unknown_namespace");
+            continue;
+        };
+        if !used.contains(&prefix) {
+            used.push(prefix);
+        }
+        let content = match value {
+            XmpValue::Simple(text) => escape_xml(&text),
+            XmpValue::Bag(items) => {
+                let items = items
+                    .iter()
+                    .map(|item| format!("     <rdf:li>{}</rdf:li>\n", escape_xml(item)))
+                    .collect::<String>();
+                format!("\n    <rdf:Bag>\n{items}    </rdf:Bag>\n   ")
+            }
+        };
+        properties.push(format!(
+            "   <{prefix}:{property}>{content}</{prefix}:{property}>\n"
+        ));
+    }
+
+    let mut packet = String::from(
+        "<?xpacket begin=\"\u{FEFF}\" id=\"W5M0MpCehiHzreSzNTczkc9d\"?>\n\
+         <x:xmpmeta xmlns:x=\"adobe:ns:meta/\">\n \
+         <rdf:RDF xmlns:rdf=\"http://www.w3.org/1999/02/22-rdf-syntax-ns#\">\n  \
+         <rdf:Description rdf:about=\"\"",
+    );
+    for (prefix, uri) in NAMESPACES
+        .iter()
+        .filter(|(prefix, _)| used.contains(prefix))
+    {
+        packet.push_str(&format!("\n    xmlns:{prefix}=\"{uri}\""));
+    }
+    packet.push_str(&format!(
+        ">\n   <xmp:CreatorTool>{CREATOR_TOOL}</xmp:CreatorTool>\n"
+    ));
+    for property in properties {
+        packet.push_str(&property);
+    }
+    packet.push_str("  </rdf:Description>\n </rdf:RDF>\n</x:xmpmeta>\n<?xpacket end=\"w\"?>\n");
+    packet
+}
+
+/// Whether `packet` is one [`build_packet`] wrote.
+pub fn is_own_packet(packet: &str) -> bool {
+    packet.contains(&format!(
+        "<xmp:CreatorTool>{CREATOR_TOOL}</xmp:CreatorTool>"
+    ))
+}
+
+/// Read the properties back out of a packet [`build_packet`] wrote, as
+/// `(key, value)` pairs in the order they appear. The `xmp:CreatorTool` mark
+/// and properties outside the known namespaces are left out.
+pub fn parse_packet(packet: &str) -> Vec<(String, XmpValue)> {
+    let Some(description) = packet
+        .find("<rdf:Description")
+        .map(|start| &packet[start..])
+    else {
+        return Vec::new();
+    };
+    let Some(mut rest) = description.find('>').map(|end| &description[end + 1..]) else {
+        return Vec::new();
+    };
+
+    let mut properties = Vec::new();
+    while let Some(open) = rest.find('<') {
+        rest = &rest[open + 1..];
+        let Some(name_end) = rest.find('>') else {
+            break;
+        };
+        let name = &rest[..name_end];
+        if name.starts_with('/') {
+            break;
+        }
+        let close = format!("</{name}>");
+        let Some(content_end) = rest.find(&close) else {
+            break;
+        };
+        let content = &rest[name_end + 1..content_end];
+        rest = &rest[content_end + close.len()..];
+
+        let key = format!("{XMP_KEY_PREFIX}{}", name.replacen(':', ".", 1));
+        let Some((prefix, property)) = split_key(&key) else {
+            continue;
+        };
+        if (prefix, property) == ("xmp", "CreatorTool") {
+            continue;
+        }
+        let value = if content.contains("<rdf:Bag>") {
+            XmpValue::Bag(
+                content
+                    .split("<rdf:li>")
+                    .skip(1)
+                    .filter_map(|item| item.split_once("</rdf:li>"))
+                    .map(|(item, _)| unescape_xml(item))
+                    .collect(),
+            )
+        } else {
+            XmpValue::Simple(unescape_xml(content))
+        };
+        properties.push((format!("{XMP_KEY_PREFIX}{prefix}.{property}"), value));
+    }
+    properties
+}
+
+fn escape_xml(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for ch in value.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+fn unescape_xml(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&amp;", "&")
+}
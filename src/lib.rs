@@ -1,6 +1,9 @@
 pub mod app;
+pub mod cli;
 pub mod core;
 pub mod http;
+pub mod logging;
 pub mod models;
 pub mod platform;
+pub mod settings;
 pub mod ui;
@@ -0,0 +1,108 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use chrono::{SecondsFormat, Utc};
+use log::{LevelFilter, Log, Metadata, Record};
+
+use crate::platform;
+
+pub const LOG_FILE_NAME: &str = "exif-editor.log";
+
+/// Appends one `key=value` line per record to a log file. Installed once at
+/// startup; whether anything is written is controlled by [`set_enabled`].
+/// The file is only created once the first record is written, so a disabled
+/// logger leaves nothing behind.
+pub struct FileLogger {
+    path: PathBuf,
+    file: Mutex<Option<File>>,
+}
+
+impl FileLogger {
+    pub fn new(path: &Path) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            file: Mutex::new(None),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn open(&self) -> std::io::Result<File> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+    }
+}
+
+impl Log for FileLogger {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record<'_>) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        let line = format_record(record);
+        if let Ok(mut file) = self.file.lock() {
+            if file.is_none() {
+                *file = self.open().ok();
+            }
+            if let Some(file) = file.as_mut() {
+                let _ = writeln!(file, "{line}");
+            }
+        }
+    }
+
+    fn flush(&self) {
+        if let Ok(mut file) = self.file.lock() {
+            if let Some(file) = file.as_mut() {
+                let _ = file.flush();
+            }
+        }
+    }
+}
+
+pub fn format_record(record: &Record<'_>) -> String {
+    format!(
+        "ts={} level={} target={} {}",
+        Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+        record.level(),
+        record.target(),
+        record.args()
+    )
+}
+
+pub fn default_log_path() -> PathBuf {
+    platform::config_dir().join(LOG_FILE_NAME)
+}
+
+/// Install the file logger, writing only when `enabled`. [`set_enabled`]
+/// switches it later, e.g. from the settings panel.
+pub fn init(path: &Path, enabled: bool) -> std::io::Result<()> {
+    log::set_boxed_logger(Box::new(FileLogger::new(path)))
+        .map_err(|err| std::io::Error::other(err.to_string()))?;
+    set_enabled(enabled);
+    Ok(())
+}
+
+pub fn set_enabled(enabled: bool) {
+    log::set_max_level(if enabled {
+        LevelFilter::Info
+    } else {
+        LevelFilter::Off
+    });
+}
+
+pub fn is_enabled() -> bool {
+    log::max_level() != LevelFilter::Off
+}
@@ -1,15 +1,28 @@
+use exif_editor::cli;
 use exif_editor::http::ReqwestClient;
+use exif_editor::logging;
+use exif_editor::settings::AppSettings;
 use exif_editor::ui::window::open_exif_editor_window;
 use gpui::{App, Application};
 use gpui_component_assets::Assets;
 
 fn main() {
+    let settings = AppSettings::load(&AppSettings::default_path());
+    let _ = logging::init(&logging::default_log_path(), settings.log_to_file);
+
+    // Only the strip command runs headless; files passed without it, as an
+    // "Open with" launch does, open in the window.
+    let files = match cli::Launch::from_args(std::env::args().skip(1).collect()) {
+        cli::Launch::Headless(args) => std::process::exit(cli::main(args)),
+        cli::Launch::Window(files) => files,
+    };
+
     Application::new()
         .with_assets(Assets)
         .with_http_client(ReqwestClient::new())
         .run(|cx: &mut App| {
             gpui_component::init(cx);
-            open_exif_editor_window(cx);
+            open_exif_editor_window(settings, files, cx);
             cx.activate(true);
         });
 }
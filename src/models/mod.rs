@@ -4,8 +4,16 @@ mod preset;
 mod tag;
 
 pub use operation::{
-    BatchJob, Operation, OperationResult, OperationSummary, OutputMode, ProgressEvent,
+    BatchJob, ExportRename, ExportSummary, ImportRootSummary, ImportSummary, Operation,
+    OperationResult, OperationSummary, OutputMode, ProgressEvent, SkipReason,
+};
+pub use photo::{
+    compute_aspect_ratio, compute_megapixels, is_list_tag, Dimensions, ImageFormat,
+    MetadataWarning, PhotoEntry, PhotoId, PhotoMetadata, TagProvenance, ThumbnailData,
+    COLOR_SPACE_KEY, COLOR_SPACE_SRGB, LIST_TAG_KEYS, ORIENTATION_KEY, PROVENANCE_APP,
 };
-pub use photo::{Dimensions, ImageFormat, PhotoEntry, PhotoId, PhotoMetadata, ThumbnailData};
 pub use preset::{PresetId, PresetRule, StripPreset};
-pub use tag::{MetadataTag, TagCategory, TagValue};
+pub use tag::{
+    format_rational_for_key, normalize_exif_datetime, validate_exif_datetime, ExifIfd, MetadataTag,
+    RawExifFormat, RawExifType, TagCategory, TagValue,
+};
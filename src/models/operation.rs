@@ -1,12 +1,15 @@
-use std::path::PathBuf;
+use std::fmt;
+use std::path::{Path, PathBuf};
 
-use crate::models::{PhotoId, PresetId};
+use crate::models::{PhotoId, PresetId, StripPreset};
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum OutputMode {
     Overwrite,
     ExportTo(PathBuf),
     Suffix(String),
+    /// Like `Suffix`, using the suffix derived from the preset being applied.
+    PresetSuffix,
 }
 
 impl OutputMode {
@@ -15,6 +18,15 @@ impl OutputMode {
             Self::Overwrite => "Overwrite",
             Self::ExportTo(_) => "ExportTo",
             Self::Suffix(_) => "Suffix",
+            Self::PresetSuffix => "PresetSuffix",
+        }
+    }
+
+    /// Replace `PresetSuffix` with the concrete suffix of `preset`.
+    pub fn resolve(&self, preset: &StripPreset) -> OutputMode {
+        match self {
+            Self::PresetSuffix => Self::Suffix(preset.default_suffix()),
+            other => other.clone(),
         }
     }
 }
@@ -53,6 +65,40 @@ impl OperationResult {
             error: Some(error.into()),
         }
     }
+
+    pub fn is_success(&self) -> bool {
+        self.success
+    }
+
+    pub fn output_path(&self) -> &Path {
+        &self.output_path
+    }
+
+    /// Why the photo failed, or `None` for a success.
+    pub fn error_message(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    /// Split `results` into `(succeeded, failed)`, each in the order given.
+    pub fn split(results: impl IntoIterator<Item = Self>) -> (Vec<Self>, Vec<Self>) {
+        results.into_iter().partition(Self::is_success)
+    }
+}
+
+impl fmt::Display for OperationResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.success {
+            write!(
+                f,
+                "photo {} -> {}",
+                self.photo_id,
+                self.output_path.display()
+            )
+        } else {
+            let error = self.error_message().unwrap_or("unknown error");
+            write!(f, "photo {} failed: {error}", self.photo_id)
+        }
+    }
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -112,4 +158,104 @@ impl OperationSummary {
             cancelled,
         }
     }
+
+    /// Photos that were attempted, whether they succeeded or not.
+    pub fn processed(&self) -> usize {
+        self.succeeded + self.failed
+    }
+
+    /// Whether every photo was processed and none failed.
+    pub fn is_complete_success(&self) -> bool {
+        self.failed == 0 && self.cancelled == 0
+    }
+}
+
+impl fmt::Display for OperationSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} succeeded, {} failed, {} cancelled",
+            self.succeeded, self.failed, self.cancelled
+        )
+    }
+}
+
+/// An export that landed under a numbered name because the plain one was taken,
+/// typically by a same-named photo from another folder.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ExportRename {
+    pub source: PathBuf,
+    pub output_path: PathBuf,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ExportSummary {
+    pub exported: usize,
+    pub failed: usize,
+    pub renamed: Vec<ExportRename>,
+}
+
+/// Why an import left a file out.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SkipReason {
+    /// Missing, a directory, or otherwise not a regular file.
+    NotAFile,
+    /// Not an image format the editor can read.
+    UnknownFormat,
+    /// Already loaded, possibly through another path to the same file.
+    Duplicate,
+    /// A hidden file found while walking a folder.
+    Hidden,
+    /// One of the editor's own metadata sidecars.
+    Sidecar,
+    /// The `.orig` copy the editor keeps of a photo before its first save.
+    Backup,
+}
+
+impl SkipReason {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::NotAFile => "Not a file",
+            Self::UnknownFormat => "Unknown format",
+            Self::Duplicate => "Already imported",
+            Self::Hidden => "Hidden file",
+            Self::Sidecar => "Metadata sidecar",
+            Self::Backup => "Original backup",
+        }
+    }
+}
+
+/// Import counts for one path the user dropped or picked. `found` counts every
+/// file under it, including the hidden files and sidecars that are never imported.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ImportRootSummary {
+    pub root: PathBuf,
+    pub found: usize,
+    pub imported: usize,
+    pub skipped: usize,
+    /// Each file counted in `skipped`, with why it was left out.
+    pub skipped_files: Vec<(PathBuf, SkipReason)>,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ImportSummary {
+    pub roots: Vec<ImportRootSummary>,
+}
+
+impl ImportSummary {
+    pub fn found(&self) -> usize {
+        self.roots.iter().map(|root| root.found).sum()
+    }
+
+    pub fn imported(&self) -> usize {
+        self.roots.iter().map(|root| root.imported).sum()
+    }
+
+    pub fn skipped(&self) -> usize {
+        self.roots.iter().map(|root| root.skipped).sum()
+    }
+
+    pub fn skipped_files(&self) -> impl Iterator<Item = &(PathBuf, SkipReason)> {
+        self.roots.iter().flat_map(|root| &root.skipped_files)
+    }
 }
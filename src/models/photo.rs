@@ -1,12 +1,36 @@
+use std::collections::BTreeMap;
+use std::fmt;
 use std::fs;
 use std::path::PathBuf;
+use std::time::SystemTime;
 
+use chrono::{SecondsFormat, Utc};
 use serde::{Deserialize, Serialize};
 
 use crate::models::{MetadataTag, TagValue};
 
 pub type PhotoId = u64;
 
+pub const ORIENTATION_KEY: &str = "Exif.Image.Orientation";
+
+pub const COLOR_SPACE_KEY: &str = "Exif.Photo.ColorSpace";
+
+/// Tags that hold a list of values, kept as [`TagValue::TextList`].
+pub const LIST_TAG_KEYS: &[&str] = &["Iptc.Application2.Keywords", "Xmp.dc.subject"];
+
+/// Whether `key` is one of [`LIST_TAG_KEYS`].
+pub fn is_list_tag(key: &str) -> bool {
+    LIST_TAG_KEYS
+        .iter()
+        .any(|list_key| list_key.eq_ignore_ascii_case(key))
+}
+
+/// `Exif.Photo.ColorSpace` value for sRGB.
+pub const COLOR_SPACE_SRGB: i64 = 1;
+
+/// Recorded as [`TagProvenance::set_by`] for every tag this app writes.
+pub const PROVENANCE_APP: &str = concat!(env!("CARGO_PKG_NAME"), " ", env!("CARGO_PKG_VERSION"));
+
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum ImageFormat {
     Jpeg,
@@ -38,12 +62,51 @@ impl ImageFormat {
     }
 }
 
-#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct Dimensions {
     pub width: u32,
     pub height: u32,
 }
 
+impl Dimensions {
+    pub fn pixel_count(&self) -> u64 {
+        u64::from(self.width) * u64::from(self.height)
+    }
+
+    pub fn megapixels(&self) -> f64 {
+        self.pixel_count() as f64 / 1_000_000.0
+    }
+
+    pub fn is_portrait(&self) -> bool {
+        self.height > self.width
+    }
+
+    /// Orientations 5-8 ask the viewer to rotate by 90 degrees, which only makes
+    /// sense for landscape-stored pixels. Portrait pixels paired with one of those
+    /// values usually means an earlier tool rotated the pixels but kept the tag.
+    pub fn conflicts_with_orientation(&self, orientation: i64) -> bool {
+        (5..=8).contains(&orientation) && self.is_portrait()
+    }
+}
+
+/// `width:height` reduced to lowest terms, e.g. `"3:2"` for 6000x4000.
+pub fn compute_aspect_ratio(width: u32, height: u32) -> String {
+    let divisor = gcd(width, height).max(1);
+    format!("{}:{}", width / divisor, height / divisor)
+}
+
+/// Pixel count in millions to one decimal place, e.g. `"24.0 MP"`.
+pub fn compute_megapixels(width: u32, height: u32) -> String {
+    format!("{:.1} MP", Dimensions { width, height }.megapixels())
+}
+
+fn gcd(mut a: u32, mut b: u32) -> u32 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct ThumbnailData {
     pub width: u32,
@@ -51,19 +114,48 @@ pub struct ThumbnailData {
     pub pixels: Vec<u8>,
 }
 
+/// Why a photo's metadata had to be synthesized from the file rather than
+/// read from its embedded EXIF, or why its sidecar was passed over.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum MetadataWarning {
+    /// The file carries no EXIF.
+    NoExif,
+    /// The file or its EXIF block is damaged; holds the parser's message.
+    Unreadable(String),
+    /// The sidecar is corrupt or from an incompatible version, so the file's
+    /// own metadata was read instead; holds the reason.
+    SidecarIgnored(String),
+}
+
+impl fmt::Display for MetadataWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoExif => write!(f, "no EXIF data"),
+            Self::Unreadable(reason) => write!(f, "unreadable metadata: {reason}"),
+            Self::SidecarIgnored(reason) => write!(f, "sidecar ignored: {reason}"),
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct PhotoEntry {
     pub id: PhotoId,
     pub path: PathBuf,
     pub filename: String,
     pub file_size: u64,
+    /// Modification time of the file when it was last loaded.
+    pub modified: Option<SystemTime>,
     pub format: ImageFormat,
     pub dimensions: Option<Dimensions>,
+    /// Downscaled pixels for the thumbnail strip; see [`PhotoEntry::needs_thumbnail`].
     pub thumbnail: Option<ThumbnailData>,
     pub metadata: PhotoMetadata,
     pub persisted_metadata: PhotoMetadata,
     pub selected: bool,
     pub dirty: bool,
+    /// Problems found when the metadata was last read from disk.
+    pub read_warnings: Vec<MetadataWarning>,
+    thumbnail_decoded: bool,
 }
 
 impl PhotoEntry {
@@ -73,13 +165,16 @@ impl PhotoEntry {
             .map(|value| value.to_string_lossy().to_string())
             .unwrap_or_else(|| String::from("unknown"));
 
-        let file_size = fs::metadata(&path).map(|meta| meta.len()).unwrap_or(0);
+        let file_meta = fs::metadata(&path).ok();
+        let file_size = file_meta.as_ref().map_or(0, |meta| meta.len());
+        let modified = file_meta.and_then(|meta| meta.modified().ok());
 
         Self {
             id,
             path,
             filename,
             file_size,
+            modified,
             format,
             dimensions: None,
             thumbnail: None,
@@ -87,17 +182,75 @@ impl PhotoEntry {
             persisted_metadata: PhotoMetadata::default(),
             selected: false,
             dirty: false,
+            read_warnings: Vec::new(),
+            thumbnail_decoded: false,
         }
     }
 
+    /// Stat the file again after it was reloaded. A changed modification time
+    /// drops the thumbnail so the strip decodes the new pixels.
+    pub fn refresh_file_stats(&mut self) {
+        let file_meta = fs::metadata(&self.path).ok();
+        self.file_size = file_meta.as_ref().map_or(0, |meta| meta.len());
+        let modified = file_meta.and_then(|meta| meta.modified().ok());
+        if modified != self.modified {
+            self.modified = modified;
+            self.thumbnail = None;
+            self.thumbnail_decoded = false;
+        }
+    }
+
+    /// Whether the strip still has to decode this photo's thumbnail. A file that
+    /// failed to decode counts as done, so it isn't retried every frame.
+    pub fn needs_thumbnail(&self) -> bool {
+        !self.thumbnail_decoded
+    }
+
+    pub fn set_thumbnail(&mut self, thumbnail: Option<ThumbnailData>) {
+        self.thumbnail = thumbnail;
+        self.thumbnail_decoded = true;
+    }
+
     pub fn set_loaded_metadata(&mut self, metadata: PhotoMetadata) {
         self.persisted_metadata = metadata.clone();
         self.metadata = metadata;
         self.dirty = false;
     }
 
+    /// A straight comparison, which stops at the first differing tag and
+    /// allocates nothing; hashing both sides would walk every tag each time.
+    /// Provenance isn't compared: a tag typed back to its saved value leaves
+    /// nothing to write, so the saved provenance comes back with it.
     pub fn recompute_dirty(&mut self) {
-        self.dirty = self.metadata != self.persisted_metadata;
+        self.dirty = !self.metadata.same_values(&self.persisted_metadata);
+        if !self.dirty {
+            self.metadata
+                .provenance
+                .clone_from(&self.persisted_metadata.provenance);
+        }
+    }
+
+    /// Whether the thumbnail strip badges this photo as having unsaved edits.
+    pub fn shows_unsaved_badge(&self) -> bool {
+        self.dirty
+    }
+
+    /// Dimensions recorded in the metadata, falling back to the decoded image header.
+    pub fn pixel_dimensions(&self) -> Option<Dimensions> {
+        self.metadata.dimensions.or(self.dimensions)
+    }
+
+    /// Whether the tag's current value differs from what was last loaded or saved.
+    pub fn is_tag_modified(&self, key: &str) -> bool {
+        let current = self.metadata.find_tag(key).map(|tag| &tag.value);
+        let persisted = self.persisted_metadata.find_tag(key).map(|tag| &tag.value);
+        current != persisted
+    }
+
+    pub fn has_orientation_mismatch(&self) -> bool {
+        self.dimensions.as_ref().is_some_and(|dimensions| {
+            dimensions.conflicts_with_orientation(self.metadata.orientation())
+        })
     }
 }
 
@@ -110,6 +263,18 @@ pub struct PhotoMetadata {
     pub date_taken: Option<String>,
     pub camera_make: Option<String>,
     pub camera_model: Option<String>,
+    pub dimensions: Option<Dimensions>,
+    /// Tags set from inside the app, keyed by tag key. Tags without an entry
+    /// still hold the value they were imported with. Only stored in sidecars.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub provenance: BTreeMap<String, TagProvenance>,
+}
+
+/// Who set a tag's current value, and when (RFC 3339, UTC).
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct TagProvenance {
+    pub set_by: String,
+    pub set_at: String,
 }
 
 impl PhotoMetadata {
@@ -120,10 +285,73 @@ impl PhotoMetadata {
             .chain(self.xmp_tags.iter())
     }
 
+    pub fn all_tags_mut(&mut self) -> impl Iterator<Item = &mut MetadataTag> {
+        self.exif_tags
+            .iter_mut()
+            .chain(self.iptc_tags.iter_mut())
+            .chain(self.xmp_tags.iter_mut())
+    }
+
+    /// Order value for a newly added tag, after every tag already present.
+    pub fn next_tag_order(&self) -> u32 {
+        self.all_tags()
+            .map(|tag| tag.order + 1)
+            .max()
+            .unwrap_or_default()
+    }
+
     pub fn total_tag_count(&self) -> usize {
         self.exif_tags.len() + self.iptc_tags.len() + self.xmp_tags.len()
     }
 
+    /// Tag counts per block, as `(exif, iptc, xmp)`.
+    pub fn tag_counts(&self) -> (usize, usize, usize) {
+        (
+            self.exif_tags.len(),
+            self.iptc_tags.len(),
+            self.xmp_tags.len(),
+        )
+    }
+
+    /// Whether both hold the same tags and summary fields, ignoring who set
+    /// them and when.
+    pub fn same_values(&self, other: &PhotoMetadata) -> bool {
+        let PhotoMetadata {
+            exif_tags,
+            iptc_tags,
+            xmp_tags,
+            has_gps,
+            date_taken,
+            camera_make,
+            camera_model,
+            dimensions,
+            provenance: _,
+        } = self;
+        *exif_tags == other.exif_tags
+            && *iptc_tags == other.iptc_tags
+            && *xmp_tags == other.xmp_tags
+            && *has_gps == other.has_gps
+            && *date_taken == other.date_taken
+            && *camera_make == other.camera_make
+            && *camera_model == other.camera_model
+            && *dimensions == other.dimensions
+    }
+
+    /// The `Exif.Image.Orientation` value, defaulting to 1 (upright) when absent.
+    pub fn orientation(&self) -> i64 {
+        self.find_tag(ORIENTATION_KEY)
+            .and_then(|tag| match tag.value {
+                TagValue::Integer(value) => Some(value),
+                _ => None,
+            })
+            .unwrap_or(1)
+    }
+
+    pub fn find_tag(&self, key: &str) -> Option<&MetadataTag> {
+        self.all_tags()
+            .find(|tag| tag.key.eq_ignore_ascii_case(key))
+    }
+
     pub fn find_tag_mut(&mut self, key: &str) -> Option<&mut MetadataTag> {
         if let Some(tag) = self
             .exif_tags
@@ -146,11 +374,33 @@ impl PhotoMetadata {
             .find(|tag| tag.key.eq_ignore_ascii_case(key))
     }
 
+    pub fn provenance_for(&self, key: &str) -> Option<&TagProvenance> {
+        self.provenance
+            .iter()
+            .find(|(tag_key, _)| tag_key.eq_ignore_ascii_case(key))
+            .map(|(_, provenance)| provenance)
+    }
+
+    /// Mark `key` as set by this app just now.
+    pub fn record_provenance(&mut self, key: &str) {
+        self.provenance
+            .retain(|tag_key, _| !tag_key.eq_ignore_ascii_case(key));
+        self.provenance.insert(
+            key.to_string(),
+            TagProvenance {
+                set_by: String::from(PROVENANCE_APP),
+                set_at: Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+            },
+        );
+    }
+
     pub fn update_summary_fields(&mut self) {
         let mut has_gps = false;
         let mut date_taken = None;
         let mut camera_make = None;
         let mut camera_model = None;
+        let mut width = None;
+        let mut height = None;
 
         for tag in self.all_tags() {
             let key = tag.key.to_ascii_lowercase();
@@ -170,11 +420,31 @@ impl PhotoMetadata {
             if camera_model.is_none() && key.ends_with("model") {
                 camera_model = Some(tag.value.to_string());
             }
+
+            if let TagValue::Integer(value) = tag.value {
+                let value = u32::try_from(value).ok().filter(|value| *value > 0);
+                // Cameras record the pixel size in `PixelXDimension` and
+                // `PixelYDimension`; `ImageWidth`/`ImageHeight` are mostly
+                // left to TIFF files.
+                if width.is_none()
+                    && (key.ends_with("imagewidth") || key.ends_with("pixelxdimension"))
+                {
+                    width = value;
+                }
+                if height.is_none()
+                    && (key.ends_with("imageheight") || key.ends_with("pixelydimension"))
+                {
+                    height = value;
+                }
+            }
         }
 
         self.has_gps = has_gps;
         self.date_taken = date_taken;
         self.camera_make = camera_make;
         self.camera_model = camera_model;
+        self.dimensions = width
+            .zip(height)
+            .map(|(width, height)| Dimensions { width, height });
     }
 }
@@ -32,6 +32,26 @@ impl StripPreset {
             is_builtin,
         }
     }
+
+    /// File-name suffix for cleaned copies, slugified from the name:
+    /// "Web Safe" becomes `_web-safe`.
+    pub fn default_suffix(&self) -> String {
+        let mut slug = String::new();
+        for ch in self.name.chars() {
+            if ch.is_alphanumeric() {
+                slug.extend(ch.to_lowercase());
+            } else if !slug.is_empty() && !slug.ends_with('-') {
+                slug.push('-');
+            }
+        }
+
+        let slug = slug.trim_end_matches('-');
+        if slug.is_empty() {
+            String::from("_clean")
+        } else {
+            format!("_{slug}")
+        }
+    }
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
@@ -43,4 +63,11 @@ pub enum PresetRule {
     RemoveGps,
     RemoveThumbnail,
     SetTag(String, String),
+    /// Rewrite recognisable timestamps (ISO 8601, slashes, missing seconds)
+    /// in the EXIF `YYYY:MM:DD HH:MM:SS` form.
+    NormalizeDateTimes,
+    /// Set `Exif.Image.Orientation` to 1 unless it holds a value from 1 to 8.
+    ResetInvalidOrientation,
+    /// Drop text tags whose value is empty or only whitespace.
+    RemoveEmptyTags,
 }
@@ -10,6 +10,42 @@ pub struct MetadataTag {
     pub category: TagCategory,
     pub editable: bool,
     pub marked_for_removal: bool,
+    /// Position in the file's EXIF layout; tags added in the app sort after
+    /// every tag that was read.
+    #[serde(default)]
+    pub order: u32,
+    /// How a tag the editor has no name for was stored, so an edit is
+    /// written back as the same field. `None` for every named tag.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw_type: Option<RawExifType>,
+}
+
+/// Storage format and IFD of an `Exif.Unknown.0xXXXX` tag.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
+pub struct RawExifType {
+    pub format: RawExifFormat,
+    pub ifd: ExifIfd,
+}
+
+/// The EXIF field formats unnamed tags are read in.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
+pub enum RawExifFormat {
+    String,
+    Int8U,
+    Int16U,
+    Int32U,
+    Rational64U,
+    Rational64S,
+    Undef,
+}
+
+/// The image file directory a tag lives in.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
+pub enum ExifIfd {
+    Generic,
+    Exif,
+    Interop,
+    Gps,
 }
 
 impl MetadataTag {
@@ -26,6 +62,8 @@ impl MetadataTag {
             category,
             editable: true,
             marked_for_removal: false,
+            order: 0,
+            raw_type: None,
         }
     }
 }
@@ -33,6 +71,8 @@ impl MetadataTag {
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum TagValue {
     Text(String),
+    /// Values of a tag that holds a list, like IPTC keywords.
+    TextList(Vec<String>),
     Integer(i64),
     Float(f64),
     Rational(u32, u32),
@@ -48,6 +88,7 @@ impl fmt::Display for TagValue {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::Text(v) => write!(f, "{v}"),
+            Self::TextList(v) => write!(f, "{}", v.join(", ")),
             Self::Integer(v) => write!(f, "{v}"),
             Self::Float(v) => write!(f, "{v}"),
             Self::Rational(n, d) => write!(f, "{n}/{d}"),
@@ -65,6 +106,165 @@ impl fmt::Display for TagValue {
     }
 }
 
+impl TagValue {
+    /// A list from comma-separated `raw`, dropping blank entries.
+    pub fn text_list(raw: &str) -> Self {
+        Self::TextList(
+            raw.split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(String::from)
+                .collect(),
+        )
+    }
+
+    /// The value as a number, for integers, floats and rationals with a
+    /// nonzero denominator.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Self::Integer(n) => Some(*n as f64),
+            Self::Float(n) => Some(*n),
+            Self::Rational(n, d) if *d != 0 => Some(f64::from(*n) / f64::from(*d)),
+            _ => None,
+        }
+    }
+
+    /// Approximate size of the value as stored in the file, in bytes.
+    pub fn payload_len(&self) -> usize {
+        match self {
+            Self::Text(v) | Self::DateTime(v) | Self::Unknown(v) => v.len(),
+            Self::TextList(v) => v.iter().map(String::len).sum(),
+            Self::Integer(_) => 4,
+            Self::Float(_) | Self::Rational(_, _) => 8,
+            Self::Gps(_, _, alt) => 48 + if alt.is_some() { 8 } else { 0 },
+            Self::Binary(v) => v.len(),
+        }
+    }
+}
+
+const EXIF_DATETIME_SHAPE: &str = "Expected YYYY:MM:DD HH:MM:SS";
+
+/// Check that `raw` is an EXIF `YYYY:MM:DD HH:MM:SS` timestamp with in-range
+/// fields and return it trimmed, or a message describing what is wrong.
+pub fn validate_exif_datetime(raw: &str) -> Result<String, String> {
+    let trimmed = raw.trim();
+    let (date, time) = trimmed
+        .split_once(' ')
+        .ok_or_else(|| String::from(EXIF_DATETIME_SHAPE))?;
+
+    let date_parts = split_fixed_fields(date, &[4, 2, 2])?;
+    let time_parts = split_fixed_fields(time, &[2, 2, 2])?;
+    let [year, month, day] = [date_parts[0], date_parts[1], date_parts[2]];
+    let [hour, minute, second] = [time_parts[0], time_parts[1], time_parts[2]];
+
+    if !(1..=12).contains(&month) {
+        return Err(format!("Month must be 01-12, got {month:02}"));
+    }
+    if chrono::NaiveDate::from_ymd_opt(year as i32, month, day).is_none() {
+        return Err(format!(
+            "Day {day:02} is not valid for {year:04}:{month:02}"
+        ));
+    }
+    if hour > 23 {
+        return Err(format!("Hour must be 00-23, got {hour:02}"));
+    }
+    if minute > 59 {
+        return Err(format!("Minute must be 00-59, got {minute:02}"));
+    }
+    if second > 59 {
+        return Err(format!("Second must be 00-59, got {second:02}"));
+    }
+
+    Ok(trimmed.to_string())
+}
+
+/// Reformat a timestamp written in a common non-EXIF style (ISO 8601, dashes
+/// or slashes, missing seconds, date only) as `YYYY:MM:DD HH:MM:SS`. Returns
+/// `None` when `raw` isn't a recognisable timestamp.
+pub fn normalize_exif_datetime(raw: &str) -> Option<String> {
+    const DATETIME_FORMATS: &[&str] = &[
+        "%Y:%m:%d %H:%M:%S",
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%dT%H:%M:%S",
+        "%Y/%m/%d %H:%M:%S",
+        "%Y:%m:%d %H:%M",
+        "%Y-%m-%d %H:%M",
+        "%Y-%m-%dT%H:%M",
+        "%Y/%m/%d %H:%M",
+    ];
+    const DATE_FORMATS: &[&str] = &["%Y:%m:%d", "%Y-%m-%d", "%Y/%m/%d"];
+
+    let trimmed = raw.trim();
+    let parsed = chrono::DateTime::parse_from_rfc3339(trimmed)
+        .map(|datetime| datetime.naive_local())
+        .ok()
+        .or_else(|| {
+            DATETIME_FORMATS
+                .iter()
+                .find_map(|format| chrono::NaiveDateTime::parse_from_str(trimmed, format).ok())
+        })
+        .or_else(|| {
+            DATE_FORMATS.iter().find_map(|format| {
+                chrono::NaiveDate::parse_from_str(trimmed, format)
+                    .ok()
+                    .and_then(|date| date.and_hms_opt(0, 0, 0))
+            })
+        })?;
+
+    Some(parsed.format("%Y:%m:%d %H:%M:%S").to_string())
+}
+
+/// Photographer-facing reading of a rational capture setting: `f/2.8` for
+/// FNumber, `1/60s` for ExposureTime, `35 mm` for FocalLength. Empty for
+/// other keys and for a zero denominator.
+pub fn format_rational_for_key(key: &str, numerator: u32, denominator: u32) -> String {
+    if denominator == 0 {
+        return String::new();
+    }
+    let value = f64::from(numerator) / f64::from(denominator);
+
+    match key {
+        "Exif.Photo.FNumber" => format!("f/{value:.1}"),
+        "Exif.Photo.ExposureTime" if numerator == 0 => String::from("0s"),
+        "Exif.Photo.ExposureTime" if value < 1.0 => {
+            format!("1/{}s", (1.0 / value).round())
+        }
+        "Exif.Photo.ExposureTime" => format!("{}s", trim_decimal(value)),
+        "Exif.Photo.FocalLength" => format!("{} mm", trim_decimal(value)),
+        _ => String::new(),
+    }
+}
+
+/// One decimal place, dropped when it is zero.
+fn trim_decimal(value: f64) -> String {
+    let formatted = format!("{value:.1}");
+    formatted
+        .strip_suffix(".0")
+        .map(str::to_string)
+        .unwrap_or(formatted)
+}
+
+/// Split a `:`-separated group whose fields are all digits of the given widths.
+fn split_fixed_fields(raw: &str, widths: &[usize]) -> Result<Vec<u32>, String> {
+    let fields = raw.split(':').collect::<Vec<_>>();
+    if fields.len() != widths.len() {
+        return Err(String::from(EXIF_DATETIME_SHAPE));
+    }
+
+    fields
+        .iter()
+        .zip(widths)
+        .map(|(field, &width)| {
+            if field.len() != width || !field.bytes().all(|byte| byte.is_ascii_digit()) {
+                return Err(String::from(EXIF_DATETIME_SHAPE));
+            }
+            field
+                .parse::<u32>()
+                .map_err(|_| String::from(EXIF_DATETIME_SHAPE))
+        })
+        .collect()
+}
+
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
 pub enum TagCategory {
     Camera,
@@ -78,6 +278,17 @@ pub enum TagCategory {
 }
 
 impl TagCategory {
+    pub const ALL: [TagCategory; 8] = [
+        Self::Camera,
+        Self::Capture,
+        Self::Location,
+        Self::DateTime,
+        Self::Image,
+        Self::Description,
+        Self::Software,
+        Self::Other,
+    ];
+
     pub fn as_str(self) -> &'static str {
         match self {
             Self::Camera => "Camera",
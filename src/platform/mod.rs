@@ -1,3 +1,6 @@
+use std::env;
+use std::path::{Component, Path, PathBuf};
+
 pub mod macos;
 pub mod windows;
 
@@ -17,3 +20,51 @@ pub fn current_platform() -> Platform {
         Platform::Other
     }
 }
+
+const CONFIG_DIR_NAME: &str = "exif-editor";
+
+/// Per-user directory for settings and logs. Falls back to the temp dir when
+/// the usual environment variables are missing.
+pub fn config_dir() -> PathBuf {
+    let base = match current_platform() {
+        Platform::MacOS => env::var_os("HOME").map(|home| {
+            PathBuf::from(home)
+                .join("Library")
+                .join("Application Support")
+        }),
+        Platform::Windows => env::var_os("APPDATA").map(PathBuf::from),
+        Platform::Other => env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config"))),
+    };
+
+    base.unwrap_or_else(env::temp_dir).join(CONFIG_DIR_NAME)
+}
+
+/// Resolve `path` against the process working directory. See [`absolute_path_from`].
+pub fn absolute_path(path: &Path) -> PathBuf {
+    let base = env::current_dir().unwrap_or_default();
+    absolute_path_from(path, &base)
+}
+
+/// Join a relative `path` onto `base` and fold `.`/`..` components lexically,
+/// without touching the filesystem (symlinks are left alone).
+pub fn absolute_path_from(path: &Path, base: &Path) -> PathBuf {
+    let joined = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        base.join(path)
+    };
+
+    let mut resolved = PathBuf::new();
+    for component in joined.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                resolved.pop();
+            }
+            other => resolved.push(other),
+        }
+    }
+    resolved
+}
@@ -0,0 +1,184 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::models::{MetadataTag, OutputMode, PresetId, TagCategory};
+use crate::platform;
+
+pub const SETTINGS_FILE_NAME: &str = "settings.json";
+
+/// Thumbnail tile edges, in logical pixels, offered by the settings panel.
+pub const THUMBNAIL_SIZE_CHOICES: [u32; 3] = [64, 96, 128];
+
+/// Where bulk runs write unless the user picks otherwise.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DefaultOutput {
+    #[default]
+    Overwrite,
+    /// Write a suffixed copy next to each original.
+    Copy,
+}
+
+impl DefaultOutput {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Overwrite => "Overwrite",
+            Self::Copy => "Copy",
+        }
+    }
+
+    pub fn output_mode(self) -> OutputMode {
+        match self {
+            Self::Overwrite => OutputMode::Overwrite,
+            Self::Copy => OutputMode::PresetSuffix,
+        }
+    }
+}
+
+/// Categories and keys that are never copied when one photo's metadata is
+/// applied to others as a template. Capture, location and date/time values
+/// describe a single exposure, and dimensions, orientation, serial numbers
+/// and the file's own name and size describe a single file, so by default
+/// only descriptive fields propagate.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TemplateBlocklist {
+    pub categories: Vec<TagCategory>,
+    pub keys: Vec<String>,
+}
+
+impl Default for TemplateBlocklist {
+    fn default() -> Self {
+        Self {
+            categories: vec![
+                TagCategory::Capture,
+                TagCategory::Location,
+                TagCategory::DateTime,
+            ],
+            keys: [
+                "Exif.Image.Orientation",
+                "Exif.Image.ImageWidth",
+                "Exif.Image.ImageHeight",
+                "Exif.Photo.PixelXDimension",
+                "Exif.Photo.PixelYDimension",
+                "Exif.Photo.BodySerialNumber",
+                "Exif.Image.CameraSerialNumber",
+                "Exif.Photo.SerialNumber",
+                "Exif.Photo.LensSerialNumber",
+                "ExifEditor.FileName",
+                "ExifEditor.FileSize",
+            ]
+            .map(String::from)
+            .to_vec(),
+        }
+    }
+}
+
+impl TemplateBlocklist {
+    pub fn blocks(&self, tag: &MetadataTag) -> bool {
+        self.categories.contains(&tag.category)
+            || self
+                .keys
+                .iter()
+                .any(|key| key.trim().eq_ignore_ascii_case(&tag.key))
+    }
+
+    /// Block `category` if it isn't blocked, or let it through if it is.
+    pub fn toggle_category(&mut self, category: TagCategory) {
+        match self
+            .categories
+            .iter()
+            .position(|blocked| *blocked == category)
+        {
+            Some(index) => {
+                self.categories.remove(index);
+            }
+            None => self.categories.push(category),
+        }
+    }
+}
+
+/// User preferences that outlive a session.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppSettings {
+    pub default_preset: Option<PresetId>,
+    pub default_output: DefaultOutput,
+    /// Keep a JSON sidecar next to each saved photo.
+    pub write_sidecars: bool,
+    /// Edge of a thumbnail tile in the carousel strip, in logical pixels.
+    pub thumbnail_size: u32,
+    /// Preset applied to each photo's metadata as it is imported. Only the
+    /// in-session copy is cleaned; nothing is written until the user saves.
+    pub strip_on_import: Option<PresetId>,
+    /// Copy each photo to `photo.jpg.orig` before its first save overwrites it.
+    pub backup_originals: bool,
+    /// Write read, write and bulk operations to the log file in the config
+    /// directory; see [`crate::logging`].
+    pub log_to_file: bool,
+    /// What applying a template leaves out. Categories are toggled in the
+    /// settings panel; extra keys can be listed in the settings file.
+    pub template_blocklist: TemplateBlocklist,
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        Self {
+            default_preset: Some(2),
+            default_output: DefaultOutput::Overwrite,
+            write_sidecars: true,
+            thumbnail_size: 96,
+            strip_on_import: None,
+            backup_originals: false,
+            log_to_file: false,
+            template_blocklist: TemplateBlocklist::default(),
+        }
+    }
+}
+
+impl AppSettings {
+    pub fn default_path() -> PathBuf {
+        platform::config_dir().join(SETTINGS_FILE_NAME)
+    }
+
+    pub fn load(path: &Path) -> Self {
+        load_json(path, "load_settings")
+    }
+
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        save_json(self, path)
+    }
+
+    /// [`Self::thumbnail_size`] limited to the range the panel offers, in case
+    /// the file was edited by hand.
+    pub fn thumbnail_edge(&self) -> u32 {
+        let min = THUMBNAIL_SIZE_CHOICES[0];
+        let max = THUMBNAIL_SIZE_CHOICES[THUMBNAIL_SIZE_CHOICES.len() - 1];
+        self.thumbnail_size.clamp(min, max)
+    }
+}
+
+/// Read a settings file from `path`. A missing or unreadable file yields the
+/// defaults rather than an error; a bad settings file should never keep the
+/// app from opening. Parse failures are logged under `op`.
+pub(crate) fn load_json<T: DeserializeOwned + Default>(path: &Path, op: &str) -> T {
+    let Ok(contents) = fs::read_to_string(path) else {
+        return T::default();
+    };
+    serde_json::from_str(&contents).unwrap_or_else(|err| {
+        log::warn!("op={op} path={} error={err}", path.display());
+        T::default()
+    })
+}
+
+/// Write `value` to `path` as pretty JSON, creating the config directory.
+pub(crate) fn save_json<T: Serialize>(value: &T, path: &Path) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let encoded = serde_json::to_string_pretty(value)?;
+    fs::write(path, encoded)
+}
@@ -1 +1,2 @@
 pub mod window;
+pub mod window_settings;
@@ -17,14 +17,48 @@ impl ExifEditorWindow {
             next = 0;
         }
 
-        self.state.select_photo(next as usize, false);
-        self.refresh_tag_rows = true;
+        self.navigate_to_photo(next as usize, cx);
+    }
+
+    /// Make `index` the active photo, first asking what to do with unsaved
+    /// edits on the current one when that confirmation is enabled.
+    pub(super) fn navigate_to_photo(&mut self, index: usize, cx: &mut Context<Self>) {
+        let leaving_dirty = self
+            .state
+            .active_photo
+            .filter(|&active| active != index)
+            .and_then(|active| self.state.photos.get(active))
+            .is_some_and(|photo| photo.dirty);
+
+        if leaving_dirty && self.confirm_leaving_unsaved {
+            self.leave_popup_target = Some(index);
+            cx.notify();
+            return;
+        }
+
+        self.activate_photo(index, cx);
+    }
+
+    pub(super) fn activate_photo(&mut self, index: usize, cx: &mut Context<Self>) {
+        let Some(photo) = self.state.photos.get(index) else {
+            return;
+        };
+        if self
+            .highlighted_tag
+            .as_ref()
+            .is_some_and(|(photo_id, _)| *photo_id != photo.id)
+        {
+            self.highlighted_tag = None;
+        }
+
         self.status = format!(
             "Selected {} ({}/{})",
-            self.state.photos[next as usize].filename,
-            next + 1,
-            len
+            photo.filename,
+            index + 1,
+            self.state.photos.len()
         );
+        self.state.select_photo(index, false);
+        self.refresh_tag_rows = true;
         cx.notify();
     }
 
@@ -38,11 +72,93 @@ impl ExifEditorWindow {
         }
     }
 
+    pub(super) fn import_exiftool_json(&mut self, cx: &mut Context<Self>) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("exiftool JSON", &["json"])
+            .pick_file()
+        else {
+            return;
+        };
+
+        match self.state.import_exiftool_json(&path) {
+            Ok(summary) => {
+                self.refresh_tag_rows = true;
+                self.status = format!(
+                    "Applied {} tag(s) to {} photo(s). {} file(s) had no loaded photo.",
+                    summary.tags_applied,
+                    summary.photos_updated,
+                    summary.unmatched_files.len()
+                );
+            }
+            Err(err) => {
+                self.status = format!("exiftool import failed: {err}");
+            }
+        }
+
+        cx.notify();
+    }
+
+    pub(super) fn export_exiftool_json(&mut self, cx: &mut Context<Self>) {
+        let Some(photo) = self
+            .state
+            .active_photo
+            .and_then(|index| self.state.photos.get(index))
+        else {
+            self.status = String::from("No active photo selected");
+            cx.notify();
+            return;
+        };
+
+        let default_name = Path::new(&photo.filename).with_extension("json");
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("exiftool JSON", &["json"])
+            .set_file_name(default_name.to_string_lossy())
+            .save_file()
+        else {
+            return;
+        };
+
+        let json = exiftool::export_exiftool_json(&photo.metadata);
+        self.status = match fs::write(&path, json) {
+            Ok(()) => format!("Exported metadata to {}", path.display()),
+            Err(err) => format!("exiftool export failed: {err}"),
+        };
+        cx.notify();
+    }
+
+    pub(super) fn export_contacts(&mut self, cx: &mut Context<Self>) {
+        let contacts = contacts::collect_contacts(&self.state.photos);
+        if contacts.is_empty() {
+            self.status = String::from("No artist or owner tags to export");
+            cx.notify();
+            return;
+        }
+
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("vCard", &["vcf"])
+            .set_file_name("contacts.vcf")
+            .save_file()
+        else {
+            return;
+        };
+
+        self.status = match fs::write(&path, contacts::contacts_to_vcard(&contacts)) {
+            Ok(()) => format!(
+                "Exported {} contact(s) to {}",
+                contacts.len(),
+                path.display()
+            ),
+            Err(err) => format!("Contact export failed: {err}"),
+        };
+        cx.notify();
+    }
+
     pub(super) fn import_paths(&mut self, paths: Vec<PathBuf>, cx: &mut Context<Self>) {
         let before_count = self.state.photos.len();
-        let expanded_paths = expand_paths(paths);
-        let skipped = self.state.import_paths(expanded_paths.clone());
-        let imported = self.state.photos.len().saturating_sub(before_count);
+        let summary = self.state.import_tree(paths);
+        let imported = summary.imported();
+        self.skipped_imports = summary.skipped_files().cloned().collect();
+        self.skipped_imports_expanded = false;
 
         if imported > 0 {
             if self.state.active_photo.is_none() {
@@ -50,31 +166,367 @@ impl ExifEditorWindow {
             }
             self.refresh_tag_rows = true;
             self.status = format!(
-                "Imported {imported} photo(s). Skipped {} unsupported path(s).",
-                skipped.len()
+                "Imported {imported} of {} file(s). Skipped {}.",
+                summary.found(),
+                summary.skipped()
             );
+            if summary.roots.len() > 1 {
+                let per_root = summary
+                    .roots
+                    .iter()
+                    .map(|root| {
+                        let name = root
+                            .root
+                            .file_name()
+                            .map(|name| name.to_string_lossy().to_string())
+                            .unwrap_or_else(|| root.root.display().to_string());
+                        format!("{name}: {}/{}", root.imported, root.found)
+                    })
+                    .collect::<Vec<_>>();
+                self.status.push_str(&format!(" ({})", per_root.join(", ")));
+            }
+
+            let mismatched = self.state.photos[before_count..]
+                .iter()
+                .filter(|photo| photo.has_orientation_mismatch())
+                .count();
+            if mismatched > 0 {
+                self.status.push_str(&format!(
+                    " {mismatched} photo(s) have an orientation tag that disagrees with their dimensions."
+                ));
+            }
+
+            let warnings = self.state.photos[before_count..]
+                .iter()
+                .flat_map(|photo| &photo.read_warnings);
+            let (mut no_exif, mut unreadable, mut sidecars_ignored) = (0, 0, 0);
+            for warning in warnings {
+                match warning {
+                    MetadataWarning::NoExif => no_exif += 1,
+                    MetadataWarning::Unreadable(_) => unreadable += 1,
+                    MetadataWarning::SidecarIgnored(_) => sidecars_ignored += 1,
+                }
+            }
+            if no_exif > 0 {
+                self.status
+                    .push_str(&format!(" {no_exif} photo(s) have no EXIF data."));
+            }
+            if unreadable > 0 {
+                self.status.push_str(&format!(
+                    " {unreadable} photo(s) have metadata that couldn't be read."
+                ));
+            }
+            if sidecars_ignored > 0 {
+                self.status.push_str(&format!(
+                    " {sidecars_ignored} unreadable sidecar(s) were ignored."
+                ));
+            }
         } else {
             self.status = String::from("No new supported photos were imported.");
         }
 
         cx.notify();
     }
+
+    pub(super) fn copy_active_path(&mut self, cx: &mut Context<Self>) {
+        let Some(photo) = self
+            .state
+            .active_photo
+            .and_then(|index| self.state.photos.get(index))
+        else {
+            self.status = String::from("No active photo selected");
+            cx.notify();
+            return;
+        };
+
+        let path = platform::absolute_path(&photo.path);
+        let path = path.to_string_lossy().to_string();
+        cx.write_to_clipboard(ClipboardItem::new_string(path.clone()));
+        self.status = format!("Copied {path}");
+        cx.notify();
+    }
+
+    /// Put the tags listed in the metadata pane on the clipboard as text.
+    pub(super) fn copy_metadata_report(&mut self, cx: &mut Context<Self>) {
+        let Some(photo_index) = self.state.active_photo else {
+            self.status = String::from("No active photo selected");
+            cx.notify();
+            return;
+        };
+
+        let tags = self.state.inspector_tags(photo_index);
+        cx.write_to_clipboard(ClipboardItem::new_string(format_metadata_report(&tags)));
+        self.status = format!("Copied {} tag(s) as text", tags.len());
+        cx.notify();
+    }
+
+    pub(super) fn fix_active_orientation(&mut self, cx: &mut Context<Self>) {
+        let Some(photo_index) = self.state.active_photo else {
+            self.status = String::from("No active photo selected");
+            cx.notify();
+            return;
+        };
+
+        match self.state.fix_orientation_mismatch(photo_index) {
+            Ok(true) => {
+                self.refresh_tag_rows = true;
+                self.status = format!(
+                    "Reset orientation for {}",
+                    self.state.photos[photo_index].filename
+                );
+            }
+            Ok(false) => {
+                self.status = String::from("Orientation already matches the image dimensions");
+            }
+            Err(err) => {
+                self.status = format!("Orientation fix failed: {err}");
+            }
+        }
+
+        cx.notify();
+    }
+
     pub(super) fn save_active(&mut self, cx: &mut Context<Self>) {
+        if self.state.is_saving || self.state.is_processing {
+            return;
+        }
+
         let Some(photo_index) = self.state.active_photo else {
             self.status = String::from("No active photo selected");
             cx.notify();
             return;
         };
 
-        match self.state.save_photo_changes(photo_index) {
-            Ok(()) => {
-                self.status = format!("Saved {}", self.state.photos[photo_index].filename);
+        let job = match self.state.begin_save(photo_index) {
+            Ok(job) => job,
+            Err(err) => {
+                self.status = format!("Save failed: {err}");
+                cx.notify();
+                return;
             }
+        };
+
+        self.status = format!("Saving {}...", job.filename());
+        let worker = cx.background_spawn(async move {
+            let result = job.run();
+            (job, result)
+        });
+        self.save_task = Some(cx.spawn(async move |this, cx| {
+            let (job, result) = worker.await;
+            let _ = this.update(cx, |this, cx| this.finish_save(job, result, cx));
+        }));
+
+        cx.notify();
+    }
+
+    fn finish_save(
+        &mut self,
+        job: SaveJob,
+        result: Result<(), MetadataError>,
+        cx: &mut Context<Self>,
+    ) {
+        let filename = job.filename();
+        self.save_task = None;
+        let leave_to = self.leave_after_save.take();
+        let status = match self.state.finish_save(job, result) {
+            Ok(0) => format!("Saved {filename}"),
+            Ok(dropped) => format!("Saved {filename}. Dropped {dropped} empty tag(s)."),
             Err(err) => {
                 self.status = format!("Save failed: {err}");
+                cx.notify();
+                return;
             }
+        };
+        if let Some(target) = leave_to {
+            self.activate_photo(target, cx);
+        }
+        self.status = status;
+        cx.notify();
+    }
+
+    pub(super) fn undo(&mut self, cx: &mut Context<Self>) {
+        if self.state.undo_last_change() {
+            self.refresh_tag_rows = true;
+            self.status = String::from("Undid last change");
+        } else {
+            self.status = String::from("Nothing to undo");
+        }
+        cx.notify();
+    }
+
+    pub(super) fn redo(&mut self, cx: &mut Context<Self>) {
+        if self.state.redo_last_change() {
+            self.refresh_tag_rows = true;
+            self.status = String::from("Redid last change");
+        } else {
+            self.status = String::from("Nothing to redo");
         }
+        cx.notify();
+    }
+
+    pub(super) fn mark_tag_for_removal(
+        &mut self,
+        tag_key: &str,
+        marked: bool,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(photo_index) = self.state.active_photo else {
+            return;
+        };
+
+        if let Err(err) = self
+            .state
+            .mark_tag_for_removal(photo_index, tag_key, marked)
+        {
+            self.status = format!("Mark failed: {err}");
+        }
+        cx.notify();
+    }
+
+    pub(super) fn strip_marked_tags(&mut self, cx: &mut Context<Self>) {
+        let Some(photo_index) = self.state.active_photo else {
+            self.status = String::from("No active photo selected");
+            cx.notify();
+            return;
+        };
+
+        self.status = match self.state.strip_marked_tags(photo_index) {
+            Ok(removed) => {
+                self.refresh_tag_rows = true;
+                format!("Stripped {removed} marked tag(s)")
+            }
+            Err(err) => format!("Strip failed: {err}"),
+        };
+        cx.notify();
+    }
+
+    pub(super) fn conform_selection_to_active(&mut self, cx: &mut Context<Self>) {
+        let Some(profile_index) = self.state.active_photo else {
+            self.status = String::from("No active photo selected");
+            cx.notify();
+            return;
+        };
 
+        let mut targets = self
+            .state
+            .selected_indices
+            .iter()
+            .copied()
+            .collect::<Vec<_>>();
+        targets.sort_unstable();
+
+        // One undo step puts back every photo the conform touched.
+        self.state.begin_undo_group();
+        let conformed = self.state.conform_to_profile(profile_index, &targets);
+        self.state.end_undo_group();
+
+        self.status = match conformed {
+            Ok(removed) => {
+                self.refresh_tag_rows = true;
+                format!(
+                    "Removed {removed} tag(s) not on {}",
+                    self.state.photos[profile_index].filename
+                )
+            }
+            Err(err) => format!("Conform failed: {err}"),
+        };
+        cx.notify();
+    }
+
+    pub(super) fn apply_active_as_template(&mut self, cx: &mut Context<Self>) {
+        let Some(template_index) = self.state.active_photo else {
+            self.status = String::from("No active photo selected");
+            cx.notify();
+            return;
+        };
+
+        let mut targets = self
+            .state
+            .selected_indices
+            .iter()
+            .copied()
+            .collect::<Vec<_>>();
+        targets.sort_unstable();
+
+        self.state.begin_undo_group();
+        let applied = self.state.apply_template(template_index, &targets);
+        self.state.end_undo_group();
+
+        self.status = match applied {
+            Ok(summary) => {
+                self.refresh_tag_rows = true;
+                let mut status = format!(
+                    "Applied {} tag(s) to {} photo(s)",
+                    summary.tags_applied, summary.photos_updated
+                );
+                if !summary.skipped_keys.is_empty() {
+                    status.push_str(&format!(
+                        "; kept per-photo tags: {}",
+                        summary.skipped_keys.join(", ")
+                    ));
+                }
+                status
+            }
+            Err(err) => format!("Template failed: {err}"),
+        };
+        cx.notify();
+    }
+
+    pub(super) fn set_selection_to_srgb(&mut self, cx: &mut Context<Self>) {
+        let mut targets = self
+            .state
+            .selected_indices
+            .iter()
+            .copied()
+            .collect::<Vec<_>>();
+        targets.sort_unstable();
+
+        self.state.begin_undo_group();
+        let changed = self.state.bulk_set_color_space(COLOR_SPACE_SRGB, &targets);
+        self.state.end_undo_group();
+
+        self.refresh_tag_rows = true;
+        self.status = format!("Set Color Space to sRGB on {changed} photo(s)");
+        cx.notify();
+    }
+
+    pub(super) fn set_selection_copyright_year(&mut self, cx: &mut Context<Self>) {
+        let mut targets = self
+            .state
+            .selected_indices
+            .iter()
+            .copied()
+            .collect::<Vec<_>>();
+        targets.sort_unstable();
+        let year = chrono::Local::now().year() as u32;
+
+        self.state.begin_undo_group();
+        let changed = self.state.bulk_set_copyright_year(year, &targets);
+        self.state.end_undo_group();
+
+        self.refresh_tag_rows = true;
+        self.status = format!("Set the copyright year to {year} on {changed} photo(s)");
+        cx.notify();
+    }
+
+    pub(super) fn duplicate_active(&mut self, cx: &mut Context<Self>) {
+        let Some(photo_index) = self.state.active_photo else {
+            self.status = String::from("No active photo selected");
+            cx.notify();
+            return;
+        };
+
+        self.status = match self.state.duplicate_photo(photo_index) {
+            Ok(copy_index) => {
+                self.state.select_photo(copy_index, false);
+                self.refresh_tag_rows = true;
+                format!(
+                    "Duplicated {}. Both entries save to the same file.",
+                    self.state.photos[copy_index].filename
+                )
+            }
+            Err(err) => format!("Duplicate failed: {err}"),
+        };
         cx.notify();
     }
 
@@ -95,16 +547,17 @@ impl ExifEditorWindow {
         };
 
         let photo = &self.state.photos[photo_index];
-        let output_path = unique_export_path(&export_dir, &photo.filename, "_export");
+        let output_path =
+            BulkProcessor::unique_export_path(&export_dir, &photo.filename, "_export");
 
-        if let Err(err) = fs::copy(&photo.path, &output_path) {
-            self.status = format!("Failed to copy file to export path: {err}");
-            cx.notify();
-            return;
-        }
-
-        if let Err(err) = MetadataEngine::write(&output_path, &photo.metadata) {
-            self.status = format!("File copied, but metadata export failed: {err}");
+        let write_sidecar = self.state.settings.write_sidecars;
+        if let Err(err) = MetadataEngine::export_with_metadata(
+            &photo.path,
+            &output_path,
+            &photo.metadata,
+            write_sidecar,
+        ) {
+            self.status = format!("Export failed: {err}");
             cx.notify();
             return;
         }
@@ -114,10 +567,17 @@ impl ExifEditorWindow {
     }
 
     pub(super) fn save_all(&mut self, cx: &mut Context<Self>) {
+        if self.state.is_saving || self.state.is_processing {
+            return;
+        }
+
         match self.state.save_all_dirty() {
-            Ok(count) => {
+            Ok((count, 0)) => {
                 self.status = format!("Saved {count} photo(s)");
             }
+            Ok((count, dropped)) => {
+                self.status = format!("Saved {count} photo(s). Dropped {dropped} empty tag(s).");
+            }
             Err(err) => {
                 self.status = format!("Save all failed: {err}");
             }
@@ -141,23 +601,55 @@ impl ExifEditorWindow {
             return;
         };
 
-        let mut ok_count = 0usize;
-        let mut fail_count = 0usize;
-        for photo in &self.state.photos {
-            let output_path = unique_export_path(&export_dir, &photo.filename, "_export");
-            if let Err(_err) = fs::copy(&photo.path, &output_path)
-                .and_then(|_| {
-                    MetadataEngine::write(&output_path, &photo.metadata)
-                        .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e.to_string()))
-                })
-            {
-                fail_count += 1;
-            } else {
-                ok_count += 1;
-            }
+        let summary = self.state.export_all(&export_dir);
+        self.status = format!(
+            "Exported {} photo(s), {} failed",
+            summary.exported, summary.failed
+        );
+        if let Some(first) = summary.renamed.first() {
+            let renamed_to = first
+                .output_path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+            self.status.push_str(&format!(
+                "; {} renamed to avoid name clashes (e.g. {renamed_to})",
+                summary.renamed.len()
+            ));
         }
+        cx.notify();
+    }
+
+    pub(super) fn export_selected_with_preset(&mut self, cx: &mut Context<Self>) {
+        let Some(preset_id) = self.state.active_preset else {
+            self.status = String::from("No preset selected");
+            cx.notify();
+            return;
+        };
 
-        self.status = format!("Exported {ok_count} photo(s), {fail_count} failed");
+        let Some(export_dir) = rfd::FileDialog::new()
+            .set_title("Choose export folder")
+            .pick_folder()
+        else {
+            self.status = String::from("Export cancelled");
+            cx.notify();
+            return;
+        };
+
+        match self
+            .state
+            .export_selected_with_preset(&export_dir, preset_id)
+        {
+            Ok(summary) => {
+                self.status = format!(
+                    "Exported {} stripped photo(s), {} failed",
+                    summary.succeeded, summary.failed
+                );
+            }
+            Err(err) => {
+                self.status = format!("Export failed: {err}");
+            }
+        }
         cx.notify();
     }
 
@@ -173,4 +665,767 @@ impl ExifEditorWindow {
         self.refresh_tag_rows = true;
         cx.notify();
     }
+
+    pub(super) fn audit_sidecars(&mut self, cx: &mut Context<Self>) {
+        let audit = self.state.audit_sidecars();
+        let stale: Vec<String> = audit
+            .iter()
+            .filter(|(_, stale)| *stale)
+            .map(|(path, _)| {
+                path.file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_else(|| path.display().to_string())
+            })
+            .collect();
+        self.status = if audit.is_empty() {
+            String::from("No sidecars to check")
+        } else if stale.is_empty() {
+            format!("All {} sidecar(s) are in sync", audit.len())
+        } else {
+            format!(
+                "{} of {} sidecar(s) are stale: {}",
+                stale.len(),
+                audit.len(),
+                stale.join(", ")
+            )
+        };
+        cx.notify();
+    }
+
+    /// List the photos whose saved metadata still carries privacy-sensitive
+    /// tags in a dialog, marking those with unsaved edits.
+    pub(super) fn audit_privacy(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let audit = self.state.privacy_audit();
+        let total = self.state.photos.len();
+        if audit.is_empty() {
+            self.status = format!("No privacy-sensitive tags saved in {total} photo(s)");
+            cx.notify();
+            return;
+        }
+        let unsaved = audit.iter().filter(|finding| finding.unsaved).count();
+        self.status = format!(
+            "{} of {total} photo(s) still carry privacy-sensitive tags",
+            audit.len()
+        );
+        cx.notify();
+
+        window.open_dialog(cx, move |dialog, _, cx| {
+            let rows: Vec<AnyElement> = audit
+                .iter()
+                .map(|finding| {
+                    let name = finding
+                        .path
+                        .file_name()
+                        .map(|name| name.to_string_lossy().to_string())
+                        .unwrap_or_else(|| finding.path.display().to_string());
+                    v_flex()
+                        .w_full()
+                        .py_1()
+                        .child(
+                            h_flex()
+                                .gap_2()
+                                .child(
+                                    div()
+                                        .text_sm()
+                                        .font_weight(gpui::FontWeight::MEDIUM)
+                                        .text_color(cx.theme().foreground)
+                                        .child(name),
+                                )
+                                .children(finding.unsaved.then(|| {
+                                    div()
+                                        .text_xs()
+                                        .text_color(cx.theme().warning)
+                                        .child("Unsaved changes")
+                                })),
+                        )
+                        .child(
+                            div()
+                                .text_xs()
+                                .text_color(cx.theme().muted_foreground)
+                                .child(finding.keys.join(", ")),
+                        )
+                        .into_any_element()
+                })
+                .collect();
+
+            let mut summary = format!(
+                "{} of {total} photo(s) still carry privacy-sensitive tags in their saved metadata.",
+                audit.len()
+            );
+            if unsaved > 0 {
+                summary.push_str(&format!(
+                    " {unsaved} have unsaved changes; save them to update the files."
+                ));
+            }
+            dialog.alert().title("Privacy audit").child(
+                v_flex()
+                    .gap_2()
+                    .child(summary)
+                    .child(
+                        div()
+                            .id(SharedString::from("privacy-audit-list"))
+                            .w_full()
+                            .max_h(px(360.0))
+                            .overflow_y_scrollbar()
+                            .child(v_flex().w_full().gap_1().children(rows)),
+                    ),
+            )
+        });
+    }
+
+    pub(super) fn revert_active(&mut self, cx: &mut Context<Self>) {
+        let Some(photo_index) = self.state.active_photo else {
+            self.status = String::from("No active photo selected");
+            cx.notify();
+            return;
+        };
+
+        match self.state.revert_photo(photo_index) {
+            Ok(()) => {
+                let filename = &self.state.photos[photo_index].filename;
+                self.status = format!("Reverted unsaved changes on {filename}");
+                self.refresh_tag_rows = true;
+            }
+            Err(err) => {
+                self.status = format!("Failed to revert photo: {err}");
+            }
+        }
+
+        cx.notify();
+    }
+
+    pub(super) fn revert_all(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let dirty = self.state.photos.iter().filter(|photo| photo.dirty).count();
+        if dirty == 0 {
+            self.status = String::from("No unsaved changes to revert");
+            cx.notify();
+            return;
+        }
+
+        let view = cx.entity().downgrade();
+        window.open_dialog(cx, move |dialog, _, _| {
+            let view = view.clone();
+            dialog
+                .confirm()
+                .title("Revert all unsaved changes?")
+                .child(format!(
+                    "{dirty} photo(s) will be restored to their last saved metadata."
+                ))
+                .button_props(DialogButtonProps::default().ok_text("Revert All"))
+                .on_ok(move |_, _, cx| {
+                    let _ = view.update(cx, |this, cx| this.revert_all_confirmed(cx));
+                    true
+                })
+        });
+    }
+
+    fn revert_all_confirmed(&mut self, cx: &mut Context<Self>) {
+        let count = self.state.revert_all_dirty();
+        self.status = format!("Reverted unsaved changes on {count} photo(s)");
+        self.refresh_tag_rows = true;
+        cx.notify();
+    }
+
+    pub(super) fn toggle_theme(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let (mode, theme) = if cx.theme().mode == ThemeMode::Dark {
+            (ThemeMode::Light, SavedTheme::Light)
+        } else {
+            (ThemeMode::Dark, SavedTheme::Dark)
+        };
+        Theme::change(mode, Some(window), cx);
+
+        self.window_settings.theme = Some(theme);
+        self.schedule_window_settings_save(cx);
+    }
+
+    pub(super) fn remember_window_bounds(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let (bounds, maximized) = match window.window_bounds() {
+            WindowBounds::Windowed(bounds) => (bounds, false),
+            WindowBounds::Maximized(bounds) | WindowBounds::Fullscreen(bounds) => (bounds, true),
+        };
+        let saved = SavedBounds {
+            x: bounds.origin.x.into(),
+            y: bounds.origin.y.into(),
+            width: bounds.size.width.into(),
+            height: bounds.size.height.into(),
+        };
+        if self.window_settings.bounds == Some(saved) && self.window_settings.maximized == maximized
+        {
+            return;
+        }
+
+        self.window_settings.bounds = Some(saved);
+        self.window_settings.maximized = maximized;
+        self.schedule_window_settings_save(cx);
+    }
+
+    /// Write the window settings once resizing settles. Scheduling again
+    /// drops the pending write, so a drag produces a single save.
+    fn schedule_window_settings_save(&mut self, cx: &mut Context<Self>) {
+        let settings = self.window_settings.clone();
+        self.window_settings_task = Some(cx.spawn(async move |_, cx| {
+            cx.background_executor()
+                .timer(WINDOW_SETTINGS_SAVE_DELAY)
+                .await;
+            let path = WindowSettings::default_path();
+            if let Err(err) = settings.save(&path) {
+                log::warn!(
+                    "op=save_window_settings path={} error={err}",
+                    path.display()
+                );
+            }
+        }));
+    }
+
+    /// Change a setting, apply it to the session and write the settings file.
+    pub(super) fn update_settings(
+        &mut self,
+        change: impl FnOnce(&mut AppSettings),
+        cx: &mut Context<Self>,
+    ) {
+        let mut settings = self.state.settings.clone();
+        change(&mut settings);
+        if settings == self.state.settings {
+            return;
+        }
+
+        let path = AppSettings::default_path();
+        if let Err(err) = settings.save(&path) {
+            log::warn!("op=save_settings path={} error={err}", path.display());
+            self.status = format!("Failed to save settings: {err}");
+        }
+        if settings.log_to_file != self.state.settings.log_to_file {
+            logging::set_enabled(settings.log_to_file);
+            if settings.log_to_file {
+                self.status = format!("Logging to {}", logging::default_log_path().display());
+            }
+        }
+        self.state.change_settings(settings);
+        cx.notify();
+    }
+
+    pub(super) fn run_bulk_selected(&mut self, cx: &mut Context<Self>) {
+        if self.state.is_processing || self.state.is_saving {
+            return;
+        }
+
+        let Some(preset_id) = self.state.active_preset else {
+            self.status = String::from("No preset selected");
+            cx.notify();
+            return;
+        };
+
+        let output_mode = self.state.bulk_output_mode.clone();
+        let job = self.state.begin_bulk_selected(preset_id, output_mode);
+        self.start_bulk_job(job, false, cx);
+    }
+
+    /// Run the active preset on the selection again, skipping the photos an
+    /// interrupted run of it already finished and that haven't changed since.
+    pub(super) fn resume_bulk_selected(&mut self, cx: &mut Context<Self>) {
+        if self.state.is_processing || self.state.is_saving {
+            return;
+        }
+
+        let Some(preset_id) = self.state.active_preset else {
+            self.status = String::from("No preset selected");
+            cx.notify();
+            return;
+        };
+
+        let output_mode = self.state.bulk_output_mode.clone();
+        let job = self.state.begin_bulk_selected(preset_id, output_mode);
+        self.start_bulk_job(job, true, cx);
+    }
+
+    pub(super) fn run_hard_clean(&mut self, cx: &mut Context<Self>) {
+        if self.state.is_processing || self.state.is_saving {
+            return;
+        }
+
+        let output_mode = self.state.bulk_output_mode.clone();
+        let job = self.state.begin_hard_clean(output_mode);
+        self.start_bulk_job(job, false, cx);
+    }
+
+    pub(super) fn open_allowlist_dialog(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.state.is_processing || self.state.is_saving {
+            return;
+        }
+
+        let input = self
+            .allowlist_input
+            .get_or_insert_with(|| {
+                cx.new(|cx| {
+                    InputState::new(window, cx)
+                        .placeholder("camera, datetime, Exif.Image.Orientation")
+                })
+            })
+            .clone();
+        let selected = self.state.selected_indices.len();
+        let view = cx.entity().downgrade();
+
+        window.open_dialog(cx, move |dialog, _, _| {
+            let view = view.clone();
+            let input = input.clone();
+            dialog
+                .confirm()
+                .title("Keep only these tags")
+                .child(
+                    v_flex()
+                        .gap_2()
+                        .child(format!(
+                            "Every other tag is stripped from {selected} selected photo(s). \
+                             Separate tag keys or categories with commas."
+                        ))
+                        .child(Input::new(&input)),
+                )
+                .button_props(DialogButtonProps::default().ok_text("Strip"))
+                .on_ok(move |_, _, cx| {
+                    let allowlist = input.read(cx).value().to_string();
+                    let _ = view.update(cx, |this, cx| this.run_bulk_allowlist(&allowlist, cx));
+                    true
+                })
+        });
+    }
+
+    pub(super) fn open_replace_dialog(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let inputs = self.replace_inputs.get_or_insert_with(|| ReplaceInputs {
+            tag_key: cx.new(|cx| {
+                InputState::new(window, cx)
+                    .placeholder("Tag key")
+                    .default_value("Exif.Image.Make")
+            }),
+            find: cx.new(|cx| InputState::new(window, cx).placeholder("Find")),
+            replace: cx.new(|cx| InputState::new(window, cx).placeholder("Replace with")),
+        });
+        let (tag_key, find, replace) = (
+            inputs.tag_key.clone(),
+            inputs.find.clone(),
+            inputs.replace.clone(),
+        );
+        let selected = self.state.selected_indices.len();
+        let view = cx.entity().downgrade();
+
+        window.open_dialog(cx, move |dialog, _, cx| {
+            let ignore_case = view
+                .upgrade()
+                .is_some_and(|view| view.read(cx).replace_ignore_case);
+            let toggle_view = view.clone();
+            let view = view.clone();
+            let (tag_key, find, replace) = (tag_key.clone(), find.clone(), replace.clone());
+            dialog
+                .confirm()
+                .title("Find and replace in a tag")
+                .child(
+                    v_flex()
+                        .gap_2()
+                        .child(format!(
+                            "Rewrites the tag's text on {selected} selected photo(s)."
+                        ))
+                        .child(Input::new(&tag_key))
+                        .child(Input::new(&find))
+                        .child(Input::new(&replace))
+                        .child(
+                            Checkbox::new("replace-ignore-case")
+                                .label("Ignore case")
+                                .checked(ignore_case)
+                                .on_click(move |checked: &bool, _, cx| {
+                                    let _ = toggle_view.update(cx, |this, cx| {
+                                        this.replace_ignore_case = *checked;
+                                        cx.notify();
+                                    });
+                                }),
+                        ),
+                )
+                .button_props(DialogButtonProps::default().ok_text("Replace"))
+                .on_ok(move |_, _, cx| {
+                    let key = tag_key.read(cx).value().trim().to_string();
+                    let find = find.read(cx).value().to_string();
+                    let replace = replace.read(cx).value().to_string();
+                    let _ = view.update(cx, |this, cx| {
+                        this.replace_in_selection(&key, &find, &replace, cx)
+                    });
+                    true
+                })
+        });
+    }
+
+    pub(super) fn open_preset_builder_dialog(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.preset_builder.is_none() {
+            let argument = cx.new(|cx| InputState::new(window, cx).placeholder("Tag key"));
+            let subscription = cx.subscribe(&argument, |_, _, event: &InputEvent, cx| {
+                if matches!(event, InputEvent::Change) {
+                    cx.notify();
+                }
+            });
+            self.preset_builder = Some(PresetBuilderForm {
+                name: cx.new(|cx| InputState::new(window, cx).placeholder("Preset name")),
+                argument,
+                value: cx.new(|cx| InputState::new(window, cx).placeholder("Value to set")),
+                kind: RuleKind::RemoveTag,
+                rules: PresetRuleBuilder::new(),
+                error: None,
+                _argument_subscription: subscription,
+            });
+        }
+        let view = cx.entity().downgrade();
+
+        window.open_dialog(cx, move |dialog, _, cx| {
+            let Some((name, argument, value, kind, rules, error)) =
+                view.upgrade().and_then(|view| {
+                    view.read(cx).preset_builder.as_ref().map(|form| {
+                        (
+                            form.name.clone(),
+                            form.argument.clone(),
+                            form.value.clone(),
+                            form.kind,
+                            form.rules.rules().to_vec(),
+                            form.error.clone(),
+                        )
+                    })
+                })
+            else {
+                return dialog;
+            };
+            let argument_text = argument.read(cx).value().to_string();
+
+            let kind_buttons = RuleKind::ALL.iter().enumerate().map(|(index, &rule_kind)| {
+                let view = view.clone();
+                Button::new(SharedString::from(format!("preset-rule-kind-{index}")))
+                    .small()
+                    .ghost()
+                    .label(rule_kind.label())
+                    .selected(rule_kind == kind)
+                    .on_click(move |_, _, cx| {
+                        let _ =
+                            view.update(cx, |this, cx| this.set_preset_rule_kind(rule_kind, cx));
+                    })
+            });
+            let key_pickers = presets::argument_suggestions(kind, &argument_text)
+                .into_iter()
+                .enumerate()
+                .map(|(index, picked)| {
+                    let view = view.clone();
+                    Button::new(SharedString::from(format!("preset-rule-pick-{index}")))
+                        .xsmall()
+                        .ghost()
+                        .label(picked)
+                        .on_click(move |_, window, cx| {
+                            let _ = view.update(cx, |this, cx| {
+                                this.pick_preset_argument(picked, window, cx)
+                            });
+                        })
+                });
+            let rule_rows = rules.iter().enumerate().map(|(index, rule)| {
+                let view = view.clone();
+                h_flex()
+                    .gap_2()
+                    .items_center()
+                    .child(div().flex_1().text_sm().child(presets::rule_summary(rule)))
+                    .child(
+                        Button::new(SharedString::from(format!("preset-rule-remove-{index}")))
+                            .xsmall()
+                            .ghost()
+                            .icon(IconName::Close)
+                            .on_click(move |_, _, cx| {
+                                let _ =
+                                    view.update(cx, |this, cx| this.remove_preset_rule(index, cx));
+                            }),
+                    )
+            });
+            let add_view = view.clone();
+            let ok_view = view.clone();
+
+            dialog
+                .confirm()
+                .title("New preset")
+                .child(
+                    v_flex()
+                        .gap_2()
+                        .child(Input::new(&name))
+                        .child(h_flex().flex_wrap().gap_1().children(kind_buttons))
+                        .children(
+                            (kind.takes_key() || kind == RuleKind::RemoveCategory)
+                                .then(|| Input::new(&argument)),
+                        )
+                        .child(h_flex().flex_wrap().gap_1().children(key_pickers))
+                        .children((kind == RuleKind::SetTag).then(|| Input::new(&value)))
+                        .child(
+                            Button::new("preset-rule-add")
+                                .small()
+                                .icon(IconName::Plus)
+                                .label("Add Rule")
+                                .on_click(move |_, window, cx| {
+                                    let _ = add_view
+                                        .update(cx, |this, cx| this.add_preset_rule(window, cx));
+                                }),
+                        )
+                        .children(error.map(|error| {
+                            div()
+                                .text_sm()
+                                .text_color(cx.theme().danger_foreground)
+                                .child(error)
+                        }))
+                        .children(rule_rows),
+                )
+                .button_props(DialogButtonProps::default().ok_text("Add Preset"))
+                .on_ok(move |_, _, cx| {
+                    ok_view
+                        .update(cx, |this, cx| this.save_built_preset(cx))
+                        .unwrap_or(true)
+                })
+        });
+    }
+
+    fn set_preset_rule_kind(&mut self, kind: RuleKind, cx: &mut Context<Self>) {
+        if let Some(form) = self.preset_builder.as_mut() {
+            form.kind = kind;
+            form.error = None;
+        }
+        cx.notify();
+    }
+
+    fn pick_preset_argument(&mut self, picked: &str, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(form) = self.preset_builder.as_ref() else {
+            return;
+        };
+        let current = form.argument.read(cx).value().to_string();
+        let text = presets::pick_argument(form.kind, &current, picked);
+        form.argument
+            .update(cx, |input, cx| input.set_value(text, window, cx));
+        cx.notify();
+    }
+
+    fn add_preset_rule(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(form) = self.preset_builder.as_mut() else {
+            return;
+        };
+        let argument = form.argument.read(cx).value().to_string();
+        let value = form.value.read(cx).value().to_string();
+        match form.rules.add(form.kind, &argument, &value) {
+            Ok(_) => {
+                form.error = None;
+                for input in [&form.argument, &form.value] {
+                    input.update(cx, |input, cx| input.set_value("", window, cx));
+                }
+            }
+            Err(err) => form.error = Some(err.to_string()),
+        }
+        cx.notify();
+    }
+
+    fn remove_preset_rule(&mut self, index: usize, cx: &mut Context<Self>) {
+        if let Some(form) = self.preset_builder.as_mut() {
+            form.rules.remove(index);
+        }
+        cx.notify();
+    }
+
+    /// Add the built preset and make it the active one. Returns whether the
+    /// dialog can close; a nameless or empty preset keeps it open.
+    fn save_built_preset(&mut self, cx: &mut Context<Self>) -> bool {
+        let Some(form) = self.preset_builder.as_mut() else {
+            return true;
+        };
+        let name = form.name.read(cx).value().trim().to_string();
+        if name.is_empty() || form.rules.rules().is_empty() {
+            form.error = Some(String::from("Name the preset and add at least one rule"));
+            cx.notify();
+            return false;
+        }
+
+        let Some(form) = self.preset_builder.take() else {
+            return true;
+        };
+        let id = self.state.add_custom_preset(&name, form.rules.build());
+        self.state.active_preset = Some(id);
+        self.status = format!("Added preset \"{name}\" and made it the active preset");
+        cx.notify();
+        true
+    }
+
+    fn replace_in_selection(
+        &mut self,
+        key: &str,
+        find: &str,
+        replace: &str,
+        cx: &mut Context<Self>,
+    ) {
+        self.state.begin_undo_group();
+        let changed = self
+            .state
+            .replace_in_tag(key, find, replace, self.replace_ignore_case);
+        self.state.end_undo_group();
+
+        self.refresh_tag_rows = true;
+        self.status = format!("Replaced \"{find}\" in {key} on {changed} photo(s)");
+        cx.notify();
+    }
+
+    fn run_bulk_allowlist(&mut self, allowlist: &str, cx: &mut Context<Self>) {
+        if self.state.is_processing || self.state.is_saving {
+            return;
+        }
+
+        let output_mode = self.state.bulk_output_mode.clone();
+        let job = self.state.begin_bulk_allowlist(allowlist, output_mode);
+        self.start_bulk_job(job, false, cx);
+    }
+
+    /// Start `job` on a worker. Finished photos go to the resume log, which a
+    /// new run starts over and a `resume` run continues.
+    fn start_bulk_job(
+        &mut self,
+        job: Result<BulkJob, AppError>,
+        resume: bool,
+        cx: &mut Context<Self>,
+    ) {
+        let job = match job {
+            Ok(job) if resume => job.resuming_from(ResumeLog::default_path()),
+            Ok(job) => job.with_resume_log(ResumeLog::default_path()),
+            Err(err) => {
+                self.status = format!("Bulk run failed: {err}");
+                cx.notify();
+                return;
+            }
+        };
+
+        let total = job.total();
+        let cancel = Arc::new(AtomicBool::new(false));
+        let worker_cancel = cancel.clone();
+        let (progress_tx, progress_rx) = mpsc::channel();
+        let worker = cx.background_spawn(async move {
+            let results = job.run(progress_tx, Some(&worker_cancel));
+            (job, results)
+        });
+
+        self.status = format!("Running preset on {total} photo(s)...");
+        self.bulk_cancel = Some(cancel);
+        self.bulk_task = Some(cx.spawn(async move |this, cx| {
+            // The worker drops its sender when it returns, which ends this loop.
+            loop {
+                cx.background_executor().timer(BULK_PROGRESS_INTERVAL).await;
+                let Ok(finished) =
+                    this.update(cx, |this, cx| this.drain_bulk_progress(&progress_rx, cx))
+                else {
+                    return;
+                };
+                if finished {
+                    break;
+                }
+            }
+
+            let (job, results) = worker.await;
+            let _ = this.update(cx, |this, cx| this.finish_bulk(job, results, cx));
+        }));
+
+        cx.notify();
+    }
+
+    pub(super) fn analyze_session(&mut self, cx: &mut Context<Self>) {
+        if self.analyze_task.is_some() || self.state.photos.is_empty() {
+            return;
+        }
+
+        let job = self.state.begin_analyze();
+        let total = job.total();
+        let (progress_tx, progress_rx) = mpsc::channel();
+        let worker = cx.background_spawn(async move { job.run(progress_tx) });
+
+        self.status = format!("Analyzing {total} photo(s)...");
+        self.analyze_task = Some(cx.spawn(async move |this, cx| {
+            loop {
+                cx.background_executor().timer(BULK_PROGRESS_INTERVAL).await;
+                let Ok(finished) =
+                    this.update(cx, |this, cx| this.drain_analyze_progress(&progress_rx, cx))
+                else {
+                    return;
+                };
+                if finished {
+                    break;
+                }
+            }
+
+            let stats = worker.await;
+            let _ = this.update(cx, |this, cx| {
+                this.analyze_task = None;
+                this.status = format!("Analyzed {} photo(s)", stats.photo_count);
+                this.state.finish_analyze(stats);
+                cx.notify();
+            });
+        }));
+
+        cx.notify();
+    }
+
+    fn drain_analyze_progress(
+        &mut self,
+        progress_rx: &Receiver<ProgressEvent>,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        let mut latest = None;
+        let finished = loop {
+            match progress_rx.try_recv() {
+                Ok(event) => latest = Some(event),
+                Err(TryRecvError::Empty) => break false,
+                Err(TryRecvError::Disconnected) => break true,
+            }
+        };
+
+        if let Some(event) = latest {
+            self.status = format!("Analyzing {}/{}...", event.current, event.total);
+            cx.notify();
+        }
+        finished
+    }
+
+    pub(super) fn cancel_bulk(&mut self, cx: &mut Context<Self>) {
+        if let Some(cancel) = &self.bulk_cancel {
+            cancel.store(true, Ordering::Relaxed);
+            self.status = String::from("Cancelling bulk run...");
+            cx.notify();
+        }
+    }
+
+    fn drain_bulk_progress(
+        &mut self,
+        progress_rx: &Receiver<ProgressEvent>,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        let mut finished = false;
+        loop {
+            match progress_rx.try_recv() {
+                Ok(event) => self.state.record_progress(event),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    finished = true;
+                    break;
+                }
+            }
+        }
+
+        if let Some(progress) = &self.state.progress {
+            self.status = format!(
+                "Processed {}/{}: {}",
+                progress.current, progress.total, progress.filename
+            );
+        }
+        cx.notify();
+        finished
+    }
+
+    fn finish_bulk(&mut self, job: BulkJob, results: Vec<OperationResult>, cx: &mut Context<Self>) {
+        let summary = self.state.finish_bulk(job, results);
+        self.bulk_cancel = None;
+        self.bulk_task = None;
+        self.bulk_resumable = ResumeLog::default_path().exists();
+        self.refresh_tag_rows = true;
+        self.status = format!("Bulk run finished: {summary}");
+        cx.notify();
+    }
 }
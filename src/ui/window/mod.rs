@@ -1,28 +1,62 @@
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::sync::Arc;
+use std::time::Duration;
 
 use chrono::{Datelike, NaiveDate};
 
-use crate::app::AppState;
-use crate::core::metadata::MetadataEngine;
-use crate::models::{MetadataTag, TagCategory, TagValue};
+use crate::app::{AppError, AppState, BulkJob, Panel, SaveJob, SessionStats};
+use crate::core::bulk::{BulkProcessor, ResumeLog};
+use crate::core::catalog::{self, TagDef};
+use crate::core::contacts;
+use crate::core::enum_labels;
+use crate::core::exiftool;
+use crate::core::formats;
+use crate::core::geocode::{self, CoordinateKey, GeocodeError, GeocodeResult, LOCATION_TAG_KEY};
+use crate::core::hex::{self, HEX_PAGE_BYTES};
+use crate::core::metadata::{
+    format_altitude, format_dms_coordinate, format_metadata_report, parse_altitude,
+    parse_dms_coordinate, AltitudeUnit, MetadataEngine, MetadataError,
+};
+use crate::core::presets::{self, PresetRuleBuilder, RuleKind};
+use crate::core::preview;
+use crate::core::thumbnail::{self, THUMBNAIL_EDGE};
+use crate::logging;
+use crate::models::{
+    compute_aspect_ratio, compute_megapixels, format_rational_for_key, validate_exif_datetime,
+    MetadataTag, MetadataWarning, OperationResult, PhotoEntry, PhotoId, ProgressEvent, SkipReason,
+    TagCategory, TagValue, ThumbnailData, COLOR_SPACE_SRGB,
+};
+use crate::platform;
+use crate::settings::{AppSettings, DefaultOutput, THUMBNAIL_SIZE_CHOICES};
+use crate::ui::window_settings::{SavedBounds, SavedTheme, WindowSettings};
 use gpui::{
-    div, img, px, size, AnyElement, App, AppContext as _, Bounds, Context, ElementId,
-    ExternalPaths, FocusHandle, Focusable, InteractiveElement as _, IntoElement, KeyDownEvent,
-    ObjectFit, ParentElement as _, Render, SharedString, StatefulInteractiveElement as _,
-    Styled as _, StyledImage as _, Window, WindowBounds, WindowOptions,
+    div, img, point, px, size, AnyElement, App, AppContext as _, Bounds, ClipboardItem, Context,
+    ElementId, ExternalPaths, FocusHandle, Focusable, InteractiveElement as _, IntoElement,
+    KeyDownEvent, ObjectFit, ParentElement as _, Render, RenderImage, SharedString,
+    StatefulInteractiveElement as _, Styled as _, StyledImage as _, Task, Window, WindowBounds,
+    WindowOptions,
 };
 use gpui_component::button::{Button, ButtonVariants as _};
 use gpui_component::calendar::Date;
+use gpui_component::checkbox::Checkbox;
 use gpui_component::date_picker::{DatePicker, DatePickerState};
+use gpui_component::dialog::DialogButtonProps;
 use gpui_component::divider::Divider;
 use gpui_component::form::{Field, Form};
 use gpui_component::input::{Input, InputEvent, InputState};
+use gpui_component::progress::Progress;
 use gpui_component::scroll::ScrollableElement as _;
+use gpui_component::select::{Select, SelectEvent, SelectState};
+use gpui_component::tag::Tag;
 use gpui_component::theme::{ActiveTheme, Theme, ThemeMode};
 use gpui_component::{
-    h_flex, v_flex, Disableable as _, Icon, IconName, Root, Sizable as _, WindowExt as _,
+    h_flex, v_flex, Disableable as _, Icon, IconName, IndexPath, Root, Selectable as _,
+    Sizable as _, WindowExt as _,
 };
 
 mod actions;
@@ -35,63 +69,24 @@ mod tag_rows;
 mod utils;
 
 use self::utils::{
-    expand_paths, image_fallback, open_url, parse_datetime_parts, unique_export_path,
+    format_bytes, image_fallback, open_url, parse_datetime_parts, render_image_from_thumbnail,
+    shortcut_hint,
 };
 
-const IMAGE_EXTENSIONS: &[&str] = &[
-    "jpg", "jpeg", "png", "tif", "tiff", "webp", "heif", "heic", "avif", "jxl",
-];
+/// How often the window drains bulk progress events while a run is in flight.
+const BULK_PROGRESS_INTERVAL: Duration = Duration::from_millis(50);
 
-// ---------------------------------------------------------------------------
-// Addable tag definitions
-// ---------------------------------------------------------------------------
+/// First entry of the add-tag popup's category dropdown; shows every category.
+const ALL_CATEGORIES_LABEL: &str = "All";
 
-struct AddableTagDef {
-    key: &'static str,
-    display_name: &'static str,
-    category: TagCategory,
-    default_value: TagValue,
-}
+/// Most decimal places the altitude precision button cycles through.
+const MAX_ALTITUDE_PRECISION: usize = 3;
+
+/// Quiet period after the last resize or move before window settings are written.
+const WINDOW_SETTINGS_SAVE_DELAY: Duration = Duration::from_millis(500);
 
-const ADDABLE_TAGS: &[AddableTagDef] = &[
-    // Description / text tags
-    AddableTagDef { key: "Exif.Image.ImageDescription", display_name: "Image Description", category: TagCategory::Description, default_value: TagValue::Text(String::new()) },
-    AddableTagDef { key: "Exif.Image.Artist", display_name: "Artist", category: TagCategory::Description, default_value: TagValue::Text(String::new()) },
-    AddableTagDef { key: "Exif.Image.Copyright", display_name: "Copyright", category: TagCategory::Description, default_value: TagValue::Text(String::new()) },
-    // Camera tags
-    AddableTagDef { key: "Exif.Image.Make", display_name: "Make", category: TagCategory::Camera, default_value: TagValue::Text(String::new()) },
-    AddableTagDef { key: "Exif.Image.Model", display_name: "Model", category: TagCategory::Camera, default_value: TagValue::Text(String::new()) },
-    AddableTagDef { key: "Exif.Photo.LensMake", display_name: "Lens Make", category: TagCategory::Camera, default_value: TagValue::Text(String::new()) },
-    AddableTagDef { key: "Exif.Photo.LensModel", display_name: "Lens Model", category: TagCategory::Camera, default_value: TagValue::Text(String::new()) },
-    AddableTagDef { key: "Exif.Photo.LensSerialNumber", display_name: "Lens Serial Number", category: TagCategory::Camera, default_value: TagValue::Text(String::new()) },
-    AddableTagDef { key: "Exif.Photo.OwnerName", display_name: "Owner Name", category: TagCategory::Camera, default_value: TagValue::Text(String::new()) },
-    AddableTagDef { key: "Exif.Photo.SerialNumber", display_name: "Serial Number", category: TagCategory::Camera, default_value: TagValue::Text(String::new()) },
-    // DateTime tags
-    AddableTagDef { key: "Exif.Photo.DateTimeOriginal", display_name: "Date Taken", category: TagCategory::DateTime, default_value: TagValue::DateTime(String::new()) },
-    AddableTagDef { key: "Exif.Photo.CreateDate", display_name: "Create Date", category: TagCategory::DateTime, default_value: TagValue::DateTime(String::new()) },
-    AddableTagDef { key: "Exif.Image.ModifyDate", display_name: "Modify Date", category: TagCategory::DateTime, default_value: TagValue::DateTime(String::new()) },
-    // Software
-    AddableTagDef { key: "Exif.Image.Software", display_name: "Software", category: TagCategory::Software, default_value: TagValue::Text(String::new()) },
-    // Image properties
-    AddableTagDef { key: "Exif.Image.Orientation", display_name: "Orientation", category: TagCategory::Image, default_value: TagValue::Integer(1) },
-    AddableTagDef { key: "Exif.Image.XResolution", display_name: "X Resolution", category: TagCategory::Image, default_value: TagValue::Rational(72, 1) },
-    AddableTagDef { key: "Exif.Image.YResolution", display_name: "Y Resolution", category: TagCategory::Image, default_value: TagValue::Rational(72, 1) },
-    AddableTagDef { key: "Exif.Image.ImageWidth", display_name: "Image Width", category: TagCategory::Image, default_value: TagValue::Integer(0) },
-    AddableTagDef { key: "Exif.Image.ImageHeight", display_name: "Image Height", category: TagCategory::Image, default_value: TagValue::Integer(0) },
-    // Capture settings
-    AddableTagDef { key: "Exif.Photo.ISO", display_name: "ISO", category: TagCategory::Capture, default_value: TagValue::Integer(100) },
-    AddableTagDef { key: "Exif.Photo.ExposureTime", display_name: "Exposure Time", category: TagCategory::Capture, default_value: TagValue::Rational(1, 60) },
-    AddableTagDef { key: "Exif.Photo.FNumber", display_name: "F-Number", category: TagCategory::Capture, default_value: TagValue::Rational(28, 10) },
-    AddableTagDef { key: "Exif.Photo.FocalLength", display_name: "Focal Length", category: TagCategory::Capture, default_value: TagValue::Rational(50, 1) },
-    AddableTagDef { key: "Exif.Photo.ApertureValue", display_name: "Aperture Value", category: TagCategory::Capture, default_value: TagValue::Rational(30, 10) },
-    AddableTagDef { key: "Exif.Photo.ExposureProgram", display_name: "Exposure Program", category: TagCategory::Capture, default_value: TagValue::Integer(0) },
-    AddableTagDef { key: "Exif.Photo.MeteringMode", display_name: "Metering Mode", category: TagCategory::Capture, default_value: TagValue::Integer(0) },
-    AddableTagDef { key: "Exif.Photo.Flash", display_name: "Flash", category: TagCategory::Capture, default_value: TagValue::Integer(0) },
-    AddableTagDef { key: "Exif.Photo.WhiteBalance", display_name: "White Balance", category: TagCategory::Capture, default_value: TagValue::Integer(0) },
-    AddableTagDef { key: "Exif.Photo.ExposureMode", display_name: "Exposure Mode", category: TagCategory::Capture, default_value: TagValue::Integer(0) },
-    AddableTagDef { key: "Exif.Photo.ColorSpace", display_name: "Color Space", category: TagCategory::Capture, default_value: TagValue::Integer(1) },
-    // Location
-    AddableTagDef { key: "Exif.GPSInfo.GPSCoordinates", display_name: "GPS Coordinates", category: TagCategory::Location, default_value: TagValue::Gps(0.0, 0.0, None) },
+const IMAGE_EXTENSIONS: &[&str] = &[
+    "jpg", "jpeg", "png", "tif", "tiff", "webp", "heif", "heic", "hif", "avif", "jxl",
 ];
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -110,6 +105,11 @@ enum TagEditorKind {
         input: gpui::Entity<InputState>,
         _subscription: gpui::Subscription,
     },
+    /// Integer tag with a known set of values, edited through their labels.
+    Enum {
+        select: gpui::Entity<SelectState<Vec<&'static str>>>,
+        _subscription: gpui::Subscription,
+    },
     Rational {
         numerator: gpui::Entity<InputState>,
         denominator: gpui::Entity<InputState>,
@@ -124,8 +124,17 @@ enum TagEditorKind {
         _lon_subscription: gpui::Subscription,
         _alt_subscription: gpui::Subscription,
     },
+    /// Values of a list tag shown as removable chips, with an input that
+    /// adds the entries typed into it.
+    List {
+        items: Vec<String>,
+        input: gpui::Entity<InputState>,
+        _subscription: gpui::Subscription,
+    },
     Binary {
-        bytes: usize,
+        data: Arc<[u8]>,
+        /// Offset of the hex page on screen, or `None` while the dump is collapsed.
+        hex_offset: Option<usize>,
     },
 }
 
@@ -145,6 +154,22 @@ struct MapPopupState {
     latitude: f64,
     longitude: f64,
     altitude: Option<f64>,
+    /// Reverse-geocoded place name, once the lookup for these coordinates resolves.
+    address: Option<String>,
+}
+
+/// Decoded, orientation-corrected preview for the active carousel photo.
+/// `image` is `None` while decoding or when the format can't be decoded.
+struct OrientedPreview {
+    path: PathBuf,
+    orientation: i64,
+    image: Option<Arc<gpui::Image>>,
+}
+
+/// The thumbnail embedded in the active photo's EXIF, shown while the full image loads.
+struct EmbeddedPreview {
+    path: PathBuf,
+    image: Option<Arc<RenderImage>>,
 }
 
 #[derive(Debug)]
@@ -156,6 +181,28 @@ struct DateTimePopupState {
     second: gpui::Entity<InputState>,
 }
 
+/// Inputs of the find-and-replace dialog, kept so the last values are offered again.
+struct ReplaceInputs {
+    tag_key: gpui::Entity<InputState>,
+    find: gpui::Entity<InputState>,
+    replace: gpui::Entity<InputState>,
+}
+
+/// The new-preset dialog's form, kept so an unfinished preset survives the
+/// dialog being closed.
+struct PresetBuilderForm {
+    name: gpui::Entity<InputState>,
+    /// The rule's key, category or "Keep only" list.
+    argument: gpui::Entity<InputState>,
+    /// The value a "Set tag" rule writes.
+    value: gpui::Entity<InputState>,
+    kind: RuleKind,
+    rules: PresetRuleBuilder,
+    error: Option<String>,
+    /// Re-renders the key pickers as the argument is typed.
+    _argument_subscription: gpui::Subscription,
+}
+
 struct ExifEditorWindow {
     state: AppState,
     status: String,
@@ -164,14 +211,67 @@ struct ExifEditorWindow {
     tag_rows_photo_index: Option<usize>,
     refresh_tag_rows: bool,
     map_popup: Option<MapPopupState>,
+    map_search_input: Option<gpui::Entity<InputState>>,
+    map_search_subscription: Option<gpui::Subscription>,
+    map_search_task: Option<Task<()>>,
+    location_names: HashMap<CoordinateKey, String>,
+    reverse_geocode_task: Option<Task<()>>,
     add_tag_popup_open: bool,
     add_tag_search: String,
     add_tag_search_input: Option<gpui::Entity<InputState>>,
     add_tag_search_subscription: Option<gpui::Subscription>,
+    add_tag_category: Option<TagCategory>,
+    add_tag_category_select: Option<gpui::Entity<SelectState<Vec<&'static str>>>>,
+    add_tag_category_subscription: Option<gpui::Subscription>,
     datetime_popup: Option<DateTimePopupState>,
+    find_tag_popup_open: bool,
+    /// Matches for the find-tag query, recomputed only when the query changes.
+    find_tag_results: Vec<(PhotoId, MetadataTag)>,
+    find_tag_input: Option<gpui::Entity<InputState>>,
+    find_tag_subscription: Option<gpui::Subscription>,
+    /// Tag row picked from the find-tag results, drawn highlighted while its
+    /// photo stays active.
+    highlighted_tag: Option<(PhotoId, String)>,
+    /// Dim rows whose value is a likely camera default; see
+    /// [`catalog::is_camera_default`].
+    flag_camera_defaults: bool,
+    /// Photo the user tried to switch to while the active one had unsaved edits.
+    leave_popup_target: Option<usize>,
+    /// Photo to open once the save started from the leave popup succeeds.
+    leave_after_save: Option<usize>,
+    /// Ask before leaving a photo with unsaved edits. Off for the rest of the
+    /// session once the user opts out.
+    confirm_leaving_unsaved: bool,
+    /// Files the last import left out, listed under the status bar on request.
+    skipped_imports: Vec<(PathBuf, SkipReason)>,
+    skipped_imports_expanded: bool,
     metadata_filter: String,
     metadata_filter_input: Option<gpui::Entity<InputState>>,
     metadata_filter_subscription: Option<gpui::Subscription>,
+    /// GPS rows take degrees/minutes/seconds instead of decimal degrees.
+    gps_dms_mode: bool,
+    altitude_unit: AltitudeUnit,
+    /// Decimal places shown for GPS altitudes.
+    altitude_precision: usize,
+    bulk_cancel: Option<Arc<AtomicBool>>,
+    bulk_task: Option<Task<()>>,
+    /// An interrupted bulk run left its resume log; see [`ResumeLog`].
+    bulk_resumable: bool,
+    save_task: Option<Task<()>>,
+    analyze_task: Option<Task<()>>,
+    allowlist_input: Option<gpui::Entity<InputState>>,
+    replace_inputs: Option<ReplaceInputs>,
+    replace_ignore_case: bool,
+    preset_builder: Option<PresetBuilderForm>,
+    oriented_preview: Option<OrientedPreview>,
+    oriented_preview_task: Option<Task<()>>,
+    embedded_preview: Option<EmbeddedPreview>,
+    embedded_preview_task: Option<Task<()>>,
+    thumbnail_images: HashMap<PathBuf, Arc<RenderImage>>,
+    thumbnail_task: Option<Task<()>>,
+    window_settings: WindowSettings,
+    window_settings_task: Option<Task<()>>,
+    window_bounds_subscription: Option<gpui::Subscription>,
 }
 
 impl Focusable for ExifEditorWindow {
@@ -180,12 +280,28 @@ impl Focusable for ExifEditorWindow {
     }
 }
 
-pub fn open_exif_editor_window(cx: &mut App) {
-    let bounds = Bounds::centered(None, size(px(1100.0), px(750.0)), cx);
+/// Open the editor window with the settings `main` already loaded, importing
+/// `files` once it is up.
+pub fn open_exif_editor_window(app_settings: AppSettings, files: Vec<PathBuf>, cx: &mut App) {
+    let settings = WindowSettings::load(&WindowSettings::default_path());
+    let bounds = settings
+        .bounds
+        .map(|saved| {
+            Bounds::new(
+                point(px(saved.x), px(saved.y)),
+                size(px(saved.width), px(saved.height)),
+            )
+        })
+        .unwrap_or_else(|| Bounds::centered(None, size(px(1100.0), px(750.0)), cx));
+    let window_bounds = if settings.maximized {
+        WindowBounds::Maximized(bounds)
+    } else {
+        WindowBounds::Windowed(bounds)
+    };
 
     cx.open_window(
         WindowOptions {
-            window_bounds: Some(WindowBounds::Windowed(bounds)),
+            window_bounds: Some(window_bounds),
             titlebar: Some(gpui::TitlebarOptions {
                 title: Some("Exif Editor".into()),
                 appears_transparent: false,
@@ -194,12 +310,33 @@ pub fn open_exif_editor_window(cx: &mut App) {
             ..Default::default()
         },
         |window, cx| {
+            if let Some(theme) = settings.theme {
+                Theme::change(theme_mode(theme), Some(window), cx);
+            }
+
             let view = cx.new(|cx| {
                 cx.on_release(|_, cx| cx.quit()).detach();
-                ExifEditorWindow::new(cx.focus_handle())
+                let mut this = ExifEditorWindow::new(cx.focus_handle(), settings, app_settings);
+                this.window_bounds_subscription = Some(cx.observe_window_bounds(
+                    window,
+                    |this: &mut ExifEditorWindow, window, cx| {
+                        this.remember_window_bounds(window, cx);
+                    },
+                ));
+                if !files.is_empty() {
+                    this.import_paths(files, cx);
+                }
+                this
             });
             cx.new(|cx| Root::new(view, window, cx))
         },
     )
     .expect("failed to open Exif Editor window");
 }
+
+fn theme_mode(theme: SavedTheme) -> ThemeMode {
+    match theme {
+        SavedTheme::Light => ThemeMode::Light,
+        SavedTheme::Dark => ThemeMode::Dark,
+    }
+}
@@ -9,12 +9,12 @@ impl ExifEditorWindow {
             return;
         };
 
-        let parsed_latitude = latitude_raw.trim().parse::<f64>().ok();
-        let parsed_longitude = longitude_raw.trim().parse::<f64>().ok();
+        let parsed_latitude = self.parse_gps_coordinate(&latitude_raw, true);
+        let parsed_longitude = self.parse_gps_coordinate(&longitude_raw, false);
         let parsed_altitude = if altitude_raw.trim().is_empty() {
             Some(None)
         } else {
-            altitude_raw.trim().parse::<f64>().ok().map(Some)
+            parse_altitude(&altitude_raw, self.altitude_unit).map(Some)
         };
 
         let fallback_gps = self
@@ -56,13 +56,39 @@ impl ExifEditorWindow {
             latitude,
             longitude,
             altitude,
+            address: None,
         });
+        self.request_reverse_geocode(cx);
 
         cx.notify();
     }
 
+    /// Close the popup drawn on top: the unsaved-changes prompt, settings, the datetime popup,
+    /// then find-tag, add-tag and the map, matching the order they are layered in `render`.
+    /// Returns false if none was open.
+    pub(super) fn close_topmost_popup(&mut self, cx: &mut Context<Self>) -> bool {
+        if self.leave_popup_target.is_some() {
+            self.close_leave_popup(cx);
+        } else if self.state.active_panel == Panel::Settings {
+            self.close_settings_panel(cx);
+        } else if self.datetime_popup.is_some() {
+            self.close_datetime_popup(cx);
+        } else if self.find_tag_popup_open {
+            self.close_find_tag_popup(cx);
+        } else if self.add_tag_popup_open {
+            self.close_add_tag_popup(cx);
+        } else if self.map_popup.is_some() {
+            self.close_map_popup(cx);
+        } else {
+            return false;
+        }
+        true
+    }
+
     pub(super) fn close_map_popup(&mut self, cx: &mut Context<Self>) {
         self.map_popup = None;
+        self.map_search_task = None;
+        self.reverse_geocode_task = None;
         cx.notify();
     }
 
@@ -84,6 +110,153 @@ impl ExifEditorWindow {
         cx.notify();
     }
 
+    /// Resolve the map popup's coordinates to a place name, reusing earlier
+    /// answers for the same coordinates.
+    pub(super) fn request_reverse_geocode(&mut self, cx: &mut Context<Self>) {
+        let Some(popup) = self.map_popup.as_mut() else {
+            return;
+        };
+
+        let (latitude, longitude) = (popup.latitude, popup.longitude);
+        let key = geocode::coordinate_key(latitude, longitude);
+        popup.address = self.location_names.get(&key).cloned();
+        if popup.address.is_some() {
+            self.reverse_geocode_task = None;
+            return;
+        }
+
+        let client = cx.http_client();
+        self.reverse_geocode_task = Some(cx.spawn(async move |this, cx| {
+            let result = geocode::reverse(client, latitude, longitude).await;
+            let _ = this.update(cx, |this, cx| {
+                this.reverse_geocode_task = None;
+                match result {
+                    Ok(address) => {
+                        if let Some(popup) = this.map_popup.as_mut() {
+                            if geocode::coordinate_key(popup.latitude, popup.longitude) == key {
+                                popup.address = Some(address.clone());
+                            }
+                        }
+                        this.location_names.insert(key, address);
+                    }
+                    Err(err) => {
+                        this.status = err.to_string();
+                    }
+                }
+                cx.notify();
+            });
+        }));
+    }
+
+    pub(super) fn apply_location_tag(&mut self, cx: &mut Context<Self>) {
+        let Some(address) = self
+            .map_popup
+            .as_ref()
+            .and_then(|popup| popup.address.clone())
+        else {
+            return;
+        };
+        let Some(photo_index) = self.state.active_photo else {
+            return;
+        };
+
+        match self.state.edit_tag(
+            photo_index,
+            LOCATION_TAG_KEY,
+            TagValue::Text(address.clone()),
+        ) {
+            Ok(()) => {
+                self.refresh_tag_rows = true;
+                self.status = format!("Set {LOCATION_TAG_KEY} to \"{address}\"");
+                self.close_map_popup(cx);
+            }
+            Err(err) => {
+                self.status = format!("Failed to set location tag: {err}");
+            }
+        }
+        cx.notify();
+    }
+
+    pub(super) fn search_map_location(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.map_popup.is_none() || self.map_search_task.is_some() {
+            return;
+        }
+        let Some(input) = &self.map_search_input else {
+            return;
+        };
+
+        let query = input.read(cx).value().trim().to_string();
+        if query.is_empty() {
+            self.status = GeocodeError::EmptyQuery.to_string();
+            cx.notify();
+            return;
+        }
+
+        let client = cx.http_client();
+        self.status = format!("Searching for \"{query}\"...");
+        self.map_search_task = Some(cx.spawn_in(window, async move |this, cx| {
+            let result = geocode::search(client, &query).await;
+            let _ = this.update_in(cx, |this, window, cx| {
+                this.map_search_task = None;
+                this.finish_map_search(result, window, cx);
+            });
+        }));
+        cx.notify();
+    }
+
+    fn finish_map_search(
+        &mut self,
+        result: Result<GeocodeResult, GeocodeError>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let place = match result {
+            Ok(place) => place,
+            Err(err) => {
+                self.status = err.to_string();
+                cx.notify();
+                return;
+            }
+        };
+        let Some(popup) = self.map_popup.as_mut() else {
+            return;
+        };
+        let Some(photo_index) = self.state.active_photo else {
+            return;
+        };
+
+        popup.latitude = place.latitude;
+        popup.longitude = place.longitude;
+        let row_id = popup.row_id.clone();
+        let tag_key = popup.tag_key.clone();
+
+        let latitude_text = self.format_gps_coordinate(place.latitude, true);
+        let longitude_text = self.format_gps_coordinate(place.longitude, false);
+        let inputs = self.tag_rows.iter().find_map(|row| match &row.kind {
+            TagEditorKind::Gps {
+                latitude,
+                longitude,
+                ..
+            } if row.row_id == row_id => Some((latitude.clone(), longitude.clone())),
+            _ => None,
+        });
+        if let Some((latitude, longitude)) = inputs {
+            latitude.update(cx, |state, cx| state.set_value(latitude_text, window, cx));
+            longitude.update(cx, |state, cx| state.set_value(longitude_text, window, cx));
+        }
+
+        self.commit_gps_from_inputs(photo_index, &row_id, &tag_key, cx);
+        self.status = if place.display_name.is_empty() {
+            format!(
+                "Moved location to {:.6}, {:.6}",
+                place.latitude, place.longitude
+            )
+        } else {
+            format!("Moved location to {}", place.display_name)
+        };
+        cx.notify();
+    }
+
     // -----------------------------------------------------------------------
     // Add-tag popup
     // -----------------------------------------------------------------------
@@ -110,6 +283,28 @@ impl ExifEditorWindow {
         search_input.update(cx, |state, cx| state.focus(window, cx));
         self.add_tag_search_input = Some(search_input);
         self.add_tag_search_subscription = Some(subscription);
+
+        let category_labels = std::iter::once(ALL_CATEGORIES_LABEL)
+            .chain(TagCategory::ALL.iter().map(|category| category.as_str()))
+            .collect::<Vec<_>>();
+        let category_select =
+            cx.new(|cx| SelectState::new(category_labels, Some(IndexPath::new(0)), window, cx));
+        let category_subscription = cx.subscribe_in(
+            &category_select,
+            window,
+            |this, _, event: &SelectEvent<Vec<&'static str>>, _, cx| {
+                let SelectEvent::Confirm(label) = event;
+                this.add_tag_category = label.and_then(|label| {
+                    TagCategory::ALL
+                        .into_iter()
+                        .find(|category| category.as_str() == label)
+                });
+                cx.notify();
+            },
+        );
+        self.add_tag_category = None;
+        self.add_tag_category_select = Some(category_select);
+        self.add_tag_category_subscription = Some(category_subscription);
         cx.notify();
     }
 
@@ -118,6 +313,9 @@ impl ExifEditorWindow {
         self.add_tag_search.clear();
         self.add_tag_search_input = None;
         self.add_tag_search_subscription = None;
+        self.add_tag_category = None;
+        self.add_tag_category_select = None;
+        self.add_tag_category_subscription = None;
         cx.notify();
     }
 
@@ -128,7 +326,7 @@ impl ExifEditorWindow {
             return;
         };
 
-        let def = ADDABLE_TAGS.iter().find(|d| d.key == key);
+        let def = catalog::lookup(key);
         let value = def.map(|d| d.default_value.clone()).unwrap_or(TagValue::Text(String::new()));
 
         match self.state.edit_tag(photo_index, key, value) {
@@ -145,6 +343,9 @@ impl ExifEditorWindow {
         self.add_tag_search.clear();
         self.add_tag_search_input = None;
         self.add_tag_search_subscription = None;
+        self.add_tag_category = None;
+        self.add_tag_category_select = None;
+        self.add_tag_category_subscription = None;
         cx.notify();
     }
 
@@ -244,28 +445,38 @@ impl ExifEditorWindow {
                                 ),
                         )
                         .child(
-                            self.add_tag_search_input.as_ref().map_or_else(
-                                || {
-                                    div()
-                                        .w_full()
-                                        .px_2()
-                                        .py_1()
-                                        .bg(cx.theme().secondary)
-                                        .border_1()
-                                        .border_color(cx.theme().border)
-                                        .rounded_sm()
-                                        .text_sm()
-                                        .text_color(cx.theme().muted_foreground)
-                                        .child("Type to search tags...")
-                                        .into_any_element()
-                                },
-                                |search_input| {
-                                    Input::new(search_input)
-                                        .w_full()
-                                        .small()
-                                        .into_any_element()
-                                },
-                            ),
+                            h_flex()
+                                .w_full()
+                                .gap_2()
+                                .child(
+                                    div().flex_1().child(
+                                        self.add_tag_search_input.as_ref().map_or_else(
+                                            || {
+                                                div()
+                                                    .w_full()
+                                                    .px_2()
+                                                    .py_1()
+                                                    .bg(cx.theme().secondary)
+                                                    .border_1()
+                                                    .border_color(cx.theme().border)
+                                                    .rounded_sm()
+                                                    .text_sm()
+                                                    .text_color(cx.theme().muted_foreground)
+                                                    .child("Type to search tags...")
+                                                    .into_any_element()
+                                            },
+                                            |search_input| {
+                                                Input::new(search_input)
+                                                    .w_full()
+                                                    .small()
+                                                    .into_any_element()
+                                            },
+                                        )
+                                    ),
+                                )
+                                .children(self.add_tag_category_select.as_ref().map(|select| {
+                                    Select::new(select).small().w(px(150.0))
+                                })),
                         )
                         .child(
                             div()
@@ -302,6 +513,201 @@ impl ExifEditorWindow {
         )
     }
 
+    // -----------------------------------------------------------------------
+    // Find-tag popup
+    // -----------------------------------------------------------------------
+
+    pub(super) fn open_find_tag_popup(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.find_tag_popup_open = true;
+        self.find_tag_results.clear();
+        self.highlighted_tag = None;
+
+        let input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("Find a value in every photo...")
+                .default_value("")
+        });
+        let subscription = cx.subscribe(&input, |this, input_state, event: &InputEvent, cx| {
+            if matches!(event, InputEvent::Change) {
+                let query = input_state.read(cx).value().to_string();
+                this.find_tag_results = this.state.find_tag_occurrences(&query);
+                cx.notify();
+            }
+        });
+
+        input.update(cx, |state, cx| state.focus(window, cx));
+        self.find_tag_input = Some(input);
+        self.find_tag_subscription = Some(subscription);
+        cx.notify();
+    }
+
+    pub(super) fn close_find_tag_popup(&mut self, cx: &mut Context<Self>) {
+        self.find_tag_popup_open = false;
+        self.find_tag_results.clear();
+        self.highlighted_tag = None;
+        self.find_tag_input = None;
+        self.find_tag_subscription = None;
+        cx.notify();
+    }
+
+    /// Jump to the photo holding a find-tag result and highlight its row.
+    pub(super) fn show_tag_occurrence(
+        &mut self,
+        photo_id: PhotoId,
+        tag_key: String,
+        cx: &mut Context<Self>,
+    ) {
+        self.close_find_tag_popup(cx);
+        let Some(photo_index) = self.state.photo_index(photo_id) else {
+            self.status = String::from("That photo is no longer loaded");
+            cx.notify();
+            return;
+        };
+        self.highlighted_tag = Some((photo_id, tag_key));
+        self.navigate_to_photo(photo_index, cx);
+    }
+
+    pub(super) fn render_find_tag_popup(&self, cx: &mut Context<Self>) -> Option<AnyElement> {
+        if !self.find_tag_popup_open {
+            return None;
+        }
+
+        // Results hold photo ids, so a photo removed since the search drops
+        // out of the list rather than pointing at whichever photo took its place.
+        let occurrences: Vec<(PhotoId, &PhotoEntry, &MetadataTag)> = self
+            .find_tag_results
+            .iter()
+            .filter_map(|(photo_id, tag)| {
+                let photo = self
+                    .state
+                    .photo_index(*photo_id)
+                    .and_then(|index| self.state.photos.get(index))?;
+                Some((*photo_id, photo, tag))
+            })
+            .collect();
+        let photo_count = occurrences
+            .iter()
+            .map(|(photo_id, _, _)| *photo_id)
+            .collect::<HashSet<_>>()
+            .len();
+
+        let result_list: Vec<AnyElement> = occurrences
+            .into_iter()
+            .enumerate()
+            .map(|(position, (photo_id, photo, tag))| {
+                let list_hover_bg = cx.theme().list_hover;
+                let filename = photo.filename.clone();
+                let tag_key = tag.key.clone();
+                h_flex()
+                    .id(SharedString::from(format!("find-tag-{position}")))
+                    .w_full()
+                    .px_3()
+                    .py_1()
+                    .gap_3()
+                    .items_center()
+                    .cursor_pointer()
+                    .hover(move |style| style.bg(list_hover_bg))
+                    .rounded_sm()
+                    .on_click(cx.listener(move |this, _, _, cx| {
+                        this.show_tag_occurrence(photo_id, tag_key.clone(), cx);
+                    }))
+                    .child(
+                        div()
+                            .w(px(160.0))
+                            .text_sm()
+                            .font_weight(gpui::FontWeight::MEDIUM)
+                            .text_color(cx.theme().foreground)
+                            .overflow_hidden()
+                            .child(filename),
+                    )
+                    .child(
+                        div()
+                            .text_xs()
+                            .text_color(cx.theme().muted_foreground)
+                            .child(tag.display_name.clone()),
+                    )
+                    .child(
+                        div()
+                            .flex_1()
+                            .text_xs()
+                            .text_color(cx.theme().foreground)
+                            .overflow_hidden()
+                            .child(tag.value.to_string()),
+                    )
+                    .into_any_element()
+            })
+            .collect();
+        let match_count = result_list.len();
+
+        Some(
+            div()
+                .absolute()
+                .top_0()
+                .left_0()
+                .right_0()
+                .bottom_0()
+                .bg(cx.theme().background)
+                .opacity(0.96)
+                .flex()
+                .items_center()
+                .justify_center()
+                .child(
+                    v_flex()
+                        .w(px(560.0))
+                        .max_h(px(520.0))
+                        .overflow_hidden()
+                        .p_4()
+                        .gap_3()
+                        .bg(cx.theme().popover)
+                        .border_1()
+                        .border_color(cx.theme().border)
+                        .rounded_md()
+                        .child(
+                            h_flex()
+                                .w_full()
+                                .items_center()
+                                .justify_between()
+                                .child(
+                                    div()
+                                        .text_lg()
+                                        .font_weight(gpui::FontWeight::SEMIBOLD)
+                                        .text_color(cx.theme().foreground)
+                                        .child("Find in All Photos"),
+                                )
+                                .child(
+                                    Button::new("close-find-tag")
+                                        .ghost()
+                                        .small()
+                                        .icon(IconName::Close)
+                                        .on_click(cx.listener(|this, _, _, cx| {
+                                            this.close_find_tag_popup(cx)
+                                        })),
+                                ),
+                        )
+                        .children(self.find_tag_input.as_ref().map(|input| {
+                            Input::new(input).w_full().small().prefix(IconName::Search)
+                        }))
+                        .child(
+                            div()
+                                .id(SharedString::from("find-tag-list"))
+                                .flex_1()
+                                .w_full()
+                                .overflow_y_scrollbar()
+                                .child(v_flex().w_full().gap_1().children(result_list)),
+                        )
+                        .child(
+                            div()
+                                .text_xs()
+                                .text_color(cx.theme().muted_foreground)
+                                .child(format!(
+                                    "{match_count} match(es) in {photo_count} photo(s)"
+                                )),
+                        ),
+                )
+                .into_any_element(),
+        )
+    }
+
     // -----------------------------------------------------------------------
     // DateTime picker popup
     // -----------------------------------------------------------------------
@@ -402,6 +808,16 @@ impl ExifEditorWindow {
 
         let tag_key = popup.tag_key.clone();
 
+        // Keep the popup open so the hour/minute/second fields can be corrected.
+        let formatted = match validate_exif_datetime(&formatted) {
+            Ok(formatted) => formatted,
+            Err(message) => {
+                self.status = format!("Invalid date/time: {message}");
+                cx.notify();
+                return;
+            }
+        };
+
         match self.state.edit_tag(photo_index, &tag_key, TagValue::DateTime(formatted.clone())) {
             Ok(()) => {
                 self.status = format!("Set {tag_key} = {formatted}");
@@ -545,6 +961,21 @@ impl ExifEditorWindow {
                                 .font_weight(gpui::FontWeight::SEMIBOLD)
                                 .child("Location Map"),
                         )
+                        .children(self.map_search_input.as_ref().map(|input| {
+                            h_flex()
+                                .gap_2()
+                                .child(div().flex_1().child(Input::new(input)))
+                                .child(
+                                    Button::new("map-search")
+                                        .small()
+                                        .icon(IconName::Search)
+                                        .label("Search")
+                                        .loading(self.map_search_task.is_some())
+                                        .on_click(cx.listener(|this, _, window, cx| {
+                                            this.search_map_location(window, cx)
+                                        })),
+                                )
+                        }))
                         .child(format!(
                             "Tag: {} | lat={:.6} lon={:.6}{}",
                             popup.tag_key,
@@ -552,9 +983,35 @@ impl ExifEditorWindow {
                             popup.longitude,
                             popup
                                 .altitude
-                                .map(|value| format!(" alt={value:.2}m"))
+                                .map(|value| format!(
+                                    " alt={}{}",
+                                    self.format_gps_altitude(value),
+                                    self.altitude_unit.suffix()
+                                ))
                                 .unwrap_or_default()
                         ))
+                        .child(
+                            h_flex()
+                                .gap_2()
+                                .items_center()
+                                .child(div().flex_1().text_sm().child(
+                                    match (&popup.address, self.reverse_geocode_task.is_some()) {
+                                        (Some(address), _) => address.clone(),
+                                        (None, true) => String::from("Looking up address..."),
+                                        (None, false) => String::from("Address unavailable"),
+                                    },
+                                ))
+                                .child(
+                                    Button::new("map-apply-location")
+                                        .small()
+                                        .icon(IconName::Check)
+                                        .label("Apply as location tag")
+                                        .disabled(popup.address.is_none())
+                                        .on_click(cx.listener(|this, _, _, cx| {
+                                            this.apply_location_tag(cx)
+                                        })),
+                                ),
+                        )
                         .child("Map preview URL (OpenStreetMap):")
                         .child(
                             div()
@@ -597,7 +1054,7 @@ impl ExifEditorWindow {
                                 .text_sm()
                                 .text_color(cx.theme().muted_foreground)
                                 .child(
-                                    "Use the GPS inputs in the row to adjust coordinates, then open in browser to inspect location.",
+                                    "Search for a place or use the GPS inputs in the row to adjust coordinates, then open in browser to inspect location.",
                                 ),
                         )
                         .child(
@@ -643,4 +1100,323 @@ impl ExifEditorWindow {
                 .into_any_element(),
         )
     }
+
+    pub(super) fn close_leave_popup(&mut self, cx: &mut Context<Self>) {
+        self.leave_popup_target = None;
+        cx.notify();
+    }
+
+    /// Save the active photo and move on once the save succeeds. A failed save
+    /// keeps the photo open so its edits aren't left behind.
+    pub(super) fn save_and_leave(&mut self, cx: &mut Context<Self>) {
+        let Some(target) = self.leave_popup_target.take() else {
+            return;
+        };
+
+        self.leave_after_save = Some(target);
+        self.save_active(cx);
+        if self.save_task.is_none() {
+            self.leave_after_save = None;
+        }
+    }
+
+    pub(super) fn discard_and_leave(&mut self, cx: &mut Context<Self>) {
+        let Some(target) = self.leave_popup_target.take() else {
+            return;
+        };
+
+        if let Some(active) = self.state.active_photo {
+            if let Err(err) = self.state.revert_photo(active) {
+                self.status = format!("Failed to discard changes: {err}");
+                cx.notify();
+                return;
+            }
+        }
+        self.activate_photo(target, cx);
+    }
+
+    pub(super) fn render_leave_popup(&self, cx: &mut Context<Self>) -> Option<AnyElement> {
+        self.leave_popup_target?;
+        let filename = self
+            .state
+            .active_photo
+            .and_then(|index| self.state.photos.get(index))
+            .map(|photo| photo.filename.clone())
+            .unwrap_or_default();
+
+        Some(
+            div()
+                .absolute()
+                .top_0()
+                .left_0()
+                .right_0()
+                .bottom_0()
+                .bg(cx.theme().background)
+                .opacity(0.96)
+                .flex()
+                .items_center()
+                .justify_center()
+                .child(
+                    v_flex()
+                        .w(px(440.0))
+                        .p_4()
+                        .gap_3()
+                        .bg(cx.theme().popover)
+                        .border_1()
+                        .border_color(cx.theme().border)
+                        .rounded_md()
+                        .child(
+                            div()
+                                .text_lg()
+                                .font_weight(gpui::FontWeight::SEMIBOLD)
+                                .text_color(cx.theme().foreground)
+                                .child("Unsaved changes"),
+                        )
+                        .child(
+                            div()
+                                .text_sm()
+                                .text_color(cx.theme().muted_foreground)
+                                .child(format!("{filename} has edits that haven't been saved.")),
+                        )
+                        .child(
+                            Checkbox::new("leave-dont-ask")
+                                .label("Don't ask again this session")
+                                .checked(!self.confirm_leaving_unsaved)
+                                .on_click(cx.listener(|this, checked: &bool, _, cx| {
+                                    this.confirm_leaving_unsaved = !*checked;
+                                    cx.notify();
+                                })),
+                        )
+                        .child(
+                            h_flex()
+                                .pt_2()
+                                .gap_2()
+                                .justify_end()
+                                .child(
+                                    Button::new("leave-save")
+                                        .small()
+                                        .primary()
+                                        .icon(IconName::Check)
+                                        .label("Save")
+                                        .disabled(self.state.is_saving)
+                                        .on_click(cx.listener(|this, _, _, cx| {
+                                            this.save_and_leave(cx);
+                                        })),
+                                )
+                                .child(
+                                    Button::new("leave-discard")
+                                        .small()
+                                        .danger()
+                                        .label("Discard")
+                                        .on_click(cx.listener(|this, _, _, cx| {
+                                            this.discard_and_leave(cx);
+                                        })),
+                                )
+                                .child(
+                                    Button::new("leave-cancel")
+                                        .small()
+                                        .ghost()
+                                        .label("Cancel")
+                                        .on_click(cx.listener(|this, _, _, cx| {
+                                            this.close_leave_popup(cx);
+                                        })),
+                                ),
+                        ),
+                )
+                .into_any_element(),
+        )
+    }
+
+    pub(super) fn open_settings_panel(&mut self, cx: &mut Context<Self>) {
+        self.state.active_panel = Panel::Settings;
+        cx.notify();
+    }
+
+    pub(super) fn close_settings_panel(&mut self, cx: &mut Context<Self>) {
+        self.state.active_panel = if self.state.photos.is_empty() {
+            Panel::Import
+        } else {
+            Panel::Photos
+        };
+        cx.notify();
+    }
+
+    pub(super) fn render_settings_popup(&self, cx: &mut Context<Self>) -> Option<AnyElement> {
+        if self.state.active_panel != Panel::Settings {
+            return None;
+        }
+        let settings = &self.state.settings;
+
+        let section = |label: &'static str| {
+            div()
+                .text_xs()
+                .font_weight(gpui::FontWeight::SEMIBOLD)
+                .text_color(cx.theme().muted_foreground)
+                .child(label)
+        };
+
+        let preset_buttons = self.state.presets.iter().map(|preset| {
+            let preset_id = preset.id;
+            Button::new(SharedString::from(format!("settings-preset-{preset_id}")))
+                .small()
+                .ghost()
+                .label(preset.name.clone())
+                .selected(settings.default_preset == Some(preset_id))
+                .on_click(cx.listener(move |this, _, _, cx| {
+                    this.update_settings(|settings| settings.default_preset = Some(preset_id), cx);
+                }))
+        });
+
+        let strip_choices = std::iter::once((None, String::from("Off"))).chain(
+            self.state
+                .presets
+                .iter()
+                .map(|preset| (Some(preset.id), preset.name.clone())),
+        );
+        let strip_buttons = strip_choices.map(|(preset_id, label)| {
+            let id = preset_id.unwrap_or_default();
+            Button::new(SharedString::from(format!("settings-strip-import-{id}")))
+                .small()
+                .ghost()
+                .label(label)
+                .selected(settings.strip_on_import == preset_id)
+                .on_click(cx.listener(move |this, _, _, cx| {
+                    this.update_settings(|settings| settings.strip_on_import = preset_id, cx);
+                }))
+        });
+
+        let output_buttons = [DefaultOutput::Overwrite, DefaultOutput::Copy].map(|output| {
+            Button::new(SharedString::from(format!(
+                "settings-output-{}",
+                output.label()
+            )))
+            .small()
+            .ghost()
+            .label(output.label())
+            .selected(settings.default_output == output)
+            .on_click(cx.listener(move |this, _, _, cx| {
+                this.update_settings(|settings| settings.default_output = output, cx);
+            }))
+        });
+
+        let thumbnail_buttons = THUMBNAIL_SIZE_CHOICES.map(|size| {
+            Button::new(SharedString::from(format!("settings-thumbnail-{size}")))
+                .small()
+                .ghost()
+                .label(format!("{size} px"))
+                .selected(settings.thumbnail_edge() == size)
+                .on_click(cx.listener(move |this, _, _, cx| {
+                    this.update_settings(|settings| settings.thumbnail_size = size, cx);
+                }))
+        });
+
+        let template_buttons = TagCategory::ALL.map(|category| {
+            Button::new(SharedString::from(format!(
+                "settings-template-{}",
+                category.as_str()
+            )))
+            .small()
+            .ghost()
+            .label(category.as_str())
+            .selected(settings.template_blocklist.categories.contains(&category))
+            .on_click(cx.listener(move |this, _, _, cx| {
+                this.update_settings(
+                    |settings| settings.template_blocklist.toggle_category(category),
+                    cx,
+                );
+            }))
+        });
+
+        Some(
+            div()
+                .absolute()
+                .top_0()
+                .left_0()
+                .right_0()
+                .bottom_0()
+                .bg(cx.theme().background)
+                .opacity(0.96)
+                .flex()
+                .items_center()
+                .justify_center()
+                .child(
+                    v_flex()
+                        .w(px(520.0))
+                        .p_4()
+                        .gap_3()
+                        .bg(cx.theme().popover)
+                        .border_1()
+                        .border_color(cx.theme().border)
+                        .rounded_md()
+                        .child(
+                            h_flex()
+                                .items_center()
+                                .justify_between()
+                                .child(
+                                    div()
+                                        .text_lg()
+                                        .font_weight(gpui::FontWeight::SEMIBOLD)
+                                        .text_color(cx.theme().foreground)
+                                        .child("Settings"),
+                                )
+                                .child(
+                                    Button::new("settings-close")
+                                        .ghost()
+                                        .small()
+                                        .icon(IconName::Close)
+                                        .on_click(cx.listener(|this, _, _, cx| {
+                                            this.close_settings_panel(cx);
+                                        })),
+                                ),
+                        )
+                        .child(section("Default preset"))
+                        .child(h_flex().flex_wrap().gap_1().children(preset_buttons))
+                        .child(section("Strip on import"))
+                        .child(h_flex().flex_wrap().gap_1().children(strip_buttons))
+                        .child(section("Bulk output"))
+                        .child(h_flex().gap_1().children(output_buttons))
+                        .child(section("Thumbnail size"))
+                        .child(h_flex().gap_1().children(thumbnail_buttons))
+                        .child(section("Templates leave out"))
+                        .child(h_flex().flex_wrap().gap_1().children(template_buttons))
+                        .child(
+                            Checkbox::new("settings-write-sidecars")
+                                .label("Write JSON sidecars next to saved photos")
+                                .checked(settings.write_sidecars)
+                                .on_click(cx.listener(|this, checked: &bool, _, cx| {
+                                    let checked = *checked;
+                                    this.update_settings(
+                                        |settings| settings.write_sidecars = checked,
+                                        cx,
+                                    );
+                                })),
+                        )
+                        .child(
+                            Checkbox::new("settings-backup-originals")
+                                .label("Keep a .orig copy of each photo before its first save")
+                                .checked(settings.backup_originals)
+                                .on_click(cx.listener(|this, checked: &bool, _, cx| {
+                                    let checked = *checked;
+                                    this.update_settings(
+                                        |settings| settings.backup_originals = checked,
+                                        cx,
+                                    );
+                                })),
+                        )
+                        .child(
+                            Checkbox::new("settings-log-to-file")
+                                .label("Log operations to a file for troubleshooting")
+                                .checked(settings.log_to_file)
+                                .on_click(cx.listener(|this, checked: &bool, _, cx| {
+                                    let checked = *checked;
+                                    this.update_settings(
+                                        |settings| settings.log_to_file = checked,
+                                        cx,
+                                    );
+                                })),
+                        ),
+                )
+                .into_any_element(),
+        )
+    }
 }
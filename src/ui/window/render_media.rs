@@ -57,6 +57,7 @@ impl ExifEditorWindow {
         let disable_nav = self.state.photos.len() <= 1;
 
         let drop_target = cx.theme().drop_target;
+        let embedded = self.embedded_preview_for(photo);
 
         v_flex()
             .flex_1()
@@ -109,37 +110,236 @@ impl ExifEditorWindow {
                         .items_center()
                         .justify_center()
                         .child(
-                            img(photo.path.clone())
-                                .w_full()
-                                .h_full()
-                                .object_fit(ObjectFit::Contain)
-                                .with_fallback(|| image_fallback("No preview available")),
+                            match self.oriented_preview_for(photo) {
+                                Some(image) => img(image),
+                                None => img(photo.path.clone()),
+                            }
+                            .w_full()
+                            .h_full()
+                            .object_fit(ObjectFit::Contain)
+                            .with_loading(move || match embedded.clone() {
+                                Some(image) => img(image)
+                                    .size_full()
+                                    .object_fit(ObjectFit::Contain)
+                                    .into_any_element(),
+                                None => div().into_any_element(),
+                            })
+                            .with_fallback(|| image_fallback("No preview available")),
                         ),
                 ),
             )
             .child(
-                h_flex().w_full().justify_start().child(
-                    div()
-                        .w_full()
-                        .text_sm()
-                        .text_color(cx.theme().muted_foreground)
-                        .child(photo.filename.clone()),
-                ),
+                h_flex()
+                    .w_full()
+                    .justify_start()
+                    .items_center()
+                    .gap_1()
+                    .child(Tag::secondary().xsmall().child(photo.format.as_str()))
+                    .child(
+                        div()
+                            .flex_1()
+                            .text_sm()
+                            .text_color(cx.theme().muted_foreground)
+                            .child(if photo.dirty {
+                                format!("{} *", photo.filename)
+                            } else {
+                                photo.filename.clone()
+                            }),
+                    )
+                    .child(
+                        Button::new("copy-path")
+                            .ghost()
+                            .xsmall()
+                            .icon(IconName::Copy)
+                            .tooltip("Copy file path")
+                            .on_click(cx.listener(|this, _, _, cx| this.copy_active_path(cx))),
+                    ),
             )
             .child(self.render_thumbnail_strip(cx))
             .into_any_element()
     }
 
+    /// Start decoding an upright preview when the active photo's orientation
+    /// needs a rotation or flip. Re-runs whenever the orientation tag changes.
+    pub(super) fn ensure_oriented_preview(&mut self, cx: &mut Context<Self>) {
+        let Some(photo) = self
+            .state
+            .active_photo
+            .and_then(|index| self.state.photos.get(index))
+        else {
+            self.oriented_preview = None;
+            return;
+        };
+
+        let orientation = photo.metadata.orientation();
+        if !(2..=8).contains(&orientation) {
+            self.oriented_preview = None;
+            return;
+        }
+
+        if self
+            .oriented_preview
+            .as_ref()
+            .is_some_and(|preview| preview.path == photo.path && preview.orientation == orientation)
+        {
+            return;
+        }
+
+        let path = photo.path.clone();
+        self.oriented_preview = Some(OrientedPreview {
+            path: path.clone(),
+            orientation,
+            image: None,
+        });
+
+        let decode_path = path.clone();
+        let decode = cx.background_spawn(async move {
+            preview::oriented_png(&decode_path, orientation, preview::PREVIEW_MAX_EDGE)
+        });
+        self.oriented_preview_task = Some(cx.spawn(async move |this, cx| {
+            let bytes = decode.await;
+            let _ = this.update(cx, |this, cx| {
+                let Some(preview) = this.oriented_preview.as_mut() else {
+                    return;
+                };
+                if preview.path == path && preview.orientation == orientation {
+                    preview.image = bytes.map(|bytes| {
+                        Arc::new(gpui::Image::from_bytes(gpui::ImageFormat::Png, bytes))
+                    });
+                    cx.notify();
+                }
+            });
+        }));
+    }
+
+    /// Pull the EXIF-embedded thumbnail for the active photo on a background task.
+    /// It only has to outlive the full decode, so it is never refreshed for a path.
+    pub(super) fn ensure_embedded_preview(&mut self, cx: &mut Context<Self>) {
+        let Some(photo) = self
+            .state
+            .active_photo
+            .and_then(|index| self.state.photos.get(index))
+        else {
+            self.embedded_preview = None;
+            return;
+        };
+
+        if self
+            .embedded_preview
+            .as_ref()
+            .is_some_and(|preview| preview.path == photo.path)
+        {
+            return;
+        }
+
+        let path = photo.path.clone();
+        self.embedded_preview = Some(EmbeddedPreview {
+            path: path.clone(),
+            image: None,
+        });
+
+        let extract_path = path.clone();
+        let extract =
+            cx.background_spawn(async move { MetadataEngine::extract_thumbnail(&extract_path) });
+        self.embedded_preview_task = Some(cx.spawn(async move |this, cx| {
+            let thumbnail = extract.await;
+            let _ = this.update(cx, |this, cx| {
+                let Some(preview) = this.embedded_preview.as_mut() else {
+                    return;
+                };
+                if preview.path == path {
+                    preview.image = thumbnail.as_ref().and_then(render_image_from_thumbnail);
+                    cx.notify();
+                }
+            });
+        }));
+    }
+
+    fn embedded_preview_for(&self, photo: &PhotoEntry) -> Option<Arc<RenderImage>> {
+        let preview = self.embedded_preview.as_ref()?;
+        if preview.path != photo.path {
+            return None;
+        }
+        preview.image.clone()
+    }
+
+    fn oriented_preview_for(&self, photo: &PhotoEntry) -> Option<Arc<gpui::Image>> {
+        let preview = self.oriented_preview.as_ref()?;
+        if preview.path != photo.path || preview.orientation != photo.metadata.orientation() {
+            return None;
+        }
+        preview.image.clone()
+    }
+
+    /// Decode thumbnails for photos that are new or were reloaded after changing
+    /// on disk, on a background task one batch at a time, so the strip never
+    /// decodes originals. Nothing is stat'ed here; see [`PhotoEntry::refresh_file_stats`].
+    pub(super) fn ensure_thumbnails(&mut self, cx: &mut Context<Self>) {
+        if self.thumbnail_task.is_some() {
+            return;
+        }
+
+        let pending = self
+            .state
+            .photos
+            .iter()
+            .filter(|photo| photo.needs_thumbnail())
+            .map(|photo| (photo.path.clone(), photo.modified))
+            .collect::<Vec<_>>();
+        if pending.is_empty() {
+            return;
+        }
+
+        let decode = cx.background_spawn(async move {
+            pending
+                .into_iter()
+                .map(|(path, modified)| {
+                    let decoded = thumbnail::decode_thumbnail(&path, THUMBNAIL_EDGE);
+                    (path, modified, decoded)
+                })
+                .collect::<Vec<_>>()
+        });
+
+        self.thumbnail_task = Some(cx.spawn(async move |this, cx| {
+            let decoded = decode.await;
+            let _ = this.update(cx, |this, cx| {
+                for (path, modified, data) in decoded {
+                    // A photo reloaded while this batch ran is picked up by the next one.
+                    let Some(photo) = this
+                        .state
+                        .photos
+                        .iter_mut()
+                        .find(|photo| photo.path == path && photo.modified == modified)
+                    else {
+                        continue;
+                    };
+                    match data.as_ref().and_then(render_image_from_thumbnail) {
+                        Some(image) => {
+                            this.thumbnail_images.insert(path, image);
+                        }
+                        None => {
+                            this.thumbnail_images.remove(&path);
+                        }
+                    }
+                    photo.set_thumbnail(data);
+                }
+                this.thumbnail_task = None;
+                cx.notify();
+            });
+        }));
+    }
+
     pub(super) fn render_thumbnail_strip(&self, cx: &mut Context<Self>) -> AnyElement {
+        let tile = self.state.settings.thumbnail_edge() as f32;
         div()
             .id(SharedString::from("carousel-thumbnails"))
-            .h(px(112.0))
+            .h(px(tile + 16.0))
             .w_full()
             .overflow_x_scrollbar()
             .child(h_flex().h_full().items_start().gap_2().pr_3().children(
                 self.state.photos.iter().enumerate().map(|(index, photo)| {
                     let is_active = self.state.active_photo == Some(index);
-                    let filename = photo.filename.clone();
+                    let show_unsaved_badge = photo.shows_unsaved_badge();
 
                     let list_hover = cx.theme().list_hover;
                     let thumb_muted = cx.theme().muted;
@@ -148,8 +348,9 @@ impl ExifEditorWindow {
 
                     div()
                         .id(SharedString::from(format!("thumb-{index}")))
-                        .w(px(96.0))
-                        .h(px(96.0))
+                        .relative()
+                        .w(px(tile))
+                        .h(px(tile))
                         .flex_none()
                         .overflow_hidden()
                         .bg(thumb_muted)
@@ -162,18 +363,27 @@ impl ExifEditorWindow {
                         .cursor_pointer()
                         .hover(move |style| style.bg(list_hover))
                         .on_click(cx.listener(move |this, _, _, cx| {
-                            this.state.select_photo(index, false);
-                            this.refresh_tag_rows = true;
-                            this.status = format!("Selected {filename}");
-                            cx.notify();
+                            this.navigate_to_photo(index, cx);
                         }))
-                        .child(
-                            img(photo.path.clone())
+                        .child(match self.thumbnail_images.get(&photo.path) {
+                            Some(image) => img(image.clone())
                                 .w_full()
                                 .h_full()
                                 .object_fit(ObjectFit::Cover)
-                                .with_fallback(|| image_fallback("No preview")),
-                        )
+                                .into_any_element(),
+                            None => image_fallback("No preview"),
+                        })
+                        .children(show_unsaved_badge.then(|| {
+                            div()
+                                .absolute()
+                                .top_1()
+                                .right_1()
+                                .size_2()
+                                .rounded_full()
+                                .bg(cx.theme().warning)
+                                .border_1()
+                                .border_color(cx.theme().background)
+                        }))
                 }),
             ))
             .into_any_element()
@@ -182,6 +392,26 @@ impl ExifEditorWindow {
     pub(super) fn render_action_row(&self, cx: &mut Context<Self>) -> AnyElement {
         let has_photo = self.state.active_photo.is_some();
         let has_photos = !self.state.photos.is_empty();
+        let orientation_mismatch = self
+            .state
+            .active_photo
+            .and_then(|index| self.state.photos.get(index))
+            .is_some_and(|photo| photo.has_orientation_mismatch());
+        let has_selection = !self.state.selected_indices.is_empty();
+        let has_dirty = self.state.photos.iter().any(|photo| photo.dirty);
+        let active_dirty = self
+            .state
+            .active_photo
+            .and_then(|index| self.state.photos.get(index))
+            .is_some_and(|photo| photo.dirty);
+        let is_processing = self.state.is_processing;
+        let is_saving = self.state.is_saving;
+        let progress_value = self
+            .state
+            .progress
+            .as_ref()
+            .filter(|_| is_processing)
+            .map(|progress| progress.current as f32 * 100.0 / progress.total.max(1) as f32);
 
         h_flex()
             .h(px(44.0))
@@ -193,8 +423,9 @@ impl ExifEditorWindow {
                     .small()
                     .primary()
                     .icon(IconName::Check)
-                    .label("Save")
-                    .disabled(!has_photo)
+                    .label(if is_saving { "Saving..." } else { "Save" })
+                    .loading(is_saving)
+                    .disabled(!has_photo || is_saving || is_processing)
                     .on_click(cx.listener(|this, _, _, cx| this.save_active(cx))),
             )
             .child(
@@ -203,9 +434,29 @@ impl ExifEditorWindow {
                     .primary()
                     .icon(IconName::Check)
                     .label("Save All")
-                    .disabled(!has_photos)
+                    .disabled(!has_photos || is_saving || is_processing)
                     .on_click(cx.listener(|this, _, _, cx| this.save_all(cx))),
             )
+            .child(
+                Button::new("drop-empty-tags")
+                    .ghost()
+                    .small()
+                    .icon(IconName::Delete)
+                    .label("Drop Empty Tags")
+                    .selected(self.state.drop_empty_tags_on_save)
+                    .on_click(cx.listener(|this, _, _, cx| {
+                        this.state.drop_empty_tags_on_save = !this.state.drop_empty_tags_on_save;
+                        cx.notify();
+                    })),
+            )
+            .child(
+                Button::new("duplicate-active")
+                    .small()
+                    .icon(IconName::Copy)
+                    .label("Duplicate")
+                    .disabled(!has_photo)
+                    .on_click(cx.listener(|this, _, _, cx| this.duplicate_active(cx))),
+            )
             .child(
                 Button::new("export-active")
                     .small()
@@ -222,6 +473,102 @@ impl ExifEditorWindow {
                     .disabled(!has_photos)
                     .on_click(cx.listener(|this, _, _, cx| this.export_all(cx))),
             )
+            .child(
+                Button::new("mirror-export-folders")
+                    .ghost()
+                    .small()
+                    .icon(IconName::FolderOpen)
+                    .label("Keep Folders")
+                    .selected(self.state.mirror_export_folders)
+                    .on_click(cx.listener(|this, _, _, cx| {
+                        this.state.mirror_export_folders = !this.state.mirror_export_folders;
+                        cx.notify();
+                    })),
+            )
+            .child(
+                Button::new("import-exiftool")
+                    .small()
+                    .icon(IconName::File)
+                    .label("Import JSON")
+                    .disabled(!has_photos)
+                    .on_click(cx.listener(|this, _, _, cx| this.import_exiftool_json(cx))),
+            )
+            .child(
+                Button::new("import-merge-strategy")
+                    .ghost()
+                    .small()
+                    .label(format!(
+                        "Merge: {}",
+                        self.state.import_merge_strategy.label()
+                    ))
+                    .tooltip("How imported JSON treats tags a photo already has")
+                    .on_click(cx.listener(|this, _, _, cx| {
+                        this.state.import_merge_strategy = this.state.import_merge_strategy.next();
+                        cx.notify();
+                    })),
+            )
+            .child(
+                Button::new("export-exiftool")
+                    .small()
+                    .icon(IconName::File)
+                    .label("Export JSON")
+                    .disabled(!has_photo)
+                    .on_click(cx.listener(|this, _, _, cx| this.export_exiftool_json(cx))),
+            )
+            .child(
+                Button::new("export-contacts")
+                    .small()
+                    .icon(IconName::User)
+                    .label("Export Contacts")
+                    .tooltip("Save the artists and copyrights across all photos as vCards")
+                    .disabled(!has_photo)
+                    .on_click(cx.listener(|this, _, _, cx| this.export_contacts(cx))),
+            )
+            .child(
+                Button::new("revert-active")
+                    .small()
+                    .icon(IconName::Undo)
+                    .label("Revert")
+                    .disabled(!active_dirty)
+                    .on_click(cx.listener(|this, _, _, cx| this.revert_active(cx))),
+            )
+            .child(
+                Button::new("revert-all")
+                    .small()
+                    .icon(IconName::Undo)
+                    .label("Revert All")
+                    .disabled(!has_dirty)
+                    .on_click(cx.listener(|this, _, window, cx| this.revert_all(window, cx))),
+            )
+            .child(
+                Button::new("find-tag")
+                    .small()
+                    .icon(IconName::Search)
+                    .label("Find Value")
+                    .tooltip("Find a tag value across every loaded photo")
+                    .disabled(!has_photos)
+                    .on_click(
+                        cx.listener(|this, _, window, cx| this.open_find_tag_popup(window, cx)),
+                    ),
+            )
+            .child(
+                Button::new("audit-sidecars")
+                    .small()
+                    .icon(IconName::CircleCheck)
+                    .label("Check Sidecars")
+                    .tooltip("Find sidecars that no longer match their photo")
+                    .disabled(!has_photos)
+                    .on_click(cx.listener(|this, _, _, cx| this.audit_sidecars(cx))),
+            )
+            .child(
+                Button::new("audit-privacy")
+                    .small()
+                    .icon(IconName::EyeOff)
+                    .label("Privacy Audit")
+                    .tooltip("List GPS, serial, owner and maker note tags saved in each photo")
+                    .disabled(!has_photos)
+                    .on_click(cx.listener(|this, _, window, cx| this.audit_privacy(window, cx))),
+            )
             .child(
                 Button::new("clear-all-meta")
                     .small()
@@ -231,7 +578,147 @@ impl ExifEditorWindow {
                     .disabled(!has_photos)
                     .on_click(cx.listener(|this, _, _, cx| this.clear_all_metadata(cx))),
             )
+            .child(
+                Button::new("fix-orientation")
+                    .small()
+                    .icon(IconName::TriangleAlert)
+                    .label("Fix Orientation")
+                    .disabled(!orientation_mismatch)
+                    .on_click(cx.listener(|this, _, _, cx| this.fix_active_orientation(cx))),
+            )
+            .child(if is_processing {
+                Button::new("cancel-bulk")
+                    .small()
+                    .icon(IconName::CircleX)
+                    .label("Cancel")
+                    .on_click(cx.listener(|this, _, _, cx| this.cancel_bulk(cx)))
+            } else {
+                Button::new("run-bulk")
+                    .small()
+                    .icon(IconName::Replace)
+                    .label("Strip Selected")
+                    .disabled(!has_selection || is_saving)
+                    .on_click(cx.listener(|this, _, _, cx| this.run_bulk_selected(cx)))
+            })
+            .children((self.bulk_resumable && !is_processing).then(|| {
+                Button::new("resume-bulk")
+                    .small()
+                    .icon(IconName::Redo)
+                    .label("Resume Interrupted Run")
+                    .tooltip(
+                        "Run the preset on the selection again, skipping photos the \
+                         interrupted run finished that haven't changed since",
+                    )
+                    .disabled(!has_selection || is_saving)
+                    .on_click(cx.listener(|this, _, _, cx| this.resume_bulk_selected(cx)))
+            }))
+            .child(
+                Button::new("run-hard-clean")
+                    .small()
+                    .danger()
+                    .icon(IconName::Delete)
+                    .label("Hard Clean")
+                    .tooltip("Strip all metadata from the selection and remove sidecars")
+                    .disabled(!has_selection || is_processing || is_saving)
+                    .on_click(cx.listener(|this, _, _, cx| this.run_hard_clean(cx))),
+            )
+            .child(
+                Button::new("run-allowlist")
+                    .small()
+                    .icon(IconName::Check)
+                    .label("Keep Only...")
+                    .disabled(!has_selection || is_processing || is_saving)
+                    .on_click(
+                        cx.listener(|this, _, window, cx| this.open_allowlist_dialog(window, cx)),
+                    ),
+            )
+            .child(
+                Button::new("new-preset")
+                    .small()
+                    .icon(IconName::Plus)
+                    .label("New Preset...")
+                    .tooltip("Build a preset from your own rules")
+                    .disabled(is_processing)
+                    .on_click(cx.listener(|this, _, window, cx| {
+                        this.open_preset_builder_dialog(window, cx)
+                    })),
+            )
+            .child(
+                Button::new("conform-to-active")
+                    .small()
+                    .icon(IconName::Copy)
+                    .label("Conform to Active")
+                    .tooltip("Strip tags the active photo doesn't have from the selection")
+                    .disabled(!has_photo || !has_selection)
+                    .on_click(cx.listener(|this, _, _, cx| this.conform_selection_to_active(cx))),
+            )
+            .child(
+                Button::new("apply-template")
+                    .small()
+                    .icon(IconName::Copy)
+                    .label("Apply as Template")
+                    .tooltip(
+                        "Copy the active photo's descriptive tags to the selection; \
+                         per-photo tags listed in Settings are left alone",
+                    )
+                    .disabled(!has_photo || !has_selection)
+                    .on_click(cx.listener(|this, _, _, cx| this.apply_active_as_template(cx))),
+            )
+            .child(
+                Button::new("replace-in-tag")
+                    .small()
+                    .icon(IconName::Replace)
+                    .label("Replace...")
+                    .tooltip("Find and replace text in one tag across the selection")
+                    .disabled(!has_selection)
+                    .on_click(
+                        cx.listener(|this, _, window, cx| this.open_replace_dialog(window, cx)),
+                    ),
+            )
+            .child(
+                Button::new("set-srgb")
+                    .small()
+                    .icon(IconName::Palette)
+                    .label("Set sRGB")
+                    .tooltip("Set Color Space to sRGB on the selection")
+                    .disabled(!has_selection)
+                    .on_click(cx.listener(|this, _, _, cx| this.set_selection_to_srgb(cx))),
+            )
+            .child(
+                Button::new("set-copyright-year")
+                    .small()
+                    .icon(IconName::Calendar)
+                    .label("Update © Year")
+                    .tooltip("Set the year in each selected photo's copyright to this year")
+                    .disabled(!has_selection)
+                    .on_click(cx.listener(|this, _, _, cx| this.set_selection_copyright_year(cx))),
+            )
+            .child(
+                Button::new("export-stripped")
+                    .small()
+                    .icon(IconName::ExternalLink)
+                    .label("Export Stripped")
+                    .disabled(!has_selection || self.state.active_preset.is_none())
+                    .on_click(cx.listener(|this, _, _, cx| this.export_selected_with_preset(cx))),
+            )
+            .child(
+                Button::new("analyze-session")
+                    .small()
+                    .icon(IconName::ChartPie)
+                    .label("Analyze")
+                    .loading(self.analyze_task.is_some())
+                    .disabled(!has_photos || self.analyze_task.is_some())
+                    .on_click(cx.listener(|this, _, _, cx| this.analyze_session(cx))),
+            )
+            .children(progress_value.map(|value| Progress::new().w(px(120.0)).value(value)))
             .child(div().flex_1())
+            .child(
+                Button::new("open-settings")
+                    .ghost()
+                    .small()
+                    .icon(IconName::Settings)
+                    .on_click(cx.listener(|this, _, _, cx| this.open_settings_panel(cx))),
+            )
             .child(
                 Button::new("toggle-theme")
                     .ghost()
@@ -241,14 +728,7 @@ impl ExifEditorWindow {
                     } else {
                         IconName::Moon
                     })
-                    .on_click(cx.listener(|_this, _, window, cx| {
-                        let new_mode = if cx.theme().mode == ThemeMode::Dark {
-                            ThemeMode::Light
-                        } else {
-                            ThemeMode::Dark
-                        };
-                        Theme::change(new_mode, Some(window), cx);
-                    })),
+                    .on_click(cx.listener(|this, _, window, cx| this.toggle_theme(window, cx))),
             )
             .into_any_element()
     }
@@ -274,8 +754,172 @@ impl ExifEditorWindow {
                     .w_full()
                     .gap_2()
                     .child(div().flex_1().child(media))
-                    .child(div().px_2().child(self.render_action_row(cx))),
+                    .children(
+                        self.state
+                            .session_stats
+                            .as_ref()
+                            .map(|stats| self.render_session_stats(stats, cx)),
+                    )
+                    .children(
+                        self.skipped_imports_expanded
+                            .then(|| self.render_skipped_imports(cx)),
+                    )
+                    .child(div().px_2().child(self.render_action_row(cx)))
+                    .child(self.render_status_bar(cx)),
+            )
+            .into_any_element()
+    }
+
+    fn render_session_stats(&self, stats: &SessionStats, cx: &mut Context<Self>) -> AnyElement {
+        let cameras = if stats.cameras.is_empty() {
+            String::from("No camera info")
+        } else {
+            stats
+                .cameras
+                .iter()
+                .take(3)
+                .map(|(camera, count)| format!("{camera} ({count})"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let dates = match (&stats.earliest_date, &stats.latest_date) {
+            (Some(earliest), Some(latest)) if earliest != latest => {
+                format!("{earliest} to {latest}")
+            }
+            (Some(date), _) => date.clone(),
+            _ => String::from("No capture dates"),
+        };
+
+        v_flex()
+            .mx_2()
+            .p_2()
+            .gap_1()
+            .bg(cx.theme().secondary)
+            .border_1()
+            .border_color(cx.theme().border)
+            .rounded_md()
+            .text_sm()
+            .child(
+                h_flex()
+                    .justify_between()
+                    .child(
+                        div()
+                            .font_weight(gpui::FontWeight::SEMIBOLD)
+                            .child(format!("Session: {} photo(s)", stats.photo_count)),
+                    )
+                    .child(
+                        Button::new("close-session-stats")
+                            .ghost()
+                            .xsmall()
+                            .icon(IconName::Close)
+                            .on_click(cx.listener(|this, _, _, cx| {
+                                this.state.session_stats = None;
+                                cx.notify();
+                            })),
+                    ),
+            )
+            .child(format!("Cameras ({}): {cameras}", stats.cameras.len()))
+            .child(format!("Dates: {dates}"))
+            .child(format!(
+                "GPS: {:.0}% ({} of {})",
+                stats.gps_percent(),
+                stats.gps_count,
+                stats.photo_count
+            ))
+            .child(format!("Metadata: {}", format_bytes(stats.metadata_bytes)))
+            .into_any_element()
+    }
+
+    fn render_skipped_imports(&self, cx: &mut Context<Self>) -> AnyElement {
+        let rows = self.skipped_imports.iter().map(|(path, reason)| {
+            let name = path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_else(|| path.display().to_string());
+            h_flex()
+                .w_full()
+                .gap_2()
+                .child(div().flex_1().truncate().child(name))
+                .child(
+                    div()
+                        .text_color(cx.theme().muted_foreground)
+                        .child(reason.label()),
+                )
+        });
+
+        v_flex()
+            .mx_2()
+            .p_2()
+            .gap_1()
+            .max_h(px(160.0))
+            .bg(cx.theme().secondary)
+            .border_1()
+            .border_color(cx.theme().border)
+            .rounded_md()
+            .text_sm()
+            .child(
+                h_flex()
+                    .justify_between()
+                    .child(
+                        div()
+                            .font_weight(gpui::FontWeight::SEMIBOLD)
+                            .child(format!("Skipped files ({})", self.skipped_imports.len())),
+                    )
+                    .child(
+                        Button::new("close-skipped-imports")
+                            .ghost()
+                            .xsmall()
+                            .icon(IconName::Close)
+                            .on_click(cx.listener(|this, _, _, cx| {
+                                this.skipped_imports_expanded = false;
+                                cx.notify();
+                            })),
+                    ),
             )
+            .child(
+                div()
+                    .id(SharedString::from("skipped-imports-list"))
+                    .flex_1()
+                    .overflow_y_scrollbar()
+                    .child(v_flex().w_full().gap_1().children(rows)),
+            )
+            .into_any_element()
+    }
+
+    fn render_status_bar(&self, cx: &mut Context<Self>) -> AnyElement {
+        let unsaved = self.state.unsaved_count();
+
+        h_flex()
+            .w_full()
+            .gap_4()
+            .px_2()
+            .py_1()
+            .border_t_1()
+            .border_color(cx.theme().border)
+            .text_xs()
+            .text_color(cx.theme().muted_foreground)
+            .child(div().flex_1().truncate().child(self.status.clone()))
+            .children((!self.skipped_imports.is_empty()).then(|| {
+                Button::new("toggle-skipped-imports")
+                    .ghost()
+                    .xsmall()
+                    .icon(if self.skipped_imports_expanded {
+                        IconName::ChevronDown
+                    } else {
+                        IconName::ChevronUp
+                    })
+                    .label(format!("{} skipped", self.skipped_imports.len()))
+                    .on_click(cx.listener(|this, _, _, cx| {
+                        this.skipped_imports_expanded = !this.skipped_imports_expanded;
+                        cx.notify();
+                    }))
+            }))
+            .children((unsaved > 0).then(|| {
+                div()
+                    .text_color(cx.theme().warning)
+                    .child(format!("{unsaved} unsaved"))
+            }))
+            .child(shortcut_hint())
             .into_any_element()
     }
 }
@@ -1,6 +1,70 @@
 use super::*;
 
 impl ExifEditorWindow {
+    fn render_hex_page(
+        &self,
+        row_id: &str,
+        data: &[u8],
+        offset: usize,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        let end = (offset + HEX_PAGE_BYTES).min(data.len());
+        let prev_row_id = row_id.to_string();
+        let next_row_id = row_id.to_string();
+
+        v_flex()
+            .w_full()
+            .gap_1()
+            .child(
+                div()
+                    .w_full()
+                    .p_1()
+                    .bg(cx.theme().muted)
+                    .font_family("monospace")
+                    .text_xs()
+                    .whitespace_nowrap()
+                    .children(
+                        hex::hex_page(data, offset, HEX_PAGE_BYTES)
+                            .lines()
+                            .map(|line| div().child(line.to_string()))
+                            .collect::<Vec<_>>(),
+                    ),
+            )
+            .child(
+                h_flex()
+                    .w_full()
+                    .gap_2()
+                    .items_center()
+                    .child(
+                        Button::new((ElementId::from("hex-prev"), row_id.to_string()))
+                            .ghost()
+                            .xsmall()
+                            .icon(IconName::ChevronLeft)
+                            .disabled(offset == 0)
+                            .on_click(cx.listener(move |this, _, _, cx| {
+                                this.page_hex_view(&prev_row_id, -1, cx);
+                            })),
+                    )
+                    .child(
+                        div()
+                            .text_xs()
+                            .text_color(cx.theme().muted_foreground)
+                            .child(format!("{offset}-{end} of {}", data.len())),
+                    )
+                    .child(
+                        Button::new((ElementId::from("hex-next"), row_id.to_string()))
+                            .ghost()
+                            .xsmall()
+                            .icon(IconName::ChevronRight)
+                            .disabled(end >= data.len())
+                            .on_click(cx.listener(move |this, _, _, cx| {
+                                this.page_hex_view(&next_row_id, 1, cx);
+                            })),
+                    ),
+            )
+            .into_any_element()
+    }
+
     pub(super) fn render_tag_field(&self, row: &TagEditorRow, cx: &mut Context<Self>) -> Field {
         let label = row.display_name.clone();
 
@@ -42,12 +106,39 @@ impl ExifEditorWindow {
                     input_widget.into_any_element()
                 }
             }
+            TagEditorKind::Enum { select, .. } => {
+                let tag_key = row.tag_key.clone();
+
+                h_flex()
+                    .w_full()
+                    .gap_1()
+                    .items_center()
+                    .child(Select::new(select).flex_1())
+                    .child(
+                        Button::new((ElementId::from("clear-enum"), row.row_id.clone()))
+                            .ghost()
+                            .xsmall()
+                            .icon(IconName::CircleX)
+                            .tab_stop(false)
+                            .on_click(cx.listener(move |this, _, _, cx| {
+                                this.clear_row(&tag_key, cx);
+                            })),
+                    )
+                    .into_any_element()
+            }
             TagEditorKind::Rational {
                 numerator,
                 denominator,
                 ..
             } => {
                 let tag_key = row.tag_key.clone();
+                let parse = |input: &gpui::Entity<InputState>| {
+                    input.read(cx).value().trim().parse::<u32>().ok()
+                };
+                let reading = match (parse(numerator), parse(denominator)) {
+                    (Some(n), Some(d)) => format_rational_for_key(&row.tag_key, n, d),
+                    _ => String::new(),
+                };
 
                 h_flex()
                     .w_full()
@@ -56,6 +147,12 @@ impl ExifEditorWindow {
                     .child(Input::new(numerator).w(px(96.0)))
                     .child(div().text_sm().text_color(cx.theme().muted_foreground).child("/"))
                     .child(Input::new(denominator).w(px(96.0)))
+                    .children((!reading.is_empty()).then(|| {
+                        div()
+                            .text_sm()
+                            .text_color(cx.theme().muted_foreground)
+                            .child(reading)
+                    }))
                     .child(
                         Button::new((ElementId::from("clear-rational"), row.row_id.clone()))
                             .ghost()
@@ -85,6 +182,35 @@ impl ExifEditorWindow {
                     .child(Input::new(latitude).w(px(110.0)))
                     .child(Input::new(longitude).w(px(110.0)))
                     .child(Input::new(altitude).w(px(110.0)))
+                    .child(
+                        Button::new((ElementId::from("gps-dms"), row.row_id.clone()))
+                            .ghost()
+                            .xsmall()
+                            .label("DMS")
+                            .selected(self.gps_dms_mode)
+                            .tab_stop(false)
+                            .on_click(cx.listener(|this, _, _, cx| this.toggle_gps_dms_mode(cx))),
+                    )
+                    .child(
+                        Button::new((ElementId::from("gps-alt-unit"), row.row_id.clone()))
+                            .ghost()
+                            .xsmall()
+                            .label(self.altitude_unit.suffix())
+                            .tab_stop(false)
+                            .tooltip("Altitude unit")
+                            .on_click(cx.listener(|this, _, _, cx| this.toggle_altitude_unit(cx))),
+                    )
+                    .child(
+                        Button::new((ElementId::from("gps-alt-precision"), row.row_id.clone()))
+                            .ghost()
+                            .xsmall()
+                            .label(format!("{} dp", self.altitude_precision))
+                            .tab_stop(false)
+                            .tooltip("Altitude decimal places")
+                            .on_click(
+                                cx.listener(|this, _, _, cx| this.cycle_altitude_precision(cx)),
+                            ),
+                    )
                     .child(
                         Button::new((ElementId::from("map"), row.row_id.clone()))
                             .small()
@@ -107,9 +233,46 @@ impl ExifEditorWindow {
                     )
                     .into_any_element()
             }
-            TagEditorKind::Binary { bytes } => {
+            TagEditorKind::List { items, input, .. } => {
+                let chips = items
+                    .iter()
+                    .enumerate()
+                    .map(|(index, item)| {
+                        let row_id = row.row_id.clone();
+                        let tag_key = row.tag_key.clone();
+                        Tag::secondary().small().child(
+                            h_flex().gap_1().items_center().child(item.clone()).child(
+                                Button::new((
+                                    ElementId::from("remove-list-item"),
+                                    format!("{}::{index}", row.row_id),
+                                ))
+                                .ghost()
+                                .xsmall()
+                                .icon(IconName::Close)
+                                .tab_stop(false)
+                                .on_click(cx.listener(
+                                    move |this, _, _, cx| {
+                                        this.remove_list_item(&row_id, &tag_key, index, cx);
+                                    },
+                                )),
+                            ),
+                        )
+                    })
+                    .collect::<Vec<_>>();
+
+                v_flex()
+                    .w_full()
+                    .gap_1()
+                    .children(
+                        (!chips.is_empty()).then(|| h_flex().flex_wrap().gap_1().children(chips)),
+                    )
+                    .child(Input::new(input).w_full())
+                    .into_any_element()
+            }
+            TagEditorKind::Binary { data, hex_offset } => {
                 let tag_key = row.tag_key.clone();
-                h_flex()
+                let toggle_row_id = row.row_id.clone();
+                let summary = h_flex()
                     .w_full()
                     .gap_2()
                     .items_center()
@@ -118,7 +281,17 @@ impl ExifEditorWindow {
                             .flex_1()
                             .text_sm()
                             .text_color(cx.theme().muted_foreground)
-                            .child(format!("<{bytes} bytes>")),
+                            .child(format!("<{} bytes>", data.len())),
+                    )
+                    .child(
+                        Button::new((ElementId::from("toggle-hex"), row.row_id.clone()))
+                            .ghost()
+                            .xsmall()
+                            .label(if hex_offset.is_some() { "Hide" } else { "Hex" })
+                            .disabled(data.is_empty())
+                            .on_click(cx.listener(move |this, _, _, cx| {
+                                this.toggle_hex_view(&toggle_row_id, cx);
+                            })),
                     )
                     .child(
                         Button::new((ElementId::from("clear-binary"), row.row_id.clone()))
@@ -129,12 +302,72 @@ impl ExifEditorWindow {
                             .on_click(cx.listener(move |this, _, _, cx| {
                                 this.clear_row(&tag_key, cx);
                             })),
-                    )
-                    .into_any_element()
+                    );
+
+                match hex_offset {
+                    Some(offset) => v_flex()
+                        .w_full()
+                        .gap_1()
+                        .child(summary)
+                        .child(self.render_hex_page(&row.row_id, data, *offset, cx))
+                        .into_any_element(),
+                    None => summary.into_any_element(),
+                }
             }
         };
 
-        let mut field = Field::new().label(label).items_start().child(editor);
+        let active = self
+            .state
+            .active_photo
+            .and_then(|index| self.state.photos.get(index));
+        let tag = active.and_then(|photo| photo.metadata.find_tag(&row.tag_key));
+        let marked = tag.is_some_and(|tag| tag.marked_for_removal);
+        let camera_default =
+            self.flag_camera_defaults && tag.is_some_and(catalog::is_camera_default);
+        let tag_key_for_mark = row.tag_key.clone();
+        let editor = h_flex()
+            .w_full()
+            .gap_2()
+            .items_start()
+            .child(
+                Checkbox::new((ElementId::from("mark-removal"), row.row_id.clone()))
+                    .checked(marked)
+                    .tab_stop(false)
+                    .on_click(cx.listener(move |this, checked: &bool, _, cx| {
+                        this.mark_tag_for_removal(&tag_key_for_mark, *checked, cx);
+                    })),
+            )
+            .child(div().flex_1().child(editor));
+
+        let highlighted = self
+            .highlighted_tag
+            .as_ref()
+            .zip(active)
+            .is_some_and(|((photo_id, key), photo)| *photo_id == photo.id && *key == row.tag_key);
+        let mut field = if marked {
+            let muted = cx.theme().muted_foreground;
+            Field::new()
+                .label_fn(move |_, _| div().line_through().text_color(muted).child(label.clone()))
+        } else if highlighted {
+            let accent = cx.theme().primary;
+            Field::new().label_fn(move |_, _| {
+                div()
+                    .font_weight(gpui::FontWeight::SEMIBOLD)
+                    .text_color(accent)
+                    .child(label.clone())
+            })
+        } else if camera_default {
+            let muted = cx.theme().muted_foreground;
+            Field::new().label_fn(move |_, _| {
+                div()
+                    .text_color(muted)
+                    .child(format!("{label} (camera default)"))
+            })
+        } else {
+            Field::new().label(label)
+        }
+        .items_start()
+        .child(editor);
         if let Some(error) = row.parse_error.as_ref() {
             let error_text = error.clone();
             let error_color = cx.theme().danger_foreground;
@@ -148,10 +381,20 @@ impl ExifEditorWindow {
 
     pub(super) fn render_metadata_editor(&self, cx: &mut Context<Self>) -> AnyElement {
     let query = self.metadata_filter.trim().to_ascii_lowercase();
+    let active_photo = self
+        .state
+        .active_photo
+        .and_then(|index| self.state.photos.get(index));
+    let modified_only = self.state.modified_only;
     let fields: Vec<Field> = self
         .tag_rows
         .iter()
         .filter(|row| {
+            if modified_only
+                && !active_photo.is_some_and(|photo| photo.is_tag_modified(&row.tag_key))
+            {
+                return false;
+            }
             if query.is_empty() {
                 return true;
             }
@@ -161,7 +404,29 @@ impl ExifEditorWindow {
         .map(|row| self.render_tag_field(row, cx))
         .collect();
 
+    // Read-only values derived from the photo, shown below the editable tags.
+    let computed_fields: Vec<Field> = active_photo
+        .and_then(|photo| photo.pixel_dimensions())
+        .filter(|dimensions| dimensions.width > 0 && dimensions.height > 0)
+        .map(|dimensions| {
+            let muted = cx.theme().muted_foreground;
+            [
+                ("Aspect Ratio", compute_aspect_ratio(dimensions.width, dimensions.height)),
+                ("Megapixels", compute_megapixels(dimensions.width, dimensions.height)),
+            ]
+            .into_iter()
+            .map(|(label, value)| {
+                Field::new()
+                    .label(label)
+                    .child(div().text_sm().text_color(muted).child(value))
+            })
+            .collect()
+        })
+        .unwrap_or_default();
+
     let has_photo = self.state.active_photo.is_some();
+    let has_marked = active_photo
+        .is_some_and(|photo| photo.metadata.all_tags().any(|tag| tag.marked_for_removal));
 
     let filter_input = self.metadata_filter_input.clone();
 
@@ -183,15 +448,75 @@ impl ExifEditorWindow {
                         .border_b_1()
                         .border_color(cx.theme().border)
                         .child(
-                            if let Some(ref input) = filter_input {
-                                Input::new(input)
-                                    .w_full()
-                                    .small()
-                                    .prefix(IconName::Search)
-                                    .into_any_element()
-                            } else {
-                                div().into_any_element()
-                            },
+                            h_flex()
+                                .w_full()
+                                .gap_2()
+                                .child(div().flex_1().child(
+                                    if let Some(ref input) = filter_input {
+                                        Input::new(input)
+                                            .w_full()
+                                            .small()
+                                            .prefix(IconName::Search)
+                                            .into_any_element()
+                                    } else {
+                                        div().into_any_element()
+                                    },
+                                ))
+                                .child(
+                                    Button::new("tag-sort")
+                                        .ghost()
+                                        .small()
+                                        .icon(IconName::SortAscending)
+                                        .label(self.state.tag_sort.label())
+                                        .tooltip("Change the order of the rows")
+                                        .disabled(!has_photo)
+                                        .on_click(cx.listener(|this, _, _, cx| {
+                                            let sort = this.state.tag_sort.next();
+                                            this.state.set_tag_sort(sort);
+                                            this.refresh_tag_rows = true;
+                                            cx.notify();
+                                        })),
+                                )
+                                .child(
+                                    Button::new("camera-defaults")
+                                        .ghost()
+                                        .small()
+                                        .icon(IconName::Info)
+                                        .label("Defaults")
+                                        .tooltip("Dim tags that hold a likely camera default")
+                                        .selected(self.flag_camera_defaults)
+                                        .disabled(!has_photo)
+                                        .on_click(cx.listener(|this, _, _, cx| {
+                                            this.flag_camera_defaults = !this.flag_camera_defaults;
+                                            cx.notify();
+                                        })),
+                                )
+                                .child(
+                                    Button::new("modified-only")
+                                        .ghost()
+                                        .small()
+                                        .icon(IconName::Asterisk)
+                                        .label("Modified")
+                                        .selected(modified_only)
+                                        .disabled(!has_photo)
+                                        .on_click(cx.listener(|this, _, _, cx| {
+                                            let modified_only = !this.state.modified_only;
+                                            this.state.set_modified_only(modified_only);
+                                            cx.notify();
+                                        })),
+                                )
+                                .child(
+                                    Button::new("copy-metadata")
+                                        .ghost()
+                                        .small()
+                                        .icon(IconName::Copy)
+                                        .label("Copy")
+                                        .tooltip("Copy the listed tags as text")
+                                        .disabled(!has_photo)
+                                        .on_click(cx.listener(|this, _, _, cx| {
+                                            this.copy_metadata_report(cx);
+                                        })),
+                                ),
                         ),
                 )
                 .child(
@@ -211,10 +536,32 @@ impl ExifEditorWindow {
                                         .children(fields)
                                         .w_full(),
                                 )
+                                .children((!computed_fields.is_empty()).then(|| {
+                                    v_flex()
+                                        .w_full()
+                                        .gap_2()
+                                        .pt_2()
+                                        .border_t_1()
+                                        .border_color(cx.theme().border)
+                                        .child(
+                                            div()
+                                                .text_xs()
+                                                .font_weight(gpui::FontWeight::SEMIBOLD)
+                                                .text_color(cx.theme().muted_foreground)
+                                                .child("Computed"),
+                                        )
+                                        .child(
+                                            Form::vertical()
+                                                .label_width(px(170.0))
+                                                .children(computed_fields)
+                                                .w_full(),
+                                        )
+                                }))
                                 .child(
-                                    div()
+                                    h_flex()
                                         .w_full()
                                         .pt_2()
+                                        .gap_2()
                                         .child(
                                         Button::new("add-metadata")
                                                 .small()
@@ -224,6 +571,17 @@ impl ExifEditorWindow {
                                                 .on_click(cx.listener(|this, _, window, cx| {
                                                     this.open_add_tag_popup(window, cx)
                                                 })),
+                                        )
+                                        .child(
+                                            Button::new("strip-marked")
+                                                .small()
+                                                .danger()
+                                                .icon(IconName::Delete)
+                                                .label("Strip Marked")
+                                                .disabled(!has_marked)
+                                                .on_click(cx.listener(|this, _, _, cx| {
+                                                    this.strip_marked_tags(cx)
+                                                })),
                                         ),
                                 ),
                         ),
@@ -3,6 +3,9 @@ use super::*;
 impl Render for ExifEditorWindow {
     fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         self.ensure_tag_rows(window, cx);
+        self.ensure_oriented_preview(cx);
+        self.ensure_embedded_preview(cx);
+        self.ensure_thumbnails(cx);
 
         div()
             .id(SharedString::from("exif-editor-root"))
@@ -19,6 +22,10 @@ impl Render for ExifEditorWindow {
             .child(self.render_metadata_editor(cx))
             .children(self.render_map_popup(cx))
             .children(self.render_add_tag_popup(cx))
+            .children(self.render_find_tag_popup(cx))
             .children(self.render_datetime_popup(cx))
+            .children(self.render_settings_popup(cx))
+            .children(self.render_leave_popup(cx))
+            .children(Root::render_dialog_layer(window, cx))
     }
 }
@@ -1,9 +1,13 @@
 use super::*;
 
 impl ExifEditorWindow {
-    pub(super) fn new(focus_handle: FocusHandle) -> Self {
+    pub(super) fn new(
+        focus_handle: FocusHandle,
+        window_settings: WindowSettings,
+        settings: AppSettings,
+    ) -> Self {
         let mut state = AppState::default();
-        state.active_preset = Some(2);
+        state.apply_settings(settings);
 
         Self {
             state,
@@ -13,14 +17,54 @@ impl ExifEditorWindow {
             tag_rows_photo_index: None,
             refresh_tag_rows: true,
             map_popup: None,
+            map_search_input: None,
+            map_search_subscription: None,
+            map_search_task: None,
+            location_names: HashMap::new(),
+            reverse_geocode_task: None,
             add_tag_popup_open: false,
             add_tag_search: String::new(),
             add_tag_search_input: None,
             add_tag_search_subscription: None,
+            add_tag_category: None,
+            add_tag_category_select: None,
+            add_tag_category_subscription: None,
             datetime_popup: None,
+            find_tag_popup_open: false,
+            find_tag_results: Vec::new(),
+            find_tag_input: None,
+            find_tag_subscription: None,
+            highlighted_tag: None,
+            flag_camera_defaults: false,
+            leave_popup_target: None,
+            leave_after_save: None,
+            confirm_leaving_unsaved: true,
+            skipped_imports: Vec::new(),
+            skipped_imports_expanded: false,
             metadata_filter: String::new(),
             metadata_filter_input: None,
             metadata_filter_subscription: None,
+            gps_dms_mode: false,
+            altitude_unit: AltitudeUnit::Meters,
+            altitude_precision: 2,
+            bulk_cancel: None,
+            bulk_task: None,
+            bulk_resumable: ResumeLog::default_path().exists(),
+            save_task: None,
+            analyze_task: None,
+            allowlist_input: None,
+            replace_inputs: None,
+            replace_ignore_case: false,
+            preset_builder: None,
+            oriented_preview: None,
+            oriented_preview_task: None,
+            embedded_preview: None,
+            embedded_preview_task: None,
+            thumbnail_images: HashMap::new(),
+            thumbnail_task: None,
+            window_settings,
+            window_settings_task: None,
+            window_bounds_subscription: None,
         }
     }
 
@@ -31,6 +75,48 @@ impl ExifEditorWindow {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
+        let keystroke = &event.keystroke;
+        // Escape is checked before the focused-input bail-out below, since the
+        // add-tag popup keeps its search input focused.
+        if keystroke.key == "escape" && !keystroke.modifiers.modified() {
+            if self.close_topmost_popup(cx) {
+                window.focus(&self.focus_handle);
+                cx.stop_propagation();
+            } else {
+                cx.propagate();
+            }
+            return;
+        }
+
+        if keystroke.modifiers.secondary() {
+            let shift = keystroke.modifiers.shift;
+            let input_focused = window.has_focused_input(cx);
+            match (keystroke.key.to_ascii_lowercase().as_str(), shift) {
+                ("s", false) => self.save_active(cx),
+                ("s", true) => self.save_all(cx),
+                ("t", false) => self.open_add_tag_popup(window, cx),
+                // Focused text inputs keep their own undo history.
+                ("z", false) if !input_focused => self.undo(cx),
+                ("z", true) if !input_focused => self.redo(cx),
+                // Plain Ctrl/Cmd+Backspace is left to the input's own
+                // delete-word / delete-line editing.
+                ("backspace", true) if input_focused => {
+                    let Some(tag_key) = self.focused_row_tag_key(window, cx) else {
+                        cx.propagate();
+                        return;
+                    };
+                    window.focus(&self.focus_handle);
+                    self.clear_row(&tag_key, cx);
+                }
+                _ => {
+                    cx.propagate();
+                    return;
+                }
+            }
+            cx.stop_propagation();
+            return;
+        }
+
         if window.has_focused_input(cx) {
             cx.propagate();
             return;
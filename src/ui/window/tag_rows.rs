@@ -19,6 +19,18 @@ impl ExifEditorWindow {
             self.metadata_filter_subscription = Some(sub);
         }
 
+        if self.map_search_input.is_none() {
+            let input =
+                cx.new(|cx| InputState::new(window, cx).placeholder("Search for a place..."));
+            let sub = cx.subscribe_in(&input, window, |this, _, event: &InputEvent, window, cx| {
+                if matches!(event, InputEvent::PressEnter { .. }) {
+                    this.search_map_location(window, cx);
+                }
+            });
+            self.map_search_input = Some(input);
+            self.map_search_subscription = Some(sub);
+        }
+
         let active = self.state.active_photo;
 
         if !self.refresh_tag_rows && self.tag_rows_photo_index == active {
@@ -82,6 +94,34 @@ impl ExifEditorWindow {
                     _subscription: subscription,
                 }
             }
+            TagValue::Integer(value) if enum_labels::label_for(&tag_key, value).is_some() => {
+                let labels = enum_labels::labels_for(&tag_key).unwrap_or_default();
+                let selected = labels.iter().position(|(known, _)| *known == value);
+                let items = labels.iter().map(|(_, label)| *label).collect::<Vec<_>>();
+                let select =
+                    cx.new(|cx| SelectState::new(items, selected.map(IndexPath::new), window, cx));
+                let sub_row_id = row_id.clone();
+                let sub_tag_key = tag_key.clone();
+                let subscription = cx.subscribe(
+                    &select,
+                    move |this, _, event: &SelectEvent<Vec<&'static str>>, cx| {
+                        if let SelectEvent::Confirm(Some(label)) = event {
+                            this.commit_enum_label(
+                                photo_index,
+                                &sub_row_id,
+                                &sub_tag_key,
+                                label,
+                                cx,
+                            );
+                        }
+                    },
+                );
+
+                TagEditorKind::Enum {
+                    select,
+                    _subscription: subscription,
+                }
+            }
             TagValue::Integer(value) => {
                 let input =
                     cx.new(|cx| InputState::new(window, cx).default_value(value.to_string()));
@@ -224,14 +264,16 @@ impl ExifEditorWindow {
                 }
             }
             TagValue::Gps(latitude, longitude, altitude) => {
-                let latitude_input = cx
-                    .new(|cx| InputState::new(window, cx).default_value(format!("{latitude:.6}")));
-                let longitude_input = cx
-                    .new(|cx| InputState::new(window, cx).default_value(format!("{longitude:.6}")));
+                let latitude_text = self.format_gps_coordinate(latitude, true);
+                let longitude_text = self.format_gps_coordinate(longitude, false);
+                let latitude_input =
+                    cx.new(|cx| InputState::new(window, cx).default_value(latitude_text));
+                let longitude_input =
+                    cx.new(|cx| InputState::new(window, cx).default_value(longitude_text));
                 let altitude_input = cx.new(|cx| {
                     InputState::new(window, cx).default_value(
                         altitude
-                            .map(|value| format!("{value:.2}"))
+                            .map(|value| self.format_gps_altitude(value))
                             .unwrap_or_default(),
                     )
                 });
@@ -287,7 +329,30 @@ impl ExifEditorWindow {
                     _alt_subscription: alt_subscription,
                 }
             }
-            TagValue::Binary(bytes) => TagEditorKind::Binary { bytes: bytes.len() },
+            TagValue::TextList(items) => {
+                let input = cx.new(|cx| InputState::new(window, cx).placeholder("Add..."));
+                let sub_row_id = row_id.clone();
+                let sub_tag_key = tag_key.clone();
+                let subscription = cx.subscribe_in(
+                    &input,
+                    window,
+                    move |this, _, event: &InputEvent, window, cx| {
+                        if matches!(event, InputEvent::PressEnter { .. }) {
+                            this.add_list_items(photo_index, &sub_row_id, &sub_tag_key, window, cx);
+                        }
+                    },
+                );
+
+                TagEditorKind::List {
+                    items,
+                    input,
+                    _subscription: subscription,
+                }
+            }
+            TagValue::Binary(bytes) => TagEditorKind::Binary {
+                data: Arc::from(bytes),
+                hex_offset: None,
+            },
         };
 
         TagEditorRow {
@@ -311,7 +376,7 @@ impl ExifEditorWindow {
         let raw = input.read(cx).value().to_string();
         let parsed = match scalar_kind {
             ScalarKind::Text => Ok(TagValue::Text(raw)),
-            ScalarKind::DateTime => Ok(TagValue::DateTime(raw)),
+            ScalarKind::DateTime => validate_exif_datetime(&raw).map(TagValue::DateTime),
             ScalarKind::Unknown => Ok(TagValue::Unknown(raw)),
             ScalarKind::Integer => raw
                 .trim()
@@ -340,6 +405,101 @@ impl ExifEditorWindow {
         cx.notify();
     }
 
+    pub(super) fn commit_enum_label(
+        &mut self,
+        photo_index: usize,
+        row_id: &str,
+        tag_key: &str,
+        label: &str,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(value) = enum_labels::value_for(tag_key, label) else {
+            self.set_row_error(row_id, Some(format!("Unknown value: {label}")));
+            cx.notify();
+            return;
+        };
+
+        self.set_row_error(row_id, None);
+        if let Err(err) = self
+            .state
+            .edit_tag(photo_index, tag_key, TagValue::Integer(value))
+        {
+            self.set_row_error(row_id, Some(format!("Failed to edit tag: {err}")));
+        }
+
+        cx.notify();
+    }
+
+    /// Add the comma-separated entries typed into a list row's input,
+    /// skipping ones already listed, and clear the input.
+    pub(super) fn add_list_items(
+        &mut self,
+        photo_index: usize,
+        row_id: &str,
+        tag_key: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(TagEditorKind::List { items, input, .. }) = self.row_kind_mut(row_id) else {
+            return;
+        };
+        let raw = input.read(cx).value().to_string();
+        for item in raw
+            .split(',')
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+        {
+            if !items.iter().any(|existing| existing == item) {
+                items.push(item.to_string());
+            }
+        }
+        let items = items.clone();
+        input.update(cx, |state, cx| state.set_value("", window, cx));
+
+        self.commit_list(photo_index, row_id, tag_key, items, cx);
+    }
+
+    pub(super) fn remove_list_item(
+        &mut self,
+        row_id: &str,
+        tag_key: &str,
+        index: usize,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(photo_index) = self.tag_rows_photo_index else {
+            return;
+        };
+        let Some(TagEditorKind::List { items, .. }) = self.row_kind_mut(row_id) else {
+            return;
+        };
+        if index >= items.len() {
+            return;
+        }
+        items.remove(index);
+        let items = items.clone();
+
+        self.commit_list(photo_index, row_id, tag_key, items, cx);
+    }
+
+    fn commit_list(
+        &mut self,
+        photo_index: usize,
+        row_id: &str,
+        tag_key: &str,
+        items: Vec<String>,
+        cx: &mut Context<Self>,
+    ) {
+        self.set_row_error(row_id, None);
+        if let Err(err) = self
+            .state
+            .edit_tag(photo_index, tag_key, TagValue::TextList(items))
+        {
+            self.set_row_error(row_id, Some(format!("Failed to edit tag: {err}")));
+        }
+
+        cx.notify();
+    }
+
     pub(super) fn commit_rational_from_inputs(
         &mut self,
         photo_index: usize,
@@ -398,27 +558,55 @@ impl ExifEditorWindow {
             return;
         };
 
-        let latitude = match latitude.trim().parse::<f64>() {
-            Ok(value) if (-90.0..=90.0).contains(&value) => value,
+        // The inputs show rounded values. A part whose text still reads as the
+        // stored value keeps it exactly, since parsing the rounded text back
+        // would move it and leave the photo marked as edited.
+        let stored = self
+            .state
+            .photos
+            .get(photo_index)
+            .and_then(|photo| photo.metadata.find_tag(tag_key))
+            .and_then(|tag| match tag.value {
+                TagValue::Gps(latitude, longitude, altitude) => {
+                    Some((latitude, longitude, altitude))
+                }
+                _ => None,
+            });
+        let stored_latitude = stored
+            .map(|(latitude, _, _)| latitude)
+            .filter(|value| self.format_gps_coordinate(*value, true) == latitude.trim());
+        let stored_longitude = stored
+            .map(|(_, longitude, _)| longitude)
+            .filter(|value| self.format_gps_coordinate(*value, false) == longitude.trim());
+        let stored_altitude = stored
+            .and_then(|(_, _, altitude)| altitude)
+            .filter(|value| self.format_gps_altitude(*value) == altitude.trim());
+
+        let latitude = stored_latitude.or_else(|| self.parse_gps_coordinate(&latitude, true));
+        let latitude = match latitude {
+            Some(value) if (-90.0..=90.0).contains(&value) => value,
             _ => {
-                self.set_row_error(
-                    row_id,
-                    Some(String::from("Latitude must be a number between -90 and 90")),
-                );
+                let message = if self.gps_dms_mode {
+                    "Latitude must look like 40°26'46\"N and be at most 90°"
+                } else {
+                    "Latitude must be a number between -90 and 90"
+                };
+                self.set_row_error(row_id, Some(String::from(message)));
                 cx.notify();
                 return;
             }
         };
 
-        let longitude = match longitude.trim().parse::<f64>() {
-            Ok(value) if (-180.0..=180.0).contains(&value) => value,
+        let longitude = stored_longitude.or_else(|| self.parse_gps_coordinate(&longitude, false));
+        let longitude = match longitude {
+            Some(value) if (-180.0..=180.0).contains(&value) => value,
             _ => {
-                self.set_row_error(
-                    row_id,
-                    Some(String::from(
-                        "Longitude must be a number between -180 and 180",
-                    )),
-                );
+                let message = if self.gps_dms_mode {
+                    "Longitude must look like 79°58'56\"W and be at most 180°"
+                } else {
+                    "Longitude must be a number between -180 and 180"
+                };
+                self.set_row_error(row_id, Some(String::from(message)));
                 cx.notify();
                 return;
             }
@@ -426,10 +614,12 @@ impl ExifEditorWindow {
 
         let altitude = if altitude.trim().is_empty() {
             None
+        } else if stored_altitude.is_some() {
+            stored_altitude
         } else {
-            match altitude.trim().parse::<f64>() {
-                Ok(value) => Some(value),
-                Err(_) => {
+            match parse_altitude(&altitude, self.altitude_unit) {
+                Some(value) => Some(value),
+                None => {
                     self.set_row_error(
                         row_id,
                         Some(String::from("Altitude must be empty or a numeric value")),
@@ -441,12 +631,15 @@ impl ExifEditorWindow {
         };
 
         self.set_row_error(row_id, None);
-        if let Err(err) = self.state.edit_tag(
+        match self.state.edit_tag(
             photo_index,
             tag_key,
             TagValue::Gps(latitude, longitude, altitude),
         ) {
-            self.set_row_error(row_id, Some(format!("Failed to edit GPS tag: {err}")));
+            Ok(()) => self.warn_if_gps_not_embedded(photo_index),
+            Err(err) => {
+                self.set_row_error(row_id, Some(format!("Failed to edit GPS tag: {err}")));
+            }
         }
 
         if let Some(popup) = self.map_popup.as_mut() {
@@ -454,12 +647,25 @@ impl ExifEditorWindow {
                 popup.latitude = latitude;
                 popup.longitude = longitude;
                 popup.altitude = altitude;
+                self.request_reverse_geocode(cx);
             }
         }
 
         cx.notify();
     }
 
+    fn warn_if_gps_not_embedded(&mut self, photo_index: usize) {
+        let Some(photo) = self.state.photos.get(photo_index) else {
+            return;
+        };
+        if !formats::format_supports_gps(photo.format) {
+            self.status = format!(
+                "{} can't reliably embed GPS; the coordinates may only be kept in the sidecar",
+                photo.format.as_str()
+            );
+        }
+    }
+
     pub(super) fn read_rational_inputs(&self, row_id: &str, cx: &Context<Self>) -> Option<(String, String)> {
         self.tag_rows.iter().find_map(|row| {
             if row.row_id != row_id {
@@ -480,6 +686,51 @@ impl ExifEditorWindow {
         })
     }
 
+    /// Switch GPS rows between decimal and DMS entry. Rows are rebuilt from the
+    /// stored coordinates, so toggling never re-parses (and never rounds) them.
+    pub(super) fn toggle_gps_dms_mode(&mut self, cx: &mut Context<Self>) {
+        self.gps_dms_mode = !self.gps_dms_mode;
+        self.refresh_tag_rows = true;
+        cx.notify();
+    }
+
+    /// Switch GPS altitudes between meters and feet. Like the DMS toggle, rows
+    /// are rebuilt from the stored meters.
+    pub(super) fn toggle_altitude_unit(&mut self, cx: &mut Context<Self>) {
+        self.altitude_unit = match self.altitude_unit {
+            AltitudeUnit::Meters => AltitudeUnit::Feet,
+            AltitudeUnit::Feet => AltitudeUnit::Meters,
+        };
+        self.refresh_tag_rows = true;
+        cx.notify();
+    }
+
+    pub(super) fn cycle_altitude_precision(&mut self, cx: &mut Context<Self>) {
+        self.altitude_precision = (self.altitude_precision + 1) % (MAX_ALTITUDE_PRECISION + 1);
+        self.refresh_tag_rows = true;
+        cx.notify();
+    }
+
+    pub(super) fn format_gps_altitude(&self, meters: f64) -> String {
+        format_altitude(meters, self.altitude_unit, self.altitude_precision)
+    }
+
+    pub(super) fn format_gps_coordinate(&self, value: f64, is_latitude: bool) -> String {
+        if self.gps_dms_mode {
+            format_dms_coordinate(value, is_latitude)
+        } else {
+            format!("{value:.6}")
+        }
+    }
+
+    pub(super) fn parse_gps_coordinate(&self, raw: &str, is_latitude: bool) -> Option<f64> {
+        if self.gps_dms_mode {
+            parse_dms_coordinate(raw, is_latitude)
+        } else {
+            raw.trim().parse::<f64>().ok()
+        }
+    }
+
     pub(super) fn read_gps_inputs(
         &self,
         row_id: &str,
@@ -512,6 +763,60 @@ impl ExifEditorWindow {
         }
     }
 
+    pub(super) fn toggle_hex_view(&mut self, row_id: &str, cx: &mut Context<Self>) {
+        if let Some(TagEditorKind::Binary { hex_offset, .. }) = self.row_kind_mut(row_id) {
+            *hex_offset = match hex_offset {
+                Some(_) => None,
+                None => Some(0),
+            };
+            cx.notify();
+        }
+    }
+
+    pub(super) fn page_hex_view(&mut self, row_id: &str, delta: isize, cx: &mut Context<Self>) {
+        if let Some(TagEditorKind::Binary {
+            data,
+            hex_offset: Some(offset),
+        }) = self.row_kind_mut(row_id)
+        {
+            *offset = hex::step_page(*offset, delta, data.len());
+            cx.notify();
+        }
+    }
+
+    fn row_kind_mut(&mut self, row_id: &str) -> Option<&mut TagEditorKind> {
+        self.tag_rows
+            .iter_mut()
+            .find(|row| row.row_id == row_id)
+            .map(|row| &mut row.kind)
+    }
+
+    /// Tag key of the row whose text input holds keyboard focus, if any.
+    pub(super) fn focused_row_tag_key(&self, window: &Window, cx: &App) -> Option<String> {
+        let focused =
+            |input: &gpui::Entity<InputState>| input.read(cx).focus_handle(cx).is_focused(window);
+        self.tag_rows
+            .iter()
+            .find(|row| match &row.kind {
+                TagEditorKind::Scalar { input, .. } | TagEditorKind::List { input, .. } => {
+                    focused(input)
+                }
+                TagEditorKind::Rational {
+                    numerator,
+                    denominator,
+                    ..
+                } => focused(numerator) || focused(denominator),
+                TagEditorKind::Gps {
+                    latitude,
+                    longitude,
+                    altitude,
+                    ..
+                } => focused(latitude) || focused(longitude) || focused(altitude),
+                TagEditorKind::Enum { .. } | TagEditorKind::Binary { .. } => false,
+            })
+            .map(|row| row.tag_key.clone())
+    }
+
     pub(super) fn clear_row(&mut self, tag_key: &str, cx: &mut Context<Self>) {
         let Some(photo_index) = self.state.active_photo else {
             self.status = String::from("No active photo selected");
@@ -535,7 +840,7 @@ impl ExifEditorWindow {
         cx.notify();
     }
 
-    pub(super) fn available_addable_tags(&self) -> Vec<&'static AddableTagDef> {
+    pub(super) fn available_addable_tags(&self) -> Vec<&'static TagDef> {
         let Some(photo_index) = self.state.active_photo else {
             return Vec::new();
         };
@@ -549,17 +854,9 @@ impl ExifEditorWindow {
             .map(|t| t.key.to_ascii_lowercase())
             .collect();
 
-        let query = self.add_tag_search.trim().to_ascii_lowercase();
-
-        ADDABLE_TAGS
-            .iter()
+        catalog::search(&self.add_tag_search, self.add_tag_category)
+            .into_iter()
             .filter(|def| !existing_keys.contains(&def.key.to_ascii_lowercase()))
-            .filter(|def| {
-                query.is_empty()
-                    || def.display_name.to_ascii_lowercase().contains(&query)
-                    || def.key.to_ascii_lowercase().contains(&query)
-                    || def.category.as_str().to_ascii_lowercase().contains(&query)
-            })
             .collect()
     }
 }
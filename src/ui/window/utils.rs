@@ -33,76 +33,40 @@ pub(super) fn image_fallback(message: &str) -> AnyElement {
         .into_any_element()
 }
 
-fn looks_like_image(path: &Path) -> bool {
-    let Some(extension) = path.extension() else {
-        return false;
-    };
-
-    let extension = extension.to_string_lossy().to_ascii_lowercase();
-    IMAGE_EXTENSIONS.iter().any(|known| *known == extension)
-}
-
-pub(super) fn expand_paths(input_paths: Vec<PathBuf>) -> Vec<PathBuf> {
-    let mut files = Vec::new();
-
-    for path in input_paths {
-        collect_path(&path, &mut files);
+/// Convert RGBA thumbnail pixels into the BGRA frame gpui uploads as a texture.
+pub(super) fn render_image_from_thumbnail(thumbnail: &ThumbnailData) -> Option<Arc<RenderImage>> {
+    let mut pixels = thumbnail.pixels.clone();
+    for pixel in pixels.chunks_exact_mut(4) {
+        pixel.swap(0, 2);
     }
 
-    files
+    let buffer = image::RgbaImage::from_raw(thumbnail.width, thumbnail.height, pixels)?;
+    Some(Arc::new(RenderImage::new(vec![image::Frame::new(buffer)])))
 }
 
-fn collect_path(path: &Path, files: &mut Vec<PathBuf>) {
-    if path.is_file() {
-        if looks_like_image(path) {
-            files.push(path.to_path_buf());
-        }
-        return;
-    }
-
-    if !path.is_dir() {
-        return;
-    }
-
-    let Ok(entries) = fs::read_dir(path) else {
-        return;
-    };
-
-    for entry in entries.flatten() {
-        collect_path(&entry.path(), files);
+/// Keyboard shortcuts handled by `on_root_key_down`, labelled for the current platform.
+pub(super) fn shortcut_hint() -> &'static str {
+    if cfg!(target_os = "macos") {
+        "⌘S Save · ⇧⌘S Save All · ⌘T Add Tag · ⌘Z Undo · ⇧⌘Z Redo · ⇧⌘⌫ Clear Field"
+    } else {
+        "Ctrl+S Save · Ctrl+Shift+S Save All · Ctrl+T Add Tag · Ctrl+Z Undo · Ctrl+Shift+Z Redo · Ctrl+Shift+Backspace Clear Field"
     }
 }
 
-pub(super) fn unique_export_path(export_dir: &Path, filename: &str, suffix: &str) -> PathBuf {
-    let input_path = Path::new(filename);
-    let stem = input_path
-        .file_stem()
-        .map(|value| value.to_string_lossy().to_string())
-        .unwrap_or_else(|| String::from("photo"));
-    let extension = input_path
-        .extension()
-        .map(|value| value.to_string_lossy().to_string());
-
-    for attempt in 0..1000_usize {
-        let candidate_name = if attempt == 0 {
-            match &extension {
-                Some(ext) => format!("{stem}{suffix}.{ext}"),
-                None => format!("{stem}{suffix}"),
-            }
-        } else {
-            match &extension {
-                Some(ext) => format!("{stem}{suffix}_{attempt}.{ext}"),
-                None => format!("{stem}{suffix}_{attempt}"),
-            }
-        };
-
-        let candidate = export_dir.join(candidate_name);
-        if !candidate.exists() {
-            return candidate;
-        }
+pub(super) fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
     }
 
-    export_dir.join(format!("{stem}{suffix}_overflow"))
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{value:.1} {}", UNITS[unit])
+    }
 }
 
 pub(super) fn open_url(url: &str) -> std::io::Result<()> {
@@ -0,0 +1,49 @@
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::platform;
+use crate::settings::{load_json, save_json};
+
+pub const WINDOW_SETTINGS_FILE_NAME: &str = "window.json";
+
+/// Window position and size in logical pixels.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SavedBounds {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SavedTheme {
+    Light,
+    Dark,
+}
+
+/// What the window restores on the next launch. Fields missing from the
+/// file keep their defaults, so older files still load.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WindowSettings {
+    /// Restore bounds; for a maximized window, the size it un-maximizes to.
+    pub bounds: Option<SavedBounds>,
+    pub maximized: bool,
+    pub theme: Option<SavedTheme>,
+}
+
+impl WindowSettings {
+    pub fn default_path() -> PathBuf {
+        platform::config_dir().join(WINDOW_SETTINGS_FILE_NAME)
+    }
+
+    pub fn load(path: &Path) -> Self {
+        load_json(path, "load_window_settings")
+    }
+
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        save_json(self, path)
+    }
+}
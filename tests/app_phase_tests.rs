@@ -1,21 +1,21 @@
 use std::fs;
-use std::path::{Path, PathBuf};
+use std::path::Path;
 use std::sync::atomic::AtomicBool;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::sync::mpsc;
 
-use exif_editor::app::{AppState, TableColumn, TableSort};
-use exif_editor::core::metadata::MetadataEngine;
-use exif_editor::models::{OutputMode, TagValue};
+use exif_editor::app::{AppError, AppState, PrivacyFinding, TableColumn, TableSort, TagSort};
+use exif_editor::core::metadata::{
+    MetadataEngine, MetadataError, SidecarPolicy, GPS_COORDINATES_KEY,
+};
+use exif_editor::models::{
+    MetadataWarning, OutputMode, PhotoId, SkipReason, TagCategory, TagValue, COLOR_SPACE_KEY,
+    COLOR_SPACE_SRGB,
+};
+use exif_editor::settings::AppSettings;
 
-fn unique_path(name: &str, ext: &str) -> PathBuf {
-    let stamp = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .expect("system time should be after unix epoch")
-        .as_nanos();
-    let mut path = std::env::temp_dir();
-    path.push(format!("exif_editor_{name}_{stamp}.{ext}"));
-    path
-}
+mod common;
+
+use common::unique_path;
 
 fn write_file(path: &Path, bytes: &[u8]) {
     fs::write(path, bytes).expect("should create file");
@@ -182,3 +182,1982 @@ fn phase4_bulk_selected_with_summary_and_cancel() {
         cleanup_file(&output);
     }
 }
+
+#[test]
+fn background_bulk_job_streams_progress_before_finishing() {
+    let files = (0..4)
+        .map(|index| unique_path(&format!("stream_{index}"), "jpg"))
+        .collect::<Vec<_>>();
+    for file in &files {
+        write_file(file, b"stream");
+    }
+
+    let mut state = AppState::default();
+    state.import_paths(files.clone());
+    state.select_range(0, files.len() - 1);
+
+    let job = state
+        .begin_bulk_selected(1, OutputMode::Overwrite)
+        .expect("job should start");
+    assert!(state.is_processing);
+    assert_eq!(job.total(), files.len());
+
+    let (progress_tx, progress_rx) = mpsc::channel();
+    let worker = std::thread::spawn(move || {
+        let results = job.run(progress_tx, None);
+        (job, results)
+    });
+
+    let (mut seen, mut highest) = (0, 0);
+    for event in progress_rx.iter() {
+        highest = highest.max(event.current);
+        state.record_progress(event);
+        seen += 1;
+        assert!(state.is_processing);
+        assert_eq!(
+            state.progress.as_ref().map(|progress| progress.current),
+            Some(highest)
+        );
+    }
+    assert_eq!(seen, files.len());
+    assert_eq!(highest, files.len());
+    assert_eq!(
+        state.progress.as_ref().map(|progress| progress.current),
+        Some(files.len())
+    );
+
+    let (job, results) = worker.join().expect("worker should not panic");
+    let summary = state.finish_bulk(job, results);
+    assert!(!state.is_processing);
+    assert_eq!(summary.succeeded, files.len());
+    assert_eq!(state.last_summary, Some(summary));
+
+    for file in &files {
+        cleanup_file(file);
+    }
+}
+
+#[test]
+fn overwrite_run_reloads_its_photos_after_the_list_changes() {
+    let kept = common::jpeg_fixture("reload_kept");
+    let processed = common::jpeg_fixture("reload_processed");
+    let mut metadata = MetadataEngine::read(&processed).expect("read should succeed");
+    MetadataEngine::set_tag_in_metadata(
+        &mut metadata,
+        "Exif.Image.Artist",
+        TagValue::Text(String::from("Jane")),
+    );
+    MetadataEngine::write_with_sidecars(&processed, &metadata, false)
+        .expect("write should succeed");
+
+    let mut state = AppState::default();
+    state.import_paths([kept.path(), processed.path()]);
+    state.select_photo(1, false);
+    let job = state
+        .begin_bulk_selected(1, OutputMode::Overwrite)
+        .expect("job should start");
+
+    // The photo ahead of it leaves the list while the job runs.
+    state.photos.remove(0);
+    let (progress_tx, _progress_rx) = mpsc::channel();
+    let results = job.run(progress_tx, None);
+    let summary = state.finish_bulk(job, results);
+
+    assert_eq!(summary.succeeded, 1);
+    assert_eq!(state.photos[0].path, processed.path());
+    assert!(state.photos[0]
+        .metadata
+        .find_tag("Exif.Image.Artist")
+        .is_none());
+}
+
+#[test]
+fn editing_one_gps_field_without_coordinates_is_rejected() {
+    let file = common::jpeg_fixture("partial_gps");
+
+    let mut state = AppState::default();
+    state.import_paths([file.path()]);
+    let err = state
+        .edit_tag(0, "Exif.GPSInfo.GPSLongitude", TagValue::Float(2.3522))
+        .expect_err("a lone longitude should be rejected");
+    assert!(matches!(
+        err,
+        AppError::Metadata(MetadataError::PartialGps(ref key)) if key == "Exif.GPSInfo.GPSLongitude"
+    ));
+    assert!(state.photos[0]
+        .metadata
+        .find_tag(GPS_COORDINATES_KEY)
+        .is_none());
+    assert!(!state.photos[0].dirty);
+    assert!(
+        !state.undo_last_change(),
+        "a rejected edit leaves no undo step"
+    );
+}
+
+#[test]
+fn modified_only_filter_returns_edited_tags() {
+    let file = unique_path("modified_only", "jpg");
+    write_file(&file, b"modified");
+
+    let mut state = AppState::default();
+    state.import_paths([file.clone()]);
+    state
+        .edit_tag(0, "Exif.Image.Artist", TagValue::Text(String::from("Jane")))
+        .expect("edit should succeed");
+    state
+        .edit_tag(0, "Exif.Image.Make", TagValue::Text(String::from("Canon")))
+        .expect("edit should succeed");
+    state.save_photo_changes(0).expect("save should succeed");
+
+    state
+        .edit_tag(0, "Exif.Image.Make", TagValue::Text(String::from("Nikon")))
+        .expect("edit should succeed");
+    state
+        .edit_tag(
+            0,
+            "Exif.Image.Software",
+            TagValue::Text(String::from("Editor")),
+        )
+        .expect("edit should succeed");
+
+    assert!(state
+        .inspector_tags(0)
+        .iter()
+        .any(|tag| tag.key == "Exif.Image.Artist"));
+
+    state.set_modified_only(true);
+    let keys = state
+        .inspector_tags(0)
+        .into_iter()
+        .map(|tag| tag.key)
+        .collect::<Vec<_>>();
+    assert_eq!(keys, vec!["Exif.Image.Make", "Exif.Image.Software"]);
+
+    cleanup_file(&file);
+}
+
+#[test]
+fn table_sorts_by_dimensions() {
+    let small = unique_path("dims_small", "jpg");
+    let large = unique_path("dims_large", "jpg");
+    write_file(&small, b"small");
+    write_file(&large, b"large");
+
+    let mut state = AppState::default();
+    state.import_paths([large.clone(), small.clone()]);
+    for (index, (width, height)) in [(6000, 4000), (640, 480)].into_iter().enumerate() {
+        state
+            .edit_tag(index, "Exif.Image.ImageWidth", TagValue::Integer(width))
+            .expect("edit should succeed");
+        state
+            .edit_tag(index, "Exif.Image.ImageHeight", TagValue::Integer(height))
+            .expect("edit should succeed");
+    }
+
+    state.set_table_sort(TableSort {
+        column: TableColumn::Dimensions,
+        descending: false,
+    });
+    assert_eq!(state.sorted_visible_indices(), vec![1, 0]);
+
+    cleanup_file(&small);
+    cleanup_file(&large);
+}
+
+#[test]
+fn table_sorts_by_format_label() {
+    let tiff = unique_path("format_tiff", "tiff");
+    let jpeg = unique_path("format_jpeg", "jpg");
+    let png = unique_path("format_png", "png");
+    write_file(&tiff, b"tiff");
+    write_file(&jpeg, b"jpeg");
+    write_file(&png, b"png");
+
+    let mut state = AppState::default();
+    state.import_paths([tiff.clone(), jpeg.clone(), png.clone()]);
+    let labels: Vec<&str> = state
+        .photos
+        .iter()
+        .map(|photo| photo.format.as_str())
+        .collect();
+    assert_eq!(labels, vec!["TIFF", "JPEG", "PNG"]);
+    assert_eq!(TableColumn::Format.label(), "Format");
+
+    state.set_table_sort(TableSort {
+        column: TableColumn::Format,
+        descending: false,
+    });
+    assert_eq!(state.sorted_visible_indices(), vec![1, 2, 0]);
+
+    cleanup_file(&tiff);
+    cleanup_file(&jpeg);
+    cleanup_file(&png);
+}
+
+#[test]
+fn typing_back_the_saved_value_leaves_the_photo_clean() {
+    let file = unique_path("typed_back", "png");
+    write_file(&file, b"typed-back-input");
+
+    let mut state = AppState::default();
+    state.import_paths([file.clone()]);
+    state
+        .edit_tag(0, "Exif.Image.Make", TagValue::Text(String::from("Nikon")))
+        .expect("edit should succeed");
+    state.save_photo_changes(0).expect("save should succeed");
+    let saved = state.photos[0].metadata.clone();
+
+    state
+        .edit_tag(0, "Exif.Image.Make", TagValue::Text(String::from("Canon")))
+        .expect("edit should succeed");
+    assert!(state.photos[0].dirty);
+    state
+        .edit_tag(0, "Exif.Image.Make", TagValue::Text(String::from("Nikon")))
+        .expect("edit should succeed");
+    assert!(!state.photos[0].dirty);
+    assert_eq!(state.photos[0].metadata, saved);
+
+    cleanup_file(&file);
+}
+
+#[test]
+fn revert_all_dirty_restores_every_edited_photo() {
+    let file_a = unique_path("revert_all_a", "jpg");
+    let file_b = unique_path("revert_all_b", "jpg");
+    let file_c = unique_path("revert_all_c", "jpg");
+    write_file(&file_a, b"a");
+    write_file(&file_b, b"b");
+    write_file(&file_c, b"c");
+
+    let mut state = AppState::default();
+    state.import_paths([file_a.clone(), file_b.clone(), file_c.clone()]);
+    let original_a = state.photos[0].metadata.clone();
+    let original_b = state.photos[1].metadata.clone();
+
+    state
+        .edit_tag(0, "Exif.Image.Artist", TagValue::Text(String::from("Jane")))
+        .expect("edit should succeed");
+    state
+        .edit_tag(1, "Exif.Image.Make", TagValue::Text(String::from("Canon")))
+        .expect("edit should succeed");
+    assert!(state.photos[0].dirty && state.photos[1].dirty);
+    assert!(!state.photos[2].dirty);
+
+    assert_eq!(state.revert_all_dirty(), 2);
+    assert!(state.photos.iter().all(|photo| !photo.dirty));
+    assert_eq!(state.photos[0].metadata, original_a);
+    assert_eq!(state.photos[1].metadata, original_b);
+    assert_eq!(state.revert_all_dirty(), 0);
+
+    cleanup_file(&file_a);
+    cleanup_file(&file_b);
+    cleanup_file(&file_c);
+}
+
+#[test]
+fn revert_photo_restores_the_last_saved_metadata_exactly() {
+    let file = unique_path("revert_one", "png");
+    let other = unique_path("revert_other", "png");
+    write_file(&file, b"revert");
+    write_file(&other, b"other");
+
+    let mut state = AppState::default();
+    state.import_paths([file.clone(), other.clone()]);
+    state
+        .edit_tag(0, "Exif.Image.Artist", TagValue::Text(String::from("Jane")))
+        .expect("edit should succeed");
+    state.save_photo_changes(0).expect("save should succeed");
+    let saved = state.photos[0].persisted_metadata.clone();
+    assert_eq!(state.photos[0].metadata, saved);
+
+    state
+        .edit_tag(0, "Exif.Image.Artist", TagValue::Text(String::from("Joe")))
+        .expect("edit should succeed");
+    state
+        .edit_tag(0, "Exif.Image.Make", TagValue::Text(String::from("Canon")))
+        .expect("edit should succeed");
+    state
+        .edit_tag(1, "Exif.Image.Make", TagValue::Text(String::from("Nikon")))
+        .expect("edit should succeed");
+    assert!(state.photos[0].dirty);
+
+    state.revert_photo(0).expect("revert should succeed");
+    assert!(!state.photos[0].dirty);
+    assert_eq!(state.photos[0].metadata, saved);
+    assert_eq!(state.photos[0].metadata, state.photos[0].persisted_metadata);
+    assert!(state.photos[1].dirty, "other photos keep their edits");
+
+    cleanup_file(&file);
+    cleanup_file(&other);
+}
+
+#[test]
+fn conform_to_profile_strips_tags_the_profile_lacks() {
+    let files = ["profile", "busy", "plain"].map(|name| unique_path(name, "png"));
+    for file in &files {
+        write_file(file, b"conform");
+    }
+
+    let mut state = AppState::default();
+    state.import_paths(files.clone());
+    let text = |value: &str| TagValue::Text(String::from(value));
+    state
+        .edit_tag(0, "Exif.Image.Make", text("Canon"))
+        .expect("edit should succeed");
+    for (key, value) in [
+        ("Exif.Image.Make", "Nikon"),
+        ("Exif.Image.Model", "D850"),
+        ("Exif.Image.Copyright", "Jane"),
+    ] {
+        state
+            .edit_tag(1, key, text(value))
+            .expect("edit should succeed");
+    }
+    let plain_before = state.photos[2].metadata.clone();
+
+    let removed = state
+        .conform_to_profile(0, &[0, 1, 2])
+        .expect("conform should succeed");
+    assert_eq!(removed, 2);
+
+    let busy = &state.photos[1].metadata;
+    assert_eq!(
+        busy.find_tag("Exif.Image.Make").map(|tag| &tag.value),
+        Some(&text("Nikon"))
+    );
+    assert!(busy.find_tag("Exif.Image.Model").is_none());
+    assert!(busy.find_tag("Exif.Image.Copyright").is_none());
+    assert!(busy
+        .all_tags()
+        .all(|tag| state.photos[0].metadata.find_tag(&tag.key).is_some()));
+    assert_eq!(state.photos[2].metadata, plain_before);
+    assert!(state.photos[0]
+        .metadata
+        .find_tag("Exif.Image.Make")
+        .is_some());
+
+    assert!(state.undo_last_change());
+    assert!(state.photos[1]
+        .metadata
+        .find_tag("Exif.Image.Model")
+        .is_some());
+    assert!(matches!(
+        state.conform_to_profile(9, &[1]),
+        Err(AppError::InvalidPhotoIndex(9))
+    ));
+
+    for file in &files {
+        cleanup_file(file);
+    }
+}
+
+#[test]
+fn unsaved_badge_follows_each_photo_dirty_state() {
+    let files = ["badge_a", "badge_b"].map(|name| unique_path(name, "png"));
+    for file in &files {
+        write_file(file, b"badge");
+    }
+
+    let mut state = AppState::default();
+    state.import_paths(files.clone());
+    assert!(state
+        .photos
+        .iter()
+        .all(|photo| !photo.shows_unsaved_badge()));
+    assert_eq!(state.unsaved_count(), 0);
+
+    state
+        .edit_tag(1, "Exif.Image.Artist", TagValue::Text(String::from("Jane")))
+        .expect("edit should succeed");
+    assert!(!state.photos[0].shows_unsaved_badge());
+    assert!(state.photos[1].shows_unsaved_badge());
+    assert_eq!(state.unsaved_count(), 1);
+
+    state.revert_photo(1).expect("revert should succeed");
+    assert!(!state.photos[1].shows_unsaved_badge());
+    assert_eq!(state.unsaved_count(), 0);
+
+    for file in &files {
+        cleanup_file(file);
+    }
+}
+
+#[test]
+fn saving_unwritable_format_reports_error_and_stays_dirty() {
+    let file = unique_path("unwritable", "tiff");
+    write_file(&file, b"tiff");
+
+    let mut state = AppState::default();
+    state.import_paths([file.clone()]);
+    state
+        .edit_tag(0, "Exif.Image.Artist", TagValue::Text(String::from("Jane")))
+        .expect("edit should succeed");
+
+    let err = state
+        .save_photo_changes(0)
+        .expect_err("tiff save should fail");
+    assert!(matches!(
+        err,
+        AppError::Metadata(MetadataError::UnsupportedFormat(_))
+    ));
+    assert!(state.photos[0].dirty);
+    assert!(!MetadataEngine::sidecar_path(&file).exists());
+
+    cleanup_file(&file);
+}
+
+#[cfg(unix)]
+#[test]
+fn template_and_conform_check_every_target_before_changing_any() {
+    let files = ["checked_a", "checked_b"].map(|name| {
+        let path = unique_path(name, "jpg");
+        write_file(&path, name.as_bytes());
+        path
+    });
+    let mut state = AppState::default();
+    state.import_paths(files.clone());
+    state
+        .edit_tag(0, "Exif.Image.Artist", TagValue::Text(String::from("Jane")))
+        .expect("edit should succeed");
+    let before = state.photos[1].metadata.clone();
+
+    assert!(matches!(
+        state.apply_template(0, &[1, 9]),
+        Err(AppError::InvalidPhotoIndex(9))
+    ));
+    assert!(matches!(
+        state.conform_to_profile(1, &[0, 9]),
+        Err(AppError::InvalidPhotoIndex(9))
+    ));
+    assert_eq!(state.photos[1].metadata, before);
+    assert!(state.photos[0]
+        .metadata
+        .find_tag("Exif.Image.Artist")
+        .is_some());
+
+    // Neither left an undo step behind: the next undo is the edit.
+    assert!(state.undo_last_change());
+    assert!(state.photos[0]
+        .metadata
+        .find_tag("Exif.Image.Artist")
+        .is_none());
+
+    for file in &files {
+        cleanup_file(file);
+    }
+}
+
+#[test]
+fn saving_an_imported_symlink_rewrites_its_target() {
+    let target = common::jpeg_fixture("symlink_target");
+    let link = unique_path("symlink_import", "jpg");
+    std::os::unix::fs::symlink(target.path(), &link).expect("should create symlink");
+
+    let mut state = AppState::default();
+    state.settings.write_sidecars = false;
+    state.import_paths([link.clone()]);
+    assert_eq!(state.photos[0].path, link);
+    state
+        .edit_tag(0, "Exif.Image.Artist", TagValue::Text(String::from("Jane")))
+        .expect("edit should succeed");
+    state.save_photo_changes(0).expect("save should succeed");
+
+    let link_meta = fs::symlink_metadata(&link).expect("should stat link");
+    assert!(
+        link_meta.file_type().is_symlink(),
+        "the link should survive the save"
+    );
+    let on_disk = MetadataEngine::read(&target).expect("should read target");
+    assert_eq!(
+        on_disk.find_tag("Exif.Image.Artist").map(|tag| &tag.value),
+        Some(&TagValue::Text(String::from("Jane")))
+    );
+
+    cleanup_file(&link);
+}
+
+#[test]
+fn saving_a_read_only_file_reports_error_and_stays_dirty() {
+    let file = common::jpeg_fixture("read_only");
+    let original = fs::read(&file).expect("should read jpeg");
+    let mut permissions = fs::metadata(&file).expect("should stat").permissions();
+    permissions.set_readonly(true);
+    fs::set_permissions(&file, permissions.clone()).expect("should mark read-only");
+
+    let mut state = AppState::default();
+    state.import_paths([file.to_path_buf()]);
+    state
+        .edit_tag(0, "Exif.Image.Artist", TagValue::Text(String::from("Jane")))
+        .expect("edit should succeed");
+
+    let err = state
+        .save_photo_changes(0)
+        .expect_err("read-only save should fail");
+    assert!(matches!(
+        err,
+        AppError::Metadata(MetadataError::Io(ref io)) if io.kind() == std::io::ErrorKind::PermissionDenied
+    ));
+    assert!(state.photos[0].dirty);
+    assert!(!MetadataEngine::sidecar_path(&file).exists());
+    assert_eq!(fs::read(&file).expect("should read jpeg"), original);
+
+    #[allow(clippy::permissions_set_readonly_false)]
+    permissions.set_readonly(false);
+    fs::set_permissions(&file, permissions).expect("should clear read-only");
+}
+
+#[test]
+fn template_skips_capture_location_and_datetime_tags() {
+    let template = unique_path("template_src", "jpg");
+    let target = unique_path("template_dst", "jpg");
+    write_file(&template, b"template");
+    write_file(&target, b"target");
+
+    let mut state = AppState::default();
+    state.import_paths([template.clone(), target.clone()]);
+    assert_eq!(state.photos.len(), 2);
+
+    state
+        .edit_tag(0, "Exif.Image.Artist", TagValue::Text(String::from("Jane")))
+        .expect("edit should succeed");
+    state
+        .edit_tag(0, "Exif.Photo.ISO", TagValue::Integer(3200))
+        .expect("edit should succeed");
+    state
+        .edit_tag(
+            0,
+            "Exif.GPSInfo.GPSCoordinates",
+            TagValue::Gps(48.8566, 2.3522, None),
+        )
+        .expect("edit should succeed");
+    state
+        .edit_tag(0, "Exif.Image.Orientation", TagValue::Integer(6))
+        .expect("edit should succeed");
+    state
+        .edit_tag(
+            0,
+            "Exif.Photo.BodySerialNumber",
+            TagValue::Text(String::from("A123")),
+        )
+        .expect("edit should succeed");
+
+    let summary = state
+        .apply_template(0, &[1])
+        .expect("template should apply");
+    assert_eq!(summary.photos_updated, 1);
+    for key in [
+        "Exif.Image.Orientation",
+        "Exif.Photo.BodySerialNumber",
+        "ExifEditor.FileName",
+        "ExifEditor.FileSize",
+    ] {
+        assert!(summary.skipped_keys.iter().any(|skipped| skipped == key));
+    }
+    assert_eq!(
+        state.photos[1]
+            .metadata
+            .find_tag("ExifEditor.FileName")
+            .map(|tag| tag.value.to_string()),
+        target
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+    );
+    assert!(state.photos[1]
+        .metadata
+        .find_tag("Exif.Photo.BodySerialNumber")
+        .is_none());
+    assert!(summary
+        .skipped_keys
+        .iter()
+        .any(|key| key == "Exif.Photo.ISO"));
+    assert!(summary
+        .skipped_keys
+        .iter()
+        .any(|key| key == "Exif.GPSInfo.GPSCoordinates"));
+
+    let target_tags = &state.photos[1].metadata;
+    assert!(target_tags
+        .all_tags()
+        .any(|tag| tag.key == "Exif.Image.Artist"
+            && tag.value == TagValue::Text(String::from("Jane"))));
+    assert!(!target_tags
+        .all_tags()
+        .any(|tag| tag.key == "Exif.Photo.ISO"));
+    assert!(!target_tags
+        .all_tags()
+        .any(|tag| tag.key == "Exif.GPSInfo.GPSCoordinates"));
+
+    state
+        .settings
+        .template_blocklist
+        .keys
+        .push(String::from("Exif.Image.Artist"));
+    state
+        .edit_tag(
+            0,
+            "Exif.Image.Artist",
+            TagValue::Text(String::from("Other")),
+        )
+        .expect("edit should succeed");
+    state
+        .apply_template(0, &[1])
+        .expect("template should apply");
+    assert!(state.photos[1]
+        .metadata
+        .all_tags()
+        .any(|tag| tag.key == "Exif.Image.Artist"
+            && tag.value == TagValue::Text(String::from("Jane"))));
+
+    cleanup_file(&template);
+    cleanup_file(&target);
+}
+
+#[test]
+fn export_with_preset_strips_copies_but_not_originals() {
+    let file_a = unique_path("export_preset_a", "jpg");
+    let file_b = unique_path("export_preset_b", "png");
+    write_file(&file_a, b"export-a");
+    write_file(&file_b, b"export-b");
+    let export_dir = unique_path("export_preset_dir", "d");
+    fs::create_dir_all(&export_dir).expect("should create export dir");
+
+    let mut state = AppState::default();
+    state.import_paths([file_a.clone(), file_b.clone()]);
+    for index in 0..2 {
+        state
+            .edit_tag(
+                index,
+                "Exif.Image.Make",
+                TagValue::Text(String::from("Canon")),
+            )
+            .expect("edit should succeed");
+        state
+            .edit_tag(
+                index,
+                "Exif.GPSInfo.GPSCoordinates",
+                TagValue::Gps(48.8566, 2.3522, None),
+            )
+            .expect("edit should succeed");
+        state
+            .save_photo_changes(index)
+            .expect("save should succeed");
+    }
+    state.select_all_visible();
+
+    assert!(matches!(
+        state.export_selected_with_preset(&export_dir, 999),
+        Err(AppError::PresetNotFound(999))
+    ));
+
+    let summary = state
+        .export_selected_with_preset(&export_dir, 1)
+        .expect("export should succeed");
+    assert_eq!(summary.succeeded, 2);
+    assert_eq!(summary.failed, 0);
+
+    for result in &state.operation_results {
+        assert!(result.output_path.starts_with(&export_dir));
+        let exported = MetadataEngine::read(&result.output_path).expect("should read export");
+        assert!(exported.find_tag("Exif.Image.Make").is_none());
+        assert!(exported.find_tag("Exif.GPSInfo.GPSCoordinates").is_none());
+    }
+
+    assert_eq!(state.photos.len(), 2);
+    for (index, path) in [&file_a, &file_b].into_iter().enumerate() {
+        let photo = &state.photos[index];
+        assert!(!photo.dirty);
+        assert!(photo.metadata.find_tag("Exif.Image.Make").is_some());
+        assert!(photo
+            .metadata
+            .find_tag("Exif.GPSInfo.GPSCoordinates")
+            .is_some());
+        let on_disk = MetadataEngine::read(path).expect("should read original");
+        assert!(on_disk.find_tag("Exif.Image.Make").is_some());
+    }
+
+    for result in &state.operation_results {
+        cleanup_file(&result.output_path);
+    }
+    let _ = fs::remove_dir_all(&export_dir);
+    cleanup_file(&file_a);
+    cleanup_file(&file_b);
+}
+
+#[test]
+fn failed_exports_leave_nothing_in_the_export_folder() {
+    let read_only = common::jpeg_fixture("export_read_only");
+    let mut metadata = MetadataEngine::read(&read_only).expect("read should succeed");
+    MetadataEngine::set_tag_in_metadata(
+        &mut metadata,
+        GPS_COORDINATES_KEY,
+        TagValue::Gps(48.8566, 2.3522, None),
+    );
+    MetadataEngine::write_with_sidecars(&read_only, &metadata, false)
+        .expect("write should succeed");
+    let mut permissions = fs::metadata(&read_only).expect("should stat").permissions();
+    permissions.set_readonly(true);
+    fs::set_permissions(&read_only, permissions.clone()).expect("should mark read-only");
+    let tiff = unique_path("export_tiff", "tiff");
+    fs::write(&tiff, common::encoded_image(image::ImageFormat::Tiff)).expect("should write tiff");
+    let export_dir = unique_path("export_leak_dir", "d");
+    fs::create_dir_all(&export_dir).expect("should create export dir");
+
+    let mut state = AppState::default();
+    state.import_paths([read_only.to_path_buf(), tiff.clone()]);
+    state
+        .edit_tag(1, "Exif.Image.Artist", TagValue::Text(String::from("Jane")))
+        .expect("edit should succeed");
+    state.select_all_visible();
+    let exported = |dir: &Path| {
+        let mut names = fs::read_dir(dir)
+            .expect("should list export dir")
+            .map(|entry| entry.expect("should read entry").file_name())
+            .collect::<Vec<_>>();
+        names.sort();
+        names
+    };
+
+    // The read-only source exports stripped; the TIFF, whose metadata can't
+    // be written, leaves no copy of the original behind.
+    let summary = state
+        .export_selected_with_preset(&export_dir, 1)
+        .expect("export should run");
+    assert_eq!((summary.succeeded, summary.failed), (1, 1));
+    let stripped = &state.operation_results[0].output_path;
+    let names = exported(&export_dir);
+    assert!(names
+        .iter()
+        .all(|name| !name.to_string_lossy().contains("export_tiff")));
+    assert!(names.contains(
+        &stripped
+            .file_name()
+            .expect("export has a name")
+            .to_os_string()
+    ));
+    let on_disk = MetadataEngine::read(stripped).expect("should read export");
+    assert!(on_disk.find_tag(GPS_COORDINATES_KEY).is_none());
+    fs::remove_dir_all(&export_dir).expect("should clear export dir");
+
+    fs::create_dir_all(&export_dir).expect("should create export dir");
+    let summary = state.export_all(&export_dir);
+    assert_eq!((summary.exported, summary.failed), (1, 1));
+    assert!(exported(&export_dir)
+        .iter()
+        .all(|name| !name.to_string_lossy().contains("export_tiff")));
+
+    #[allow(clippy::permissions_set_readonly_false)]
+    permissions.set_readonly(false);
+    fs::set_permissions(&read_only, permissions).expect("should clear read-only");
+    let _ = fs::remove_dir_all(&export_dir);
+    cleanup_file(&tiff);
+}
+
+#[test]
+fn export_with_preset_carries_unsaved_edits_without_saving_them() {
+    let file = unique_path("export_preset_unsaved", "jpg");
+    write_file(&file, b"export-unsaved");
+    let export_dir = unique_path("export_preset_unsaved_dir", "d");
+    fs::create_dir_all(&export_dir).expect("should create export dir");
+
+    let mut state = AppState::default();
+    state.import_paths([file.clone()]);
+    state
+        .edit_tag(0, "Exif.Image.Artist", TagValue::Text(String::from("Ana")))
+        .expect("edit should succeed");
+    state
+        .edit_tag(
+            0,
+            "Exif.GPSInfo.GPSCoordinates",
+            TagValue::Gps(48.8566, 2.3522, None),
+        )
+        .expect("edit should succeed");
+    state.select_all_visible();
+
+    // Privacy Clean drops the location but keeps the artist.
+    let summary = state
+        .export_selected_with_preset(&export_dir, 2)
+        .expect("export should succeed");
+    assert_eq!(summary.succeeded, 1);
+
+    let output = &state.operation_results[0].output_path;
+    assert!(output.starts_with(&export_dir));
+    let exported = MetadataEngine::read(output).expect("should read export");
+    assert_eq!(
+        exported
+            .find_tag("Exif.Image.Artist")
+            .map(|tag| tag.value.to_string()),
+        Some(String::from("Ana"))
+    );
+    assert!(exported.find_tag("Exif.GPSInfo.GPSCoordinates").is_none());
+
+    // The session keeps its edits unsaved, and the original never got them.
+    let photo = &state.photos[0];
+    assert!(photo.dirty);
+    assert!(photo
+        .metadata
+        .find_tag("Exif.GPSInfo.GPSCoordinates")
+        .is_some());
+    let on_disk = MetadataEngine::read(&file).expect("should read original");
+    assert!(on_disk.find_tag("Exif.Image.Artist").is_none());
+
+    cleanup_file(output);
+    let _ = fs::remove_dir_all(&export_dir);
+    cleanup_file(&file);
+}
+
+#[test]
+fn bulk_run_to_an_export_folder_leaves_originals_untouched() {
+    let (file_a, file_b) = (
+        unique_path("bulk_export_a", "jpg"),
+        unique_path("bulk_export_b", "jpg"),
+    );
+    write_file(&file_a, b"bulk-export-a");
+    write_file(&file_b, b"bulk-export-b");
+    let export_dir = unique_path("bulk_export_dir", "d");
+
+    let mut state = AppState::default();
+    state.import_paths([file_a.clone(), file_b.clone()]);
+    state.select_all_visible();
+
+    let summary = state
+        .run_bulk_selected(1, OutputMode::ExportTo(export_dir.clone()), None)
+        .expect("bulk run should start");
+    assert_eq!(summary.succeeded, 2);
+
+    for (result, source) in state.operation_results.iter().zip([&file_a, &file_b]) {
+        assert_eq!(result.output_path.parent(), Some(export_dir.as_path()));
+        assert_eq!(result.output_path.file_name(), source.file_name());
+        assert!(result.output_path.exists());
+    }
+    assert_eq!(fs::read(&file_a).expect("original a"), b"bulk-export-a");
+    assert_eq!(fs::read(&file_b).expect("original b"), b"bulk-export-b");
+    // The outputs are imported alongside the originals.
+    assert_eq!(state.photos.len(), 4);
+
+    let _ = fs::remove_dir_all(&export_dir);
+    cleanup_file(&file_a);
+    cleanup_file(&file_b);
+}
+
+#[test]
+fn export_all_reports_renames_for_same_named_photos() {
+    let root = unique_path("export_clash_src", "d");
+    let (dir_a, dir_b) = (root.join("day1"), root.join("day2"));
+    fs::create_dir_all(&dir_a).expect("should create source dir");
+    fs::create_dir_all(&dir_b).expect("should create source dir");
+    let (file_a, file_b) = (dir_a.join("IMG_0001.jpg"), dir_b.join("IMG_0001.jpg"));
+    write_file(&file_a, b"first");
+    write_file(&file_b, b"second");
+    let export_dir = unique_path("export_clash_dst", "d");
+    fs::create_dir_all(&export_dir).expect("should create export dir");
+
+    let mut state = AppState::default();
+    state.import_paths([file_a.clone(), file_b.clone()]);
+    state.mirror_export_folders = false;
+
+    let summary = state.export_all(&export_dir);
+    assert_eq!(summary.exported, 2);
+    assert_eq!(summary.failed, 0);
+    assert_eq!(summary.renamed.len(), 1);
+    assert_eq!(summary.renamed[0].source, file_b);
+    assert_eq!(
+        summary.renamed[0].output_path,
+        export_dir.join("IMG_0001_export_1.jpg")
+    );
+    assert_eq!(
+        fs::read(export_dir.join("IMG_0001_export.jpg")).expect("first export"),
+        b"first"
+    );
+    assert_eq!(
+        fs::read(export_dir.join("IMG_0001_export_1.jpg")).expect("second export"),
+        b"second"
+    );
+
+    let _ = fs::remove_dir_all(&export_dir);
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn analyze_session_aggregates_cameras_dates_and_gps() {
+    let files = ["a", "b", "c"].map(|name| unique_path(&format!("analyze_{name}"), "jpg"));
+    for file in &files {
+        write_file(file, b"analyze");
+    }
+
+    let mut state = AppState::default();
+    state.import_paths(files.clone());
+    let crafted = [
+        ("Canon", "2024:06:01 09:30:00", true),
+        ("Nikon", "2023:12:24 18:00:00", false),
+        ("Canon", "2024:01:15 12:00:00", false),
+    ];
+    for (index, (make, date, gps)) in crafted.into_iter().enumerate() {
+        state
+            .edit_tag(index, "Exif.Image.Make", TagValue::Text(String::from(make)))
+            .expect("edit should succeed");
+        state
+            .edit_tag(
+                index,
+                "Exif.Photo.DateTimeOriginal",
+                TagValue::DateTime(String::from(date)),
+            )
+            .expect("edit should succeed");
+        if gps {
+            state
+                .edit_tag(
+                    index,
+                    "Exif.GPSInfo.GPSCoordinates",
+                    TagValue::Gps(40.0, -74.0, None),
+                )
+                .expect("edit should succeed");
+        }
+    }
+
+    let (progress_tx, progress_rx) = mpsc::channel();
+    let background = state.begin_analyze().run(progress_tx);
+    assert_eq!(progress_rx.try_iter().count(), 3);
+
+    let stats = state.analyze_session();
+    assert_eq!(stats, background);
+    assert_eq!(state.session_stats.as_ref(), Some(&stats));
+    assert_eq!(stats.photo_count, 3);
+    assert_eq!(
+        stats.cameras,
+        vec![(String::from("Canon"), 2), (String::from("Nikon"), 1)]
+    );
+    assert_eq!(stats.earliest_date.as_deref(), Some("2023:12:24 18:00:00"));
+    assert_eq!(stats.latest_date.as_deref(), Some("2024:06:01 09:30:00"));
+    assert_eq!(stats.gps_count, 1);
+    assert!((stats.gps_percent() - 100.0 / 3.0).abs() < 0.01);
+
+    let expected_bytes: u64 = state
+        .photos
+        .iter()
+        .flat_map(|photo| photo.metadata.all_tags())
+        .map(|tag| tag.value.payload_len() as u64)
+        .sum();
+    assert_eq!(stats.metadata_bytes, expected_bytes);
+    assert!(stats.metadata_bytes > 0);
+
+    for file in &files {
+        cleanup_file(file);
+    }
+}
+
+#[test]
+fn bulk_allowlist_builds_preset_and_keeps_only_listed_tags() {
+    let file = unique_path("allowlist", "jpg");
+    write_file(&file, b"allowlist");
+
+    let mut state = AppState::default();
+    state.import_paths([file.clone()]);
+    state
+        .edit_tag(0, "Exif.Image.Make", TagValue::Text(String::from("Canon")))
+        .expect("edit should succeed");
+    state
+        .edit_tag(0, "Exif.Image.Orientation", TagValue::Integer(1))
+        .expect("edit should succeed");
+    state
+        .edit_tag(0, "Exif.Image.Artist", TagValue::Text(String::from("Jo")))
+        .expect("edit should succeed");
+    state.save_photo_changes(0).expect("save should succeed");
+    state.select_photo(0, false);
+
+    let err = state
+        .run_bulk_allowlist("camera, colour", OutputMode::Overwrite, None)
+        .expect_err("unknown token should be rejected");
+    assert!(matches!(err, AppError::UnrecognizedAllowlist(ref tokens) if tokens == &["colour"]));
+    assert!(matches!(
+        state.run_bulk_allowlist(" , ", OutputMode::Overwrite, None),
+        Err(AppError::EmptyAllowlist)
+    ));
+    assert!(!state.is_processing);
+
+    let summary = state
+        .run_bulk_allowlist(
+            "camera, Exif.Image.Orientation",
+            OutputMode::Suffix(String::from("_keep")),
+            None,
+        )
+        .expect("bulk should succeed");
+    assert_eq!(summary.succeeded, 1);
+    assert_eq!(state.active_preset, None);
+
+    let output = state.operation_results[0].output_path.clone();
+    let exported = state
+        .photos
+        .iter()
+        .find(|photo| photo.path == output)
+        .expect("output should be imported");
+    assert!(exported.metadata.find_tag("Exif.Image.Make").is_some());
+    assert!(exported
+        .metadata
+        .find_tag("Exif.Image.Orientation")
+        .is_some());
+    assert!(exported.metadata.find_tag("Exif.Image.Artist").is_none());
+
+    cleanup_file(&file);
+    cleanup_file(&output);
+}
+
+#[test]
+fn redo_reapplies_undone_edit_until_a_new_edit() {
+    let file = unique_path("redo", "jpg");
+    write_file(&file, b"redo");
+
+    let mut state = AppState::default();
+    state.import_paths([file.clone()]);
+    assert!(!state.redo_last_change());
+
+    state
+        .edit_tag(0, "Exif.Image.Make", TagValue::Text(String::from("Canon")))
+        .expect("edit should succeed");
+    assert!(state.undo_last_change());
+    assert!(state.photos[0]
+        .metadata
+        .find_tag("Exif.Image.Make")
+        .is_none());
+
+    assert!(state.redo_last_change());
+    assert!(state.photos[0].dirty);
+    assert_eq!(
+        state.photos[0]
+            .metadata
+            .find_tag("Exif.Image.Make")
+            .map(|tag| tag.value.clone()),
+        Some(TagValue::Text(String::from("Canon")))
+    );
+    assert!(!state.redo_last_change());
+
+    assert!(state.undo_last_change());
+    state
+        .edit_tag(0, "Exif.Image.Model", TagValue::Text(String::from("R5")))
+        .expect("edit should succeed");
+    assert!(!state.redo_last_change());
+
+    cleanup_file(&file);
+}
+
+#[test]
+fn grouped_operations_undo_and_redo_as_one_step() {
+    let files = ["group_a", "group_b"].map(|name| unique_path(name, "jpg"));
+    for file in &files {
+        write_file(file, b"group");
+    }
+
+    let mut state = AppState::default();
+    state.import_paths(files.clone());
+    let original = state
+        .photos
+        .iter()
+        .map(|photo| photo.metadata.clone())
+        .collect::<Vec<_>>();
+    let text = |value: &str| TagValue::Text(String::from(value));
+
+    state.begin_undo_group();
+    state
+        .edit_tag(0, "Exif.Image.Artist", text("Jane"))
+        .expect("edit should succeed");
+    state
+        .apply_template(0, &[1])
+        .expect("template should apply");
+    state
+        .edit_tag(0, "Exif.Image.Artist", text("Joe"))
+        .expect("edit should succeed");
+    assert!(state.end_undo_group());
+    let grouped = state
+        .photos
+        .iter()
+        .map(|photo| photo.metadata.clone())
+        .collect::<Vec<_>>();
+
+    state
+        .edit_tag(1, "Exif.Image.Make", text("Canon"))
+        .expect("edit should succeed");
+
+    // The edit outside the group undoes on its own.
+    assert!(state.undo_last_change());
+    assert_eq!(state.photos[1].metadata, grouped[1]);
+
+    assert!(state.undo_last_change());
+    assert_eq!(state.photos[0].metadata, original[0]);
+    assert_eq!(state.photos[1].metadata, original[1]);
+    assert!(state.photos.iter().all(|photo| !photo.dirty));
+    assert!(!state.undo_last_change());
+
+    assert!(state.redo_last_change());
+    assert_eq!(state.photos[0].metadata, grouped[0]);
+    assert_eq!(state.photos[1].metadata, grouped[1]);
+
+    state.begin_undo_group();
+    assert!(!state.end_undo_group(), "an empty group records nothing");
+
+    for file in &files {
+        cleanup_file(file);
+    }
+}
+
+#[test]
+fn saving_flag_is_set_while_a_save_job_runs() {
+    let file = unique_path("save_job", "jpg");
+    write_file(&file, b"save");
+
+    let mut state = AppState::default();
+    state.import_paths([file.clone()]);
+    state
+        .edit_tag(0, "Exif.Image.Make", TagValue::Text(String::from("Canon")))
+        .expect("edit should succeed");
+    assert!(!state.is_saving);
+
+    let job = state.begin_save(0).expect("save should start");
+    assert!(state.is_saving);
+
+    // An edit made while the write runs is not part of the saved snapshot.
+    state
+        .edit_tag(0, "Exif.Image.Model", TagValue::Text(String::from("R5")))
+        .expect("edit should succeed");
+
+    let (job, result) = std::thread::spawn(move || {
+        let result = job.run();
+        (job, result)
+    })
+    .join()
+    .expect("worker should not panic");
+    state.finish_save(job, result).expect("save should succeed");
+
+    assert!(!state.is_saving);
+    assert!(state.photos[0].dirty);
+    assert!(state.photos[0]
+        .persisted_metadata
+        .find_tag("Exif.Image.Make")
+        .is_some());
+    assert!(state.photos[0]
+        .persisted_metadata
+        .find_tag("Exif.Image.Model")
+        .is_none());
+
+    // Removing a photo ahead of it while the write runs shifts its index.
+    let first = unique_path("save_job_first", "jpg");
+    write_file(&first, b"first");
+    let mut state = AppState::default();
+    state.import_paths([first.clone(), file.clone()]);
+    state
+        .edit_tag(1, "Exif.Image.Model", TagValue::Text(String::from("R6")))
+        .expect("edit should succeed");
+    let job = state.begin_save(1).expect("save should start");
+    state.photos.remove(0);
+    let result = job.run();
+    state.finish_save(job, result).expect("save should succeed");
+    assert!(!state.photos[0].dirty);
+    cleanup_file(&first);
+
+    let missing = unique_path("save_job_missing", "jpg");
+    write_file(&missing, b"gone");
+    state.import_paths([missing.clone()]);
+    let job = state.begin_save(1).expect("save should start");
+    cleanup_file(&missing);
+    let result = job.run();
+    assert!(state.finish_save(job, result).is_err());
+    assert!(!state.is_saving);
+
+    cleanup_file(&file);
+}
+
+#[test]
+fn import_tree_skips_hidden_files_and_sidecars_and_counts_per_root() {
+    let root = unique_path("import_tree", "d");
+    let nested = root.join("nested");
+    fs::create_dir_all(&nested).expect("should create directories");
+
+    let photo = root.join("a.jpg");
+    write_file(&photo, b"photo-a");
+    MetadataEngine::write(&photo, &Default::default()).expect("should write sidecar");
+    write_file(&nested.join("b.png"), b"photo-b");
+    write_file(&root.join(".hidden.jpg"), b"hidden");
+    write_file(&root.join("notes.txt"), b"notes");
+
+    let loose = unique_path("import_tree_loose", "jpg");
+    write_file(&loose, b"loose");
+
+    let mut state = AppState::default();
+    let summary = state.import_tree([root.clone(), loose.clone()]);
+
+    assert_eq!(state.photos.len(), 3);
+    assert!(state
+        .photos
+        .iter()
+        .all(|photo| !photo.filename.starts_with('.')));
+    assert_eq!(summary.roots.len(), 2);
+    assert_eq!(summary.roots[0].root, root);
+    assert_eq!(summary.roots[0].found, 5);
+    assert_eq!(summary.roots[0].imported, 2);
+    assert_eq!(summary.roots[0].skipped, 3);
+    assert_eq!(summary.roots[1].found, 1);
+    assert_eq!(summary.roots[1].imported, 1);
+    assert_eq!(
+        (summary.found(), summary.imported(), summary.skipped()),
+        (6, 3, 3)
+    );
+
+    let again = state.import_tree([root.clone()]);
+    assert_eq!(again.imported(), 0);
+    assert_eq!(again.skipped(), 5);
+
+    let _ = fs::remove_dir_all(&root);
+    cleanup_file(&loose);
+}
+
+#[test]
+fn empty_artist_is_dropped_on_save_only_when_enabled() {
+    let file = unique_path("drop_empty", "jpg");
+    write_file(&file, b"drop-empty");
+
+    let mut state = AppState::default();
+    state.import_paths([file.clone()]);
+    state
+        .edit_tag(0, "Exif.Image.Artist", TagValue::Text(String::new()))
+        .expect("tag edit should succeed");
+
+    assert_eq!(state.save_photo_changes(0).expect("save should succeed"), 0);
+    let kept = MetadataEngine::read(&file).expect("read should succeed");
+    assert!(kept.find_tag("Exif.Image.Artist").is_some());
+
+    state.drop_empty_tags_on_save = true;
+    state
+        .edit_tag(0, "Exif.Image.Artist", TagValue::Text(String::from("  ")))
+        .expect("tag edit should succeed");
+    assert_eq!(state.save_photo_changes(0).expect("save should succeed"), 1);
+
+    let saved = MetadataEngine::read(&file).expect("read should succeed");
+    assert!(saved.find_tag("Exif.Image.Artist").is_none());
+    assert!(state.photos[0]
+        .metadata
+        .find_tag("Exif.Image.Artist")
+        .is_none());
+    assert!(!state.photos[0].dirty);
+
+    cleanup_file(&file);
+}
+
+#[test]
+fn import_dedupes_the_same_file_reached_by_different_paths() {
+    let file = unique_path("canonical", "jpg");
+    write_file(&file, b"canonical");
+
+    let dir = file.parent().expect("temp file should have a parent");
+    let dotted = dir
+        .join(".")
+        .join(file.file_name().expect("temp file should have a name"));
+
+    let mut state = AppState::default();
+    state.import_paths([file.clone(), dotted]);
+
+    #[cfg(unix)]
+    let link = {
+        let link = unique_path("canonical_link", "jpg");
+        std::os::unix::fs::symlink(&file, &link).expect("should create symlink");
+        state.import_paths([link.clone()]);
+        link
+    };
+
+    assert_eq!(state.photos.len(), 1);
+    // The photo keeps the path it was first imported by.
+    assert_eq!(state.photos[0].path, file);
+
+    #[cfg(unix)]
+    let _ = fs::remove_file(&link);
+    cleanup_file(&file);
+}
+
+#[test]
+fn duplicated_photo_has_independent_metadata() {
+    let file = unique_path("duplicate", "jpg");
+    write_file(&file, b"duplicate");
+
+    let mut state = AppState::default();
+    state.import_paths([file.clone()]);
+    state
+        .edit_tag(0, "Exif.Image.Artist", TagValue::Text(String::from("Jo")))
+        .expect("tag edit should succeed");
+
+    let copy = state.duplicate_photo(0).expect("duplicate should succeed");
+    assert_eq!(copy, 1);
+    assert_ne!(state.photos[copy].id, state.photos[0].id);
+    assert_eq!(state.photos[copy].path, state.photos[0].path);
+    assert_eq!(state.photos[copy].metadata, state.photos[0].metadata);
+
+    state
+        .edit_tag(
+            copy,
+            "Exif.Image.Artist",
+            TagValue::Text(String::from("Sam")),
+        )
+        .expect("tag edit should succeed");
+    assert_eq!(
+        state.photos[0]
+            .metadata
+            .find_tag("Exif.Image.Artist")
+            .map(|tag| tag.value.to_string()),
+        Some(String::from("Jo"))
+    );
+    assert!(state.duplicate_photo(5).is_err());
+
+    cleanup_file(&file);
+}
+
+#[test]
+fn stripping_marked_tags_removes_exactly_the_marked_ones() {
+    let file = unique_path("strip_marked", "jpg");
+    write_file(&file, b"strip-marked");
+
+    let mut state = AppState::default();
+    state.import_paths([file.clone()]);
+    for (key, value) in [
+        ("Exif.Image.Artist", "Jo"),
+        ("Exif.Image.Copyright", "(c) Jo"),
+        ("Exif.Image.Make", "Canon"),
+    ] {
+        state
+            .edit_tag(0, key, TagValue::Text(String::from(value)))
+            .expect("tag edit should succeed");
+    }
+    let before = state.photos[0].metadata.total_tag_count();
+
+    for key in ["Exif.Image.Artist", "Exif.Image.Copyright"] {
+        assert!(state
+            .mark_tag_for_removal(0, key, true)
+            .expect("mark should succeed"));
+    }
+    assert_eq!(state.strip_marked_tags(0).expect("strip should succeed"), 2);
+
+    let metadata = &state.photos[0].metadata;
+    assert_eq!(metadata.total_tag_count(), before - 2);
+    assert!(metadata.find_tag("Exif.Image.Artist").is_none());
+    assert!(metadata.find_tag("Exif.Image.Copyright").is_none());
+    assert!(metadata.find_tag("Exif.Image.Make").is_some());
+
+    cleanup_file(&file);
+}
+
+#[test]
+fn disabled_sidecars_are_neither_written_nor_read() {
+    let file = common::jpeg_fixture("no_sidecar");
+    let export_dir = unique_path("no_sidecar_export", "dir");
+    fs::create_dir_all(&export_dir).expect("should create export dir");
+
+    let mut state = AppState::default();
+    state.apply_settings(AppSettings {
+        write_sidecars: false,
+        ..AppSettings::default()
+    });
+    state.import_paths([file.to_path_buf()]);
+    state
+        .edit_tag(0, "Exif.Image.Artist", TagValue::Text(String::from("Jane")))
+        .expect("edit should succeed");
+    state.save_photo_changes(0).expect("save should succeed");
+    assert!(!MetadataEngine::sidecar_path(&file).exists());
+
+    let summary = state.export_all(&export_dir);
+    assert_eq!(summary.exported, 1);
+    let exported = fs::read_dir(&export_dir)
+        .expect("export dir should list")
+        .map(|entry| entry.expect("entry").path())
+        .collect::<Vec<_>>();
+    assert_eq!(exported.len(), 1);
+    assert!(!MetadataEngine::is_sidecar(&exported[0]));
+
+    // A sidecar left by an earlier session is removed rather than left stale.
+    let stale = state.photos[0].metadata.clone();
+    MetadataEngine::write(&file, &stale).expect("write should succeed");
+    state
+        .edit_tag(0, "Exif.Image.Artist", TagValue::Text(String::from("Sam")))
+        .expect("edit should succeed");
+    state.save_photo_changes(0).expect("save should succeed");
+    assert!(!MetadataEngine::sidecar_path(&file).exists());
+    state
+        .reload_photo_from_disk(0)
+        .expect("reload should succeed");
+    assert_eq!(
+        state.photos[0]
+            .metadata
+            .find_tag("Exif.Image.Artist")
+            .map(|tag| tag.value.to_string()),
+        Some(String::from("Sam"))
+    );
+
+    let _ = fs::remove_dir_all(&export_dir);
+}
+
+#[test]
+fn bulk_set_color_space_updates_only_the_selection() {
+    let files = ["srgb_a", "srgb_b", "srgb_c"].map(|name| unique_path(name, "png"));
+    for file in &files {
+        write_file(file, b"srgb");
+    }
+
+    let mut state = AppState::default();
+    state.import_paths(files.clone());
+    state
+        .edit_tag(2, COLOR_SPACE_KEY, TagValue::Integer(0xFFFF))
+        .expect("edit should succeed");
+
+    assert_eq!(state.bulk_set_color_space(COLOR_SPACE_SRGB, &[0, 2, 9]), 2);
+    let color_space = |state: &AppState, index: usize| {
+        state.photos[index]
+            .metadata
+            .find_tag(COLOR_SPACE_KEY)
+            .map(|tag| tag.value.clone())
+    };
+    assert_eq!(color_space(&state, 0), Some(TagValue::Integer(1)));
+    assert_eq!(color_space(&state, 1), None);
+    assert_eq!(color_space(&state, 2), Some(TagValue::Integer(1)));
+
+    // Photos already in sRGB are left alone.
+    assert_eq!(state.bulk_set_color_space(COLOR_SPACE_SRGB, &[0, 2]), 0);
+
+    for file in &files {
+        cleanup_file(file);
+    }
+}
+
+#[test]
+fn clear_tag_removes_only_the_targeted_value() {
+    let file = unique_path("clear_field", "png");
+    write_file(&file, b"clear");
+
+    let mut state = AppState::default();
+    state.import_paths([file.clone()]);
+    let text = |value: &str| TagValue::Text(String::from(value));
+    state
+        .edit_tag(0, "Exif.Image.Artist", text("Jane"))
+        .expect("edit should succeed");
+    state
+        .edit_tag(0, "Exif.Image.Make", text("Canon"))
+        .expect("edit should succeed");
+
+    assert!(state
+        .clear_tag(0, "Exif.Image.Artist")
+        .expect("clear should succeed"));
+    let metadata = &state.photos[0].metadata;
+    assert!(metadata.find_tag("Exif.Image.Artist").is_none());
+    assert_eq!(
+        metadata.find_tag("Exif.Image.Make").map(|tag| &tag.value),
+        Some(&text("Canon"))
+    );
+    assert!(!state
+        .clear_tag(0, "Exif.Image.Artist")
+        .expect("clearing a missing tag should succeed"));
+    assert!(matches!(
+        state.clear_tag(5, "Exif.Image.Make"),
+        Err(AppError::InvalidPhotoIndex(5))
+    ));
+
+    cleanup_file(&file);
+}
+
+#[test]
+fn sidecar_audit_flags_a_photo_changed_after_its_save() {
+    let fresh = unique_path("audit_fresh", "png");
+    let touched = unique_path("audit_touched", "png");
+    let bare = unique_path("audit_bare", "png");
+    for file in [&fresh, &touched, &bare] {
+        write_file(file, b"audit");
+    }
+
+    let mut state = AppState::default();
+    state.import_paths([fresh.clone(), touched.clone(), bare.clone()]);
+    let artist = TagValue::Text(String::from("Jane"));
+    for index in [0, 1] {
+        state
+            .edit_tag(index, "Exif.Image.Artist", artist.clone())
+            .expect("edit should succeed");
+        state
+            .save_photo_changes(index)
+            .expect("save should succeed");
+    }
+    assert_eq!(
+        state.audit_sidecars(),
+        vec![(fresh.clone(), false), (touched.clone(), false)]
+    );
+
+    write_file(&touched, b"edited in another app");
+    assert_eq!(
+        state.audit_sidecars(),
+        vec![(fresh.clone(), false), (touched.clone(), true)]
+    );
+
+    for file in [&fresh, &touched, &bare] {
+        cleanup_file(file);
+    }
+}
+
+#[test]
+fn find_tag_occurrences_locates_a_serial_across_photos() {
+    let files = ["find_a", "find_b", "find_c"].map(|name| unique_path(name, "png"));
+    for file in &files {
+        write_file(file, b"find");
+    }
+
+    let mut state = AppState::default();
+    state.import_paths(files.clone());
+    let text = |value: &str| TagValue::Text(String::from(value));
+    for (index, serial) in [(0, "SN-4821"), (1, "SN-1000"), (2, "sn-4821")] {
+        state
+            .edit_tag(index, "Exif.Photo.SerialNumber", text(serial))
+            .expect("edit should succeed");
+    }
+    state
+        .edit_tag(1, "Exif.Image.Artist", text("Jane"))
+        .expect("edit should succeed");
+
+    let found = state.find_tag_occurrences(" SN-4821 ");
+    let photos: Vec<PhotoId> = found.iter().map(|(id, _)| *id).collect();
+    assert_eq!(photos, vec![state.photos[0].id, state.photos[2].id]);
+    assert_eq!(state.photo_index(photos[1]), Some(2));
+    assert!(found
+        .iter()
+        .all(|(_, tag)| tag.key == "Exif.Photo.SerialNumber"));
+    assert!(state.find_tag_occurrences("   ").is_empty());
+
+    for file in &files {
+        cleanup_file(file);
+    }
+}
+
+#[test]
+fn import_tree_reports_why_each_file_was_skipped() {
+    let root = unique_path("import_skips", "d");
+    fs::create_dir_all(&root).expect("should create directory");
+    let photo = root.join("a.jpg");
+    write_file(&photo, b"photo-a");
+    MetadataEngine::write(&photo, &Default::default()).expect("should write sidecar");
+    MetadataEngine::backup_original(&photo).expect("should back up the photo");
+    write_file(&root.join(".hidden.jpg"), b"hidden");
+    write_file(&root.join("notes.txt"), b"notes");
+
+    let mut state = AppState::default();
+    let first = state.import_tree([root.clone()]);
+    let mut reasons: Vec<(String, SkipReason)> = first
+        .skipped_files()
+        .map(|(path, reason)| {
+            let name = path.file_name().expect("skipped file has a name");
+            (name.to_string_lossy().to_string(), *reason)
+        })
+        .collect();
+    reasons.sort_by(|left, right| left.0.cmp(&right.0));
+    assert_eq!(
+        reasons,
+        vec![
+            (String::from(".hidden.jpg"), SkipReason::Hidden),
+            (String::from("a.jpg.exif_editor.json"), SkipReason::Sidecar),
+            (String::from("a.jpg.orig"), SkipReason::Backup),
+            (String::from("notes.txt"), SkipReason::UnknownFormat),
+        ]
+    );
+    assert_eq!(first.skipped(), reasons.len());
+
+    let again = state.import_tree([photo.clone()]);
+    assert_eq!(
+        again.skipped_files().collect::<Vec<_>>(),
+        vec![&(photo.clone(), SkipReason::Duplicate)]
+    );
+
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn replace_in_tag_fixes_a_misspelled_make_across_the_selection() {
+    let files = ["replace_a", "replace_b", "replace_c"].map(|name| unique_path(name, "png"));
+    for file in &files {
+        write_file(file, b"replace");
+    }
+
+    let mut state = AppState::default();
+    state.import_paths(files.clone());
+    let text = |value: &str| TagValue::Text(String::from(value));
+    for (index, make) in [(0, "Canan"), (1, "CANAN EOS"), (2, "Canan")] {
+        state
+            .edit_tag(index, "Exif.Image.Make", text(make))
+            .expect("edit should succeed");
+    }
+    state.select_photo(0, false);
+    state.select_photo(1, true);
+
+    assert_eq!(
+        state.replace_in_tag("Exif.Image.Make", "Canan", "Canon", false),
+        1
+    );
+    assert_eq!(
+        state.replace_in_tag("Exif.Image.Make", "canan", "Canon", true),
+        1
+    );
+    let make = |index: usize| state.photos[index].metadata.find_tag("Exif.Image.Make");
+    assert_eq!(make(0).map(|tag| &tag.value), Some(&text("Canon")));
+    assert_eq!(make(1).map(|tag| &tag.value), Some(&text("Canon EOS")));
+    assert_eq!(
+        make(2).map(|tag| &tag.value),
+        Some(&text("Canan")),
+        "unselected photos are left alone"
+    );
+
+    assert!(state.undo_last_change());
+    assert_eq!(
+        state.photos[1]
+            .metadata
+            .find_tag("Exif.Image.Make")
+            .map(|tag| &tag.value),
+        Some(&text("CANAN EOS"))
+    );
+
+    for file in &files {
+        cleanup_file(file);
+    }
+}
+
+#[test]
+fn import_paths_gives_a_reason_for_every_skipped_path() {
+    let dir = unique_path("skip_reasons", "d");
+    fs::create_dir_all(&dir).expect("should create directory");
+    let photo = dir.join("photo.jpg");
+    write_file(&photo, b"photo");
+    let text_file = dir.join("notes.txt");
+    write_file(&text_file, b"notes");
+    let missing = dir.join("missing.jpg");
+    let same_photo = dir.join(".").join("photo.jpg");
+
+    let mut state = AppState::default();
+    let skipped = state.import_paths([
+        photo.clone(),
+        dir.clone(),
+        missing.clone(),
+        text_file.clone(),
+        same_photo.clone(),
+    ]);
+
+    assert_eq!(state.photos.len(), 1);
+    assert_eq!(state.photos[0].path, photo);
+    assert_eq!(
+        skipped,
+        vec![
+            (dir.clone(), SkipReason::NotAFile),
+            (missing, SkipReason::NotAFile),
+            (text_file, SkipReason::UnknownFormat),
+            (same_photo, SkipReason::Duplicate),
+        ]
+    );
+    assert_eq!(
+        state.import_paths([photo.clone()]),
+        vec![(photo, SkipReason::Duplicate)]
+    );
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn inspector_tags_group_by_category_or_keep_file_order() {
+    let file = unique_path("tag_sort", "png");
+    write_file(&file, b"sort");
+
+    let mut state = AppState::default();
+    state.import_paths([file.clone()]);
+    let text = |value: &str| TagValue::Text(String::from(value));
+    let paris = TagValue::Gps(48.85, 2.35, None);
+    for (key, value) in [
+        ("Exif.GPSInfo.GPSImgDirection", text("90")),
+        ("Exif.Image.Artist", text("Jane")),
+        ("Exif.GPSInfo.GPSCoordinates", paris),
+        ("Exif.Image.Make", text("Canon")),
+    ] {
+        state.edit_tag(0, key, value).expect("edit should succeed");
+    }
+
+    state.set_tag_sort(TagSort::ByCategory);
+    let categories: Vec<TagCategory> = state
+        .inspector_tags(0)
+        .iter()
+        .map(|tag| tag.category)
+        .collect();
+    let location: Vec<usize> = categories
+        .iter()
+        .enumerate()
+        .filter(|(_, category)| **category == TagCategory::Location)
+        .map(|(index, _)| index)
+        .collect();
+    assert_eq!(location.len(), 2);
+    assert_eq!(location[1] - location[0], 1, "location tags sit together");
+    let ranks: Vec<usize> = categories
+        .iter()
+        .map(|category| {
+            TagCategory::ALL
+                .iter()
+                .position(|c| c == category)
+                .expect("every category is listed")
+        })
+        .collect();
+    assert!(ranks.windows(2).all(|pair| pair[0] <= pair[1]));
+
+    state.set_tag_sort(TagSort::FileOrder);
+    let file_order: Vec<String> = state
+        .inspector_tags(0)
+        .into_iter()
+        .map(|tag| tag.key)
+        .collect();
+    let stored: Vec<String> = state.photos[0]
+        .metadata
+        .all_tags()
+        .map(|tag| tag.key.clone())
+        .collect();
+    assert_eq!(file_order, stored);
+
+    cleanup_file(&file);
+}
+
+#[test]
+fn hard_clean_leaves_no_metadata_and_no_sidecars() {
+    let files = (0..2)
+        .map(|index| unique_path(&format!("hard_clean_{index}"), "jpg"))
+        .collect::<Vec<_>>();
+    for file in &files {
+        common::write_jpeg(file);
+        let mut metadata = MetadataEngine::read(file).expect("read should succeed");
+        MetadataEngine::set_tag_in_metadata(
+            &mut metadata,
+            "Exif.Image.Artist",
+            TagValue::Text(String::from("Jane")),
+        );
+        MetadataEngine::write(file, &metadata).expect("write should succeed");
+        assert!(MetadataEngine::sidecar_path(file).exists());
+    }
+
+    let mut state = AppState::default();
+    state.import_paths(files.clone());
+    state.select_range(0, files.len() - 1);
+    let job = state
+        .begin_hard_clean(OutputMode::Overwrite)
+        .expect("job should start");
+    let (progress_tx, _progress_rx) = mpsc::channel();
+    let results = job.run(progress_tx, None);
+    let summary = state.finish_bulk(job, results);
+    assert_eq!(summary.succeeded, files.len());
+
+    for file in &files {
+        let (_, warnings) = MetadataEngine::read_with_warnings(file, SidecarPolicy::Ignore)
+            .expect("read should succeed");
+        assert_eq!(warnings, vec![MetadataWarning::NoExif]);
+        assert!(!MetadataEngine::sidecar_path(file).exists());
+        cleanup_file(file);
+    }
+}
+
+#[test]
+fn strip_on_import_cleans_metadata_in_session_only() {
+    let file = common::jpeg_fixture("strip_on_import");
+    let mut metadata = MetadataEngine::read(&file).expect("read should succeed");
+    MetadataEngine::set_tag_in_metadata(
+        &mut metadata,
+        "Exif.Image.Artist",
+        TagValue::Text(String::from("Jane")),
+    );
+    MetadataEngine::set_tag_in_metadata(
+        &mut metadata,
+        "Exif.GPSInfo.GPSCoordinates",
+        TagValue::Gps(48.85, 2.35, None),
+    );
+    MetadataEngine::write_with_sidecars(&file, &metadata, false).expect("write should succeed");
+
+    let mut state = AppState::default();
+    state.apply_settings(AppSettings {
+        write_sidecars: false,
+        strip_on_import: Some(4),
+        ..AppSettings::default()
+    });
+    state.import_paths([file.to_path_buf()]);
+
+    let photo = &state.photos[0];
+    let gps = "Exif.GPSInfo.GPSCoordinates";
+    assert!(photo.metadata.find_tag(gps).is_none());
+    assert!(photo.metadata.find_tag("Exif.Image.Artist").is_some());
+    assert!(photo.dirty, "the cleaned metadata is waiting to be saved");
+    let on_disk = MetadataEngine::read_with_policy(&file, SidecarPolicy::Ignore)
+        .expect("read should succeed");
+    assert!(on_disk.find_tag(gps).is_some());
+}
+
+#[test]
+fn privacy_audit_lists_saved_sensitive_tags_and_flags_unsaved_photos() {
+    let located = unique_path("privacy_gps", "jpg");
+    let serial = unique_path("privacy_serial", "jpg");
+    let clean = unique_path("privacy_clean", "jpg");
+    for path in [&located, &serial, &clean] {
+        write_file(path, b"jpeg");
+    }
+
+    let mut state = AppState::default();
+    state.import_paths([located.clone(), serial.clone(), clean.clone()]);
+    state
+        .edit_tag(
+            0,
+            "Exif.GPSInfo.GPSCoordinates",
+            TagValue::Gps(48.8584, 2.2945, None),
+        )
+        .expect("gps edit should succeed");
+    state
+        .edit_tag(
+            1,
+            "Exif.Photo.BodySerialNumber",
+            TagValue::Text(String::from("SN-1234")),
+        )
+        .expect("serial edit should succeed");
+    state
+        .edit_tag(
+            1,
+            "Exif.Photo.OwnerName",
+            TagValue::Text(String::from("Jane")),
+        )
+        .expect("owner edit should succeed");
+    state
+        .edit_tag(2, "Exif.Image.Artist", TagValue::Text(String::from("Jane")))
+        .expect("artist edit should succeed");
+
+    // Nothing sensitive has been saved yet.
+    assert!(state.privacy_audit().is_empty());
+
+    for index in 0..3 {
+        state
+            .save_photo_changes(index)
+            .expect("save should succeed");
+    }
+    // An unsaved strip leaves the saved GPS in place, so the photo stays
+    // listed and is flagged as unsaved.
+    state
+        .clear_tag(0, "Exif.GPSInfo.GPSCoordinates")
+        .expect("strip should succeed");
+
+    let audit = state.privacy_audit();
+    assert_eq!(
+        audit,
+        vec![
+            PrivacyFinding {
+                path: located.clone(),
+                keys: vec![String::from("Exif.GPSInfo.GPSCoordinates")],
+                unsaved: true,
+            },
+            PrivacyFinding {
+                path: serial.clone(),
+                keys: vec![
+                    String::from("Exif.Photo.BodySerialNumber"),
+                    String::from("Exif.Photo.OwnerName"),
+                ],
+                unsaved: false,
+            },
+        ]
+    );
+
+    state.save_photo_changes(0).expect("save should succeed");
+    assert_eq!(state.privacy_audit().len(), 1);
+
+    for path in [&located, &serial, &clean] {
+        cleanup_file(path);
+    }
+}
+
+#[test]
+fn first_save_backs_up_the_original_once() {
+    let file = common::jpeg_fixture("backup");
+    let original = fs::read(&file).expect("should read jpeg");
+    let backup = MetadataEngine::backup_path(&file);
+
+    let mut state = AppState::default();
+    state.apply_settings(AppSettings {
+        backup_originals: true,
+        ..AppSettings::default()
+    });
+    state.import_paths([file.to_path_buf()]);
+    for artist in ["Jane", "Sam"] {
+        state
+            .edit_tag(0, "Exif.Image.Artist", TagValue::Text(String::from(artist)))
+            .expect("edit should succeed");
+        state.save_photo_changes(0).expect("save should succeed");
+    }
+
+    assert_ne!(fs::read(&file).expect("should read jpeg"), original);
+    assert_eq!(fs::read(&backup).expect("backup should exist"), original);
+    let backups = fs::read_dir(file.parent().expect("temp dir"))
+        .expect("temp dir should list")
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| {
+            entry.path().extension().is_some_and(|ext| ext == "orig")
+                && entry
+                    .file_name()
+                    .to_string_lossy()
+                    .starts_with(&*file.file_name().expect("file name").to_string_lossy())
+        })
+        .count();
+    assert_eq!(backups, 1);
+}
+
+#[test]
+fn in_place_bulk_run_backs_up_originals_too() {
+    let file = common::jpeg_fixture("bulk_backup");
+    let mut metadata = MetadataEngine::read(&file).expect("read should succeed");
+    MetadataEngine::set_tag_in_metadata(
+        &mut metadata,
+        "Exif.Image.Artist",
+        TagValue::Text(String::from("Jane")),
+    );
+    MetadataEngine::write_with_sidecars(&file, &metadata, false).expect("write should succeed");
+    let original = fs::read(&file).expect("should read jpeg");
+    let backup = MetadataEngine::backup_path(&file);
+
+    let mut state = AppState::default();
+    state.apply_settings(AppSettings {
+        backup_originals: true,
+        ..AppSettings::default()
+    });
+    state.import_paths([file.to_path_buf()]);
+    state.select_range(0, 0);
+    let summary = state
+        .run_bulk_selected(1, OutputMode::Overwrite, None)
+        .expect("bulk run should start");
+
+    assert_eq!(summary.succeeded, 1);
+    assert_ne!(fs::read(&file).expect("should read jpeg"), original);
+    assert_eq!(fs::read(&backup).expect("backup should exist"), original);
+}
+
+#[test]
+fn copyright_year_update_skips_notices_without_a_year() {
+    let dated = unique_path("copyright_dated", "jpg");
+    let undated = unique_path("copyright_undated", "jpg");
+    write_file(&dated, b"jpeg");
+    write_file(&undated, b"jpeg");
+
+    let mut state = AppState::default();
+    state.import_paths([dated.clone(), undated.clone()]);
+    for (index, notice) in [(0, "© 2023 Jane"), (1, "© Jane")] {
+        state
+            .edit_tag(
+                index,
+                "Exif.Image.Copyright",
+                TagValue::Text(String::from(notice)),
+            )
+            .expect("edit should succeed");
+    }
+    state.save_all_dirty().expect("save should succeed");
+
+    assert_eq!(state.bulk_set_copyright_year(2024, &[0, 1]), 1);
+    let notice = |index: usize| {
+        state.photos[index]
+            .metadata
+            .find_tag("Exif.Image.Copyright")
+            .map(|tag| tag.value.to_string())
+    };
+    assert_eq!(notice(0), Some(String::from("© 2024 Jane")));
+    assert_eq!(notice(1), Some(String::from("© Jane")));
+    assert!(state.photos[0].dirty);
+    assert!(!state.photos[1].dirty);
+
+    cleanup_file(&dated);
+    cleanup_file(&undated);
+}
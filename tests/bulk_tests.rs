@@ -1,19 +1,17 @@
 use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc;
-use std::time::{SystemTime, UNIX_EPOCH};
-
-use exif_editor::core::bulk::BulkProcessor;
-use exif_editor::models::{ImageFormat, OutputMode, PhotoEntry, PresetRule, StripPreset};
-
-fn unique_path(name: &str, ext: &str) -> std::path::PathBuf {
-    let stamp = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .expect("system time should be after unix epoch")
-        .as_nanos();
-    let mut path = std::env::temp_dir();
-    path.push(format!("exif_editor_{name}_{stamp}.{ext}"));
-    path
-}
+
+use exif_editor::core::bulk::{BulkProcessor, WriteOptions};
+use exif_editor::core::metadata::MetadataEngine;
+use exif_editor::models::{
+    ImageFormat, OperationSummary, OutputMode, PhotoEntry, PresetRule, StripPreset,
+};
+
+mod common;
+
+use common::unique_path;
 
 #[test]
 fn process_with_suffix_creates_output_file_and_progress_event() {
@@ -86,3 +84,529 @@ fn process_with_export_dir_writes_to_export_location() {
     let _ = fs::remove_file(&results[0].output_path);
     let _ = fs::remove_dir_all(&export_dir);
 }
+
+#[test]
+fn process_twenty_files_in_parallel_preserves_input_order() {
+    let inputs = (0..20)
+        .map(|index| {
+            let path = unique_path(&format!("parallel_{index}"), "jpg");
+            fs::write(&path, format!("bytes-{index}")).expect("should create input file");
+            path
+        })
+        .collect::<Vec<_>>();
+
+    let photos = inputs
+        .iter()
+        .enumerate()
+        .map(|(index, path)| PhotoEntry::from_path(index as u64, path.clone(), ImageFormat::Jpeg))
+        .collect::<Vec<_>>();
+    let preset = StripPreset::new(
+        1,
+        "Strip All",
+        "remove all",
+        "trash",
+        vec![PresetRule::RemoveAll],
+        false,
+    );
+
+    let (tx, rx) = mpsc::channel();
+    let results = BulkProcessor::process(
+        &photos,
+        &preset,
+        &OutputMode::Suffix(String::from("_parallel")),
+        tx,
+    );
+
+    assert_eq!(results.len(), 20);
+    assert!(results.iter().all(|result| result.success));
+    for (index, result) in results.iter().enumerate() {
+        assert_eq!(result.photo_id, index as u64);
+    }
+
+    let mut counts = rx.try_iter().map(|event| event.current).collect::<Vec<_>>();
+    counts.sort_unstable();
+    assert_eq!(counts, (1..=20).collect::<Vec<_>>());
+
+    for (input, result) in inputs.iter().zip(&results) {
+        let _ = fs::remove_file(input);
+        let _ = fs::remove_file(&result.output_path);
+        let _ = fs::remove_file(MetadataEngine::sidecar_path(input));
+        let _ = fs::remove_file(MetadataEngine::sidecar_path(&result.output_path));
+    }
+}
+
+#[test]
+fn cancelled_run_reports_unprocessed_photos_as_cancelled() {
+    let inputs = (0..100)
+        .map(|index| {
+            let path = unique_path(&format!("cancel_{index}"), "jpg");
+            fs::write(&path, format!("bytes-{index}")).expect("should create input file");
+            path
+        })
+        .collect::<Vec<_>>();
+
+    let photos = inputs
+        .iter()
+        .enumerate()
+        .map(|(index, path)| PhotoEntry::from_path(index as u64, path.clone(), ImageFormat::Jpeg))
+        .collect::<Vec<_>>();
+    let preset = StripPreset::new(
+        1,
+        "Strip All",
+        "remove all",
+        "trash",
+        vec![PresetRule::RemoveAll],
+        false,
+    );
+
+    // Cancel from the progress receiver once a few photos are done, the way
+    // the window's cancel button lands partway through a run.
+    let cancel = AtomicBool::new(false);
+    let (tx, rx) = mpsc::channel();
+    let (results, progress_events) = std::thread::scope(|scope| {
+        let worker = scope.spawn(|| {
+            BulkProcessor::process_with_cancel(
+                &photos,
+                &preset,
+                &OutputMode::Suffix(String::from("_cancel")),
+                WriteOptions::sidecars(true),
+                tx,
+                Some(&cancel),
+            )
+        });
+        let mut progress_events = 0;
+        for _ in rx.iter() {
+            progress_events += 1;
+            if progress_events == 5 {
+                cancel.store(true, Ordering::SeqCst);
+            }
+        }
+        (
+            worker.join().expect("worker should not panic"),
+            progress_events,
+        )
+    });
+
+    let summary = OperationSummary::from_results(photos.len(), &results);
+    assert_eq!(summary.total, 100);
+    assert_eq!(summary.failed, 0);
+    assert!(summary.succeeded >= 5);
+    assert_eq!(summary.succeeded + summary.cancelled, 100);
+    assert_eq!(progress_events, summary.succeeded);
+    // Finished photos have their output; cancelled ones have none.
+    let finished = results
+        .iter()
+        .map(|result| result.output_path.clone())
+        .collect::<Vec<_>>();
+    for input in &inputs {
+        let output = BulkProcessor::add_suffix(input, "_cancel");
+        assert_eq!(output.exists(), finished.contains(&output));
+    }
+
+    for input in &inputs {
+        let output = BulkProcessor::add_suffix(input, "_cancel");
+        let _ = fs::remove_file(input);
+        let _ = fs::remove_file(&output);
+        let _ = fs::remove_file(MetadataEngine::sidecar_path(input));
+        let _ = fs::remove_file(MetadataEngine::sidecar_path(&output));
+    }
+}
+
+#[test]
+fn photo_interrupted_by_cancel_leaves_no_partial_output() {
+    let input = unique_path("interrupted", "jpg");
+    fs::write(&input, b"interrupted-bytes").expect("should create input file");
+    let export_dir = unique_path("interrupted_out", "d");
+
+    let photo = PhotoEntry::from_path(1, input.clone(), ImageFormat::Jpeg);
+    let preset = StripPreset::new(
+        1,
+        "Strip All",
+        "remove all",
+        "trash",
+        vec![PresetRule::RemoveAll],
+        false,
+    );
+    let output_mode = OutputMode::ExportTo(export_dir.clone());
+    let output = BulkProcessor::output_path(&photo, &preset, &output_mode);
+
+    // The flag is already set, so the photo is cancelled right after its copy lands.
+    let cancel = AtomicBool::new(true);
+    let result = BulkProcessor::process_photo(
+        &photo,
+        &preset,
+        &output_mode,
+        WriteOptions::sidecars(true),
+        Some(&cancel),
+    );
+    assert!(result.is_none());
+    assert!(!output.exists());
+    assert!(!MetadataEngine::sidecar_path(&output).exists());
+    assert!(input.exists());
+
+    let result = BulkProcessor::process_photo(
+        &photo,
+        &preset,
+        &output_mode,
+        WriteOptions::sidecars(true),
+        None,
+    )
+    .expect("uncancelled photo should be processed");
+    assert!(result.success);
+    assert!(output.exists());
+
+    let _ = fs::remove_file(&input);
+    let _ = fs::remove_dir_all(&export_dir);
+}
+
+#[test]
+fn failed_or_cancelled_export_keeps_the_file_already_at_the_output() {
+    let input = unique_path("kept_output", "txt");
+    fs::write(&input, b"not an image").expect("should create input file");
+    let export_dir = unique_path("kept_output_out", "d");
+    fs::create_dir_all(&export_dir).expect("should create export dir");
+
+    let photo = PhotoEntry::from_path(1, input.clone(), ImageFormat::Unknown);
+    let preset = StripPreset::new(
+        1,
+        "Strip All",
+        "remove all",
+        "trash",
+        vec![PresetRule::RemoveAll],
+        false,
+    );
+    let output_mode = OutputMode::ExportTo(export_dir.clone());
+    let output = BulkProcessor::output_path(&photo, &preset, &output_mode);
+    fs::write(&output, b"previous export").expect("should create previous output");
+
+    let failed = BulkProcessor::process_photo(
+        &photo,
+        &preset,
+        &output_mode,
+        WriteOptions::sidecars(true),
+        None,
+    )
+    .expect("a failed photo still reports a result");
+    assert!(!failed.success);
+
+    let cancel = AtomicBool::new(true);
+    let cancelled = BulkProcessor::process_photo(
+        &photo,
+        &preset,
+        &output_mode,
+        WriteOptions::sidecars(true),
+        Some(&cancel),
+    );
+    assert!(cancelled.is_none());
+
+    assert_eq!(fs::read(&output).unwrap(), b"previous export");
+    let left = fs::read_dir(&export_dir)
+        .expect("export dir should be readable")
+        .map(|entry| entry.expect("entry").file_name())
+        .collect::<Vec<_>>();
+    assert_eq!(left, [output.file_name().unwrap()], "staging files must go");
+
+    let _ = fs::remove_file(&input);
+    let _ = fs::remove_dir_all(&export_dir);
+}
+
+#[test]
+fn exporting_into_the_source_folder_adds_the_preset_suffix() {
+    let input = unique_path("export_same_dir", "jpg");
+    let photo = PhotoEntry::from_path(1, input.clone(), ImageFormat::Jpeg);
+    let preset = StripPreset::new(
+        1,
+        "Strip All",
+        "remove all",
+        "trash",
+        vec![PresetRule::RemoveAll],
+        false,
+    );
+
+    let source_dir = input
+        .parent()
+        .expect("temp file has a folder")
+        .to_path_buf();
+    let output = BulkProcessor::output_path(&photo, &preset, &OutputMode::ExportTo(source_dir));
+    assert_ne!(output, input);
+    assert_eq!(
+        output,
+        BulkProcessor::add_suffix(&input, &preset.default_suffix())
+    );
+}
+
+#[test]
+fn exporting_into_the_source_folder_by_a_relative_path_keeps_the_original() {
+    let input = common::jpeg_fixture("export_relative");
+    let original = fs::read(&input).expect("should read jpeg");
+    let photo = PhotoEntry::from_path(1, input.to_path_buf(), ImageFormat::Jpeg);
+    let preset = StripPreset::new(
+        1,
+        "Strip All",
+        "remove all",
+        "trash",
+        vec![PresetRule::RemoveAll],
+        false,
+    );
+    let source_dir = input.parent().expect("temp file has a folder");
+
+    // The same folder spelled relative to the working directory, and through `..`.
+    let cwd = std::env::current_dir().expect("should have a working directory");
+    let mut relative = cwd.components().skip(1).map(|_| "..").collect::<PathBuf>();
+    relative.push(source_dir.strip_prefix("/").unwrap_or(source_dir));
+    let dotted = source_dir
+        .join("..")
+        .join(source_dir.file_name().expect("temp dir has a name"));
+
+    for dir in [relative, dotted] {
+        let output_mode = OutputMode::ExportTo(dir.clone());
+        let output = BulkProcessor::output_path(&photo, &preset, &output_mode);
+        assert_ne!(output.file_name(), input.file_name(), "{}", dir.display());
+
+        let (tx, _rx) = mpsc::channel();
+        let results = BulkProcessor::process_with_cancel(
+            std::slice::from_ref(&photo),
+            &preset,
+            &output_mode,
+            WriteOptions::default(),
+            tx,
+            None,
+        );
+        assert!(results[0].success, "{}", dir.display());
+        assert_eq!(fs::read(&input).expect("should read jpeg"), original);
+        let _ = fs::remove_file(&output);
+    }
+}
+
+#[test]
+fn nested_sources_map_to_mirrored_export_paths() {
+    let source_root = Path::new("/photos/2024");
+    let sources = [
+        source_root.join("paris").join("day1").join("a.jpg"),
+        source_root.join("paris").join("b.jpg"),
+        source_root.join("rome").join("c.png"),
+    ];
+
+    let ancestor = BulkProcessor::common_ancestor(sources.iter().map(PathBuf::as_path))
+        .expect("sources share an ancestor");
+    assert_eq!(ancestor, source_root);
+
+    let export_root = Path::new("/exports");
+    let mirrored = sources
+        .iter()
+        .map(|source| BulkProcessor::relative_export_path(export_root, &ancestor, source))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        mirrored,
+        vec![
+            export_root.join("paris").join("day1").join("a.jpg"),
+            export_root.join("paris").join("b.jpg"),
+            export_root.join("rome").join("c.png"),
+        ]
+    );
+
+    assert_eq!(
+        BulkProcessor::relative_export_path(export_root, &ancestor, Path::new("/other/d.jpg")),
+        export_root.join("d.jpg")
+    );
+}
+
+#[test]
+fn common_ancestor_of_one_file_is_its_folder() {
+    let source = Path::new("/photos/2024/a.jpg");
+
+    assert_eq!(
+        BulkProcessor::common_ancestor([source]),
+        Some(PathBuf::from("/photos/2024"))
+    );
+    assert_eq!(BulkProcessor::common_ancestor(Vec::<&Path>::new()), None);
+}
+
+#[test]
+fn preset_suffix_is_slugified_from_the_preset_name() {
+    let preset = StripPreset::new(
+        7,
+        "Web Safe",
+        "strip for sharing",
+        "share",
+        vec![PresetRule::RemoveGps],
+        false,
+    );
+    assert_eq!(preset.default_suffix(), "_web-safe");
+
+    let mut odd = preset.clone();
+    odd.name = String::from("  Client: Final (v2)!  ");
+    assert_eq!(odd.default_suffix(), "_client-final-v2");
+    odd.name = String::from("***");
+    assert_eq!(odd.default_suffix(), "_clean");
+
+    let dir = unique_path("preset_suffix", "d");
+    fs::create_dir_all(&dir).expect("should create directory");
+    let input = dir.join("photo.jpg");
+    fs::write(&input, b"sample-bytes").expect("should create input file");
+
+    let photo = PhotoEntry::from_path(1, input.clone(), ImageFormat::Jpeg);
+    let (tx, _rx) = mpsc::channel();
+    let results = BulkProcessor::process(&[photo], &preset, &OutputMode::PresetSuffix, tx);
+    assert!(results[0].success);
+    assert_eq!(results[0].output_path, dir.join("photo_web-safe.jpg"));
+    assert!(results[0].output_path.exists());
+
+    assert_eq!(
+        BulkProcessor::unique_export_path(&dir, "photo.jpg", &preset.default_suffix()),
+        dir.join("photo_web-safe_1.jpg")
+    );
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn resumed_run_skips_files_the_log_records_as_done() {
+    let inputs = (0..4)
+        .map(|index| unique_path(&format!("resume_{index}"), "jpg"))
+        .collect::<Vec<_>>();
+    let create_input = |index: usize| {
+        fs::write(&inputs[index], format!("bytes-{index}")).expect("should create input file");
+    };
+    let photos = inputs
+        .iter()
+        .enumerate()
+        .map(|(index, path)| PhotoEntry::from_path(index as u64, path.clone(), ImageFormat::Jpeg))
+        .collect::<Vec<_>>();
+    let preset = StripPreset::new(
+        1,
+        "Strip All",
+        "remove all",
+        "trash",
+        vec![PresetRule::RemoveAll],
+        false,
+    );
+    let output_mode = OutputMode::Suffix(String::from("_resume"));
+    let log_path = unique_path("resume_log", "log");
+
+    // A run that only got through the first two photos; the rest are missing.
+    create_input(0);
+    create_input(1);
+    let (tx, _rx) = mpsc::channel();
+    let first = BulkProcessor::process_with_log(
+        &photos,
+        &preset,
+        &output_mode,
+        WriteOptions::default(),
+        tx,
+        None,
+        &log_path,
+    );
+    let succeeded = first.iter().filter(|result| result.success).count();
+    assert_eq!(succeeded, 2);
+    let log = fs::read_to_string(&log_path).expect("resume log should be written");
+    assert_eq!(log.lines().count(), 3, "{log}");
+
+    // Finished outputs are marked so a redo would show up.
+    let done_outputs = inputs[..2]
+        .iter()
+        .map(|input| BulkProcessor::add_suffix(input, "_resume"))
+        .collect::<Vec<_>>();
+    for output in &done_outputs {
+        fs::write(output, "already done").expect("should mark output");
+    }
+
+    create_input(2);
+    create_input(3);
+    let (tx, rx) = mpsc::channel();
+    let results = BulkProcessor::process_with_resume(
+        &photos,
+        &preset,
+        &output_mode,
+        WriteOptions::default(),
+        tx,
+        None,
+        &log_path,
+    );
+    assert_eq!(results.len(), 4);
+    assert!(results.iter().all(|result| result.success));
+    assert_eq!(rx.try_iter().count(), 4);
+    for output in &done_outputs {
+        assert_eq!(fs::read_to_string(output).unwrap(), "already done");
+    }
+    assert!(BulkProcessor::add_suffix(&inputs[2], "_resume").exists());
+    assert!(BulkProcessor::add_suffix(&inputs[3], "_resume").exists());
+    assert!(!log_path.exists(), "a finished run should drop its log");
+
+    for input in &inputs {
+        let _ = fs::remove_file(input);
+        let _ = fs::remove_file(BulkProcessor::add_suffix(input, "_resume"));
+    }
+}
+
+#[test]
+fn resume_redoes_changed_files_and_a_new_run_starts_over() {
+    let inputs = (0..3)
+        .map(|index| unique_path(&format!("resume_changed_{index}"), "jpg"))
+        .collect::<Vec<_>>();
+    let photos = inputs
+        .iter()
+        .enumerate()
+        .map(|(index, path)| PhotoEntry::from_path(index as u64, path.clone(), ImageFormat::Jpeg))
+        .collect::<Vec<_>>();
+    let preset = StripPreset::new(
+        1,
+        "Strip All",
+        "remove all",
+        "trash",
+        vec![PresetRule::RemoveAll],
+        false,
+    );
+    let output_mode = OutputMode::Suffix(String::from("_changed"));
+    let log_path = unique_path("resume_changed_log", "log");
+    let outputs = inputs
+        .iter()
+        .map(|input| BulkProcessor::add_suffix(input, "_changed"))
+        .collect::<Vec<_>>();
+    let run = |resume: bool| {
+        let (tx, _rx) = mpsc::channel();
+        let process = if resume {
+            BulkProcessor::process_with_resume
+        } else {
+            BulkProcessor::process_with_log
+        };
+        process(
+            &photos,
+            &preset,
+            &output_mode,
+            WriteOptions::default(),
+            tx,
+            None,
+            &log_path,
+        )
+    };
+    let mark_outputs = || {
+        for output in &outputs[..2] {
+            fs::write(output, "already done").expect("should mark output");
+        }
+    };
+
+    // The third photo never exists, so every run is left unfinished and
+    // keeps its log.
+    fs::write(&inputs[0], "first").expect("should create input file");
+    fs::write(&inputs[1], "second").expect("should create input file");
+    assert_eq!(run(false).iter().filter(|result| result.success).count(), 2);
+
+    // The first photo changes after it was logged, so resuming redoes it.
+    mark_outputs();
+    fs::write(&inputs[0], "first, edited since").expect("should edit input");
+    run(true);
+    assert_ne!(fs::read_to_string(&outputs[0]).unwrap(), "already done");
+    assert_eq!(fs::read_to_string(&outputs[1]).unwrap(), "already done");
+
+    // A run that isn't a resume discards the log and does everything again.
+    mark_outputs();
+    run(false);
+    for output in &outputs[..2] {
+        assert_ne!(fs::read_to_string(output).unwrap(), "already done");
+    }
+
+    for path in inputs.iter().chain(&outputs).chain([&log_path]) {
+        let _ = fs::remove_file(path);
+    }
+}
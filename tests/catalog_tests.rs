@@ -0,0 +1,52 @@
+use exif_editor::core::catalog::{self, TAG_CATALOG};
+use exif_editor::models::{MetadataTag, TagCategory, TagValue};
+
+#[test]
+fn category_filter_narrows_search_and_combines_with_query() {
+    let everything = catalog::search("", None);
+    assert_eq!(everything.len(), TAG_CATALOG.len());
+
+    let camera = catalog::search("", Some(TagCategory::Camera));
+    assert!(!camera.is_empty());
+    assert!(camera.len() < everything.len());
+    assert!(camera.iter().all(|def| def.category == TagCategory::Camera));
+
+    let camera_make = catalog::search("make", Some(TagCategory::Camera));
+    assert!(camera_make.iter().any(|def| def.key == "Exif.Image.Make"));
+    assert!(camera_make
+        .iter()
+        .all(|def| def.category == TagCategory::Camera));
+
+    assert!(catalog::search("make", Some(TagCategory::Location)).is_empty());
+}
+
+#[test]
+fn camera_defaults_are_told_apart_from_meaningful_values() {
+    let tag = |key: &str, value: TagValue| MetadataTag::new(key, key, value, TagCategory::Capture);
+
+    assert!(catalog::is_camera_default(&tag(
+        "Exif.Photo.ISO",
+        TagValue::Integer(100)
+    )));
+    assert!(catalog::is_camera_default(&tag(
+        "Exif.Photo.Flash",
+        TagValue::Integer(0)
+    )));
+    assert!(catalog::is_camera_default(&tag(
+        "Exif.Image.XResolution",
+        TagValue::Rational(720, 10)
+    )));
+
+    assert!(!catalog::is_camera_default(&tag(
+        "Exif.Photo.ISO",
+        TagValue::Integer(3200)
+    )));
+    assert!(!catalog::is_camera_default(&tag(
+        "Exif.Photo.Flash",
+        TagValue::Integer(1)
+    )));
+    assert!(!catalog::is_camera_default(&tag(
+        "Exif.Image.Artist",
+        TagValue::Text(String::from("100"))
+    )));
+}
@@ -0,0 +1,119 @@
+use std::fs;
+use std::path::PathBuf;
+
+use exif_editor::cli::{self, CliError, CliOptions, Launch};
+use exif_editor::core::metadata::MetadataEngine;
+use exif_editor::models::{OutputMode, TagValue};
+
+mod common;
+
+fn unique_dir(name: &str) -> PathBuf {
+    let stamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .expect("system time should be after unix epoch")
+        .as_nanos();
+    std::env::temp_dir().join(format!("exif_editor_cli_{name}_{stamp}"))
+}
+
+#[test]
+fn arguments_pick_a_preset_output_and_files() {
+    let options = cli::parse_args(["--strip-gps", "--out", "./clean", "a.jpg", "b.jpg"])
+        .expect("arguments should parse")
+        .expect("not a help request");
+    assert_eq!(
+        options,
+        CliOptions {
+            preset_id: 4,
+            output_mode: OutputMode::ExportTo(PathBuf::from("./clean")),
+            write_sidecars: false,
+            files: vec![PathBuf::from("a.jpg"), PathBuf::from("b.jpg")],
+        }
+    );
+
+    let named = cli::parse_args(["--preset", "keep-basics", "--overwrite", "a.jpg"])
+        .expect("arguments should parse")
+        .expect("not a help request");
+    assert_eq!(named.preset_id, 5);
+    assert_eq!(named.output_mode, OutputMode::Overwrite);
+
+    assert!(matches!(cli::parse_args(["--help"]), Ok(None)));
+    assert!(matches!(
+        cli::parse_args(["--out", "x", "--overwrite", "a.jpg"]),
+        Err(CliError::ConflictingOutput)
+    ));
+    assert!(matches!(
+        cli::parse_args(["--preset", "nope", "a.jpg"]),
+        Err(CliError::UnknownPreset(_))
+    ));
+    assert!(matches!(
+        cli::parse_args(["--strip-gps"]),
+        Err(CliError::NoFiles)
+    ));
+}
+
+#[test]
+fn only_the_strip_command_runs_headless() {
+    let args = |args: &[&str]| args.iter().map(|arg| String::from(*arg)).collect();
+
+    assert_eq!(
+        Launch::from_args(args(&["strip", "--strip-gps", "a.jpg"])),
+        Launch::Headless(args(&["--strip-gps", "a.jpg"]))
+    );
+    assert_eq!(
+        Launch::from_args(args(&["--help"])),
+        Launch::Headless(args(&["--help"]))
+    );
+    // Files handed over by "Open with" open in the window, untouched.
+    assert_eq!(
+        Launch::from_args(args(&["a.jpg", "b.jpg"])),
+        Launch::Window(vec![PathBuf::from("a.jpg"), PathBuf::from("b.jpg")])
+    );
+    assert_eq!(Launch::from_args(Vec::new()), Launch::Window(Vec::new()));
+}
+
+#[test]
+fn headless_run_strips_gps_into_the_output_folder() {
+    let dir = unique_dir("strip_gps");
+    fs::create_dir_all(&dir).expect("should create dir");
+    let file = dir.join("photo.jpg");
+    common::write_jpeg(&file);
+    let mut metadata = MetadataEngine::read(&file).expect("read should succeed");
+    MetadataEngine::set_tag_in_metadata(
+        &mut metadata,
+        "Exif.Image.Artist",
+        TagValue::Text(String::from("Jane")),
+    );
+    MetadataEngine::set_tag_in_metadata(
+        &mut metadata,
+        "Exif.GPSInfo.GPSCoordinates",
+        TagValue::Gps(48.85, 2.35, None),
+    );
+    MetadataEngine::write_with_sidecars(&file, &metadata, false).expect("write should succeed");
+    let out = dir.join("clean");
+
+    let options = cli::parse_args([
+        String::from("--strip-gps"),
+        String::from("--out"),
+        out.display().to_string(),
+        file.display().to_string(),
+        dir.join("missing.jpg").display().to_string(),
+    ])
+    .expect("arguments should parse")
+    .expect("not a help request");
+    let outcome = cli::run(&options).expect("run should succeed");
+
+    assert_eq!(outcome.summary.succeeded, 1);
+    assert!(outcome.summary.is_complete_success());
+    assert_eq!(outcome.skipped.len(), 1);
+    let output = out.join("photo.jpg");
+    assert_eq!(outcome.results[0].output_path(), output);
+    assert!(!MetadataEngine::sidecar_path(&output).exists());
+
+    let stripped = MetadataEngine::read(&output).expect("output should read");
+    assert!(!stripped.has_gps);
+    assert!(stripped.find_tag("Exif.Image.Artist").is_some());
+    let original = MetadataEngine::read(&file).expect("original should read");
+    assert!(original.has_gps);
+
+    let _ = fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,85 @@
+//! Fixtures shared by the integration tests. Each test file that needs them
+//! declares `mod common;`, so not every file uses every helper.
+#![allow(dead_code)]
+
+use std::fs;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use exif_editor::core::metadata::MetadataEngine;
+
+/// A unique path in the temp dir: `exif_editor_<name>_<stamp>.<ext>`. The
+/// stamp combines the process id, the time and a counter, so tests running
+/// in parallel never share a file.
+pub fn unique_path(name: &str, ext: &str) -> PathBuf {
+    static COUNTER: AtomicU32 = AtomicU32::new(0);
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time should be after unix epoch")
+        .as_nanos();
+    let count = COUNTER.fetch_add(1, Ordering::Relaxed);
+    std::env::temp_dir().join(format!(
+        "exif_editor_{name}_{}_{nanos}_{count}.{ext}",
+        std::process::id()
+    ))
+}
+
+/// The small solid-color image every photo fixture is made from, encoded
+/// as `format`.
+pub fn encoded_image(format: image::ImageFormat) -> Vec<u8> {
+    let mut encoded = std::io::Cursor::new(Vec::new());
+    image::RgbImage::from_pixel(4, 3, image::Rgb([10, 120, 200]))
+        .write_to(&mut encoded, format)
+        .expect("image should encode");
+    encoded.into_inner()
+}
+
+/// Write the fixture image to `path` as a JPEG with no metadata.
+pub fn write_jpeg(path: &Path) {
+    fs::write(path, encoded_image(image::ImageFormat::Jpeg)).expect("should write jpeg");
+}
+
+/// A metadata-free JPEG at a [`unique_path`], removed with everything the
+/// editor writes next to it when dropped.
+pub fn jpeg_fixture(name: &str) -> TempPhoto {
+    let path = unique_path(name, "jpg");
+    write_jpeg(&path);
+    TempPhoto { path }
+}
+
+/// A photo file owned by a test. Dropping it removes the file, its JSON and
+/// XMP sidecars and its `.orig` backup.
+pub struct TempPhoto {
+    path: PathBuf,
+}
+
+impl TempPhoto {
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Deref for TempPhoto {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl AsRef<Path> for TempPhoto {
+    fn as_ref(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for TempPhoto {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+        let _ = fs::remove_file(MetadataEngine::sidecar_path(&self.path));
+        let _ = fs::remove_file(MetadataEngine::xmp_sidecar_path(&self.path));
+        let _ = fs::remove_file(MetadataEngine::backup_path(&self.path));
+    }
+}
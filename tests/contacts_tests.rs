@@ -0,0 +1,95 @@
+use exif_editor::core::contacts::{
+    collect_contacts, contacts_to_vcard, replace_copyright_year, ContactInfo,
+};
+use exif_editor::core::metadata::MetadataEngine;
+use exif_editor::models::{ImageFormat, PhotoEntry, TagValue};
+
+fn photo(id: u64, tags: &[(&str, &str)]) -> PhotoEntry {
+    let mut photo = PhotoEntry::from_path(id, format!("photo_{id}.jpg").into(), ImageFormat::Jpeg);
+    for (key, value) in tags {
+        MetadataEngine::set_tag_in_metadata(
+            &mut photo.metadata,
+            key,
+            TagValue::Text(String::from(*value)),
+        );
+    }
+    photo
+}
+
+#[test]
+fn collects_each_distinct_artist_once_with_their_copyrights() {
+    let photos = [
+        photo(
+            1,
+            &[
+                ("Exif.Image.Artist", "Jane Doe"),
+                ("Exif.Image.Copyright", "(c) 2024 Jane Doe"),
+            ],
+        ),
+        photo(
+            2,
+            &[
+                ("Exif.Image.Artist", " jane doe "),
+                ("Exif.Image.Copyright", "(c) 2025 Jane Doe"),
+            ],
+        ),
+        photo(3, &[("Exif.Photo.OwnerName", "Sam Lee")]),
+        photo(4, &[("Exif.Image.Copyright", "Unattributed")]),
+        photo(5, &[("Exif.Image.Artist", "Ana; Studio")]),
+    ];
+
+    let contacts = collect_contacts(&photos);
+    assert_eq!(
+        contacts,
+        vec![
+            ContactInfo {
+                name: String::from("Ana; Studio"),
+                copyrights: Vec::new(),
+                photo_count: 1,
+            },
+            ContactInfo {
+                name: String::from("Jane Doe"),
+                copyrights: vec![
+                    String::from("(c) 2024 Jane Doe"),
+                    String::from("(c) 2025 Jane Doe"),
+                ],
+                photo_count: 2,
+            },
+            ContactInfo {
+                name: String::from("Sam Lee"),
+                copyrights: Vec::new(),
+                photo_count: 1,
+            },
+        ]
+    );
+
+    let vcard = contacts_to_vcard(&contacts);
+    assert_eq!(vcard.matches("BEGIN:VCARD").count(), 3);
+    assert!(vcard.contains("FN:Ana\\; Studio\r\n"));
+    assert!(vcard.contains("NOTE:(c) 2025 Jane Doe\r\n"));
+}
+
+#[test]
+fn copyright_year_is_replaced_and_the_rest_of_the_notice_kept() {
+    assert_eq!(
+        replace_copyright_year("© 2023 Jane Doe", 2024).as_deref(),
+        Some("© 2024 Jane Doe")
+    );
+    assert_eq!(
+        replace_copyright_year("Copyright 2019-2023 Jane, all rights reserved", 2024).as_deref(),
+        Some("Copyright 2019-2024 Jane, all rights reserved")
+    );
+    // Runs of other lengths aren't years.
+    assert_eq!(
+        replace_copyright_year("Studio 12345, 2022", 2024).as_deref(),
+        Some("Studio 12345, 2024")
+    );
+    // Other four-digit numbers outside the range of years are left alone.
+    assert_eq!(
+        replace_copyright_year("© 2023 Jane, Suite 1200", 2024).as_deref(),
+        Some("© 2024 Jane, Suite 1200")
+    );
+    assert_eq!(replace_copyright_year("PO Box 9876, Jane Doe", 2024), None);
+    assert_eq!(replace_copyright_year("© Jane Doe", 2024), None);
+    assert_eq!(replace_copyright_year("Unit 42, Jane Doe", 2024), None);
+}
@@ -0,0 +1,40 @@
+use exif_editor::core::enum_labels::{label_for, labels_for, value_for};
+use exif_editor::models::ORIENTATION_KEY;
+
+const ENUM_KEYS: &[&str] = &[
+    ORIENTATION_KEY,
+    "Exif.Photo.ExposureProgram",
+    "Exif.Photo.MeteringMode",
+    "Exif.Photo.Flash",
+    "Exif.Photo.WhiteBalance",
+    "Exif.Photo.ColorSpace",
+    "Exif.Photo.FileSource",
+    "Exif.Photo.SceneType",
+];
+
+#[test]
+fn enum_labels_round_trip_in_both_directions() {
+    assert_eq!(label_for("Exif.Photo.Flash", 16), Some("Off, did not fire"));
+    assert_eq!(value_for("Exif.Photo.Flash", "Off, did not fire"), Some(16));
+    assert_eq!(
+        label_for("Exif.Photo.ColorSpace", 0xFFFF),
+        Some("Uncalibrated")
+    );
+    assert_eq!(value_for(ORIENTATION_KEY, "Rotate 90 CW"), Some(6));
+
+    for key in ENUM_KEYS {
+        let labels = labels_for(key).expect("known enum tag");
+        for (value, label) in labels {
+            assert_eq!(label_for(key, *value), Some(*label), "{key} = {value}");
+            assert_eq!(value_for(key, label), Some(*value), "{key} = {label}");
+        }
+    }
+}
+
+#[test]
+fn unknown_keys_and_values_have_no_label() {
+    assert!(labels_for("Exif.Photo.ISOSpeedRatings").is_none());
+    assert_eq!(label_for("Exif.Photo.ISOSpeedRatings", 100), None);
+    assert_eq!(label_for("Exif.Photo.MeteringMode", 42), None);
+    assert_eq!(value_for("Exif.Photo.WhiteBalance", "Sunny"), None);
+}
@@ -0,0 +1,167 @@
+use std::fs;
+
+use exif_editor::app::AppState;
+use exif_editor::core::exiftool;
+use exif_editor::models::{MetadataTag, PhotoMetadata, TagCategory, TagValue};
+
+mod common;
+
+fn tag_value<'a>(tags: &'a [(String, TagValue)], key: &str) -> Option<&'a TagValue> {
+    tags.iter()
+        .find(|(tag_key, _)| tag_key == key)
+        .map(|(_, value)| value)
+}
+
+#[test]
+fn parses_grouped_and_printed_exiftool_values() {
+    let json = r#"[{
+        "SourceFile": "/photos/IMG_0001.JPG",
+        "EXIF:Make": "Canon",
+        "EXIF:Orientation": "Rotate 90 CW",
+        "EXIF:ExposureTime": "1/250",
+        "EXIF:FNumber": 2.8,
+        "EXIF:ISO": 400,
+        "EXIF:GPSLatitude": "40 deg 30' 0.00\" N",
+        "EXIF:GPSLongitude": -74.25,
+        "File:FileSize": "2.1 MB"
+    }]"#;
+
+    let records = exiftool::parse_exiftool_json(json).expect("sample should parse");
+    assert_eq!(records.len(), 1);
+
+    let record = &records[0];
+    assert_eq!(record.file_name(), "IMG_0001.JPG");
+    assert_eq!(
+        tag_value(&record.tags, "Exif.Image.Make"),
+        Some(&TagValue::Text(String::from("Canon")))
+    );
+    assert_eq!(
+        tag_value(&record.tags, "Exif.Image.Orientation"),
+        Some(&TagValue::Integer(6))
+    );
+    assert_eq!(
+        tag_value(&record.tags, "Exif.Photo.ExposureTime"),
+        Some(&TagValue::Rational(1, 250))
+    );
+    assert_eq!(
+        tag_value(&record.tags, "Exif.Photo.FNumber"),
+        Some(&TagValue::Rational(14, 5))
+    );
+    assert_eq!(
+        tag_value(&record.tags, "Exif.Photo.ISO"),
+        Some(&TagValue::Integer(400))
+    );
+    assert_eq!(
+        tag_value(&record.tags, "Exif.GPSInfo.GPSCoordinates"),
+        Some(&TagValue::Gps(40.5, -74.25, None))
+    );
+    assert_eq!(record.tags.len(), 6);
+}
+
+#[test]
+fn import_applies_tags_to_photos_matched_by_filename() {
+    let photo = common::jpeg_fixture("exiftool_target");
+    let file_name = photo
+        .file_name()
+        .expect("temp path has a file name")
+        .to_string_lossy()
+        .to_string();
+
+    let json_path = common::unique_path("exiftool_dump", "json");
+    let json = serde_json::json!([
+        {
+            "SourceFile": format!("some/other/dir/{file_name}"),
+            "Artist": "Jane Doe",
+            "ISO": 200
+        },
+        {
+            "SourceFile": "missing.jpg",
+            "Artist": "Nobody"
+        }
+    ]);
+    fs::write(&json_path, json.to_string()).expect("should write json");
+
+    let mut state = AppState::default();
+    state.import_paths([photo.to_path_buf()]);
+
+    let summary = state
+        .import_exiftool_json(&json_path)
+        .expect("import should succeed");
+    assert_eq!(summary.photos_updated, 1);
+    assert_eq!(summary.tags_applied, 2);
+    assert_eq!(summary.unmatched_files, vec![String::from("missing.jpg")]);
+
+    let metadata = &state.photos[0].metadata;
+    assert_eq!(
+        metadata.find_tag("Exif.Image.Artist").map(|tag| &tag.value),
+        Some(&TagValue::Text(String::from("Jane Doe")))
+    );
+    assert_eq!(
+        metadata.find_tag("Exif.Photo.ISO").map(|tag| &tag.value),
+        Some(&TagValue::Integer(200))
+    );
+    assert!(state.photos[0].dirty);
+
+    let _ = fs::remove_file(&json_path);
+}
+
+#[test]
+fn export_uses_exiftool_keys_and_round_trips() {
+    let metadata = PhotoMetadata {
+        exif_tags: vec![
+            MetadataTag::new(
+                "Exif.Image.Make",
+                "Make",
+                TagValue::Text(String::from("Nikon")),
+                TagCategory::Camera,
+            ),
+            MetadataTag::new(
+                "Exif.Photo.ExposureTime",
+                "Exposure Time",
+                TagValue::Rational(3, 7),
+                TagCategory::Capture,
+            ),
+            MetadataTag::new(
+                "Exif.Photo.ISO",
+                "ISO",
+                TagValue::Integer(800),
+                TagCategory::Capture,
+            ),
+            MetadataTag::new(
+                "Exif.GPSInfo.GPSCoordinates",
+                "GPS Coordinates",
+                TagValue::Gps(51.5, -0.125, Some(35.0)),
+                TagCategory::Location,
+            ),
+            MetadataTag::new(
+                "ExifEditor.FileName",
+                "File Name",
+                TagValue::Text(String::from("a.jpg")),
+                TagCategory::Image,
+            ),
+        ],
+        ..PhotoMetadata::default()
+    };
+
+    let json = exiftool::export_exiftool_json(&metadata);
+    let value: serde_json::Value = serde_json::from_str(&json).expect("export should be json");
+    let object = value[0]
+        .as_object()
+        .expect("export should be an array of objects");
+    assert_eq!(object["SourceFile"], "*");
+    assert_eq!(object["EXIF:Make"], "Nikon");
+    assert_eq!(object["EXIF:ExposureTime"], "3/7");
+    assert_eq!(object["EXIF:ISO"], 800);
+    assert_eq!(object["Composite:GPSLatitude"], 51.5);
+    assert!(!object.keys().any(|key| key.contains("FileName")));
+
+    let records = exiftool::parse_exiftool_json(&json).expect("export should re-import");
+    let mut reimported = records[0].tags.clone();
+    reimported.sort_by(|left, right| left.0.cmp(&right.0));
+    let mut expected = metadata.exif_tags[..4]
+        .iter()
+        .map(|tag| (tag.key.clone(), tag.value.clone()))
+        .collect::<Vec<_>>();
+    expected.sort_by(|left, right| left.0.cmp(&right.0));
+    assert_eq!(reimported, expected);
+}
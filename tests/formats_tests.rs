@@ -0,0 +1,354 @@
+use std::fs;
+use std::path::Path;
+
+use exif_editor::app::AppState;
+use exif_editor::core::formats;
+use exif_editor::core::metadata::MetadataEngine;
+use exif_editor::models::{
+    Dimensions, ImageFormat, MetadataTag, PhotoMetadata, TagCategory, TagValue, ORIENTATION_KEY,
+};
+
+mod common;
+
+use common::unique_path;
+
+fn cleanup_file(path: &Path) {
+    let _ = fs::remove_file(path);
+    let _ = fs::remove_file(MetadataEngine::sidecar_path(path));
+}
+
+fn png_bytes(width: u32, height: u32) -> Vec<u8> {
+    let mut bytes = vec![0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+    bytes.extend_from_slice(&13u32.to_be_bytes());
+    bytes.extend_from_slice(b"IHDR");
+    bytes.extend_from_slice(&width.to_be_bytes());
+    bytes.extend_from_slice(&height.to_be_bytes());
+    bytes.extend_from_slice(&[8, 2, 0, 0, 0]);
+    bytes
+}
+
+fn jpeg_bytes(width: u16, height: u16) -> Vec<u8> {
+    let mut bytes = vec![0xFF, 0xD8];
+    // APP0 segment that has to be skipped before the frame header.
+    bytes.extend_from_slice(&[0xFF, 0xE0, 0x00, 0x06, b'J', b'F', b'I', b'F']);
+    bytes.extend_from_slice(&[0xFF, 0xC0, 0x00, 0x0B, 0x08]);
+    bytes.extend_from_slice(&height.to_be_bytes());
+    bytes.extend_from_slice(&width.to_be_bytes());
+    bytes.extend_from_slice(&[0x01, 0x01, 0x11, 0x00]);
+    bytes.extend_from_slice(&[0xFF, 0xD9]);
+    bytes
+}
+
+#[test]
+fn reads_dimensions_from_png_and_jpeg_headers() {
+    let png = unique_path("dims_png", "png");
+    let jpeg = unique_path("dims_jpeg", "jpg");
+    let other = unique_path("dims_other", "jpg");
+    fs::write(&png, png_bytes(640, 480)).expect("should write png");
+    fs::write(&jpeg, jpeg_bytes(300, 400)).expect("should write jpeg");
+    fs::write(&other, b"not an image").expect("should write file");
+
+    assert_eq!(
+        formats::read_dimensions(&png),
+        Some(Dimensions {
+            width: 640,
+            height: 480
+        })
+    );
+    assert_eq!(
+        formats::read_dimensions(&jpeg),
+        Some(Dimensions {
+            width: 300,
+            height: 400
+        })
+    );
+    assert_eq!(formats::read_dimensions(&other), None);
+
+    cleanup_file(&png);
+    cleanup_file(&jpeg);
+    cleanup_file(&other);
+}
+
+#[test]
+fn rotated_orientation_conflicts_only_with_portrait_pixels() {
+    let portrait = Dimensions {
+        width: 300,
+        height: 400,
+    };
+    let landscape = Dimensions {
+        width: 400,
+        height: 300,
+    };
+
+    for orientation in 5..=8 {
+        assert!(portrait.conflicts_with_orientation(orientation));
+        assert!(!landscape.conflicts_with_orientation(orientation));
+    }
+    for orientation in 1..=4 {
+        assert!(!portrait.conflicts_with_orientation(orientation));
+    }
+}
+
+#[test]
+fn fix_orientation_mismatch_resets_tag_and_is_undoable() {
+    let path = unique_path("orientation_fix", "png");
+    fs::write(&path, png_bytes(300, 400)).expect("should write png");
+
+    let mut state = AppState::default();
+    state.import_paths([path.clone()]);
+    assert!(state.orientation_mismatches().is_empty());
+
+    state
+        .edit_tag(0, ORIENTATION_KEY, TagValue::Integer(6))
+        .expect("edit should succeed");
+    assert_eq!(state.orientation_mismatches(), vec![0]);
+
+    assert!(state
+        .fix_orientation_mismatch(0)
+        .expect("fix should succeed"));
+    assert_eq!(state.photos[0].metadata.orientation(), 1);
+    assert!(state.orientation_mismatches().is_empty());
+    assert!(!state
+        .fix_orientation_mismatch(0)
+        .expect("second fix should succeed"));
+
+    assert!(state.undo_last_change());
+    assert_eq!(state.photos[0].metadata.orientation(), 6);
+
+    cleanup_file(&path);
+}
+
+#[test]
+fn sniffs_magic_bytes_for_extensionless_files() {
+    let jpeg = unique_path("sniff_jpeg", "jpg").with_extension("");
+    fs::write(&jpeg, jpeg_bytes(320, 240)).expect("should write jpeg");
+
+    assert_eq!(formats::detect_format(&jpeg), ImageFormat::Jpeg);
+
+    let mut state = AppState::default();
+    let skipped = state.import_paths([jpeg.clone()]);
+    assert!(skipped.is_empty());
+    assert_eq!(state.photos.len(), 1);
+    assert_eq!(state.photos[0].format, ImageFormat::Jpeg);
+    assert_eq!(
+        state.photos[0].dimensions,
+        Some(Dimensions {
+            width: 320,
+            height: 240
+        })
+    );
+
+    cleanup_file(&jpeg);
+}
+
+#[test]
+fn magic_bytes_identify_common_containers() {
+    assert_eq!(
+        formats::format_from_magic(b"II*\0\x08\0\0\0"),
+        ImageFormat::Tiff
+    );
+    assert_eq!(
+        formats::format_from_magic(b"RIFF\x10\0\0\0WEBPVP8 "),
+        ImageFormat::WebP
+    );
+    assert_eq!(
+        formats::format_from_magic(b"\0\0\0\x18ftypheic\0\0\0\0"),
+        ImageFormat::Heif
+    );
+    assert_eq!(
+        formats::format_from_magic(b"\0\0\0\x1cftypavif\0\0\0\0"),
+        ImageFormat::Avif
+    );
+    assert_eq!(
+        formats::format_from_magic(b"not an image"),
+        ImageFormat::Unknown
+    );
+}
+
+fn ftyp_box(major: &[u8; 4], compatible: &[&[u8; 4]]) -> Vec<u8> {
+    let len = 16 + 4 * compatible.len() as u32;
+    let mut bytes = len.to_be_bytes().to_vec();
+    bytes.extend_from_slice(b"ftyp");
+    bytes.extend_from_slice(major);
+    bytes.extend_from_slice(&[0, 0, 0, 0]);
+    for brand in compatible {
+        bytes.extend_from_slice(*brand);
+    }
+    bytes.extend_from_slice(b"\0\0\0\x08meta");
+    bytes
+}
+
+#[test]
+fn detects_heif_avif_and_jxl_containers_by_content() {
+    let cases = [
+        (ftyp_box(b"heic", &[b"mif1", b"heic"]), ImageFormat::Heif),
+        (ftyp_box(b"mif1", &[b"mif1", b"heic"]), ImageFormat::Heif),
+        (ftyp_box(b"avif", &[b"avif", b"mif1"]), ImageFormat::Avif),
+        (ftyp_box(b"mif1", &[b"mif1", b"avif"]), ImageFormat::Avif),
+        (vec![0xFF, 0x0A, 0xFA, 0x7F], ImageFormat::Jxl),
+        (
+            vec![
+                0x00, 0x00, 0x00, 0x0C, b'J', b'X', b'L', b' ', 0x0D, 0x0A, 0x87, 0x0A,
+            ],
+            ImageFormat::Jxl,
+        ),
+    ];
+
+    for (index, (bytes, expected)) in cases.into_iter().enumerate() {
+        assert_eq!(formats::format_from_magic(&bytes), expected, "case {index}");
+
+        let path = unique_path(&format!("container_{index}"), "bin");
+        fs::write(&path, &bytes).expect("should write container");
+        let detected = formats::detect_format(&path);
+        assert!(!detected.is_unknown(), "case {index}");
+        assert_eq!(detected, expected, "case {index}");
+        cleanup_file(&path);
+    }
+
+    assert_eq!(
+        formats::format_from_magic(&ftyp_box(b"isom", &[b"mp41"])),
+        ImageFormat::Unknown
+    );
+}
+
+#[test]
+fn falls_back_to_extension_when_content_is_unrecognised() {
+    for (ext, expected) in [
+        ("HEIC", ImageFormat::Heif),
+        ("heif", ImageFormat::Heif),
+        ("hif", ImageFormat::Heif),
+        ("avif", ImageFormat::Avif),
+        ("jxl", ImageFormat::Jxl),
+    ] {
+        let path = unique_path("extension_only", ext);
+        assert_eq!(formats::detect_format(&path), expected, "{ext}");
+        assert!(formats::is_supported(&path), "{ext}");
+    }
+}
+
+#[test]
+fn mislabeled_png_is_imported_and_written_as_png() {
+    let path = unique_path("mislabeled", "jpg");
+    let encoded = common::encoded_image(image::ImageFormat::Png);
+    fs::write(&path, encoded).expect("should write png");
+
+    let mut state = AppState::default();
+    state.import_paths([path.clone()]);
+    assert_eq!(state.photos[0].format, ImageFormat::Png);
+
+    let mut metadata = state.photos[0].metadata.clone();
+    metadata.exif_tags.push(MetadataTag::new(
+        "Exif.Image.Artist",
+        "Artist",
+        TagValue::Text(String::from("Jo")),
+        TagCategory::Description,
+    ));
+    MetadataEngine::write(&path, &metadata).expect("write should succeed");
+
+    assert_eq!(formats::sniff_format(&path), ImageFormat::Png);
+    let decoded = image::ImageReader::open(&path)
+        .and_then(|reader| reader.with_guessed_format())
+        .map(|reader| reader.decode());
+    assert!(matches!(decoded, Ok(Ok(_))), "file should still decode");
+
+    let _ = fs::remove_file(MetadataEngine::sidecar_path(&path));
+    let embedded = MetadataEngine::read(&path).expect("read should succeed");
+    assert_eq!(
+        embedded
+            .find_tag("Exif.Image.Artist")
+            .map(|tag| tag.value.to_string()),
+        Some(String::from("Jo"))
+    );
+
+    cleanup_file(&path);
+}
+
+/// An APP1 EXIF segment whose IFD0 holds a single `Make` string.
+fn exif_segment_with_make(make: &str) -> Vec<u8> {
+    let mut value = make.as_bytes().to_vec();
+    value.push(0);
+    let value_offset = 8u32 + 2 + 12 + 4;
+
+    let mut tiff = Vec::new();
+    tiff.extend_from_slice(b"II");
+    tiff.extend_from_slice(&42u16.to_le_bytes());
+    tiff.extend_from_slice(&8u32.to_le_bytes());
+    tiff.extend_from_slice(&1u16.to_le_bytes());
+    tiff.extend_from_slice(&0x010Fu16.to_le_bytes());
+    tiff.extend_from_slice(&2u16.to_le_bytes());
+    tiff.extend_from_slice(&(value.len() as u32).to_le_bytes());
+    tiff.extend_from_slice(&value_offset.to_le_bytes());
+    tiff.extend_from_slice(&0u32.to_le_bytes());
+    tiff.extend_from_slice(&value);
+
+    let mut segment = vec![0xFF, 0xE1];
+    segment.extend_from_slice(&((tiff.len() + 8) as u16).to_be_bytes());
+    segment.extend_from_slice(b"Exif\0\0");
+    segment.extend_from_slice(&tiff);
+    segment
+}
+
+#[test]
+fn jpeg_with_two_exif_segments_uses_the_first() {
+    let path = unique_path("double_exif", "jpg");
+    let encoded = common::encoded_image(image::ImageFormat::Jpeg);
+
+    let mut bytes = encoded[..2].to_vec();
+    bytes.extend_from_slice(&exif_segment_with_make("First"));
+    bytes.extend_from_slice(&exif_segment_with_make("Second"));
+    bytes.extend_from_slice(&encoded[2..]);
+    assert_eq!(formats::jpeg_exif_segments(&bytes).len(), 2);
+    fs::write(&path, &bytes).expect("should write jpeg");
+
+    let make = |metadata: &PhotoMetadata| {
+        metadata
+            .find_tag("Exif.Image.Make")
+            .map(|tag| tag.value.to_string())
+    };
+    let metadata = MetadataEngine::read(&path).expect("read should succeed");
+    assert_eq!(make(&metadata), Some(String::from("First")));
+
+    // Saving drops the duplicate so other readers agree with the editor.
+    MetadataEngine::write(&path, &metadata).expect("write should succeed");
+    let written = fs::read(&path).expect("should read jpeg");
+    assert_eq!(formats::jpeg_exif_segments(&written).len(), 1);
+    let _ = fs::remove_file(MetadataEngine::sidecar_path(&path));
+    let reread = MetadataEngine::read(&path).expect("read should succeed");
+    assert_eq!(make(&reread), Some(String::from("First")));
+
+    // A leading segment with a broken TIFF header is skipped, not preferred.
+    let mut broken_first = encoded[..2].to_vec();
+    broken_first.extend_from_slice(&[0xFF, 0xE1, 0x00, 0x0C]);
+    broken_first.extend_from_slice(b"Exif\0\0junk");
+    broken_first.extend_from_slice(&exif_segment_with_make("Valid"));
+    broken_first.extend_from_slice(&encoded[2..]);
+    let repaired = formats::keep_first_jpeg_exif(&broken_first).expect("should repair");
+    let kept = formats::jpeg_exif_segments(&repaired);
+    assert_eq!(kept.len(), 1);
+    assert!(kept[0].payload(&repaired).ends_with(b"Valid\0"));
+    assert!(formats::keep_first_jpeg_exif(&repaired).is_none());
+
+    cleanup_file(&path);
+}
+
+#[test]
+fn only_exif_native_formats_reliably_embed_gps() {
+    let cases = [
+        (ImageFormat::Jpeg, true),
+        (ImageFormat::Tiff, true),
+        (ImageFormat::Heif, true),
+        (ImageFormat::Avif, true),
+        (ImageFormat::Jxl, true),
+        (ImageFormat::Png, false),
+        (ImageFormat::WebP, false),
+        (ImageFormat::Unknown, false),
+    ];
+    for (format, expected) in cases {
+        assert_eq!(
+            formats::format_supports_gps(format),
+            expected,
+            "{}",
+            format.as_str()
+        );
+    }
+}
@@ -0,0 +1,87 @@
+use exif_editor::core::geocode::{
+    coordinate_key, parse_reverse_response, parse_search_response, reverse_url, search_url,
+    GeocodeError,
+};
+
+#[test]
+fn parses_first_nominatim_result() {
+    let body = r#"[
+        {"place_id": 1, "lat": "48.8588897", "lon": "2.3200410", "display_name": "Paris, France"},
+        {"place_id": 2, "lat": "33.6617962", "lon": "-95.5555130", "display_name": "Paris, Texas"}
+    ]"#;
+
+    let place = parse_search_response(body).expect("first result");
+    assert!((place.latitude - 48.8588897).abs() < 1e-9);
+    assert!((place.longitude - 2.3200410).abs() < 1e-9);
+    assert_eq!(place.display_name, "Paris, France");
+}
+
+#[test]
+fn rejects_empty_or_malformed_responses() {
+    assert!(matches!(
+        parse_search_response("[]"),
+        Err(GeocodeError::NoResults)
+    ));
+    assert!(matches!(
+        parse_search_response("<html>rate limited</html>"),
+        Err(GeocodeError::InvalidResponse(_))
+    ));
+    assert!(matches!(
+        parse_search_response(r#"[{"lat": "123.0", "lon": "0.0"}]"#),
+        Err(GeocodeError::InvalidResponse(_))
+    ));
+}
+
+#[test]
+fn search_url_encodes_the_query() {
+    let url = search_url("  Pike Place Market, Seattle ").expect("url");
+    assert!(url.starts_with("https://nominatim.openstreetmap.org/search?"));
+    assert!(url.contains("q=Pike+Place+Market%2C+Seattle"));
+    assert!(url.contains("format=json"));
+    assert!(url.contains("limit=1"));
+
+    assert!(matches!(search_url("   "), Err(GeocodeError::EmptyQuery)));
+}
+
+#[test]
+fn reverse_response_prefers_locality_and_country() {
+    let body = r#"{
+        "display_name": "Paris, Ile-de-France, Metropolitan France, France",
+        "address": {"city": "Paris", "state": "Ile-de-France", "country": "France"}
+    }"#;
+    assert_eq!(
+        parse_reverse_response(body).expect("label"),
+        "Paris, France"
+    );
+
+    let village =
+        r#"{"display_name": "x", "address": {"village": "Giverny", "country": "France"}}"#;
+    assert_eq!(
+        parse_reverse_response(village).expect("label"),
+        "Giverny, France"
+    );
+
+    let ocean = r#"{"display_name": "North Atlantic Ocean", "address": {}}"#;
+    assert_eq!(
+        parse_reverse_response(ocean).expect("label"),
+        "North Atlantic Ocean"
+    );
+
+    assert!(matches!(
+        parse_reverse_response(r#"{"error": "Unable to geocode"}"#),
+        Err(GeocodeError::NoResults)
+    ));
+}
+
+#[test]
+fn nearby_coordinates_share_a_cache_key() {
+    assert_eq!(
+        coordinate_key(48.8588897, 2.3200410),
+        coordinate_key(48.8588901, 2.3200398)
+    );
+    assert_ne!(
+        coordinate_key(48.85889, 2.32004),
+        coordinate_key(48.85899, 2.32004)
+    );
+    assert!(reverse_url(48.8588897, 2.320041).contains("lat=48.858890&lon=2.320041"));
+}
@@ -0,0 +1,46 @@
+use exif_editor::core::hex::{self, HEX_PAGE_BYTES};
+
+#[test]
+fn hex_page_formats_a_window_from_the_middle_of_a_blob() {
+    let blob = (0..=255u8)
+        .cycle()
+        .take(4 * 1024 * 1024)
+        .collect::<Vec<_>>();
+
+    let page = hex::hex_page(&blob, 0x200040, 20);
+    let lines = page.lines().collect::<Vec<_>>();
+
+    assert_eq!(lines.len(), 2);
+    assert_eq!(
+        lines[0],
+        "00200040  40 41 42 43 44 45 46 47 48 49 4a 4b 4c 4d 4e 4f  |@ABCDEFGHIJKLMNO|"
+    );
+    assert_eq!(
+        lines[1],
+        "00200050  50 51 52 53                                      |PQRS|"
+    );
+}
+
+#[test]
+fn hex_page_clamps_to_the_end_of_the_blob() {
+    let blob = [0x00, 0x7f, b'a', 0xff];
+
+    assert_eq!(
+        hex::hex_page(&blob, 2, HEX_PAGE_BYTES),
+        "00000002  61 ff                                            |a.|"
+    );
+    assert_eq!(hex::hex_page(&blob, 10, 16), "");
+}
+
+#[test]
+fn step_page_stays_within_the_blob() {
+    let total = HEX_PAGE_BYTES * 3 + 10;
+
+    assert_eq!(hex::step_page(0, -1, total), 0);
+    assert_eq!(hex::step_page(0, 1, total), HEX_PAGE_BYTES);
+    assert_eq!(
+        hex::step_page(HEX_PAGE_BYTES * 3, 1, total),
+        HEX_PAGE_BYTES * 3
+    );
+    assert_eq!(hex::step_page(HEX_PAGE_BYTES * 2, -5, total), 0);
+}
@@ -0,0 +1,81 @@
+use std::fs;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use exif_editor::core::metadata::MetadataEngine;
+use exif_editor::logging;
+use log::{Log, Metadata, Record};
+
+struct TestLogger {
+    lines: Mutex<Vec<String>>,
+}
+
+impl Log for TestLogger {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record<'_>) {
+        if self.enabled(record.metadata()) {
+            self.lines
+                .lock()
+                .expect("logger lock should not be poisoned")
+                .push(logging::format_record(record));
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: TestLogger = TestLogger {
+    lines: Mutex::new(Vec::new()),
+};
+
+fn read_record() -> Record<'static> {
+    Record::builder()
+        .level(log::Level::Info)
+        .target("exif_editor")
+        .args(format_args!("op=read path=a.jpg"))
+        .build()
+}
+
+#[test]
+fn write_operation_emits_structured_log_line() {
+    log::set_logger(&LOGGER).expect("test logger should install once");
+
+    let stamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time should be after unix epoch")
+        .as_nanos();
+    let path = std::env::temp_dir().join(format!("exif_editor_logging_{stamp}.png"));
+    fs::write(&path, b"logging").expect("should create file");
+    let log_path = std::env::temp_dir()
+        .join(format!("exif_editor_log_dir_{stamp}"))
+        .join("exif_editor.log");
+    let file_logger = logging::FileLogger::new(&log_path);
+
+    logging::set_enabled(false);
+    let metadata = MetadataEngine::read(&path).expect("read should succeed");
+    assert!(LOGGER.lines.lock().unwrap().is_empty());
+    file_logger.log(&read_record());
+    assert!(!log_path.exists());
+
+    logging::set_enabled(true);
+    assert!(logging::is_enabled());
+    MetadataEngine::write(&path, &metadata).expect("write should succeed");
+
+    let lines = LOGGER.lines.lock().unwrap().clone();
+    let expected_path = format!("path={}", path.display());
+    assert!(lines.iter().any(|line| line.contains("level=INFO")
+        && line.contains("op=write")
+        && line.contains(&expected_path)));
+    file_logger.log(&read_record());
+    file_logger.flush();
+    let contents = fs::read_to_string(&log_path).expect("log file should exist once enabled");
+    assert!(contents.contains("op=read path=a.jpg"));
+
+    logging::set_enabled(false);
+    let _ = fs::remove_dir_all(log_path.parent().expect("log path has a parent"));
+    let _ = fs::remove_file(&path);
+    let _ = fs::remove_file(MetadataEngine::sidecar_path(&path));
+}
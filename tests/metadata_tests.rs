@@ -1,9 +1,18 @@
 use std::path::Path;
-use exif_editor::core::metadata::MetadataEngine;
+use exif_editor::core::{catalog, enum_labels};
+use exif_editor::core::metadata::{
+    decimal_to_dms, dms_to_decimal, format_altitude, format_dms_coordinate, format_metadata_report,
+    parse_altitude, parse_dms_coordinate, AltitudeUnit, MergeStrategy, MetadataEngine,
+    MetadataError, SidecarPolicy, GPS_COORDINATES_KEY, SIDECAR_VERSION,
+};
 use exif_editor::models::{
-    MetadataTag, PhotoMetadata, PresetRule, StripPreset, TagCategory, TagValue,
+    compute_aspect_ratio, compute_megapixels, format_rational_for_key, validate_exif_datetime,
+    Dimensions, ImageFormat, MetadataTag, MetadataWarning, PhotoEntry, PhotoMetadata, PresetRule,
+    StripPreset, TagCategory, TagValue, PROVENANCE_APP,
 };
 
+mod common;
+
 #[test]
 fn remove_gps_rule_drops_location_tags() {
     let mut metadata = PhotoMetadata {
@@ -27,6 +36,8 @@ fn remove_gps_rule_drops_location_tags() {
         date_taken: None,
         camera_make: None,
         camera_model: None,
+        dimensions: None,
+        provenance: Default::default(),
     };
 
     let preset = StripPreset::new(
@@ -73,6 +84,8 @@ fn remove_all_except_keeps_only_allowed_keys() {
         date_taken: None,
         camera_make: None,
         camera_model: None,
+        dimensions: None,
+        provenance: Default::default(),
     };
 
     let preset = StripPreset::new(
@@ -107,6 +120,8 @@ fn set_tag_updates_existing_and_inserts_new() {
         date_taken: None,
         camera_make: None,
         camera_model: None,
+        dimensions: None,
+        provenance: Default::default(),
     };
 
     MetadataEngine::set_tag_in_metadata(
@@ -129,6 +144,53 @@ fn set_tag_updates_existing_and_inserts_new() {
         .any(|tag| tag.key == "Exif.Image.Copyright"));
 }
 
+#[test]
+fn read_reports_dimensions_from_width_and_height_tags() {
+    let path = common::jpeg_fixture("dimensions");
+
+    // Either pair gives the dimensions: TIFF-style width and height, or the
+    // pixel dimensions cameras write. Nothing comes from a sidecar.
+    for (width_key, height_key) in [
+        ("Exif.Image.ImageWidth", "Exif.Image.ImageHeight"),
+        ("Exif.Photo.PixelXDimension", "Exif.Photo.PixelYDimension"),
+    ] {
+        let metadata = PhotoMetadata {
+            exif_tags: vec![
+                MetadataTag::new(
+                    width_key,
+                    width_key,
+                    TagValue::Integer(4000),
+                    TagCategory::Image,
+                ),
+                MetadataTag::new(
+                    height_key,
+                    height_key,
+                    TagValue::Integer(3000),
+                    TagCategory::Image,
+                ),
+            ],
+            ..PhotoMetadata::default()
+        };
+        MetadataEngine::write_with_sidecars(&path, &metadata, false).expect("write should succeed");
+        assert!(!MetadataEngine::sidecar_path(&path).exists());
+
+        let read_back = MetadataEngine::read(&path).expect("read should succeed");
+        assert_eq!(
+            read_back.dimensions,
+            Some(Dimensions {
+                width: 4000,
+                height: 3000
+            })
+        );
+        assert_eq!(
+            read_back
+                .dimensions
+                .map(|dimensions| dimensions.megapixels()),
+            Some(12.0)
+        );
+    }
+}
+
 #[test]
 fn read_heic_extracts_full_exif() {
     let path = Path::new("demo_images/IMG_0205.HEIC");
@@ -193,3 +255,1381 @@ fn read_heic_extracts_full_exif() {
         metadata.has_gps
     );
 }
+
+#[test]
+fn exif_datetime_validation_rejects_malformed_values() {
+    assert_eq!(
+        validate_exif_datetime(" 2024:02:29 23:59:59 "),
+        Ok(String::from("2024:02:29 23:59:59"))
+    );
+
+    for (raw, expected) in [
+        ("99:99:99", "Expected YYYY:MM:DD HH:MM:SS"),
+        ("2024-01-02 03:04:05", "Expected YYYY:MM:DD HH:MM:SS"),
+        ("2024:1:02 03:04:05", "Expected YYYY:MM:DD HH:MM:SS"),
+        ("2024:01:02 03:04", "Expected YYYY:MM:DD HH:MM:SS"),
+        ("2024:13:02 03:04:05", "Month must be 01-12, got 13"),
+        ("2023:02:29 03:04:05", "Day 29 is not valid for 2023:02"),
+        ("2024:01:02 24:00:00", "Hour must be 00-23, got 24"),
+        ("2024:01:02 12:60:00", "Minute must be 00-59, got 60"),
+        ("2024:01:02 12:00:99", "Second must be 00-59, got 99"),
+    ] {
+        assert_eq!(
+            validate_exif_datetime(raw),
+            Err(String::from(expected)),
+            "{raw}"
+        );
+    }
+}
+
+#[test]
+fn dms_coordinates_round_trip_round_values_exactly() {
+    for (latitude, longitude) in [(40.5, -73.25), (-33.75, 151.0), (0.0, 0.125)] {
+        let lat_text = format_dms_coordinate(latitude, true);
+        let lon_text = format_dms_coordinate(longitude, false);
+
+        assert_eq!(parse_dms_coordinate(&lat_text, true), Some(latitude));
+        assert_eq!(parse_dms_coordinate(&lon_text, false), Some(longitude));
+    }
+
+    assert_eq!(format_dms_coordinate(40.5, true), "40°30'0\"N");
+    assert_eq!(format_dms_coordinate(-73.25, false), "73°15'0\"W");
+}
+
+#[test]
+fn dms_parsing_accepts_field_note_formats() {
+    let expected = dms_to_decimal(40.0, 26.0, 46.0);
+
+    assert_eq!(parse_dms_coordinate("40°26'46\"N", true), Some(expected));
+    assert_eq!(parse_dms_coordinate("N 40 26 46", true), Some(expected));
+    assert_eq!(parse_dms_coordinate("40°26'46\"S", true), Some(-expected));
+    assert_eq!(parse_dms_coordinate("-40 26 46", true), Some(-expected));
+    assert_eq!(
+        parse_dms_coordinate("79°58'56.5\"W", false),
+        Some(-dms_to_decimal(79.0, 58.0, 56.5))
+    );
+
+    assert_eq!(parse_dms_coordinate("40°26'46\"E", true), None);
+    assert_eq!(parse_dms_coordinate("40°61'00\"N", true), None);
+    assert_eq!(parse_dms_coordinate("-40°26'46\"N", true), None);
+    assert_eq!(parse_dms_coordinate("north", true), None);
+}
+
+#[test]
+fn edited_tags_carry_provenance_through_the_sidecar() {
+    let path =
+        std::env::temp_dir().join(format!("exif_editor_provenance_{}.jpg", std::process::id()));
+    std::fs::write(&path, b"provenance").expect("should create file");
+
+    let mut metadata = PhotoMetadata::default();
+    MetadataEngine::set_tag_in_metadata(
+        &mut metadata,
+        "Exif.Image.Artist",
+        TagValue::Text(String::from("Jo")),
+    );
+    MetadataEngine::write(&path, &metadata).expect("write should succeed");
+
+    let read_back = MetadataEngine::read(&path).expect("read should succeed");
+    let provenance = read_back
+        .provenance_for("exif.image.artist")
+        .expect("edited tag should have provenance");
+    assert_eq!(provenance.set_by, PROVENANCE_APP);
+    assert!(chrono::DateTime::parse_from_rfc3339(&provenance.set_at).is_ok());
+    assert!(read_back.provenance_for("Exif.Image.Make").is_none());
+
+    let mut stripped = read_back.clone();
+    MetadataEngine::remove_tags_by_key(&mut stripped, &[String::from("Exif.Image.Artist")]);
+    assert!(stripped.provenance.is_empty());
+
+    // Sidecars written before provenance existed have no `provenance` field.
+    let legacy = r#"{
+        "exif_tags": [{
+            "key": "Exif.Image.Make",
+            "display_name": "Make",
+            "value": {"Text": "Canon"},
+            "category": "Camera",
+            "editable": true,
+            "marked_for_removal": false
+        }],
+        "iptc_tags": [],
+        "xmp_tags": [],
+        "has_gps": false,
+        "date_taken": null,
+        "camera_make": "Canon",
+        "camera_model": null,
+        "dimensions": null
+    }"#;
+    std::fs::write(MetadataEngine::sidecar_path(&path), legacy).expect("should write sidecar");
+    let legacy_metadata = MetadataEngine::read(&path).expect("legacy sidecar should load");
+    assert!(legacy_metadata.provenance.is_empty());
+    assert!(legacy_metadata.find_tag("Exif.Image.Make").is_some());
+
+    let _ = std::fs::remove_file(&path);
+    let _ = std::fs::remove_file(MetadataEngine::sidecar_path(&path));
+}
+
+#[test]
+fn tag_counts_report_each_block_separately() {
+    let text = |key: &str| {
+        MetadataTag::new(
+            key,
+            key,
+            TagValue::Text(String::from("x")),
+            TagCategory::Other,
+        )
+    };
+    let metadata = PhotoMetadata {
+        exif_tags: vec![
+            text("Exif.Image.Make"),
+            text("Exif.Image.Model"),
+            text("Exif.Image.Artist"),
+        ],
+        iptc_tags: vec![text("Iptc.Application2.Keywords")],
+        xmp_tags: vec![text("Xmp.dc.title"), text("Xmp.dc.creator")],
+        ..PhotoMetadata::default()
+    };
+
+    assert_eq!(metadata.tag_counts(), (3, 1, 2));
+    assert_eq!(metadata.total_tag_count(), 6);
+    assert_eq!(PhotoMetadata::default().tag_counts(), (0, 0, 0));
+}
+
+#[test]
+fn rational_capture_settings_read_like_a_camera_display() {
+    assert_eq!(
+        format_rational_for_key("Exif.Photo.FNumber", 28, 10),
+        "f/2.8"
+    );
+    assert_eq!(format_rational_for_key("Exif.Photo.FNumber", 8, 1), "f/8.0");
+    assert_eq!(
+        format_rational_for_key("Exif.Photo.ExposureTime", 1, 60),
+        "1/60s"
+    );
+    assert_eq!(
+        format_rational_for_key("Exif.Photo.ExposureTime", 10, 1250),
+        "1/125s"
+    );
+    assert_eq!(
+        format_rational_for_key("Exif.Photo.ExposureTime", 2, 1),
+        "2s"
+    );
+    assert_eq!(
+        format_rational_for_key("Exif.Photo.ExposureTime", 13, 10),
+        "1.3s"
+    );
+    assert_eq!(
+        format_rational_for_key("Exif.Photo.FocalLength", 50, 1),
+        "50 mm"
+    );
+    assert_eq!(
+        format_rational_for_key("Exif.Photo.FocalLength", 185, 10),
+        "18.5 mm"
+    );
+
+    assert_eq!(format_rational_for_key("Exif.Photo.FNumber", 28, 0), "");
+    assert_eq!(format_rational_for_key("Exif.Image.XResolution", 72, 1), "");
+}
+
+#[test]
+fn altitude_formats_in_meters_and_feet_at_any_precision() {
+    assert_eq!(format_altitude(100.0, AltitudeUnit::Meters, 2), "100.00");
+    assert_eq!(format_altitude(1234.567, AltitudeUnit::Meters, 1), "1234.6");
+    assert_eq!(format_altitude(100.0, AltitudeUnit::Feet, 2), "328.08");
+    assert_eq!(format_altitude(8848.86, AltitudeUnit::Feet, 0), "29032");
+    assert_eq!(format_altitude(-12.4, AltitudeUnit::Meters, 0), "-12");
+
+    let meters = parse_altitude("328.084", AltitudeUnit::Feet).expect("feet should parse");
+    assert!((meters - 100.0).abs() < 1e-3);
+    assert_eq!(parse_altitude(" 42 ", AltitudeUnit::Meters), Some(42.0));
+    assert_eq!(parse_altitude("high", AltitudeUnit::Meters), None);
+}
+
+#[test]
+fn zero_rational_denominators_read_as_whole_numbers() {
+    let path = common::jpeg_fixture("zero_denominator");
+
+    let metadata = PhotoMetadata {
+        exif_tags: vec![MetadataTag::new(
+            "Exif.Photo.FNumber",
+            "F-Number",
+            TagValue::Rational(28, 0),
+            TagCategory::Camera,
+        )],
+        ..PhotoMetadata::default()
+    };
+    MetadataEngine::write(&path, &metadata).expect("write should succeed");
+    // Read the embedded EXIF, not the sidecar that mirrors what was written.
+    let _ = std::fs::remove_file(MetadataEngine::sidecar_path(&path));
+
+    let read_back = MetadataEngine::read(&path).expect("read should succeed");
+    let f_number = read_back
+        .find_tag("Exif.Photo.FNumber")
+        .expect("FNumber should survive the round trip");
+    assert_eq!(f_number.value, TagValue::Rational(28, 1));
+}
+
+#[test]
+fn stripping_a_photo_whose_exif_cannot_be_cleared_fails_and_keeps_it() {
+    let path = common::unique_path("unclearable", "png");
+    std::fs::write(&path, common::encoded_image(image::ImageFormat::Png))
+        .expect("should write png");
+    let tagged = PhotoMetadata {
+        exif_tags: vec![MetadataTag::new(
+            "Exif.Image.Artist",
+            "Artist",
+            TagValue::Text(String::from("Jane Doe")),
+            TagCategory::Description,
+        )],
+        ..PhotoMetadata::default()
+    };
+    MetadataEngine::write(&path, &tagged).expect("write should succeed");
+
+    // A damaged checksum on the last chunk makes the PNG unparseable.
+    let mut bytes = std::fs::read(&path).expect("should read png");
+    *bytes.last_mut().unwrap() ^= 0xFF;
+    std::fs::write(&path, &bytes).expect("should damage png");
+
+    assert!(MetadataEngine::write(&path, &PhotoMetadata::default()).is_err());
+    assert_eq!(std::fs::read(&path).unwrap(), bytes);
+
+    let _ = std::fs::remove_file(MetadataEngine::sidecar_path(&path));
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn exif_that_cannot_be_embedded_fails_the_write_without_a_sidecar() {
+    let path = common::unique_path("unembeddable", "jpg");
+    std::fs::write(&path, b"not really a jpeg").expect("should create file");
+    let tagged = PhotoMetadata {
+        exif_tags: vec![MetadataTag::new(
+            "Exif.Image.Artist",
+            "Artist",
+            TagValue::Text(String::from("Jane Doe")),
+            TagCategory::Description,
+        )],
+        ..PhotoMetadata::default()
+    };
+
+    assert!(MetadataEngine::write_with_sidecars(&path, &tagged, false).is_err());
+    assert_eq!(std::fs::read(&path).unwrap(), b"not really a jpeg");
+
+    // With a sidecar the edits still land somewhere, so the write goes through.
+    MetadataEngine::write_with_sidecars(&path, &tagged, true).expect("sidecar should carry it");
+    let read_back = MetadataEngine::read(&path).expect("read should succeed");
+    assert!(read_back.find_tag("Exif.Image.Artist").is_some());
+
+    let _ = std::fs::remove_file(MetadataEngine::sidecar_path(&path));
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn dirty_flag_stays_correct_across_many_edits_to_a_large_tag_set() {
+    let metadata = PhotoMetadata {
+        exif_tags: (0..5_000)
+            .map(|index| {
+                MetadataTag::new(
+                    format!("Exif.Test.Tag{index}"),
+                    format!("Tag {index}"),
+                    TagValue::Integer(index),
+                    TagCategory::Other,
+                )
+            })
+            .collect(),
+        ..PhotoMetadata::default()
+    };
+    let mut photo = PhotoEntry::from_path(1, "large.jpg".into(), ImageFormat::Jpeg);
+    photo.set_loaded_metadata(metadata);
+
+    for round in 0..200 {
+        let key = format!("Exif.Test.Tag{}", round * 25);
+        let tag = photo.metadata.find_tag_mut(&key).expect("tag should exist");
+        let original = tag.value.clone();
+
+        tag.value = TagValue::Integer(-1);
+        photo.recompute_dirty();
+        assert!(photo.dirty, "editing {key} should mark the photo dirty");
+
+        photo
+            .metadata
+            .find_tag_mut(&key)
+            .expect("tag should exist")
+            .value = original;
+        photo.recompute_dirty();
+        assert!(!photo.dirty, "restoring {key} should clear the dirty flag");
+    }
+
+    // Tag order is part of what gets written, so a reorder counts as an edit.
+    photo.metadata.exif_tags.swap(0, 1);
+    photo.recompute_dirty();
+    assert!(photo.dirty);
+
+    let saved = photo.metadata.clone();
+    photo.persisted_metadata = saved;
+    photo.recompute_dirty();
+    assert!(!photo.dirty);
+}
+
+#[test]
+fn photo_modified_after_its_sidecar_reads_the_embedded_values() {
+    let path = common::jpeg_fixture("stale_sidecar");
+    let with_artist = |artist: &str| PhotoMetadata {
+        exif_tags: vec![MetadataTag::new(
+            "Exif.Image.Artist",
+            "Artist",
+            TagValue::Text(String::from(artist)),
+            TagCategory::Other,
+        )],
+        ..PhotoMetadata::default()
+    };
+    let artist = |metadata: &PhotoMetadata| {
+        metadata
+            .find_tag("Exif.Image.Artist")
+            .map(|tag| tag.value.to_string())
+    };
+
+    // Leave a sidecar from an earlier save behind, then have "another app"
+    // change the embedded value after it.
+    let sidecar = MetadataEngine::sidecar_path(&path);
+    MetadataEngine::write(&path, &with_artist("Before")).expect("write should succeed");
+    let old_sidecar = std::fs::read(&sidecar).expect("sidecar should exist");
+    MetadataEngine::write(&path, &with_artist("After")).expect("write should succeed");
+    std::fs::write(&sidecar, old_sidecar).expect("should restore sidecar");
+    let an_hour_ago = std::time::SystemTime::now() - std::time::Duration::from_secs(3600);
+    std::fs::File::options()
+        .write(true)
+        .open(&sidecar)
+        .and_then(|file| file.set_modified(an_hour_ago))
+        .expect("should age sidecar");
+
+    let read = MetadataEngine::read(&path).expect("read should succeed");
+    assert_eq!(artist(&read), Some(String::from("After")));
+    let forced = MetadataEngine::read_with_policy(&path, SidecarPolicy::Always)
+        .expect("read should succeed");
+    assert_eq!(artist(&forced), Some(String::from("Before")));
+}
+
+#[test]
+fn scene_type_round_trips_as_a_single_undef_byte() {
+    let path = common::jpeg_fixture("scene_type");
+
+    let metadata = PhotoMetadata {
+        exif_tags: vec![
+            MetadataTag::new(
+                "Exif.Photo.SceneType",
+                "Scene Type",
+                TagValue::Integer(1),
+                TagCategory::Capture,
+            ),
+            MetadataTag::new(
+                "Exif.Photo.FileSource",
+                "File Source",
+                TagValue::Integer(3),
+                TagCategory::Capture,
+            ),
+        ],
+        ..PhotoMetadata::default()
+    };
+    MetadataEngine::write_with_sidecars(&path, &metadata, false).expect("write should succeed");
+
+    let read_back = MetadataEngine::read(&path).expect("read should succeed");
+    let value = |key: &str| read_back.find_tag(key).map(|tag| tag.value.clone());
+    assert_eq!(value("Exif.Photo.SceneType"), Some(TagValue::Integer(1)));
+    assert_eq!(value("Exif.Photo.FileSource"), Some(TagValue::Integer(3)));
+    assert_eq!(
+        enum_labels::label_for("Exif.Photo.SceneType", 1),
+        Some("Directly photographed")
+    );
+}
+
+#[test]
+fn read_warnings_tell_missing_exif_from_unreadable_files() {
+    let read = |path: &Path| {
+        MetadataEngine::read_with_warnings(path, SidecarPolicy::Ignore)
+            .expect("read should fall back rather than fail")
+    };
+
+    let valid = common::jpeg_fixture("warnings_valid");
+    let artist = PhotoMetadata {
+        exif_tags: vec![MetadataTag::new(
+            "Exif.Image.Artist",
+            "Artist",
+            TagValue::Text(String::from("Jane")),
+            TagCategory::Other,
+        )],
+        ..PhotoMetadata::default()
+    };
+    MetadataEngine::write_with_sidecars(&valid, &artist, false).expect("write should succeed");
+    let (metadata, warnings) = read(&valid);
+    assert!(warnings.is_empty());
+    assert!(metadata.find_tag("Exif.Image.Artist").is_some());
+
+    let empty = common::jpeg_fixture("warnings_empty");
+    assert_eq!(read(&empty).1, vec![MetadataWarning::NoExif]);
+
+    let corrupt = common::unique_path("warnings_corrupt", "jpg");
+    std::fs::write(&corrupt, b"not really a jpeg").expect("should write file");
+    let (metadata, warnings) = read(&corrupt);
+    assert!(matches!(warnings.as_slice(), [MetadataWarning::Unreadable(_)]));
+    assert!(metadata.find_tag("ExifEditor.FileName").is_some());
+
+    let _ = std::fs::remove_file(&corrupt);
+}
+
+#[test]
+fn computed_fields_reduce_the_aspect_ratio_and_count_megapixels() {
+    assert_eq!(compute_aspect_ratio(6000, 4000), "3:2");
+    assert_eq!(compute_megapixels(6000, 4000), "24.0 MP");
+    assert_eq!(compute_aspect_ratio(1920, 1080), "16:9");
+    assert_eq!(compute_aspect_ratio(4000, 6000), "2:3");
+    assert_eq!(compute_megapixels(6024, 4024), "24.2 MP");
+}
+
+#[test]
+fn synthesized_date_taken_uses_the_exif_datetime_format() {
+    let path = std::env::temp_dir().join(format!(
+        "exif_editor_synthesized_date_{}.png",
+        std::process::id()
+    ));
+    std::fs::write(&path, b"no metadata here").expect("should write file");
+
+    let metadata = MetadataEngine::read_with_policy(&path, SidecarPolicy::Ignore)
+        .expect("read should fall back to file details");
+    let date = metadata
+        .find_tag("Exif.Photo.DateTimeOriginal")
+        .expect("date taken should be synthesized")
+        .value
+        .to_string();
+    assert_eq!(validate_exif_datetime(&date), Ok(date.clone()));
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn lens_and_owner_text_tags_survive_a_write_cycle() {
+    let path = common::jpeg_fixture("lens_tags");
+
+    let text_tags = [
+        ("Exif.Photo.LensMake", "Lens Make", "Canon"),
+        ("Exif.Photo.LensModel", "Lens Model", "EF 50mm f/1.8 STM"),
+        ("Exif.Photo.LensSerialNumber", "Lens Serial Number", "LS42"),
+        ("Exif.Photo.OwnerName", "Owner Name", "Jane Doe"),
+        ("Exif.Photo.SerialNumber", "Serial Number", "SN-1234"),
+    ];
+    let metadata = PhotoMetadata {
+        exif_tags: text_tags
+            .iter()
+            .map(|&(key, name, value)| {
+                MetadataTag::new(
+                    key,
+                    name,
+                    TagValue::Text(String::from(value)),
+                    TagCategory::Camera,
+                )
+            })
+            .collect(),
+        ..PhotoMetadata::default()
+    };
+    MetadataEngine::write_with_sidecars(&path, &metadata, false).expect("write should succeed");
+
+    let read_back = MetadataEngine::read(&path).expect("read should succeed");
+    for (key, _, value) in text_tags {
+        assert_eq!(
+            read_back.find_tag(key).map(|tag| tag.value.clone()),
+            Some(TagValue::Text(String::from(value))),
+            "{key} should read back"
+        );
+    }
+}
+
+#[test]
+fn offset_time_original_survives_a_write_cycle() {
+    let path = common::jpeg_fixture("offset_time");
+
+    let text = |value: &str| TagValue::Text(String::from(value));
+    let metadata = PhotoMetadata {
+        exif_tags: vec![
+            MetadataTag::new(
+                "Exif.Photo.OffsetTimeOriginal",
+                "Offset Time Original",
+                text("+02:00"),
+                TagCategory::DateTime,
+            ),
+            MetadataTag::new(
+                "Exif.Photo.SubSecTimeOriginal",
+                "Sub Sec Time Original",
+                text("125"),
+                TagCategory::DateTime,
+            ),
+        ],
+        ..PhotoMetadata::default()
+    };
+    MetadataEngine::write_with_sidecars(&path, &metadata, false).expect("write should succeed");
+
+    let read_back = MetadataEngine::read(&path).expect("read should succeed");
+    let value = |key: &str| read_back.find_tag(key).map(|tag| tag.value.clone());
+    assert_eq!(value("Exif.Photo.OffsetTimeOriginal"), Some(text("+02:00")));
+    assert_eq!(value("Exif.Photo.SubSecTimeOriginal"), Some(text("125")));
+    assert!(catalog::lookup("Exif.Photo.OffsetTimeOriginal").is_some());
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn tags_removed_from_the_metadata_are_dropped_from_the_embedded_exif() {
+    let path = common::jpeg_fixture("stale_exif");
+
+    let text = |value: &str| TagValue::Text(String::from(value));
+    let mut metadata = PhotoMetadata::default();
+    MetadataEngine::set_tag_in_metadata(&mut metadata, "Exif.Image.Artist", text("Jane"));
+    MetadataEngine::set_tag_in_metadata(&mut metadata, "Exif.Image.Make", text("Canon"));
+    MetadataEngine::set_tag_in_metadata(
+        &mut metadata,
+        GPS_COORDINATES_KEY,
+        TagValue::Gps(48.8566, 2.3522, None),
+    );
+    MetadataEngine::write_with_sidecars(&path, &metadata, false).expect("write should succeed");
+
+    // Removing one tag leaves the others, including the GPS fields it
+    // doesn't touch, in the file.
+    let mut metadata = MetadataEngine::read(&path).expect("read should succeed");
+    MetadataEngine::remove_tags_by_key(&mut metadata, &[String::from("Exif.Image.Artist")]);
+    MetadataEngine::write_with_sidecars(&path, &metadata, false).expect("write should succeed");
+    let read_back = MetadataEngine::read(&path).expect("read should succeed");
+    assert!(read_back.find_tag("Exif.Image.Artist").is_none());
+    assert!(read_back.find_tag("Exif.Image.Make").is_some());
+    assert!(read_back.find_tag(GPS_COORDINATES_KEY).is_some());
+
+    let mut metadata = read_back;
+    MetadataEngine::remove_tags_by_key(&mut metadata, &[String::from(GPS_COORDINATES_KEY)]);
+    MetadataEngine::write_with_sidecars(&path, &metadata, false).expect("write should succeed");
+    let read_back = MetadataEngine::read(&path).expect("read should succeed");
+    assert!(read_back.find_tag(GPS_COORDINATES_KEY).is_none());
+    assert!(read_back.find_tag("Exif.Image.Make").is_some());
+}
+
+fn merge_fixture() -> (PhotoMetadata, Vec<(String, TagValue)>) {
+    let text = |value: &str| TagValue::Text(String::from(value));
+    let mut dest = PhotoMetadata::default();
+    for (key, value) in [
+        ("Exif.Image.Artist", "Jane"),
+        ("Exif.Image.Copyright", "  "),
+        ("Exif.Image.Make", "Canon"),
+    ] {
+        MetadataEngine::set_tag_in_metadata(&mut dest, key, text(value));
+    }
+    let src = [
+        ("Exif.Image.Artist", "Joe"),
+        ("Exif.Image.Copyright", "CC-BY"),
+        ("Exif.Image.Model", "EOS R5"),
+    ]
+    .map(|(key, value)| (String::from(key), text(value)))
+    .to_vec();
+    (dest, src)
+}
+
+fn merged_text(metadata: &PhotoMetadata, key: &str) -> String {
+    metadata
+        .find_tag(key)
+        .map(|tag| tag.value.to_string())
+        .unwrap_or_default()
+}
+
+#[test]
+fn merge_overwrite_all_replaces_every_conflicting_tag() {
+    let (mut dest, src) = merge_fixture();
+    let applied = MetadataEngine::merge_metadata(&mut dest, &src, MergeStrategy::OverwriteAll);
+
+    assert_eq!(applied, 3);
+    assert_eq!(merged_text(&dest, "Exif.Image.Artist"), "Joe");
+    assert_eq!(merged_text(&dest, "Exif.Image.Copyright"), "CC-BY");
+    assert_eq!(merged_text(&dest, "Exif.Image.Model"), "EOS R5");
+    assert_eq!(merged_text(&dest, "Exif.Image.Make"), "Canon");
+}
+
+#[test]
+fn merge_fill_empty_only_replaces_blank_values() {
+    let (mut dest, src) = merge_fixture();
+    let applied = MetadataEngine::merge_metadata(&mut dest, &src, MergeStrategy::FillEmpty);
+
+    assert_eq!(applied, 2);
+    assert_eq!(merged_text(&dest, "Exif.Image.Artist"), "Jane");
+    assert_eq!(merged_text(&dest, "Exif.Image.Copyright"), "CC-BY");
+    assert_eq!(merged_text(&dest, "Exif.Image.Model"), "EOS R5");
+}
+
+#[test]
+fn merge_keep_existing_only_adds_missing_tags() {
+    let (mut dest, src) = merge_fixture();
+    let applied = MetadataEngine::merge_metadata(&mut dest, &src, MergeStrategy::KeepExisting);
+
+    assert_eq!(applied, 1);
+    assert_eq!(merged_text(&dest, "Exif.Image.Artist"), "Jane");
+    assert_eq!(merged_text(&dest, "Exif.Image.Copyright"), "  ");
+    assert_eq!(merged_text(&dest, "Exif.Image.Model"), "EOS R5");
+}
+
+#[test]
+fn read_tags_keep_their_exif_layout_order() {
+    let path = common::jpeg_fixture("tag_order");
+
+    // Listed against the layout: Make lives in IFD0, ahead of the Exif sub-IFD.
+    let metadata = PhotoMetadata {
+        exif_tags: vec![
+            MetadataTag::new(
+                "Exif.Photo.SerialNumber",
+                "Serial Number",
+                TagValue::Text(String::from("SN-1234")),
+                TagCategory::Camera,
+            ),
+            MetadataTag::new(
+                "Exif.Image.Make",
+                "Make",
+                TagValue::Text(String::from("Canon")),
+                TagCategory::Camera,
+            ),
+        ],
+        ..PhotoMetadata::default()
+    };
+    MetadataEngine::write_with_sidecars(&path, &metadata, false).expect("write should succeed");
+
+    let mut read_back = MetadataEngine::read(&path).expect("read should succeed");
+    let order_of = |metadata: &PhotoMetadata, key: &str| {
+        metadata
+            .find_tag(key)
+            .map(|tag| tag.order)
+            .expect("tag should read back")
+    };
+    let make = order_of(&read_back, "Exif.Image.Make");
+    let serial = order_of(&read_back, "Exif.Photo.SerialNumber");
+    assert!(make < serial, "make={make} serial={serial}");
+
+    MetadataEngine::set_tag_in_metadata(
+        &mut read_back,
+        "Exif.Image.Artist",
+        TagValue::Text(String::from("Jane")),
+    );
+    assert!(order_of(&read_back, "Exif.Image.Artist") > serial);
+    MetadataEngine::set_tag_in_metadata(
+        &mut read_back,
+        "Exif.Image.Make",
+        TagValue::Text(String::from("Nikon")),
+    );
+    assert_eq!(order_of(&read_back, "Exif.Image.Make"), make);
+}
+
+#[test]
+fn adding_gps_over_a_partial_gps_tag_leaves_one_coordinates_tag() {
+    // A sidecar or import can carry a lone position field next to no coordinates.
+    let mut metadata = PhotoMetadata {
+        exif_tags: vec![MetadataTag::new(
+            "Exif.GPSInfo.GPSLatitude",
+            "GPS Latitude",
+            TagValue::Text(String::from("48.8566")),
+            TagCategory::Location,
+        )],
+        ..PhotoMetadata::default()
+    };
+
+    MetadataEngine::set_tag_in_metadata(
+        &mut metadata,
+        GPS_COORDINATES_KEY,
+        TagValue::Gps(48.8566, 2.3522, None),
+    );
+    let gps_keys = |metadata: &PhotoMetadata| {
+        metadata
+            .all_tags()
+            .filter(|tag| tag.key.starts_with("Exif.GPSInfo."))
+            .map(|tag| tag.key.clone())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(gps_keys(&metadata), vec![GPS_COORDINATES_KEY]);
+
+    // Position fields set later are folded into the same tag.
+    MetadataEngine::set_tag_in_metadata(
+        &mut metadata,
+        "Exif.GPSInfo.GPSLongitudeRef",
+        TagValue::Text(String::from("W")),
+    );
+    MetadataEngine::set_tag_in_metadata(
+        &mut metadata,
+        "Exif.GPSInfo.GPSAltitude",
+        TagValue::Float(35.0),
+    );
+    assert_eq!(gps_keys(&metadata), vec![GPS_COORDINATES_KEY]);
+    assert_eq!(
+        metadata.find_tag(GPS_COORDINATES_KEY).map(|tag| &tag.value),
+        Some(&TagValue::Gps(48.8566, -2.3522, Some(35.0)))
+    );
+}
+
+#[test]
+fn a_lone_position_field_never_invents_the_other_half() {
+    let mut metadata = PhotoMetadata::default();
+    let latitude = TagValue::Float(48.8566);
+
+    assert!(matches!(
+        MetadataEngine::check_tag_edit(&metadata, "Exif.GPSInfo.GPSLatitude", &latitude),
+        Err(MetadataError::PartialGps(_))
+    ));
+    MetadataEngine::set_tag_in_metadata(&mut metadata, "Exif.GPSInfo.GPSLatitude", latitude);
+    assert_eq!(metadata, PhotoMetadata::default());
+
+    // A full position is always accepted, and fields fold into it after.
+    let full = TagValue::Gps(1.0, 2.3522, None);
+    assert!(MetadataEngine::check_tag_edit(&metadata, GPS_COORDINATES_KEY, &full).is_ok());
+    MetadataEngine::set_tag_in_metadata(&mut metadata, GPS_COORDINATES_KEY, full);
+    let latitude = TagValue::Float(48.8566);
+    assert!(
+        MetadataEngine::check_tag_edit(&metadata, "Exif.GPSInfo.GPSLatitude", &latitude).is_ok()
+    );
+    MetadataEngine::set_tag_in_metadata(&mut metadata, "Exif.GPSInfo.GPSLatitude", latitude);
+    assert_eq!(
+        metadata.find_tag(GPS_COORDINATES_KEY).map(|tag| &tag.value),
+        Some(&TagValue::Gps(48.8566, 2.3522, None))
+    );
+}
+
+#[test]
+fn position_fields_keep_the_southern_and_western_hemispheres() {
+    let mut metadata = PhotoMetadata::default();
+    MetadataEngine::set_tag_in_metadata(
+        &mut metadata,
+        GPS_COORDINATES_KEY,
+        TagValue::Gps(-33.8688, -151.2093, Some(-5.0)),
+    );
+
+    for (key, value) in [
+        ("Exif.GPSInfo.GPSLatitude", TagValue::Float(33.9)),
+        ("Exif.GPSInfo.GPSLongitude", TagValue::Text(String::from("151.3"))),
+        ("Exif.GPSInfo.GPSAltitude", TagValue::Float(7.5)),
+    ] {
+        MetadataEngine::set_tag_in_metadata(&mut metadata, key, value);
+    }
+    assert_eq!(
+        metadata.find_tag(GPS_COORDINATES_KEY).map(|tag| &tag.value),
+        Some(&TagValue::Gps(-33.9, -151.3, Some(-7.5)))
+    );
+
+    // A signed entry says which hemisphere it is in.
+    MetadataEngine::set_tag_in_metadata(
+        &mut metadata,
+        GPS_COORDINATES_KEY,
+        TagValue::Gps(48.8566, 2.3522, Some(-7.5)),
+    );
+    MetadataEngine::set_tag_in_metadata(
+        &mut metadata,
+        "Exif.GPSInfo.GPSLatitude",
+        TagValue::Float(-12.5),
+    );
+    MetadataEngine::set_tag_in_metadata(
+        &mut metadata,
+        "Exif.GPSInfo.GPSLongitude",
+        TagValue::Float(-45.0),
+    );
+    assert_eq!(
+        metadata.find_tag(GPS_COORDINATES_KEY).map(|tag| &tag.value),
+        Some(&TagValue::Gps(-12.5, -45.0, Some(-7.5)))
+    );
+}
+
+#[test]
+fn gps_altitude_and_its_reference_survive_a_write_cycle() {
+    let path = common::jpeg_fixture("altitude");
+
+    for altitude in [-12.34, 250.5, 8848.861, 0.0] {
+        let mut metadata = PhotoMetadata::default();
+        MetadataEngine::set_tag_in_metadata(
+            &mut metadata,
+            GPS_COORDINATES_KEY,
+            TagValue::Gps(27.9881, 86.925, Some(altitude)),
+        );
+        MetadataEngine::write_with_sidecars(&path, &metadata, false).expect("write should succeed");
+
+        let read_back = MetadataEngine::read(&path).expect("read should succeed");
+        let Some(TagValue::Gps(_, _, Some(read))) = read_back
+            .find_tag(GPS_COORDINATES_KEY)
+            .map(|tag| tag.value.clone())
+        else {
+            panic!("altitude {altitude} should read back");
+        };
+        assert!(
+            (read - altitude).abs() < 0.01,
+            "wrote {altitude}, read {read}"
+        );
+        assert_eq!(read.is_sign_negative(), altitude < 0.0);
+    }
+}
+
+#[test]
+fn dms_round_trips_coordinates_within_a_hundred_thousandth_of_a_degree() {
+    let decode = |(degrees, minutes, seconds_num, seconds_den): (u32, u32, u32, u32)| {
+        assert!(minutes < 60 && seconds_num < 60 * seconds_den);
+        dms_to_decimal(
+            f64::from(degrees),
+            f64::from(minutes),
+            f64::from(seconds_num) / f64::from(seconds_den),
+        )
+    };
+
+    // Odd steps so the samples land on awkward fractions of a second.
+    let mut value = 0.0;
+    while value <= 180.0 {
+        let error = (decode(decimal_to_dms(value)) - value).abs();
+        assert!(error < 1e-5, "{value} came back off by {error}");
+        value += 0.013_579_1;
+    }
+    for value in [40.689167, 74.044444, 89.999_999_99, 179.999_999_99] {
+        let error = (decode(decimal_to_dms(value)) - value).abs();
+        assert!(error < 1e-5, "{value} came back off by {error}");
+    }
+
+    // And through the encoding written to a file.
+    let path = common::jpeg_fixture("dms");
+    let mut metadata = PhotoMetadata::default();
+    MetadataEngine::set_tag_in_metadata(
+        &mut metadata,
+        GPS_COORDINATES_KEY,
+        TagValue::Gps(40.689167, -74.044444, None),
+    );
+    MetadataEngine::write_with_sidecars(&path, &metadata, false).expect("write should succeed");
+    let read_back = MetadataEngine::read(&path).expect("read should succeed");
+    let Some(TagValue::Gps(lat, lon, _)) = read_back
+        .find_tag(GPS_COORDINATES_KEY)
+        .map(|tag| tag.value.clone())
+    else {
+        panic!("coordinates should read back");
+    };
+    assert!((lat - 40.689167).abs() < 1e-5, "latitude {lat}");
+    assert!((lon + 74.044444).abs() < 1e-5, "longitude {lon}");
+}
+
+#[test]
+fn iptc_caption_survives_a_write_cycle() {
+    let path = common::jpeg_fixture("iptc");
+
+    let mut metadata = PhotoMetadata::default();
+    let text = |value: &str| TagValue::Text(String::from(value));
+    MetadataEngine::set_tag_in_metadata(&mut metadata, "Exif.Image.Artist", text("Jane"));
+    MetadataEngine::set_tag_in_metadata(
+        &mut metadata,
+        "Iptc.Application2.Caption",
+        text("Sunset over the Seine — été"),
+    );
+    MetadataEngine::set_tag_in_metadata(
+        &mut metadata,
+        "Iptc.Application2.Keywords",
+        text("paris, river"),
+    );
+    MetadataEngine::set_tag_in_metadata(&mut metadata, "Xmp.dc.title", text("Seine & sky"));
+    MetadataEngine::write_with_sidecars(&path, &metadata, false).expect("write should succeed");
+
+    let read_back = MetadataEngine::read(&path).expect("read should succeed");
+    let value = |key: &str| read_back.find_tag(key).map(|tag| tag.value.to_string());
+    assert_eq!(
+        value("Iptc.Application2.Caption").as_deref(),
+        Some("Sunset over the Seine — été")
+    );
+    assert_eq!(
+        value("Iptc.Application2.Keywords").as_deref(),
+        Some("paris, river")
+    );
+    assert_eq!(value("Exif.Image.Artist").as_deref(), Some("Jane"));
+    let xmp = std::fs::read_to_string(MetadataEngine::xmp_sidecar_path(&path))
+        .expect("xmp sidecar should be written");
+    assert!(xmp.contains("<dc:title>Seine &amp; sky</dc:title>"));
+
+    // Stripping everything drops the IPTC block and the sidecar this app wrote.
+    MetadataEngine::write_with_sidecars(&path, &PhotoMetadata::default(), false)
+        .expect("write should succeed");
+    let stripped = MetadataEngine::read(&path).expect("read should succeed");
+    assert!(stripped.iptc_tags.is_empty());
+    assert!(!MetadataEngine::xmp_sidecar_path(&path).exists());
+}
+
+#[test]
+fn unknown_tags_edit_as_typed_values_and_keep_their_exif_type() {
+    let path = common::jpeg_fixture("unknown");
+
+    // Tags little_exif has no name for, written the way a maker would.
+    let mut exif = little_exif::metadata::Metadata::new();
+    exif.set_tag(little_exif::exif_tag::ExifTag::UnknownINT16U(
+        vec![5],
+        0xC7A1,
+        little_exif::ifd::ExifTagGroup::EXIF,
+    ));
+    exif.set_tag(little_exif::exif_tag::ExifTag::UnknownINT32U(
+        vec![1, 2, 3],
+        0xC7A2,
+        little_exif::ifd::ExifTagGroup::EXIF,
+    ));
+    exif.write_to_file(&path).expect("exif should write");
+
+    let mut metadata = MetadataEngine::read(&path).expect("read should succeed");
+    let single = metadata
+        .find_tag("Exif.Unknown.0xC7A1")
+        .expect("unknown tag should read");
+    assert_eq!(single.value, TagValue::Integer(5));
+    assert!(single.editable);
+    let list = metadata
+        .find_tag("Exif.Unknown.0xC7A2")
+        .expect("unknown tag should read");
+    assert_eq!(list.value, TagValue::Text(String::from("1, 2, 3")));
+
+    MetadataEngine::set_tag_in_metadata(
+        &mut metadata,
+        "Exif.Unknown.0xC7A1",
+        TagValue::Integer(640),
+    );
+    MetadataEngine::set_tag_in_metadata(
+        &mut metadata,
+        "Exif.Unknown.0xC7A2",
+        TagValue::Text(String::from("7, 8")),
+    );
+    MetadataEngine::write_with_sidecars(&path, &metadata, false).expect("write should succeed");
+
+    let read_back = MetadataEngine::read(&path).expect("read should succeed");
+    let value = |key: &str| read_back.find_tag(key).map(|tag| tag.value.clone());
+    assert_eq!(value("Exif.Unknown.0xC7A1"), Some(TagValue::Integer(640)));
+    assert_eq!(
+        value("Exif.Unknown.0xC7A2"),
+        Some(TagValue::Text(String::from("7, 8")))
+    );
+}
+
+#[test]
+fn unknown_tag_edits_that_do_not_fit_their_exif_type_are_refused() {
+    let path = common::jpeg_fixture("unknown_range");
+    let mut exif = little_exif::metadata::Metadata::new();
+    exif.set_tag(little_exif::exif_tag::ExifTag::UnknownINT16U(
+        vec![5],
+        0xC7A1,
+        little_exif::ifd::ExifTagGroup::EXIF,
+    ));
+    exif.set_tag(little_exif::exif_tag::ExifTag::UnknownRATIONAL64U(
+        vec![little_exif::rational::uR64 {
+            nominator: 1,
+            denominator: 2,
+        }],
+        0xC7A3,
+        little_exif::ifd::ExifTagGroup::EXIF,
+    ));
+    exif.write_to_file(&path).expect("exif should write");
+    let mut metadata = MetadataEngine::read(&path).expect("read should succeed");
+
+    for (key, value) in [
+        ("Exif.Unknown.0xC7A1", TagValue::Integer(70000)),
+        ("Exif.Unknown.0xC7A1", TagValue::Text(String::from("abc"))),
+        ("Exif.Unknown.0xC7A3", TagValue::Rational(1, 0)),
+    ] {
+        assert!(matches!(
+            MetadataEngine::check_tag_edit(&metadata, key, &value),
+            Err(MetadataError::InvalidTagValue(ref refused)) if refused == key
+        ));
+    }
+    assert!(MetadataEngine::check_tag_edit(
+        &metadata,
+        "Exif.Unknown.0xC7A1",
+        &TagValue::Integer(65535)
+    )
+    .is_ok());
+
+    // Written anyway, the value fails the save instead of leaving the old
+    // one on disk.
+    MetadataEngine::set_tag_in_metadata(
+        &mut metadata,
+        "Exif.Unknown.0xC7A1",
+        TagValue::Integer(70000),
+    );
+    let saved = std::fs::read(&path).expect("should read photo");
+    assert!(matches!(
+        MetadataEngine::write_with_sidecars(&path, &metadata, false),
+        Err(MetadataError::InvalidTagValue(_))
+    ));
+    assert_eq!(std::fs::read(&path).expect("should read photo"), saved);
+}
+
+#[test]
+fn metadata_report_renders_every_value_kind_as_a_line() {
+    let tag = |key: &str, value: TagValue| MetadataTag::new(key, key, value, TagCategory::Other);
+    let tags = vec![
+        tag(
+            "Exif.Image.ImageDescription",
+            TagValue::Text(String::from("Harbor\nat dusk")),
+        ),
+        tag("Exif.Photo.ISOSpeedRatings", TagValue::Integer(400)),
+        tag("Exif.Photo.BrightnessValue", TagValue::Float(2.5)),
+        tag("Exif.Photo.ExposureTime", TagValue::Rational(1, 250)),
+        tag("Exif.Image.XResolution", TagValue::Rational(72, 1)),
+        tag(
+            "Exif.Photo.DateTimeOriginal",
+            TagValue::DateTime(String::from("2024:05:01 18:30:00")),
+        ),
+        tag(
+            GPS_COORDINATES_KEY,
+            TagValue::Gps(48.8566, 2.3522, Some(35.0)),
+        ),
+        tag("Exif.Photo.MakerNote", TagValue::Binary(vec![0; 16])),
+        tag(
+            "Exif.Unknown.0xC7A1",
+            TagValue::Unknown(String::from("raw")),
+        ),
+    ];
+
+    let report = format_metadata_report(&tags);
+    let lines = report.lines().collect::<Vec<_>>();
+    assert_eq!(
+        lines,
+        vec![
+            "Exif.Image.ImageDescription: Harbor at dusk",
+            "Exif.Photo.ISOSpeedRatings: 400",
+            "Exif.Photo.BrightnessValue: 2.5",
+            "Exif.Photo.ExposureTime: 1/250 (1/250s)",
+            "Exif.Image.XResolution: 72/1",
+            "Exif.Photo.DateTimeOriginal: 2024:05:01 18:30:00",
+            &format!("{GPS_COORDINATES_KEY}: 48.856600, 2.352200 @ 35.00m"),
+            "Exif.Photo.MakerNote: <16 bytes>",
+            "Exif.Unknown.0xC7A1: raw",
+        ]
+    );
+    assert!(format_metadata_report(&[]).is_empty());
+}
+
+#[test]
+fn version_one_sidecar_drops_a_lone_latitude() {
+    let path = common::jpeg_fixture("v1_lone_latitude");
+
+    let v1 = r#"{"exif_tags":[{"key":"Exif.GPSInfo.GPSLatitude","display_name":"GPS Latitude","value":{"Float":33.8688},"category":"Other","editable":true,"marked_for_removal":false}],"iptc_tags":[],"xmp_tags":[],"has_gps":false,"date_taken":null,"camera_make":null,"camera_model":null,"dimensions":null}"#;
+    std::fs::write(MetadataEngine::sidecar_path(&path), v1).expect("should write sidecar");
+
+    let metadata = MetadataEngine::read_with_policy(&path, SidecarPolicy::Always)
+        .expect("v1 sidecar should read");
+    assert!(metadata.exif_tags.is_empty());
+    assert!(!metadata.has_gps);
+}
+
+#[test]
+fn version_one_sidecar_reads_into_the_current_model() {
+    let path = common::jpeg_fixture("v1");
+
+    // Written before sidecars had a version: no tag order, and the GPS
+    // position stored as its separate fields.
+    let tag = |key: &str, value: &str| {
+        format!(
+            r#"{{"key":"{key}","display_name":"{key}","value":{value},"category":"Other","editable":true,"marked_for_removal":false}}"#
+        )
+    };
+    let v1 = format!(
+        r#"{{"exif_tags":[{},{},{},{}],"iptc_tags":[],"xmp_tags":[],"has_gps":false,"date_taken":null,"camera_make":null,"camera_model":null,"dimensions":null}}"#,
+        tag("Exif.Image.Artist", r#"{"Text":"Jane"}"#),
+        tag("Exif.GPSInfo.GPSLatitudeRef", r#"{"Text":"S"}"#),
+        tag("Exif.GPSInfo.GPSLatitude", r#"{"Float":33.8688}"#),
+        tag("Exif.GPSInfo.GPSLongitude", r#"{"Float":151.2093}"#),
+    );
+    let sidecar = MetadataEngine::sidecar_path(&path);
+    std::fs::write(&sidecar, v1).expect("should write sidecar");
+
+    let metadata = MetadataEngine::read_with_policy(&path, SidecarPolicy::Always)
+        .expect("v1 sidecar should read");
+    let keys = metadata
+        .exif_tags
+        .iter()
+        .map(|tag| (tag.key.as_str(), tag.order))
+        .collect::<Vec<_>>();
+    assert_eq!(keys, vec![("Exif.Image.Artist", 0), (GPS_COORDINATES_KEY, 1)]);
+    assert_eq!(
+        metadata.find_tag(GPS_COORDINATES_KEY).map(|tag| &tag.value),
+        Some(&TagValue::Gps(-33.8688, 151.2093, None))
+    );
+    assert!(metadata.has_gps);
+
+    // Saving writes the current version, which reads back unchanged.
+    MetadataEngine::write(&path, &metadata).expect("write should succeed");
+    let written = std::fs::read_to_string(&sidecar).expect("sidecar should exist");
+    assert!(written.contains(&format!("\"version\": {SIDECAR_VERSION}")));
+    let read_back = MetadataEngine::read_with_policy(&path, SidecarPolicy::Always)
+        .expect("read should succeed");
+    assert_eq!(read_back.exif_tags, metadata.exif_tags);
+}
+
+#[test]
+fn corrupt_sidecar_falls_back_to_the_embedded_exif() {
+    let path = common::jpeg_fixture("corrupt_sidecar");
+    let mut metadata = PhotoMetadata::default();
+    MetadataEngine::set_tag_in_metadata(
+        &mut metadata,
+        "Exif.Image.Artist",
+        TagValue::Text(String::from("Embedded")),
+    );
+    MetadataEngine::write_with_sidecars(&path, &metadata, false).expect("write should succeed");
+    let sidecar = MetadataEngine::sidecar_path(&path);
+    std::fs::write(&sidecar, "{\"exif_tags\": [truncated").expect("should write sidecar");
+
+    let (read, warnings) = MetadataEngine::read_with_warnings(&path, SidecarPolicy::Always)
+        .expect("a corrupt sidecar shouldn't fail the read");
+    assert_eq!(
+        read.find_tag("Exif.Image.Artist")
+            .map(|tag| tag.value.to_string()),
+        Some(String::from("Embedded"))
+    );
+    assert!(
+        matches!(warnings.as_slice(), [MetadataWarning::SidecarIgnored(_)]),
+        "{warnings:?}"
+    );
+}
+
+#[test]
+fn keyword_lists_render_comma_joined_and_serialize_as_lists() {
+    let keywords = keywords_of(&["paris", "river", "night"]);
+    assert_eq!(keywords.to_string(), "paris, river, night");
+
+    let json = serde_json::to_string(&keywords).expect("should serialize");
+    assert_eq!(json, r#"{"TextList":["paris","river","night"]}"#);
+    let decoded: TagValue = serde_json::from_str(&json).expect("should deserialize");
+    assert_eq!(decoded, keywords);
+
+    // Typed text on a list tag is split into its entries.
+    let mut metadata = PhotoMetadata::default();
+    MetadataEngine::set_tag_in_metadata(
+        &mut metadata,
+        "Xmp.dc.subject",
+        TagValue::Text(String::from("paris, , river,night")),
+    );
+    assert_eq!(
+        metadata.find_tag("Xmp.dc.subject").map(|tag| &tag.value),
+        Some(&keywords)
+    );
+}
+
+fn keywords_of(items: &[&str]) -> TagValue {
+    TagValue::TextList(items.iter().map(|item| item.to_string()).collect())
+}
+
+#[test]
+fn iptc_tags_that_cannot_be_embedded_need_a_sidecar() {
+    let jpeg = common::jpeg_fixture("iptc_too_large");
+    let png = common::unique_path("iptc_png", "png");
+    std::fs::write(&png, common::encoded_image(image::ImageFormat::Png)).expect("should write png");
+    let keywords = |text: String| {
+        let mut metadata = PhotoMetadata::default();
+        MetadataEngine::set_tag_in_metadata(
+            &mut metadata,
+            "Iptc.Application2.Keywords",
+            TagValue::Text(text),
+        );
+        metadata
+    };
+
+    // A PNG has no IPTC block, and no APP13 segment holds 90 000 bytes.
+    for (path, metadata) in [
+        (png.as_path(), keywords(String::from("paris, river"))),
+        (jpeg.path(), keywords(vec!["x".repeat(30_000); 3].join(", "))),
+    ] {
+        let saved = std::fs::read(path).expect("should read photo");
+        assert!(MetadataEngine::write_with_sidecars(path, &metadata, false).is_err());
+        assert_eq!(std::fs::read(path).expect("should read photo"), saved);
+
+        MetadataEngine::write_with_sidecars(path, &metadata, true)
+            .expect("the sidecar keeps the tags");
+        let read_back = MetadataEngine::read(path).expect("read should succeed");
+        assert_eq!(read_back.iptc_tags, metadata.iptc_tags);
+    }
+
+    let _ = std::fs::remove_file(MetadataEngine::sidecar_path(&png));
+    let _ = std::fs::remove_file(&png);
+}
+
+#[test]
+fn iptc_keywords_keep_commas_inside_an_entry_through_a_write_cycle() {
+    let path = common::jpeg_fixture("keywords");
+
+    let keywords = keywords_of(&["Paris, France", "river", "night"]);
+    let mut metadata = PhotoMetadata::default();
+    MetadataEngine::set_tag_in_metadata(
+        &mut metadata,
+        "Iptc.Application2.Keywords",
+        keywords.clone(),
+    );
+    MetadataEngine::set_tag_in_metadata(&mut metadata, "Xmp.dc.subject", keywords.clone());
+    MetadataEngine::write_with_sidecars(&path, &metadata, false).expect("write should succeed");
+
+    let read_back = MetadataEngine::read(&path).expect("read should succeed");
+    assert_eq!(
+        read_back
+            .find_tag("Iptc.Application2.Keywords")
+            .map(|tag| &tag.value),
+        Some(&keywords)
+    );
+    let xmp = std::fs::read_to_string(MetadataEngine::xmp_sidecar_path(&path))
+        .expect("xmp sidecar should be written");
+    assert!(xmp.contains("<rdf:li>Paris, France</rdf:li>"), "{xmp}");
+    assert_eq!(xmp.matches("<rdf:li>").count(), 3);
+}
+
+#[test]
+fn xmp_tags_round_trip_through_their_sidecar() {
+    let path = common::jpeg_fixture("xmp_round_trip");
+
+    let keywords = keywords_of(&["Paris, France", "river"]);
+    let title = TagValue::Text(String::from("Seine & <sky>"));
+    let mut metadata = PhotoMetadata::default();
+    MetadataEngine::set_tag_in_metadata(&mut metadata, "Xmp.dc.title", title.clone());
+    MetadataEngine::set_tag_in_metadata(&mut metadata, "Xmp.dc.subject", keywords.clone());
+    MetadataEngine::write_with_sidecars(&path, &metadata, false).expect("write should succeed");
+
+    let read_back = MetadataEngine::read(&path).expect("read should succeed");
+    let value = |key: &str| read_back.find_tag(key).map(|tag| tag.value.clone());
+    assert_eq!(value("Xmp.dc.title"), Some(title));
+    assert_eq!(value("Xmp.dc.subject"), Some(keywords));
+    assert_eq!(read_back.xmp_tags.len(), 2);
+}
+
+#[test]
+fn xmp_sidecar_from_another_program_is_never_replaced_or_read() {
+    let path = common::jpeg_fixture("foreign_xmp");
+    let foreign = "<x:xmpmeta xmlns:x=\"adobe:ns:meta/\"><rdf:RDF><rdf:Description \
+                   darktable:xmp_version=\"5\"><dc:title>Theirs</dc:title>\
+                   </rdf:Description></rdf:RDF></x:xmpmeta>";
+    let xmp_path = MetadataEngine::xmp_sidecar_path(&path);
+    std::fs::write(&xmp_path, foreign).expect("should write foreign sidecar");
+
+    let mut metadata = PhotoMetadata::default();
+    MetadataEngine::set_tag_in_metadata(
+        &mut metadata,
+        "Xmp.dc.title",
+        TagValue::Text(String::from("Ours")),
+    );
+    assert!(MetadataEngine::write_with_sidecars(&path, &metadata, false).is_err());
+    MetadataEngine::write_with_sidecars(&path, &PhotoMetadata::default(), false)
+        .expect("a write without XMP tags leaves the sidecar alone");
+    assert_eq!(std::fs::read_to_string(&xmp_path).unwrap(), foreign);
+
+    let read_back = MetadataEngine::read(&path).expect("read should succeed");
+    assert!(read_back.xmp_tags.is_empty());
+}
+
+#[test]
+fn saves_reclaim_orphaned_temp_files_and_fail_cleanly_when_blocked() {
+    let dir = common::unique_path("orphaned_temp", "d");
+    std::fs::create_dir_all(&dir).expect("should create dir");
+    let path = dir.join("photo.jpg");
+    common::write_jpeg(&path);
+    let mut metadata = PhotoMetadata {
+        exif_tags: vec![MetadataTag::new(
+            "Exif.Image.Artist",
+            "Artist",
+            TagValue::Text(String::from("Jane")),
+            TagCategory::Other,
+        )],
+        ..PhotoMetadata::default()
+    };
+
+    // A temp file untouched for hours is what a crashed save leaves behind.
+    let temp = MetadataEngine::temp_path(&path);
+    std::fs::write(&temp, b"interrupted save").expect("should create orphan");
+    let hours_ago = std::time::SystemTime::now() - std::time::Duration::from_secs(3 * 60 * 60);
+    std::fs::File::options()
+        .write(true)
+        .open(&temp)
+        .and_then(|file| file.set_modified(hours_ago))
+        .expect("should age orphan");
+    MetadataEngine::write_with_sidecars(&path, &metadata, false).expect("write should succeed");
+    assert!(!temp.exists(), "the orphan should be reclaimed");
+
+    // Every temp name the save tries is taken by something it can't remove,
+    // so it fails before the photo is touched.
+    let saved = std::fs::read(&path).expect("should read photo");
+    std::fs::create_dir(&temp).expect("should block the temp path");
+    for attempt in 1..16 {
+        std::fs::create_dir(temp.with_extension(format!("{attempt}.tmp")))
+            .expect("should block a numbered temp path");
+    }
+    metadata.exif_tags[0].value = TagValue::Text(String::from("Sam"));
+    assert!(MetadataEngine::write_with_sidecars(&path, &metadata, false).is_err());
+    assert_eq!(std::fs::read(&path).expect("should read photo"), saved);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[cfg(unix)]
+#[test]
+fn saving_through_a_symlink_rewrites_the_target_and_keeps_the_link() {
+    let dir = common::unique_path("symlink_save", "d");
+    std::fs::create_dir_all(&dir).expect("should create dir");
+    let target = dir.join("photo.jpg");
+    common::write_jpeg(&target);
+    let link = dir.join("link.jpg");
+    std::os::unix::fs::symlink(&target, &link).expect("should create symlink");
+    let metadata = PhotoMetadata {
+        exif_tags: vec![MetadataTag::new(
+            "Exif.Image.Artist",
+            "Artist",
+            TagValue::Text(String::from("Jane")),
+            TagCategory::Other,
+        )],
+        ..PhotoMetadata::default()
+    };
+
+    MetadataEngine::write_with_sidecars(&link, &metadata, false).expect("write should succeed");
+
+    let link_meta = std::fs::symlink_metadata(&link).expect("should stat link");
+    assert!(link_meta.file_type().is_symlink(), "the link should survive the save");
+    let read_back = MetadataEngine::read(&target).expect("read should succeed");
+    assert_eq!(
+        read_back.find_tag("Exif.Image.Artist").map(|tag| &tag.value),
+        Some(&TagValue::Text(String::from("Jane")))
+    );
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn writes_replace_files_whole_and_leave_no_temp_files() {
+    let dir = std::env::temp_dir().join(format!("exif_editor_atomic_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).expect("should create dir");
+    let path = dir.join("photo.jpg");
+    common::write_jpeg(&path);
+    let metadata = PhotoMetadata {
+        exif_tags: vec![MetadataTag::new(
+            "Exif.Image.Artist",
+            "Artist",
+            TagValue::Text(String::from("Jane")),
+            TagCategory::Other,
+        )],
+        ..PhotoMetadata::default()
+    };
+
+    MetadataEngine::write(&path, &metadata).expect("write should succeed");
+    let mut names = std::fs::read_dir(&dir)
+        .expect("dir should list")
+        .map(|entry| {
+            entry
+                .expect("entry")
+                .file_name()
+                .to_string_lossy()
+                .to_string()
+        })
+        .collect::<Vec<_>>();
+    names.sort();
+    assert_eq!(names, ["photo.jpg", "photo.jpg.exif_editor.json"]);
+
+    // A file already sitting at the temp name isn't ours: the save picks
+    // another name and leaves it alone.
+    let temp = MetadataEngine::temp_path(&path);
+    std::fs::write(&temp, b"someone else's").expect("should create unrelated file");
+    let mut edited = metadata.clone();
+    edited.exif_tags[0].value = TagValue::Text(String::from("Sam"));
+    MetadataEngine::write(&path, &edited).expect("write should succeed");
+    assert_eq!(
+        std::fs::read(&temp).expect("unrelated file should survive"),
+        b"someone else's"
+    );
+    let reread = MetadataEngine::read(&path).expect("read should succeed");
+    assert_eq!(
+        reread.find_tag("Exif.Image.Artist").map(|tag| &tag.value),
+        Some(&TagValue::Text(String::from("Sam")))
+    );
+    let count = std::fs::read_dir(&dir).expect("dir should list").count();
+    assert_eq!(count, 3);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,53 @@
+use std::path::PathBuf;
+
+use exif_editor::models::{OperationResult, OperationSummary};
+
+fn results() -> Vec<OperationResult> {
+    vec![
+        OperationResult::success(1, PathBuf::from("/out/a.jpg")),
+        OperationResult::failure(2, PathBuf::from("/out/b.jpg"), "permission denied"),
+        OperationResult::success(3, PathBuf::from("/out/c.jpg")),
+    ]
+}
+
+#[test]
+fn split_keeps_succeeded_and_failed_results_in_order() {
+    let (succeeded, failed) = OperationResult::split(results());
+
+    let ids = |results: &[OperationResult]| {
+        results
+            .iter()
+            .map(|result| result.photo_id)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(ids(&succeeded), vec![1, 3]);
+    assert_eq!(ids(&failed), vec![2]);
+    assert_eq!(failed[0].error_message(), Some("permission denied"));
+    assert!(succeeded
+        .iter()
+        .all(|result| result.error_message().is_none()));
+    assert_eq!(succeeded[1].output_path(), PathBuf::from("/out/c.jpg"));
+}
+
+#[test]
+fn summary_counts_unreturned_photos_as_cancelled() {
+    let summary = OperationSummary::from_results(5, &results());
+    assert_eq!(summary.total, 5);
+    assert_eq!(summary.succeeded, 2);
+    assert_eq!(summary.failed, 1);
+    assert_eq!(summary.cancelled, 2);
+    assert_eq!(summary.processed(), 3);
+    assert!(!summary.is_complete_success());
+    assert_eq!(summary.to_string(), "2 succeeded, 1 failed, 2 cancelled");
+
+    let (succeeded, _) = OperationResult::split(results());
+    let clean = OperationSummary::from_results(succeeded.len(), &succeeded);
+    assert!(clean.is_complete_success());
+}
+
+#[test]
+fn results_display_their_output_or_error() {
+    let results = results();
+    assert_eq!(results[0].to_string(), "photo 1 -> /out/a.jpg");
+    assert_eq!(results[1].to_string(), "photo 2 failed: permission denied");
+}
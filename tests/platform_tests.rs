@@ -0,0 +1,29 @@
+use std::path::{Path, PathBuf};
+
+use exif_editor::platform;
+
+#[test]
+fn relative_paths_resolve_against_the_base_directory() {
+    let base = std::env::temp_dir().join("exif_editor_base");
+
+    assert_eq!(
+        platform::absolute_path_from(Path::new("photos/./a.jpg"), &base),
+        base.join("photos").join("a.jpg")
+    );
+    assert_eq!(
+        platform::absolute_path_from(Path::new("../shared/b.png"), &base.join("photos")),
+        base.join("shared").join("b.png")
+    );
+}
+
+#[test]
+fn absolute_paths_are_kept_but_normalized() {
+    let absolute = std::env::temp_dir().join("exif_editor_abs").join("c.jpg");
+    let unrelated_base = PathBuf::from("ignored");
+
+    assert_eq!(
+        platform::absolute_path_from(&absolute, &unrelated_base),
+        absolute
+    );
+    assert!(platform::absolute_path(Path::new("d.jpg")).is_absolute());
+}
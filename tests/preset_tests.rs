@@ -1,5 +1,11 @@
-use exif_editor::core::presets::builtin_presets;
-use exif_editor::models::{PresetRule, TagCategory};
+use exif_editor::core::metadata::MetadataEngine;
+use exif_editor::core::presets::{
+    argument_suggestions, builtin_presets, parse_allowlist, pick_argument, rule_summary,
+    PresetRuleBuilder, RuleBuildError, RuleKind, ALLOWLIST_PRESET_ID, HARD_CLEAN_PRESET_ID,
+};
+use exif_editor::models::{
+    MetadataTag, PhotoMetadata, PresetRule, TagCategory, TagValue, ORIENTATION_KEY,
+};
 
 #[test]
 fn builtin_presets_include_expected_names() {
@@ -9,13 +15,22 @@ fn builtin_presets_include_expected_names() {
         .map(|preset| preset.name.as_str())
         .collect::<Vec<_>>();
 
-    assert_eq!(presets.len(), 6);
+    assert_eq!(presets.len(), 7);
     assert!(names.contains(&"Strip All"));
     assert!(names.contains(&"Privacy Clean"));
     assert!(names.contains(&"Social Media"));
     assert!(names.contains(&"GPS Only"));
     assert!(names.contains(&"Keep Basics"));
     assert!(names.contains(&"Copyright Stamp"));
+    assert!(names.contains(&"Touch Up"));
+}
+
+#[test]
+fn reserved_preset_ids_are_distinct_from_each_other_and_the_builtins() {
+    assert_ne!(ALLOWLIST_PRESET_ID, HARD_CLEAN_PRESET_ID);
+    assert!(builtin_presets()
+        .iter()
+        .all(|preset| preset.id != ALLOWLIST_PRESET_ID && preset.id != HARD_CLEAN_PRESET_ID));
 }
 
 #[test]
@@ -34,3 +49,204 @@ fn privacy_clean_has_gps_and_software_rules() {
         "Exif.Photo.BodySerialNumber"
     ))));
 }
+
+#[test]
+fn touch_up_normalizes_a_messy_metadata_set() {
+    let touch_up = builtin_presets()
+        .into_iter()
+        .find(|preset| preset.name == "Touch Up")
+        .expect("touch up preset should exist");
+
+    let mut metadata = PhotoMetadata {
+        exif_tags: vec![
+            MetadataTag::new(
+                "Exif.Photo.DateTimeOriginal",
+                "Date Taken",
+                TagValue::DateTime(String::from("2024-03-05T14:07:09")),
+                TagCategory::DateTime,
+            ),
+            MetadataTag::new(
+                "Exif.Image.DateTime",
+                "Modified",
+                TagValue::Text(String::from("2024/03/06 08:30")),
+                TagCategory::DateTime,
+            ),
+            MetadataTag::new(
+                ORIENTATION_KEY,
+                "Orientation",
+                TagValue::Integer(42),
+                TagCategory::Image,
+            ),
+            MetadataTag::new(
+                "Exif.Image.Artist",
+                "Artist",
+                TagValue::Text(String::from("   ")),
+                TagCategory::Description,
+            ),
+            MetadataTag::new(
+                "Exif.Image.Make",
+                "Camera Make",
+                TagValue::Text(String::from("Canon")),
+                TagCategory::Camera,
+            ),
+        ],
+        iptc_tags: vec![MetadataTag::new(
+            "Iptc.Application2.Caption",
+            "Caption",
+            TagValue::Text(String::new()),
+            TagCategory::Description,
+        )],
+        ..PhotoMetadata::default()
+    };
+
+    MetadataEngine::apply_preset_to_metadata(&mut metadata, &touch_up);
+
+    assert_eq!(
+        metadata
+            .find_tag("Exif.Photo.DateTimeOriginal")
+            .map(|tag| &tag.value),
+        Some(&TagValue::DateTime(String::from("2024:03:05 14:07:09")))
+    );
+    assert_eq!(
+        metadata
+            .find_tag("Exif.Image.DateTime")
+            .map(|tag| &tag.value),
+        Some(&TagValue::DateTime(String::from("2024:03:06 08:30:00")))
+    );
+    assert_eq!(
+        metadata.find_tag(ORIENTATION_KEY).map(|tag| &tag.value),
+        Some(&TagValue::Integer(1))
+    );
+    assert!(metadata.find_tag("Exif.Image.Artist").is_none());
+    assert!(metadata.find_tag("Iptc.Application2.Caption").is_none());
+    assert!(metadata.find_tag("Exif.Image.Make").is_some());
+    assert_eq!(metadata.total_tag_count(), 4);
+    assert!(metadata.provenance_for(ORIENTATION_KEY).is_some());
+    assert!(metadata.provenance_for("Exif.Image.Make").is_none());
+}
+
+#[test]
+fn rule_builder_builds_every_rule_type() {
+    let mut builder = PresetRuleBuilder::new();
+    let inputs = [
+        (RuleKind::RemoveCategory, "Camera", ""),
+        (RuleKind::RemoveTag, "exif.image.artist", ""),
+        (
+            RuleKind::RemoveAllExcept,
+            "Exif.Image.Orientation, datetime",
+            "",
+        ),
+        (RuleKind::RemoveAll, "", ""),
+        (RuleKind::RemoveGps, "", ""),
+        (RuleKind::RemoveThumbnail, "", ""),
+        (RuleKind::SetTag, "Exif.Image.Copyright", "(c) Jo"),
+        (RuleKind::NormalizeDateTimes, "", ""),
+        (RuleKind::ResetInvalidOrientation, "", ""),
+        (RuleKind::RemoveEmptyTags, "", ""),
+    ];
+    assert_eq!(inputs.len(), RuleKind::ALL.len());
+    for (kind, argument, value) in inputs {
+        builder
+            .add(kind, argument, value)
+            .unwrap_or_else(|err| panic!("{} should build: {err}", kind.label()));
+    }
+
+    assert_eq!(
+        builder.build(),
+        vec![
+            PresetRule::RemoveCategory(TagCategory::Camera),
+            PresetRule::RemoveTag(String::from("Exif.Image.Artist")),
+            PresetRule::RemoveAllExcept(vec![
+                String::from("Exif.Image.Orientation"),
+                String::from("datetime"),
+            ]),
+            PresetRule::RemoveAll,
+            PresetRule::RemoveGps,
+            PresetRule::RemoveThumbnail,
+            PresetRule::SetTag(String::from("Exif.Image.Copyright"), String::from("(c) Jo")),
+            PresetRule::NormalizeDateTimes,
+            PresetRule::ResetInvalidOrientation,
+            PresetRule::RemoveEmptyTags,
+        ]
+    );
+}
+
+#[test]
+fn builder_pickers_suggest_and_fill_in_arguments() {
+    let artist = argument_suggestions(RuleKind::RemoveTag, "artist");
+    assert!(artist.contains(&"Exif.Image.Artist"), "{artist:?}");
+    assert!(argument_suggestions(RuleKind::RemoveGps, "").is_empty());
+    assert_eq!(
+        argument_suggestions(RuleKind::RemoveCategory, "cam"),
+        vec!["Camera"]
+    );
+    // "Keep only" matches the entry after the last comma.
+    assert!(
+        argument_suggestions(RuleKind::RemoveAllExcept, "camera, orient")
+            .contains(&"Exif.Image.Orientation")
+    );
+
+    assert_eq!(
+        pick_argument(RuleKind::RemoveTag, "arti", "Exif.Image.Artist"),
+        "Exif.Image.Artist"
+    );
+    let list = pick_argument(
+        RuleKind::RemoveAllExcept,
+        "camera, orient",
+        "Exif.Image.Orientation",
+    );
+    assert_eq!(list, "camera, Exif.Image.Orientation, ");
+    assert!(PresetRuleBuilder::build_rule(RuleKind::RemoveAllExcept, &list, "").is_ok());
+
+    let rule = PresetRuleBuilder::build_rule(RuleKind::SetTag, "exif.image.artist", "Jo")
+        .expect("rule should build");
+    assert_eq!(rule_summary(&rule), "Set tag: Exif.Image.Artist = Jo");
+    assert_eq!(rule_summary(&PresetRule::RemoveGps), "Remove GPS");
+}
+
+#[test]
+fn allowlist_accepts_categories_and_catalog_keys_only() {
+    let (accepted, unrecognized) =
+        parse_allowlist("camera, exif.image.orientation, Exif.Image.Orientaton, colour");
+    assert_eq!(
+        accepted,
+        vec![
+            String::from("camera"),
+            String::from("Exif.Image.Orientation")
+        ]
+    );
+    assert_eq!(
+        unrecognized,
+        vec![
+            String::from("Exif.Image.Orientaton"),
+            String::from("colour")
+        ]
+    );
+}
+
+#[test]
+fn rule_builder_rejects_keys_missing_from_the_catalog() {
+    let mut builder = PresetRuleBuilder::new();
+
+    assert_eq!(
+        builder.add(RuleKind::RemoveTag, "Exif.Image.Artst", ""),
+        Err(RuleBuildError::UnknownKeys(vec![String::from(
+            "Exif.Image.Artst"
+        )]))
+    );
+    assert_eq!(
+        builder.add(RuleKind::RemoveAllExcept, "camera, Exif.Nope.Key", ""),
+        Err(RuleBuildError::UnknownKeys(vec![String::from(
+            "Exif.Nope.Key"
+        )]))
+    );
+    assert_eq!(
+        builder.add(RuleKind::RemoveCategory, "lenses", ""),
+        Err(RuleBuildError::UnknownCategory(String::from("lenses")))
+    );
+    assert_eq!(
+        builder.add(RuleKind::SetTag, "  ", "value"),
+        Err(RuleBuildError::MissingArgument(RuleKind::SetTag))
+    );
+    assert!(builder.rules().is_empty());
+}
@@ -0,0 +1,39 @@
+use exif_editor::core::preview;
+use image::{DynamicImage, GenericImageView, Rgba, RgbaImage};
+
+const RED: Rgba<u8> = Rgba([255, 0, 0, 255]);
+const BLUE: Rgba<u8> = Rgba([0, 0, 255, 255]);
+
+fn red_left_blue_right() -> DynamicImage {
+    let mut image = RgbaImage::new(2, 1);
+    image.put_pixel(0, 0, RED);
+    image.put_pixel(1, 0, BLUE);
+    DynamicImage::ImageRgba8(image)
+}
+
+#[test]
+fn orientation_six_rotates_clockwise() {
+    let rotated = preview::apply_orientation(red_left_blue_right(), 6);
+
+    assert_eq!(rotated.dimensions(), (1, 2));
+    assert_eq!(rotated.get_pixel(0, 0), RED);
+    assert_eq!(rotated.get_pixel(0, 1), BLUE);
+}
+
+#[test]
+fn orientation_eight_rotates_counter_clockwise() {
+    let rotated = preview::apply_orientation(red_left_blue_right(), 8);
+
+    assert_eq!(rotated.dimensions(), (1, 2));
+    assert_eq!(rotated.get_pixel(0, 0), BLUE);
+    assert_eq!(rotated.get_pixel(0, 1), RED);
+}
+
+#[test]
+fn unknown_orientation_leaves_pixels_untouched() {
+    for orientation in [0, 1, 9, -3] {
+        let image = preview::apply_orientation(red_left_blue_right(), orientation);
+        assert_eq!(image.dimensions(), (2, 1));
+        assert_eq!(image.get_pixel(0, 0), RED);
+    }
+}
@@ -0,0 +1,110 @@
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use exif_editor::app::AppState;
+use exif_editor::models::{OutputMode, TagCategory};
+use exif_editor::settings::{AppSettings, DefaultOutput, TemplateBlocklist};
+
+fn unique_path(name: &str) -> PathBuf {
+    let stamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time should be after unix epoch")
+        .as_nanos();
+    std::env::temp_dir()
+        .join(format!("exif_editor_{name}_{stamp}"))
+        .join("settings.json")
+}
+
+#[test]
+fn app_settings_round_trip_through_the_settings_file() {
+    let path = unique_path("app_settings");
+    let settings = AppSettings {
+        default_preset: Some(4),
+        default_output: DefaultOutput::Copy,
+        write_sidecars: false,
+        thumbnail_size: 128,
+        strip_on_import: Some(2),
+        backup_originals: true,
+        log_to_file: true,
+        template_blocklist: TemplateBlocklist {
+            categories: vec![TagCategory::Camera],
+            keys: vec![String::from("Exif.Image.Artist")],
+        },
+    };
+
+    settings.save(&path).expect("settings should save");
+    let contents = fs::read_to_string(&path).expect("settings file should exist");
+    assert!(contents.contains("\"default_output\": \"copy\""));
+    assert_eq!(AppSettings::load(&path), settings);
+
+    let _ = fs::remove_dir_all(path.parent().expect("settings dir"));
+}
+
+#[test]
+fn missing_partial_or_corrupt_app_settings_fall_back_to_defaults() {
+    let path = unique_path("app_settings_fallback");
+    assert_eq!(AppSettings::load(&path), AppSettings::default());
+
+    fs::create_dir_all(path.parent().expect("settings dir")).expect("should create dir");
+    fs::write(&path, r#"{"write_sidecars": false}"#).expect("should write settings");
+    let partial = AppSettings::load(&path);
+    assert!(!partial.write_sidecars);
+    let defaults = AppSettings::default();
+    assert_eq!(partial.default_preset, defaults.default_preset);
+    assert_eq!(partial.thumbnail_size, defaults.thumbnail_size);
+
+    fs::write(&path, "{ not json").expect("should write settings");
+    assert_eq!(AppSettings::load(&path), AppSettings::default());
+
+    let _ = fs::remove_dir_all(path.parent().expect("settings dir"));
+}
+
+#[test]
+fn applied_settings_set_the_session_defaults() {
+    let mut state = AppState::default();
+    state.apply_settings(AppSettings {
+        default_preset: Some(4),
+        default_output: DefaultOutput::Copy,
+        ..AppSettings::default()
+    });
+    assert_eq!(state.active_preset, Some(4));
+    assert_eq!(state.bulk_output_mode, OutputMode::PresetSuffix);
+
+    // A preset id that no longer exists leaves nothing selected.
+    state.apply_settings(AppSettings {
+        default_preset: Some(9999),
+        ..AppSettings::default()
+    });
+    assert_eq!(state.active_preset, None);
+    assert_eq!(state.bulk_output_mode, OutputMode::Overwrite);
+}
+
+#[test]
+fn changing_an_unrelated_setting_keeps_the_session_preset_and_output() {
+    let mut state = AppState::default();
+    state.apply_settings(AppSettings {
+        default_preset: Some(4),
+        ..AppSettings::default()
+    });
+    state.active_preset = Some(1);
+    state.bulk_output_mode = OutputMode::ExportTo(PathBuf::from("/tmp/clean"));
+
+    state.change_settings(AppSettings {
+        backup_originals: true,
+        ..state.settings.clone()
+    });
+    assert_eq!(state.active_preset, Some(1));
+    assert_eq!(
+        state.bulk_output_mode,
+        OutputMode::ExportTo(PathBuf::from("/tmp/clean"))
+    );
+
+    state.change_settings(AppSettings {
+        default_preset: Some(5),
+        default_output: DefaultOutput::Copy,
+        ..state.settings.clone()
+    });
+    assert_eq!(state.active_preset, Some(5));
+    assert_eq!(state.bulk_output_mode, OutputMode::PresetSuffix);
+}
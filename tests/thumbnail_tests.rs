@@ -0,0 +1,148 @@
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+use exif_editor::app::AppState;
+use exif_editor::core::metadata::MetadataEngine;
+use image::codecs::jpeg::JpegEncoder;
+use image::{RgbImage, Rgba, RgbaImage};
+
+mod common;
+
+use common::unique_path;
+
+fn write_png(path: &Path, width: u32, height: u32) {
+    RgbaImage::from_pixel(width, height, Rgba([40, 80, 120, 255]))
+        .save(path)
+        .expect("should write png");
+}
+
+fn encode_jpeg(width: u32, height: u32) -> Vec<u8> {
+    let image = RgbImage::from_pixel(width, height, image::Rgb([200, 30, 30]));
+    let mut bytes = Vec::new();
+    JpegEncoder::new(&mut bytes)
+        .encode_image(&image)
+        .expect("should encode jpeg");
+    bytes
+}
+
+/// A JPEG whose APP1 segment holds a little-endian TIFF block with an empty IFD0
+/// and an IFD1 pointing at `thumbnail`.
+fn jpeg_with_embedded_thumbnail(thumbnail: &[u8]) -> Vec<u8> {
+    let ifd1_offset = 8 + 2 + 4;
+    let thumbnail_offset = ifd1_offset + 2 + 2 * 12 + 4;
+
+    let mut tiff = Vec::new();
+    tiff.extend_from_slice(b"II");
+    tiff.extend_from_slice(&42u16.to_le_bytes());
+    tiff.extend_from_slice(&8u32.to_le_bytes());
+    tiff.extend_from_slice(&0u16.to_le_bytes());
+    tiff.extend_from_slice(&(ifd1_offset as u32).to_le_bytes());
+    tiff.extend_from_slice(&2u16.to_le_bytes());
+    for (tag, value) in [
+        (0x0201u16, thumbnail_offset as u32),
+        (0x0202u16, thumbnail.len() as u32),
+    ] {
+        tiff.extend_from_slice(&tag.to_le_bytes());
+        tiff.extend_from_slice(&4u16.to_le_bytes());
+        tiff.extend_from_slice(&1u32.to_le_bytes());
+        tiff.extend_from_slice(&value.to_le_bytes());
+    }
+    tiff.extend_from_slice(&0u32.to_le_bytes());
+    tiff.extend_from_slice(thumbnail);
+
+    let main = encode_jpeg(64, 48);
+    let mut bytes = main[..2].to_vec();
+    bytes.extend_from_slice(&[0xFF, 0xE1]);
+    bytes.extend_from_slice(&((tiff.len() + 8) as u16).to_be_bytes());
+    bytes.extend_from_slice(b"Exif\0\0");
+    bytes.extend_from_slice(&tiff);
+    bytes.extend_from_slice(&main[2..]);
+    bytes
+}
+
+#[test]
+fn strip_of_three_hundred_photos_decodes_each_original_once() {
+    let paths = (0..300)
+        .map(|index| unique_path(&format!("thumb_{index}"), "png"))
+        .collect::<Vec<_>>();
+    for path in &paths {
+        write_png(path, 8, 6);
+    }
+
+    let mut state = AppState::default();
+    state.import_paths(paths.clone());
+    assert_eq!(state.photos.len(), 300);
+    assert!(state.photos.iter().all(|photo| photo.needs_thumbnail()));
+    for index in 0..state.photos.len() {
+        state.thumbnail_for(index).expect("png should decode");
+    }
+    assert!(state.photos.iter().all(|photo| !photo.needs_thumbnail()));
+
+    // Later "frames" of the strip are served without touching the originals.
+    for path in &paths {
+        let _ = fs::remove_file(path);
+    }
+    for _ in 0..5 {
+        for index in 0..state.photos.len() {
+            let thumbnail = state
+                .thumbnail_for(index)
+                .expect("thumbnail should be kept");
+            assert_eq!((thumbnail.width, thumbnail.height), (8, 6));
+        }
+    }
+}
+
+#[test]
+fn thumbnail_is_decoded_again_after_a_reload_finds_the_file_changed() {
+    let path = unique_path("thumb_changed", "png");
+    write_png(&path, 400, 200);
+
+    let mut state = AppState::default();
+    state.import_paths([path.clone()]);
+
+    let first = state.thumbnail_for(0).expect("png should decode");
+    assert_eq!((first.width, first.height), (192, 96));
+
+    write_png(&path, 100, 200);
+    let later = SystemTime::now() + Duration::from_secs(5);
+    fs::File::options()
+        .write(true)
+        .open(&path)
+        .and_then(|file| file.set_modified(later))
+        .expect("should bump mtime");
+
+    // Rendering alone doesn't stat the file, so the old thumbnail stays.
+    let unchanged = state.thumbnail_for(0).expect("thumbnail should be kept");
+    assert_eq!((unchanged.width, unchanged.height), (192, 96));
+
+    state
+        .reload_photo_from_disk(0)
+        .expect("reload should succeed");
+    assert!(state.photos[0].needs_thumbnail());
+    let second = state.thumbnail_for(0).expect("png should decode");
+    assert_eq!((second.width, second.height), (96, 192));
+
+    let _ = fs::remove_file(&path);
+}
+
+#[test]
+fn extracts_embedded_exif_thumbnail_from_jpeg() {
+    let with_thumbnail = unique_path("embedded_thumb", "jpg");
+    let without_thumbnail = common::jpeg_fixture("no_embedded_thumb");
+    fs::write(
+        &with_thumbnail,
+        jpeg_with_embedded_thumbnail(&encode_jpeg(16, 12)),
+    )
+    .expect("should write jpeg");
+
+    let thumbnail =
+        MetadataEngine::extract_thumbnail(&with_thumbnail).expect("thumbnail should be found");
+    assert_eq!((thumbnail.width, thumbnail.height), (16, 12));
+    assert_eq!(thumbnail.pixels.len(), 16 * 12 * 4);
+    assert!(image::open(&with_thumbnail).is_ok());
+
+    assert!(MetadataEngine::extract_thumbnail(&without_thumbnail).is_none());
+
+    let _ = fs::remove_file(&with_thumbnail);
+}
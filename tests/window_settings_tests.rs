@@ -0,0 +1,55 @@
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use exif_editor::ui::window_settings::{SavedBounds, SavedTheme, WindowSettings};
+
+fn unique_path(name: &str) -> PathBuf {
+    let stamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time should be after unix epoch")
+        .as_nanos();
+    std::env::temp_dir()
+        .join(format!("exif_editor_{name}_{stamp}"))
+        .join("window.json")
+}
+
+#[test]
+fn window_settings_round_trip_through_the_settings_file() {
+    let path = unique_path("window_settings");
+    let settings = WindowSettings {
+        bounds: Some(SavedBounds {
+            x: 120.0,
+            y: 64.5,
+            width: 1380.0,
+            height: 900.0,
+        }),
+        maximized: true,
+        theme: Some(SavedTheme::Light),
+    };
+
+    settings.save(&path).expect("settings should save");
+    let contents = fs::read_to_string(&path).expect("settings file should exist");
+    assert!(contents.contains("\"theme\": \"light\""));
+    assert_eq!(WindowSettings::load(&path), settings);
+
+    let _ = fs::remove_dir_all(path.parent().expect("settings dir"));
+}
+
+#[test]
+fn missing_partial_or_corrupt_settings_fall_back_to_defaults() {
+    let path = unique_path("window_settings_fallback");
+    assert_eq!(WindowSettings::load(&path), WindowSettings::default());
+
+    fs::create_dir_all(path.parent().expect("settings dir")).expect("should create dir");
+    fs::write(&path, r#"{"theme": "dark"}"#).expect("should write settings");
+    let partial = WindowSettings::load(&path);
+    assert_eq!(partial.theme, Some(SavedTheme::Dark));
+    assert_eq!(partial.bounds, None);
+    assert!(!partial.maximized);
+
+    fs::write(&path, "{ not json").expect("should write settings");
+    assert_eq!(WindowSettings::load(&path), WindowSettings::default());
+
+    let _ = fs::remove_dir_all(path.parent().expect("settings dir"));
+}